@@ -0,0 +1,140 @@
+// Angular (Bending) Springs
+// One thread per particle triplet (a, b, c), bending at b. Runs right after the bond
+// pass and writes the triplet's three forces (zero when it exerts none);
+// bond_gather.wgsl adds them to the particles with the bond forces.
+//   θ = atan2(d1 × d2, d1 · d2), d1 = b - a, d2 = c - b (0 = straight)
+//   F = -k (θ - θ_rest) ∂θ/∂x, ∂θ/∂a = perp(d1)/|d1|², ∂θ/∂c = perp(d2)/|d2|², F_b = -(F_a + F_c)
+// Triplets bent past their break angle deactivate.
+// Mirrors bending::bending_forces.
+
+struct Particle {
+    pos: vec2<f32>,
+    vel: vec2<f32>,
+    mass: f32,
+    density: f32,
+    pressure: f32,
+    z_height: f32,
+    layer_mask: u32,
+    cell_id: u32,
+    _padding: vec2<f32>,
+}
+
+// Triplet (matches Rust AngularSpring)
+struct AngularSpring {
+    particle_a: u32,
+    particle_b: u32,
+    particle_c: u32,
+    family: u32,
+    rest_angle: f32,
+    stiffness: f32,
+    break_angle: f32,
+    is_active: u32,
+}
+
+// Forces of one triplet (matches Rust bending::TripletForce)
+struct TripletForce {
+    a: vec2<f32>,
+    b: vec2<f32>,
+    c: vec2<f32>,
+    _padding: vec2<f32>,
+}
+
+// Simulation parameters (only `rigid_bodies` is read here)
+struct SimParams {
+    delta_time: f32,
+    gravity: f32,
+    smoothing_radius: f32,
+    target_density_water: f32,
+    target_density_air: f32,
+    wind_interaction_threshold: f32,
+    _reserved: f32,
+    sheet_extension: f32,
+    bounds: vec4<f32>,
+    integrator: u32,
+    pressure_solver: u32,
+    pressure_tolerance: f32,
+    pressure_max_iterations: u32,
+    max_velocity: f32,
+    kernel: u32,
+    boundary_handling: u32,
+    rigid_bodies: u32, // 0 = springs, 1 = shape matching
+    open_world: u32, // 1 = bounds follow the fleet, fluid wraps along x instead of walls
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
+}
+
+// Rigid body membership (matches Rust RigidMember)
+struct RigidMember {
+    body: u32,
+    driven: u32, // 1 = moved by a kinematic joint of its own body
+    rest_offset: vec2<f32>,
+}
+
+const FORCE_LIMIT: f32 = 2000000.0;
+const TAU: f32 = 6.28318530718;
+const NO_BODY: u32 = 0xFFFFFFFFu;
+const RIGID_SHAPE_MATCHING: u32 = 1u;
+
+@group(0) @binding(0) var<storage, read> particles: array<Particle>;
+@group(0) @binding(1) var<storage, read_write> springs: array<AngularSpring>;
+@group(0) @binding(2) var<storage, read_write> triplet_forces: array<TripletForce>;
+@group(0) @binding(3) var<uniform> params: SimParams;
+@group(0) @binding(4) var<storage, read> members: array<RigidMember>;
+
+fn perp(v: vec2<f32>) -> vec2<f32> {
+    return vec2<f32>(-v.y, v.x);
+}
+
+fn limit(force: vec2<f32>) -> vec2<f32> {
+    return clamp(force, vec2<f32>(-FORCE_LIMIT), vec2<f32>(FORCE_LIMIT));
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let idx = global_id.x;
+    if idx >= arrayLength(&springs) {
+        return;
+    }
+    triplet_forces[idx] = TripletForce();
+
+    var spring = springs[idx];
+    if spring.is_active == 0u {
+        return;
+    }
+
+    // Shape matching already keeps a rigid body's shape
+    let body = members[spring.particle_b].body;
+    if params.rigid_bodies == RIGID_SHAPE_MATCHING && body != NO_BODY
+        && body == members[spring.particle_a].body && body == members[spring.particle_c].body {
+        return;
+    }
+
+    let a = particles[spring.particle_a].pos;
+    let b = particles[spring.particle_b].pos;
+    let c = particles[spring.particle_c].pos;
+    let d1 = b - a;
+    let d2 = c - b;
+    let len1 = dot(d1, d1);
+    let len2 = dot(d2, d2);
+    if len1 < 1e-12 || len2 < 1e-12 {
+        return;
+    }
+
+    let angle = atan2(d1.x * d2.y - d1.y * d2.x, dot(d1, d2));
+    var bend = angle - spring.rest_angle;
+    bend -= TAU * round(bend / TAU);
+
+    if spring.break_angle > 0.0 && abs(bend) > spring.break_angle {
+        spring.is_active = 0u;
+        springs[idx] = spring;
+        return;
+    }
+
+    let torque = -spring.stiffness * bend;
+    let force_a = torque * perp(d1) / len1;
+    let force_c = torque * perp(d2) / len2;
+    triplet_forces[idx] = TripletForce(
+        limit(force_a), limit(-(force_a + force_c)), limit(force_c), vec2<f32>(0.0)
+    );
+}
@@ -0,0 +1,145 @@
+// Bond Adjacency Rebuild
+// Rebuilds the per-particle bond lists read by bond_gather.wgsl after bonds were written
+// into slots at runtime (weld.wgsl). Same counting sort as the particle grid:
+//   plan:    single thread, sets the indirect dispatch args of every pass below; zero
+//            workgroups unless BondAllocator.changes moved since the last rebuild
+//   clear:   one thread per particle, zero the counts
+//   count:   one thread per bond slot, count both ends of every used slot (rest length > 0)
+//   (prefix_sum.wgsl's blocked scan: counts -> offsets, and the cursors start at them)
+//   scatter: one thread per bond slot, append the bond to both ends' lists
+//   sort:    one thread per particle, sort its list so the gather sums in index order
+// The adjacency buffer is CSR packed: particle_count + 1 offsets, then the entries.
+// Mirrors adjacency::bond_adjacency.
+
+struct Bond {
+    particle_a: u32,
+    particle_b: u32,
+    rest_length: f32,
+    stiffness: f32,
+    breaking_strain: f32,
+    bond_type: u32,
+    is_active: u32,
+    yield_strain: f32,
+    damage: f32,
+    compressive_breaking_strain: f32,
+    shear_limit: f32,
+    _padding: u32,
+}
+
+// Matches Rust welding::BondAllocator (only the change counters are used here)
+struct BondAllocator {
+    next_free: u32,
+    dropped: u32,
+    edit_count: u32,
+    free_count: u32,
+    free_taken: u32,
+    changes: u32,
+    rebuilt_changes: u32,
+    _padding: u32,
+}
+
+struct DispatchArgs {
+    x: u32,
+    y: u32,
+    z: u32,
+}
+
+@group(0) @binding(0) var<storage, read> bonds: array<Bond>;
+@group(0) @binding(1) var<storage, read_write> adjacency: array<u32>;
+// Write cursors (start at each list's offset, set by the prefix sum)
+@group(0) @binding(2) var<storage, read_write> cursor: array<atomic<u32>>;
+// Bonds per particle
+@group(0) @binding(3) var<storage, read_write> counts: array<atomic<u32>>;
+
+// Group 1 (plan only): the indirect args can't be bound to the passes they dispatch
+@group(1) @binding(0) var<storage, read_write> allocator: BondAllocator;
+// Particle passes, bond slot passes, scan blocks, scan block sums (matches
+// setup::adjacency_dispatch)
+@group(1) @binding(1) var<storage, read_write> dispatch: array<DispatchArgs, 4>;
+
+// Particles per scan block (matches prefix_sum.wgsl)
+const SCAN_BLOCK: u32 = 512u;
+
+fn particle_count() -> u32 {
+    return arrayLength(&counts);
+}
+
+@compute @workgroup_size(1)
+fn plan() {
+    let n = particle_count();
+    var particle_groups = 0u;
+    var bond_groups = 0u;
+    var scan_blocks = 0u;
+    var scan_sums = 0u;
+    if allocator.changes != allocator.rebuilt_changes {
+        allocator.rebuilt_changes = allocator.changes;
+        particle_groups = (n + 63u) / 64u;
+        bond_groups = (arrayLength(&bonds) + 255u) / 256u;
+        scan_blocks = (n + SCAN_BLOCK - 1u) / SCAN_BLOCK;
+        scan_sums = 1u;
+    }
+    dispatch[0] = DispatchArgs(particle_groups, 1u, 1u);
+    dispatch[1] = DispatchArgs(bond_groups, 1u, 1u);
+    dispatch[2] = DispatchArgs(scan_blocks, 1u, 1u);
+    dispatch[3] = DispatchArgs(scan_sums, 1u, 1u);
+}
+
+@compute @workgroup_size(64)
+fn clear(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let idx = global_id.x;
+    if idx < particle_count() {
+        atomicStore(&counts[idx], 0u);
+    }
+}
+
+@compute @workgroup_size(256)
+fn count(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let idx = global_id.x;
+    if idx >= arrayLength(&bonds) {
+        return;
+    }
+    let bond = bonds[idx];
+    let n = particle_count();
+    if bond.rest_length <= 0.0 || bond.particle_a >= n || bond.particle_b >= n {
+        return;
+    }
+    atomicAdd(&counts[bond.particle_a], 1u);
+    atomicAdd(&counts[bond.particle_b], 1u);
+}
+
+@compute @workgroup_size(256)
+fn scatter(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let idx = global_id.x;
+    if idx >= arrayLength(&bonds) {
+        return;
+    }
+    let bond = bonds[idx];
+    let n = particle_count();
+    if bond.rest_length <= 0.0 || bond.particle_a >= n || bond.particle_b >= n {
+        return;
+    }
+    let entries = n + 1u;
+    adjacency[entries + atomicAdd(&cursor[bond.particle_a], 1u)] = idx;
+    adjacency[entries + atomicAdd(&cursor[bond.particle_b], 1u)] = idx;
+}
+
+@compute @workgroup_size(64)
+fn sort(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let idx = global_id.x;
+    let n = particle_count();
+    if idx >= n {
+        return;
+    }
+    // Lists are a handful of bonds long: insertion sort
+    let start = n + 1u + adjacency[idx];
+    let end = n + 1u + adjacency[idx + 1u];
+    for (var i = start + 1u; i < end; i++) {
+        let bond = adjacency[i];
+        var j = i;
+        while j > start && adjacency[j - 1u] > bond {
+            adjacency[j] = adjacency[j - 1u];
+            j--;
+        }
+        adjacency[j] = bond;
+    }
+}
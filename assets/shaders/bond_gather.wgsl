@@ -0,0 +1,97 @@
+// Bond Force Gather
+// One thread per particle. Sums the per-bond forces written by bonds.wgsl (+f on
+// particle_a, -f on particle_b) and the per-triplet forces written by bending.wgsl into
+// ParticleForce.bond, walking the particle's adjacency lists in ascending index order.
+// No atomics: every run adds the same floats in the same order.
+// Adjacency buffers are CSR packed into one array: particle_count + 1 offsets, then the
+// entries (see adjacency::Adjacency::packed).
+// Mirrors adjacency::gather_bond_force.
+
+struct Bond {
+    particle_a: u32,
+    particle_b: u32,
+    rest_length: f32,
+    stiffness: f32,
+    breaking_strain: f32,
+    bond_type: u32,
+    is_active: u32,
+    yield_strain: f32,
+    damage: f32,
+    compressive_breaking_strain: f32,
+    shear_limit: f32,
+    _padding: u32,
+}
+
+// Triplet (matches Rust AngularSpring)
+struct AngularSpring {
+    particle_a: u32,
+    particle_b: u32,
+    particle_c: u32,
+    family: u32,
+    rest_angle: f32,
+    stiffness: f32,
+    break_angle: f32,
+    is_active: u32,
+}
+
+// Forces of one triplet (matches Rust bending::TripletForce)
+struct TripletForce {
+    a: vec2<f32>,
+    b: vec2<f32>,
+    c: vec2<f32>,
+    _padding: vec2<f32>,
+}
+
+struct ParticleForce {
+    fluid: vec2<f32>,
+    bond: vec2<f32>,        // Written here
+    xsph: vec2<f32>,
+    state: vec2<f32>,
+    state_tag: u32,
+    _padding: u32,
+}
+
+@group(0) @binding(0) var<storage, read> bonds: array<Bond>;
+@group(0) @binding(1) var<storage, read> bond_forces: array<vec2<f32>>;
+@group(0) @binding(2) var<storage, read> bond_adjacency: array<u32>;
+@group(0) @binding(3) var<storage, read> springs: array<AngularSpring>;
+@group(0) @binding(4) var<storage, read> triplet_forces: array<TripletForce>;
+@group(0) @binding(5) var<storage, read> triplet_adjacency: array<u32>;
+@group(0) @binding(6) var<storage, read_write> forces: array<ParticleForce>;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let idx = global_id.x;
+    let particle_count = arrayLength(&forces);
+    if idx >= particle_count {
+        return;
+    }
+
+    // Entries start after the particle_count + 1 offsets
+    let entries = particle_count + 1u;
+    var total = vec2<f32>(0.0);
+
+    for (var k = bond_adjacency[idx]; k < bond_adjacency[idx + 1u]; k++) {
+        let bond = bond_adjacency[entries + k];
+        if bonds[bond].particle_a == idx {
+            total += bond_forces[bond];
+        } else {
+            total -= bond_forces[bond];
+        }
+    }
+
+    for (var k = triplet_adjacency[idx]; k < triplet_adjacency[idx + 1u]; k++) {
+        let triplet = triplet_adjacency[entries + k];
+        let spring = springs[triplet];
+        let force = triplet_forces[triplet];
+        if spring.particle_a == idx {
+            total += force.a;
+        } else if spring.particle_b == idx {
+            total += force.b;
+        } else {
+            total += force.c;
+        }
+    }
+
+    forces[idx].bond = total;
+}
@@ -37,6 +37,10 @@ struct Bond {
     breaking_strain: f32,
     bond_type: u32,
     is_active: u32,
+    yield_strain: f32,
+    damage: f32,
+    compressive_breaking_strain: f32,
+    shear_limit: f32,
     _padding: u32,
 }
 
@@ -1,5 +1,13 @@
 // Peridynamics Bond Kernel
-// Computes spring forces between connected particles
+// Computes spring forces between connected particles.
+// Sheets and ropes are tension-only; sheet rest lengths are scaled by sheet_extension.
+// Bonds with a yield strain dent (rest length follows the stretch) and accumulate damage.
+// Bonds break when stretched, crushed (compressive_breaking_strain) or sheared (shear_limit).
+// Every break is appended to the break log ring (read back as BondBroken events), with
+// the particles' original ids (see reorder.wgsl).
+// Writes one float force per bond (on particle_a; particle_b gets the opposite), zero
+// when the bond exerts none; bond_gather.wgsl sums them per particle.
+// Mirrors bonds::step_bond.
 
 struct Particle {
     pos: vec2<f32>,
@@ -21,16 +29,101 @@ struct Bond {
     breaking_strain: f32,
     bond_type: u32,
     is_active: u32,
+    yield_strain: f32,
+    damage: f32,
+    compressive_breaking_strain: f32,
+    shear_limit: f32,
     _padding: u32,
 }
 
-// Fixed-point conversion factor for atomic force accumulation
-// Float force * SCALER = Int force
-const FORCE_SCALER: f32 = 1000.0; 
+// Simulation parameters (`sheet_extension` and `rigid_bodies` are read here)
+struct SimParams {
+    delta_time: f32,
+    gravity: f32,
+    smoothing_radius: f32,
+    target_density_water: f32,
+    target_density_air: f32,
+    wind_interaction_threshold: f32,
+    _reserved: f32,
+    sheet_extension: f32,
+    bounds: vec4<f32>,
+    integrator: u32,
+    pressure_solver: u32,
+    pressure_tolerance: f32,
+    pressure_max_iterations: u32,
+    max_velocity: f32,
+    kernel: u32,
+    boundary_handling: u32,
+    rigid_bodies: u32, // 0 = springs, 1 = shape matching
+    open_world: u32, // 1 = bounds follow the fleet, fluid wraps along x instead of walls
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
+}
+
+// Rigid body membership (matches Rust RigidMember)
+struct RigidMember {
+    body: u32,
+    driven: u32, // 1 = moved by a kinematic joint of its own body
+    rest_offset: vec2<f32>,
+}
+
+// One break (matches Rust bond_events::BondBreakRecord)
+struct BondBreakRecord {
+    bond_index: u32,
+    bond_type: u32,
+    particle_a: u32,
+    particle_b: u32,
+    position: vec2<f32>,
+    strain: f32,
+    cause: u32,
+}
+
+// Ring of breaks (matches Rust BondBreakLogHeader + BOND_BREAK_CAPACITY records)
+struct BondBreakLog {
+    head: atomic<u32>,
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
+    records: array<BondBreakRecord, 256>,
+}
 
 @group(0) @binding(0) var<storage, read_write> particles: array<Particle>;
 @group(0) @binding(1) var<storage, read_write> bonds: array<Bond>;
-@group(0) @binding(2) var<storage, read_write> forces: array<atomic<i32>>; // [x0, y0, x1, y1, ...]
+@group(0) @binding(2) var<storage, read_write> bond_forces: array<vec2<f32>>;
+@group(0) @binding(3) var<uniform> params: SimParams;
+@group(0) @binding(4) var<storage, read> members: array<RigidMember>;
+@group(0) @binding(5) var<storage, read_write> break_log: BondBreakLog;
+@group(0) @binding(6) var<storage, read> particle_ids: array<u32>;  // Slot -> original id
+
+const NO_BODY: u32 = 0xFFFFFFFFu;
+const RIGID_SHAPE_MATCHING: u32 = 1u;
+
+// Bond types (matches Rust resources::bond_type)
+const BOND_SHEET: u32 = 2u;
+const BOND_ROPE: u32 = 4u;
+
+// Spring clamp and damping (matches Rust bonds::SPRING_FORCE_LIMIT, BOND_DAMPING)
+const SPRING_FORCE_LIMIT: f32 = 2000000.0;
+const BOND_DAMPING: f32 = 200.0;
+
+// Break causes (matches Rust bond_events::BreakCause)
+const CAUSE_STRETCHED: u32 = 0u;
+const CAUSE_CRUSHED: u32 = 1u;
+const CAUSE_SHEARED: u32 = 2u;
+const CAUSE_WORN: u32 = 3u;
+const BREAK_LOG_CAPACITY: u32 = 256u;
+
+fn record_break(idx: u32, bond: Bond, midpoint: vec2<f32>, strain: f32, cause: u32) {
+    let slot = atomicAdd(&break_log.head, 1u) % BREAK_LOG_CAPACITY;
+    break_log.records[slot] = BondBreakRecord(
+        idx, bond.bond_type, particle_ids[bond.particle_a], particle_ids[bond.particle_b], midpoint, strain, cause
+    );
+}
+
+// Fatigue (matches Rust bonds::FATIGUE_RATE, ENDURANCE_FRACTION)
+const FATIGUE_RATE: f32 = 0.05;
+const ENDURANCE_FRACTION: f32 = 0.5;
 
 @compute @workgroup_size(256)
 fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
@@ -40,6 +133,7 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     if idx >= bond_count {
         return;
     }
+    bond_forces[idx] = vec2<f32>(0.0);
 
     var bond = bonds[idx];
 
@@ -48,6 +142,13 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
         return;
     }
 
+    // Shape matching keeps rigid bodies together; their internal springs are redundant
+    let body_a = members[bond.particle_a].body;
+    if params.rigid_bodies == RIGID_SHAPE_MATCHING && body_a != NO_BODY
+        && body_a == members[bond.particle_b].body {
+        return;
+    }
+
     let pA = particles[bond.particle_a];
     let pB = particles[bond.particle_b];
 
@@ -59,49 +160,92 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
         return;
     }
 
+    // Sheets are trimmed by scaling their rest length
+    let is_sheet = bond.bond_type == BOND_SHEET;
+    let extension = select(1.0, params.sheet_extension, is_sheet);
+    var rest_length = bond.rest_length * extension;
+
     // Calculate Strain
-    let strain = (dist - bond.rest_length) / bond.rest_length;
+    let strain = (dist - rest_length) / rest_length;
 
-    // Check Fracture
-    if strain > bond.breaking_strain {
+    let tension_only = is_sheet || bond.bond_type == BOND_ROPE;
+    let vel_diff = pB.vel - pA.vel;
+    let force_dir = diff / dist;
+    
+    // Project relative velocity along spring direction
+    let rel_vel_along_spring = dot(vel_diff, force_dir);
+    let transverse_speed = length(vel_diff - force_dir * rel_vel_along_spring);
+
+    // Check Fracture (damage lowers the breaking strains; tension-only bonds can't be crushed)
+    let intact = max(1.0 - bond.damage, 0.0);
+    let stretched = strain > bond.breaking_strain * intact || bond.damage >= 1.0;
+    let crushed = bond.compressive_breaking_strain > 0.0 && !tension_only
+        && -strain > bond.compressive_breaking_strain * intact;
+    let sheared = bond.shear_limit > 0.0 && transverse_speed > bond.shear_limit;
+    let midpoint = 0.5 * (pA.pos + pB.pos);
+    if stretched || crushed || sheared {
         bond.is_active = 0u;
         bonds[idx] = bond;
+        var cause = CAUSE_STRETCHED;
+        if bond.damage >= 1.0 {
+            cause = CAUSE_WORN;
+        } else if crushed {
+            cause = CAUSE_CRUSHED;
+        } else if sheared {
+            cause = CAUSE_SHEARED;
+        }
+        record_break(idx, bond, midpoint, strain, cause);
+        return;
+    }
+
+    // Plasticity and fatigue
+    if bond.yield_strain > 0.0 {
+        // Strain cycled above the endurance limit wears the bond
+        if abs(strain) > ENDURANCE_FRACTION * bond.yield_strain {
+            let strain_change = abs(rel_vel_along_spring * params.delta_time) / rest_length;
+            bond.damage += FATIGUE_RATE * strain_change;
+        }
+
+        // Past the yield point the rest length follows the stretch (tension-only
+        // bonds are slack in compression and cannot yield there)
+        let excess = abs(strain) - bond.yield_strain;
+        if excess > 0.0 && !(tension_only && strain < 0.0) {
+            rest_length = dist / (1.0 + bond.yield_strain * sign(strain));
+            bond.rest_length = rest_length / extension;
+            bond.damage += excess / bond.breaking_strain;
+        }
+        bond.damage = min(bond.damage, 1.0);
+
+        if bond.damage >= 1.0 {
+            bond.is_active = 0u;
+        }
+        bonds[idx] = bond;
+        if bond.is_active == 0u {
+            record_break(idx, bond, midpoint, strain, CAUSE_WORN);
+            return;
+        }
+    }
+
+    // Tension-only: a slack sheet lets the sail luff, a slack rope sags
+    if tension_only && dist <= rest_length {
         return;
     }
 
     // Calculate Force Modulus (Hooke's Law with Damping)
     // Spring force: F = k * (dist - rest)
     // Damping force: F_d = -c * v_relative_along_spring
-
-    let vel_diff = pB.vel - pA.vel;
-    let force_dir = diff / dist;
-    
-    // Project relative velocity along spring direction
-    let rel_vel_along_spring = dot(vel_diff, force_dir);
     
     // Spring force (limited to prevent extreme values)
-    let spring_force_raw = bond.stiffness * (dist - bond.rest_length);
-    let spring_force = clamp(spring_force_raw, -2000000.0, 2000000.0);
+    let spring_force_raw = bond.stiffness * (dist - rest_length);
+    let spring_force = clamp(spring_force_raw, -SPRING_FORCE_LIMIT, SPRING_FORCE_LIMIT);
     
     // Damping force (proportional to relative velocity along spring)
-    let damping_coeff = 200.0; // Damping coefficient (was 50)
-    let damping_force = damping_coeff * rel_vel_along_spring;
+    let damping_force = BOND_DAMPING * rel_vel_along_spring;
     
     // Total force (spring + damping)
     let total_force = spring_force + damping_force;
     let force_vec = force_dir * total_force;
 
-    // Atomic Accumulation with clamping to prevent overflow
-    // Scaled by 1000, so 2M becomes 2 billion (fits in i32)
-    let force_clamped = clamp(force_vec, vec2<f32>(-2000000.0), vec2<f32>(2000000.0));
-    let fx_int = i32(force_clamped.x * FORCE_SCALER);
-    let fy_int = i32(force_clamped.y * FORCE_SCALER);
-
-    // Add to A
-    atomicAdd(&forces[bond.particle_a * 2u], fx_int);
-    atomicAdd(&forces[bond.particle_a * 2u + 1u], fy_int);
-
-    // Subtract from B (Add negative)
-    atomicAdd(&forces[bond.particle_b * 2u], -fx_int);
-    atomicAdd(&forces[bond.particle_b * 2u + 1u], -fy_int);
+    // Force on A (B gets the opposite when gathered)
+    bond_forces[idx] = clamp(force_vec, vec2<f32>(-SPRING_FORCE_LIMIT), vec2<f32>(SPRING_FORCE_LIMIT));
 }
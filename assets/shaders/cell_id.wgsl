@@ -19,6 +19,7 @@ struct GridParams {
     grid_height: u32,     // Number of cells in Y
     grid_origin_x: f32,   // World X of grid origin (left edge)
     grid_origin_y: f32,   // World Y of grid origin (bottom edge)
+    wrap: u32,            // 1 = the grid wraps along x (open world)
     _padding: vec3<f32>,
 }
 
@@ -1,5 +1,10 @@
 // Position-Based Collision Constraints
 // Prevents particles from penetrating too deeply by direct position correction
+// Ping-pong: reads the previous state from `particles`, writes every particle's next
+// state to `particles_next` (see ping_pong.rs), so corrections in one iteration never
+// see each other and the result does not depend on invocation order.
+
+#import "shaders/sph_kernels.wgsl"::{kernel_support}
 
 struct Particle {
     pos: vec2<f32>,
@@ -19,6 +24,7 @@ struct GridParams {
     grid_height: u32,
     grid_origin_x: f32,
     grid_origin_y: f32,
+    wrap: u32,
     _padding: vec3<f32>,
 }
 
@@ -29,24 +35,64 @@ struct SimParams {
     target_density_water: f32,
     target_density_air: f32,
     wind_interaction_threshold: f32,
-    rudder_angle: f32,
+    _reserved: f32,
     sheet_extension: f32,
     bounds: vec4<f32>,
-    _padding: vec4<f32>,
+    integrator: u32,    // 0=Symplectic Euler, 1=Velocity Verlet, 2=Leapfrog
+    pressure_solver: u32,         // 0=Tait WCSPH, 1=PCISPH
+    pressure_tolerance: f32,      // PCISPH max relative density error
+    pressure_max_iterations: u32, // PCISPH iteration budget
+    max_velocity: f32, // Post-integration speed clamp (0 = off)
+    kernel: u32, // SPH kernel (see SphKernel)
+    boundary_handling: u32, // 0 = repulsion, 1 = Akinci
+    rigid_bodies: u32, // 0 = springs, 1 = shape matching
+    open_world: u32, // 1 = bounds follow the fleet, fluid wraps along x instead of walls
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
 }
 
-@group(0) @binding(0) var<storage, read_write> particles: array<Particle>;
+@group(0) @binding(0) var<storage, read> particles: array<Particle>;  // Previous state
 @group(0) @binding(1) var<storage, read> indices: array<u32>;
 @group(0) @binding(2) var<storage, read> cell_offsets: array<u32>;
 @group(0) @binding(3) var<uniform> grid: GridParams;
 @group(0) @binding(4) var<uniform> params: SimParams;
+@group(0) @binding(6) var<storage, read_write> particles_next: array<Particle>;  // Next state
 
-// Minimum distance fraction of smoothing radius
-const PBD_RADIUS_FACTOR: f32 = 0.5; 
+// Contact distance as a fraction of the kernel support radius (0.25 * 2h = 0.5h)
+const PBD_RADIUS_FACTOR: f32 = 0.25;
 // How much of the overlap to correct per step (0.0 - 1.0)
 // Lower values are more stable but "squishier". 
 const STIFFNESS: f32 = 0.8; 
 
+const NO_CELL: u32 = 0xFFFFFFFFu;
+
+// Cell (x, y) of a 3x3 neighbourhood, or NO_CELL off the grid. A wrapping grid (the open
+// world) continues on the opposite side along x only. Mirrors
+// reference::NeighbourGrid::neighbours.
+fn neighbor_cell(x_in: i32, y: i32) -> u32 {
+    let width = i32(grid.grid_width);
+    let height = i32(grid.grid_height);
+    var x = x_in;
+    if grid.wrap != 0u {
+        x = (x + width) % width;
+    }
+    if x < 0 || y < 0 || x >= width || y >= height {
+        return NO_CELL;
+    }
+    return u32(y) * grid.grid_width + u32(x);
+}
+
+// b - a, the short way round a wrapping grid along x (mirrors GridParams::offset)
+fn grid_offset(a: vec2<f32>, b: vec2<f32>) -> vec2<f32> {
+    var d = b - a;
+    if grid.wrap != 0u {
+        let width = f32(grid.grid_width) * grid.cell_size;
+        d.x -= round(d.x / width) * width;
+    }
+    return d;
+}
+
 @compute @workgroup_size(64)
 fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     let idx = global_id.x;
@@ -58,13 +104,14 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
 
     var p = particles[idx];
     
-    // Skip static particles (infinite mass)
+    // Skip static particles (infinite mass); every particle is still copied to the next state
     if p.mass > 10000.0 {
+        particles_next[idx] = p;
         return;
     }
 
     let h = params.smoothing_radius;
-    let collision_dist = h * PBD_RADIUS_FACTOR;
+    let collision_dist = kernel_support(h) * PBD_RADIUS_FACTOR;
     let cell_x = i32(p.cell_id % grid.grid_width);
     let cell_y = i32(p.cell_id / grid.grid_width);
 
@@ -74,14 +121,10 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     // Iterate neighbors
     for (var dy = -1; dy <= 1; dy++) {
         for (var dx = -1; dx <= 1; dx++) {
-            let nx = cell_x + dx;
-            let ny = cell_y + dy;
-
-            if nx < 0 || ny < 0 || u32(nx) >= grid.grid_width || u32(ny) >= grid.grid_height {
+            let neighbor_cell_id = neighbor_cell(cell_x + dx, cell_y + dy);
+            if neighbor_cell_id == NO_CELL {
                 continue;
             }
-
-            let neighbor_cell_id = u32(ny) * grid.grid_width + u32(nx);
             let cell_start = cell_offsets[neighbor_cell_id];
             let cell_end = cell_offsets[neighbor_cell_id + 1u];
 
@@ -96,7 +139,7 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
                     continue;
                 }
 
-                let diff = p.pos - neighbor.pos;
+                let diff = grid_offset(neighbor.pos, p.pos);
                 let dist = length(diff);
 
                 if dist < 0.001 || dist >= collision_dist {
@@ -131,8 +174,7 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
         } else {
             p.pos += move_vec;
         }
-
-        // Write back
-        particles[idx] = p;
     }
+
+    particles_next[idx] = p;
 }
@@ -19,6 +19,7 @@ struct GridParams {
     grid_height: u32,
     grid_origin_x: f32,
     grid_origin_y: f32,
+    wrap: u32,
     _padding: vec3<f32>,
 }
 
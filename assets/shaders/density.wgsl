@@ -1,5 +1,10 @@
 // SPH Density Kernel (Grid-Based)
-// Computes density for each particle using grid-accelerated neighbor search
+// Computes density for each particle using grid-accelerated neighbor search, and the
+// Tait pressure forces.wgsl reads for it and its neighbours.
+// Ping-pong: reads the previous state from `particles`, writes the next state to
+// `particles_next` (see ping_pong.rs), so no neighbour is read while it is written.
+
+#import "shaders/sph_kernels.wgsl"::{kernel_w}
 
 struct Particle {
     pos: vec2<f32>,
@@ -19,6 +24,7 @@ struct GridParams {
     grid_height: u32,
     grid_origin_x: f32,
     grid_origin_y: f32,
+    wrap: u32,
     _padding: vec3<f32>,
 }
 
@@ -29,36 +35,130 @@ struct SimParams {
     target_density_water: f32,
     target_density_air: f32,
     wind_interaction_threshold: f32,
-    rudder_angle: f32,
+    _reserved: f32,
     sheet_extension: f32,
     bounds: vec4<f32>,
-    _padding: vec4<f32>,
+    integrator: u32,    // 0=Symplectic Euler, 1=Velocity Verlet, 2=Leapfrog
+    pressure_solver: u32,         // 0=Tait WCSPH, 1=PCISPH
+    pressure_tolerance: f32,      // PCISPH max relative density error
+    pressure_max_iterations: u32, // PCISPH iteration budget
+    max_velocity: f32, // Post-integration speed clamp (0 = off)
+    kernel: u32, // SPH kernel (see SphKernel)
+    boundary_handling: u32, // 0 = repulsion, 1 = Akinci
+    rigid_bodies: u32, // 0 = springs, 1 = shape matching
+    open_world: u32, // 1 = bounds follow the fleet, fluid wraps along x instead of walls
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
+}
+
+// Per-material-pair interaction profile (matches Rust InteractionProfile)
+struct InteractionProfile {
+    repulsion_strength: f32,
+    repulsion_radius: f32,
+    repulsion_ramp: u32,  // 0=Linear, 1=Quadratic
+    _padding: u32,
+}
+
+// Full interaction table (matches Rust InteractionTable); only the pressure terms are used here
+struct InteractionTable {
+    profiles: array<InteractionProfile, 25>,
+    sph_viscosity: f32,
+    sph_pressure_stiffness: f32,
+    sph_close_repulsion: f32,
+    xsph_epsilon: f32,
+    velocity_damping: f32,
+    pressure_cap: f32,
+    _padding: vec2<f32>,
 }
 
-@group(0) @binding(0) var<storage, read_write> particles: array<Particle>;
+// Tait EOS exponent (matches forces.wgsl)
+const PRESSURE_GAMMA: f32 = 7.0;
+
+@group(0) @binding(0) var<storage, read> particles: array<Particle>;  // Previous state
 @group(0) @binding(1) var<storage, read> indices: array<u32>;
 @group(0) @binding(2) var<storage, read> cell_offsets: array<u32>;  // From counting sort: offset[i] = start of cell i
 @group(0) @binding(3) var<uniform> grid: GridParams;
 @group(0) @binding(4) var<uniform> params: SimParams;
+@group(0) @binding(5) var<storage, read_write> boundary_volumes: array<f32>;  // Akinci V_b (solids only)
+@group(0) @binding(6) var<storage, read_write> particles_next: array<Particle>;  // Next state
+@group(0) @binding(7) var<uniform> interactions: InteractionTable;
+
+const BOUNDARY_AKINCI: u32 = 1u;
 
-fn wendland_c2_kernel(r_sq: f32, h: f32) -> f32 {
-    // Wendland C2 has support radius 2h, so q ∈ [0, 2]
-    let r = sqrt(r_sq);
-    let q = r / h;
+fn is_solid_mask(layer_mask: u32) -> bool {
+    return (layer_mask & (4u | 8u | 16u)) != 0u;
+}
 
-    if q >= 2.0 {
-        return 0.0;
+const NO_CELL: u32 = 0xFFFFFFFFu;
+
+// Cell (x, y) of a 3x3 neighbourhood, or NO_CELL off the grid. A wrapping grid (the open
+// world) continues on the opposite side along x only. Mirrors
+// reference::NeighbourGrid::neighbours.
+fn neighbor_cell(x_in: i32, y: i32) -> u32 {
+    let width = i32(grid.grid_width);
+    let height = i32(grid.grid_height);
+    var x = x_in;
+    if grid.wrap != 0u {
+        x = (x + width) % width;
     }
-    
-    // 2D Wendland C2: 7 / (4πh^2) * (1 - q/2)^4 * (2q + 1)
-    let one_minus_q_half = 1.0 - q * 0.5;
-    let term2 = one_minus_q_half * one_minus_q_half;
-    let term4 = term2 * term2;
+    if x < 0 || y < 0 || x >= width || y >= height {
+        return NO_CELL;
+    }
+    return u32(y) * grid.grid_width + u32(x);
+}
+
+// b - a, the short way round a wrapping grid along x (mirrors GridParams::offset)
+fn grid_offset(a: vec2<f32>, b: vec2<f32>) -> vec2<f32> {
+    var d = b - a;
+    if grid.wrap != 0u {
+        let width = f32(grid.grid_width) * grid.cell_size;
+        d.x -= round(d.x / width) * width;
+    }
+    return d;
+}
+
+// ==================== BOUNDARY VOLUME ====================
+// Akinci et al. 2012: V_b = 1 / Σ_k W(x_b - x_k) over solid neighbours at the same z level.
+// Runs before `main` so fluids can use Ψ_b = ρ0 · V_b as boundary pseudo-mass.
+@compute @workgroup_size(64)
+fn boundary_volume(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let idx = global_id.x;
+    if idx >= arrayLength(&particles) {
+        return;
+    }
+
+    let p = particles[idx];
+    if !is_solid_mask(p.layer_mask) {
+        boundary_volumes[idx] = 0.0;
+        return;
+    }
+
+    let h = params.smoothing_radius;
+    let cell_x = i32(p.cell_id % grid.grid_width);
+    let cell_y = i32(p.cell_id / grid.grid_width);
+    var kernel_sum = 0.0;
+
+    for (var dy = -1; dy <= 1; dy++) {
+        for (var dx = -1; dx <= 1; dx++) {
+            let neighbor_cell_id = neighbor_cell(cell_x + dx, cell_y + dy);
+            if neighbor_cell_id == NO_CELL {
+                continue;
+            }
+            let cell_start = cell_offsets[neighbor_cell_id];
+            let cell_end = cell_offsets[neighbor_cell_id + 1u];
 
-    let h_sq = h * h;
-    let coeff = 7.0 / (4.0 * 3.14159265359 * h_sq);
+            for (var j = cell_start; j < cell_end; j++) {
+                let neighbor = particles[indices[j]];
+                if !is_solid_mask(neighbor.layer_mask) || abs(p.z_height - neighbor.z_height) > 0.5 {
+                    continue;
+                }
+                kernel_sum += kernel_w(params.kernel, length(grid_offset(neighbor.pos, p.pos)), h);
+            }
+        }
+    }
 
-    return coeff * term4 * (2.0 * q + 1.0);
+    boundary_volumes[idx] = 1.0 / max(kernel_sum, 1e-6);
 }
 
 @compute @workgroup_size(64)
@@ -72,6 +172,8 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
 
     var p = particles[idx];
     let h = params.smoothing_radius;
+    let akinci_boundary = params.boundary_handling == BOUNDARY_AKINCI && !is_solid_mask(p.layer_mask);
+    let rest_density = select(params.target_density_air, params.target_density_water, (p.layer_mask & 1u) != 0u);
     
     // Use stored cell_id to derive cell coordinates (avoids floating point precision mismatches)
     // cell_id = cell_y * grid_width + cell_x, so we reverse it
@@ -83,15 +185,10 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     // Iterate over 3x3 neighborhood of cells
     for (var dy = -1; dy <= 1; dy++) {
         for (var dx = -1; dx <= 1; dx++) {
-            let nx = cell_x + dx;
-            let ny = cell_y + dy;
-            
-            // Skip out-of-bounds cells
-            if nx < 0 || ny < 0 || u32(nx) >= grid.grid_width || u32(ny) >= grid.grid_height {
+            let neighbor_cell_id = neighbor_cell(cell_x + dx, cell_y + dy);
+            if neighbor_cell_id == NO_CELL {
                 continue;
             }
-
-            let neighbor_cell_id = u32(ny) * grid.grid_width + u32(nx);
             
             // Counting sort layout: cell_offsets[i] = start, cell_offsets[i+1] = end
             let cell_start = cell_offsets[neighbor_cell_id];
@@ -107,7 +204,7 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
                 let neighbor_idx = indices[j];
                 let neighbor = particles[neighbor_idx];
 
-                let diff = p.pos - neighbor.pos;
+                let diff = grid_offset(neighbor.pos, p.pos);
                 let r_sq = dot(diff, diff);
                 
                 // Z-HEIGHT CHECK: Skip density contribution from particles at different z levels
@@ -135,8 +232,13 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
                     continue;
                 }
 
-                // Accumulate density contribution
-                density += neighbor.mass * wendland_c2_kernel(r_sq, h);
+                // Accumulate density contribution. Under Akinci, solids count as boundary
+                // pseudo-mass Ψ_b = ρ0 · V_b of the fluid rather than their own mass.
+                var contribution_mass = neighbor.mass;
+                if akinci_boundary && neighbor_is_solid {
+                    contribution_mass = rest_density * boundary_volumes[neighbor_idx];
+                }
+                density += contribution_mass * kernel_w(params.kernel, sqrt(r_sq), h);
             }
         }
     }
@@ -144,5 +246,9 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     // Ensure minimum density to prevent division by zero / force explosion
     p.density = max(density, 0.1);
 
-    particles[idx] = p;
+    // Pressure from Tait EOS with caps (PCISPH replaces the water pressure after forces)
+    let raw_pressure = interactions.sph_pressure_stiffness * (pow(p.density / rest_density, PRESSURE_GAMMA) - 1.0);
+    p.pressure = clamp(raw_pressure, 0.0, interactions.pressure_cap);
+
+    particles_next[idx] = p;
 }
@@ -1,5 +1,11 @@
 // SPH Forces Kernel (Grid-Based)
-// Computes pressure force and viscosity using grid-accelerated neighbor search
+// Computes pressure force and viscosity using grid-accelerated neighbor search.
+// Writes the total fluid force into the force buffer; velocity is only changed by
+// the integrator (physics.wgsl), so every force source sees the same velocities.
+// Particles are read-only here: the pressure comes from density.wgsl, so no invocation
+// writes what its neighbours read.
+
+#import "shaders/sph_kernels.wgsl"::{kernel_support, kernel_w, kernel_gradient, kernel_laplacian}
 
 // ==================== TUNABLE PARAMETERS ====================
 // Pressure (Tait EOS)
@@ -48,6 +54,7 @@ struct GridParams {
     grid_height: u32,
     grid_origin_x: f32,
     grid_origin_y: f32,
+    wrap: u32,
     _padding: vec3<f32>,
 }
 
@@ -58,10 +65,21 @@ struct SimParams {
     target_density_water: f32,
     target_density_air: f32,
     wind_interaction_threshold: f32,
-    rudder_angle: f32,
+    _reserved: f32,
     sheet_extension: f32,
     bounds: vec4<f32>,
-    _padding: vec4<f32>,
+    integrator: u32,    // 0=Symplectic Euler, 1=Velocity Verlet, 2=Leapfrog
+    pressure_solver: u32,         // 0=Tait WCSPH, 1=PCISPH
+    pressure_tolerance: f32,      // PCISPH max relative density error
+    pressure_max_iterations: u32, // PCISPH iteration budget
+    max_velocity: f32, // Post-integration speed clamp (0 = off)
+    kernel: u32, // SPH kernel (see SphKernel)
+    boundary_handling: u32, // 0 = repulsion, 1 = Akinci
+    rigid_bodies: u32, // 0 = springs, 1 = shape matching
+    open_world: u32, // 1 = bounds follow the fleet, fluid wraps along x instead of walls
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
 }
 
 // Per-material-pair interaction profile (matches Rust InteractionProfile)
@@ -86,12 +104,26 @@ struct InteractionTable {
     _padding: vec2<f32>,
 }
 
-@group(0) @binding(0) var<storage, read_write> particles: array<Particle>;
+// Per-particle force accumulator (matches Rust ParticleForce)
+struct ParticleForce {
+    fluid: vec2<f32>,       // Written here: SPH + repulsion + aerodynamics
+    bond: vec2<f32>,        // Bond + bending forces (bond_gather.wgsl)
+    xsph: vec2<f32>,        // Written here: XSPH velocity correction
+    state: vec2<f32>,       // Integrator history
+    state_tag: u32,
+    _padding: u32,
+}
+
+@group(0) @binding(0) var<storage, read> particles: array<Particle>;
 @group(0) @binding(1) var<storage, read> indices: array<u32>;
 @group(0) @binding(2) var<storage, read> cell_offsets: array<u32>;  // From counting sort: offset[i] = start of cell i
 @group(0) @binding(3) var<uniform> grid: GridParams;
 @group(0) @binding(4) var<uniform> params: SimParams;
 @group(0) @binding(5) var<uniform> interactions: InteractionTable;
+@group(0) @binding(6) var<storage, read_write> forces: array<ParticleForce>;
+@group(0) @binding(7) var<storage, read> boundary_volumes: array<f32>;  // Akinci V_b (density.wgsl)
+
+const BOUNDARY_AKINCI: u32 = 1u;
 
 // Get material type index from layer_mask (for interaction table lookup)
 fn get_type_index(layer_mask: u32) -> u32 {
@@ -108,49 +140,32 @@ fn compute_pressure(density: f32, target_density: f32) -> f32 {
     return PRESSURE_STIFFNESS * (pow(ratio, PRESSURE_GAMMA) - 1.0);
 }
 
-// Poly6 kernel for XSPH smoothing (2D version)
-fn poly6_kernel(r_sq: f32, h: f32) -> f32 {
-    let h_sq = h * h;
-    if r_sq >= h_sq {
-        return 0.0;
+const NO_CELL: u32 = 0xFFFFFFFFu;
+
+// Cell (x, y) of a 3x3 neighbourhood, or NO_CELL off the grid. A wrapping grid (the open
+// world) continues on the opposite side along x only. Mirrors
+// reference::NeighbourGrid::neighbours.
+fn neighbor_cell(x_in: i32, y: i32) -> u32 {
+    let width = i32(grid.grid_width);
+    let height = i32(grid.grid_height);
+    var x = x_in;
+    if grid.wrap != 0u {
+        x = (x + width) % width;
     }
-    let diff = h_sq - r_sq;
-    // 2D poly6: 4 / (π * h^8) * (h² - r²)³
-    let h8 = h_sq * h_sq * h_sq * h_sq;
-    let coeff = 4.0 / (3.14159265359 * h8);
-    return coeff * diff * diff * diff;
-}
-
-fn wendland_c2_gradient(r: vec2<f32>, r_len: f32, h: f32) -> vec2<f32> {
-    if r_len >= h || r_len < 0.01 {
-        return vec2<f32>(0.0, 0.0);
+    if x < 0 || y < 0 || x >= width || y >= height {
+        return NO_CELL;
     }
-
-    let q = r_len / h;
-    
-    // 2D Wendland C2: W(q) = 7/(πh²) * (1-q)⁴ * (1+4q)
-    // Gradient: ∇W = -140q/(πh³) * (1-q)³ * r̂
-    // Simplified: coeff * (1-q)³ * q * r̂
-
-    let one_minus_q = 1.0 - q;
-    let term3 = one_minus_q * one_minus_q * one_minus_q;
-
-    let h3 = h * h * h;
-    let coeff = -140.0 / (3.14159265359 * h3);
-    
-    // r̂ = r / r_len (unit vector)
-    return coeff * term3 * q * normalize(r);
+    return u32(y) * grid.grid_width + u32(x);
 }
 
-// Viscosity kernel laplacian (2D version)
-fn viscosity_laplacian(r_len: f32, h: f32) -> f32 {
-    if r_len >= h {
-        return 0.0;
+// b - a, the short way round a wrapping grid along x (mirrors GridParams::offset)
+fn grid_offset(a: vec2<f32>, b: vec2<f32>) -> vec2<f32> {
+    var d = b - a;
+    if grid.wrap != 0u {
+        let width = f32(grid.grid_width) * grid.cell_size;
+        d.x -= round(d.x / width) * width;
     }
-    // 2D viscosity laplacian: 40 / (π * h^5) * (h - r)
-    let h5 = h * h * h * h * h;
-    let coeff = 40.0 / (3.14159265359 * h5);
-    return coeff * (h - r_len);
+    return d;
 }
 
 @compute @workgroup_size(64)
@@ -162,17 +177,18 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
         return;
     }
 
-    var p = particles[idx];
+    let p = particles[idx];
     let h = params.smoothing_radius;
+    let support = kernel_support(h);
     
     // Determine target density based on particle type
     let is_water = (p.layer_mask & 1u) != 0u;
     let target_density = select(params.target_density_air, params.target_density_water, is_water);
     
-    // Compute pressure from Tait EOS with caps (inlined to use interaction table)
-    let pressure_ratio = p.density / target_density;
-    let raw_pressure = interactions.sph_pressure_stiffness * (pow(pressure_ratio, PRESSURE_GAMMA) - 1.0);
-    p.pressure = clamp(raw_pressure, 0.0, interactions.pressure_cap);
+    // Pressure (Tait EOS with caps) was computed by density.wgsl with the density
+
+    // Under PCISPH, water pressure is solved iteratively by pcisph.wgsl after this pass
+    let pcisph_pressure = params.pressure_solver == 1u && is_water;
     
     // Use stored cell_id to derive cell coordinates (avoids floating point precision mismatches)
     let cell_x = i32(p.cell_id % grid.grid_width);
@@ -186,14 +202,10 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     // Iterate over 3x3 neighborhood of cells
     for (var dy = -1; dy <= 1; dy++) {
         for (var dx = -1; dx <= 1; dx++) {
-            let nx = cell_x + dx;
-            let ny = cell_y + dy;
-
-            if nx < 0 || ny < 0 || u32(nx) >= grid.grid_width || u32(ny) >= grid.grid_height {
+            let neighbor_cell_id = neighbor_cell(cell_x + dx, cell_y + dy);
+            if neighbor_cell_id == NO_CELL {
                 continue;
             }
-
-            let neighbor_cell_id = u32(ny) * grid.grid_width + u32(nx);
             
             // Counting sort layout: cell_offsets[i] = start, cell_offsets[i+1] = end
             let cell_start = cell_offsets[neighbor_cell_id];
@@ -212,10 +224,10 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
                 }
 
                 let neighbor = particles[neighbor_idx];
-                let r = p.pos - neighbor.pos;
+                let r = grid_offset(neighbor.pos, p.pos);
                 let r_len = length(r);
 
-                if r_len >= h || r_len < MIN_DISTANCE {
+                if r_len >= support || r_len < MIN_DISTANCE {
                     continue;
                 }
                 
@@ -267,9 +279,36 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
                     continue;
                 }
                 
+                // ==================== AKINCI BOUNDARY PARTICLES ====================
+                // Solids act as boundary pseudo-mass Ψ_b = ρ0 · V_b of the fluid (Akinci 2012).
+                // Fluid side: a_i = -Ψ_b p_i/ρ_i² ∇W + viscosity; the solid side gathers the
+                // equal and opposite force. Forces here are force densities (divided by ρ below),
+                // so the solid side is scaled by ρ_b / m_b.
+                if is_fluid != neighbor_is_fluid && params.boundary_handling == BOUNDARY_AKINCI {
+                    let grad = kernel_gradient(params.kernel, r, r_len, h);
+                    let lap = kernel_laplacian(params.kernel, r_len, h);
+                    let vel_diff = neighbor.vel - p.vel;
+                    if is_fluid {
+                        let volume = boundary_volumes[neighbor_idx];
+                        pressure_force -= target_density * volume * (p.pressure / p.density) * grad;
+                        viscosity_force += interactions.sph_viscosity * volume * vel_diff * lap;
+                    } else {
+                        let fluid_rest_density = select(params.target_density_air, params.target_density_water, neighbor_is_water);
+                        let volume = boundary_volumes[idx];
+                        let to_density = p.density / p.mass;
+                        let fluid_accel_per_mass = neighbor.mass / (neighbor.density * neighbor.density);
+                        pressure_force -= to_density * fluid_rest_density * volume * neighbor.pressure * fluid_accel_per_mass * grad;
+                        viscosity_force += to_density * (neighbor.mass / neighbor.density)
+                            * interactions.sph_viscosity * volume * vel_diff * lap;
+                    }
+                    continue;
+                }
+                // =====================================================================
+
                 // ==================== SOFT-SPHERE SOLID-FLUID REPULSION ====================
-                // Prevents fluid from penetrating solids at the same z level
-                // Uses per-material-pair profiles from InteractionTable
+                // Fallback coupling (BoundaryHandling::Repulsion): prevents fluid from
+                // penetrating solids at the same z level using per-material-pair profiles
+                // from InteractionTable
                 if is_fluid != neighbor_is_fluid {
                     // Look up interaction profile for this material pair
                     let my_type = get_type_index(p.layer_mask);
@@ -298,8 +337,10 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
 
                 // Pressure force (symmetric formulation) - repulsion
                 // Only applies to same-type pairs (water↔water, air↔air, hull↔hull, sail↔sail)
-                let pressure_term = (p.pressure + neighbor.pressure) / (2.0 * neighbor.density);
-                pressure_force -= neighbor.mass * pressure_term * wendland_c2_gradient(r, r_len, h);
+                if !pcisph_pressure {
+                    let pressure_term = (p.pressure + neighbor.pressure) / (2.0 * neighbor.density);
+                    pressure_force -= neighbor.mass * pressure_term * kernel_gradient(params.kernel, r, r_len, h);
+                }
                 
                 // Close-range repulsion to prevent clumping (tensile correction)
                 let close_threshold = h * CLOSE_RANGE;
@@ -310,34 +351,29 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
                 
                 // Viscosity force
                 let vel_diff = neighbor.vel - p.vel;
-                viscosity_force += interactions.sph_viscosity * neighbor.mass * (vel_diff / neighbor.density) * viscosity_laplacian(r_len, h);
+                viscosity_force += interactions.sph_viscosity * neighbor.mass * (vel_diff / neighbor.density) * kernel_laplacian(params.kernel, r_len, h);
                 
                 // XSPH velocity smoothing - averages velocity with neighbors OF THE SAME TYPE
                 if same_layer {
                     let avg_density = (p.density + neighbor.density) * 0.5;
-                    let w = poly6_kernel(r_len * r_len, h);
+                    let w = kernel_w(params.kernel, r_len, h);
                     xsph_correction += (neighbor.mass / avg_density) * vel_diff * w;
                 }
             }
         }
     }
     
-    // Apply forces to velocity with safety clamp
+    // Convert to acceleration (SPH force density / density) with safety clamp
     let total_force = pressure_force + viscosity_force;
-    let acceleration = total_force / p.density;
+    var acceleration = total_force / p.density;
     
     // Clamp acceleration magnitude to prevent explosions
     let max_accel = 50000.0; // Cap acceleration
     let accel_len = length(acceleration);
     if accel_len > max_accel {
-        p.vel += (acceleration / accel_len) * max_accel * params.delta_time;
-    } else {
-        p.vel += acceleration * params.delta_time;
+        acceleration = (acceleration / accel_len) * max_accel;
     }
     
-    // Apply XSPH smoothing to reduce oscillation
-    p.vel += interactions.xsph_epsilon * xsph_correction;
-    
     // ==================== SAIL AERODYNAMICS ====================
     let is_sail = (p.layer_mask & 8u) != 0u;
     if is_sail {
@@ -346,21 +382,19 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
         
         // Force from relative wind (sail catches wind)
         let rel_wind = wind_vel - p.vel;
-        let wind_force = 0.15 * rel_wind;  // Sail absorbs some wind momentum
-        p.vel += wind_force * params.delta_time;
+        acceleration += 0.15 * rel_wind;  // Sail absorbs some wind momentum
         
         // Quadratic drag (air resistance on the sail)
         let drag_coeff = 0.3;
         let vel_mag = length(p.vel);
         if vel_mag > 0.1 {
-            let drag_force = -drag_coeff * vel_mag * p.vel;
-            p.vel += drag_force * params.delta_time;
+            acceleration += -drag_coeff * vel_mag * p.vel;
         }
     }
     // ==========================================================
     
-    // Apply damping to prevent energy buildup
-    p.vel *= interactions.velocity_damping;
-
-    particles[idx] = p;
+    // Hand the force to the integrator (F = m·a so it can divide by mass uniformly).
+    // XSPH smoothing and damping are velocity filters and are applied there too.
+    forces[idx].fluid = acceleration * p.mass;
+    forces[idx].xsph = xsph_correction;
 }
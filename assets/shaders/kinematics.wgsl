@@ -0,0 +1,163 @@
+// Kinematic Constraints
+// Runs after integration, before the rigid body fit. Every particle attached to a
+// driver is placed relative to its pivot body's last fitted pose:
+//   x = c + R(pivot angle) (hinge + R(driver angle) q + driver translation)
+// Velocity is set from the displacement so the fluid sees the motion.
+// A driven particle that is itself a member of its (dynamic) pivot body is not
+// placed: its rest offset in the body is re-posed and it is marked `driven`. The shape
+// match then moves it, or in springs mode the joint spring in rigid_body.wgsl pulls it
+// towards the pose, so forces on it act on the whole body (the rudder). Only a frozen
+// member (mass > STATIC_MASS in springs mode, a static obstacle) is still placed.
+// Mirrors kinematics::driven_position.
+
+struct Particle {
+    pos: vec2<f32>,
+    vel: vec2<f32>,
+    mass: f32,
+    density: f32,
+    pressure: f32,
+    z_height: f32,
+    layer_mask: u32,
+    cell_id: u32,
+    _padding: vec2<f32>,
+}
+
+struct SimParams {
+    delta_time: f32,
+    gravity: f32,
+    smoothing_radius: f32,
+    target_density_water: f32,
+    target_density_air: f32,
+    wind_interaction_threshold: f32,
+    _reserved: f32,
+    sheet_extension: f32,
+    bounds: vec4<f32>,
+    integrator: u32,
+    pressure_solver: u32,
+    pressure_tolerance: f32,
+    pressure_max_iterations: u32,
+    max_velocity: f32,
+    kernel: u32,
+    boundary_handling: u32,
+    rigid_bodies: u32, // 0 = springs, 1 = shape matching
+    open_world: u32, // 1 = bounds follow the fleet, fluid wraps along x instead of walls
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
+}
+
+// Per-particle joint (matches Rust KinematicTarget)
+struct KinematicTarget {
+    driver: u32,
+    pivot_body: u32,
+    captured: u32,
+    _padding: u32,
+    rest_offset: vec2<f32>,
+    hinge: vec2<f32>, // World spawn position until captured, then pivot frame
+}
+
+// Per-particle body membership (matches Rust RigidMember)
+struct RigidMember {
+    body: u32,
+    driven: u32, // 1 = moved by a kinematic joint of its own body
+    rest_offset: vec2<f32>,
+}
+
+// Driver values (matches Rust GpuKinematicDriver)
+struct KinematicDriver {
+    angle: f32,
+    _padding: f32,
+    translation: vec2<f32>,
+}
+
+// Body state (matches Rust RigidBody)
+struct RigidBody {
+    first_particle: u32,
+    particle_count: u32,
+    kind: u32,
+    stiffness: f32,
+    centre_of_mass: vec2<f32>,
+    linear_velocity: vec2<f32>,
+    angle: f32,
+    angular_velocity: f32,
+    mass: f32,
+    inertia: f32,
+    captured: u32,
+    _pad0: u32,
+    _pad1: u32,
+    _pad2: u32,
+}
+
+@group(0) @binding(0) var<storage, read_write> particles: array<Particle>;
+@group(0) @binding(1) var<uniform> params: SimParams;
+@group(0) @binding(2) var<storage, read_write> targets: array<KinematicTarget>;
+@group(0) @binding(3) var<storage, read> bodies: array<RigidBody>;
+@group(0) @binding(4) var<storage, read> drivers: array<KinematicDriver>;
+@group(0) @binding(5) var<storage, read_write> members: array<RigidMember>;
+
+const NO_DRIVER: u32 = 0xFFFFFFFFu;
+
+// physics.wgsl does not integrate heavier particles outside shape matching
+const STATIC_MASS: f32 = 10000.0;
+
+// Rigid body modes (matches Rust RigidBodyMode)
+const RIGID_SHAPE_MATCHING: u32 = 1u;
+
+// Body kinds (matches Rust RigidBodyKind)
+const BODY_DYNAMIC: u32 = 0u;
+
+fn rotate(v: vec2<f32>, angle: f32) -> vec2<f32> {
+    let c = cos(angle);
+    let s = sin(angle);
+    return vec2<f32>(c * v.x - s * v.y, s * v.x + c * v.y);
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let idx = global_id.x;
+    if idx >= arrayLength(&particles) {
+        return;
+    }
+
+    var joint = targets[idx];
+    if joint.driver == NO_DRIVER {
+        return;
+    }
+
+    // Wait until the pivot body has been measured once
+    let pivot = bodies[joint.pivot_body];
+    if pivot.captured == 0u {
+        return;
+    }
+
+    var p = particles[idx];
+
+    // The spawn pose is the pose at zero drive
+    if joint.captured == 0u {
+        joint.hinge = rotate(joint.hinge - pivot.centre_of_mass, -pivot.angle);
+        joint.rest_offset = rotate(p.pos - pivot.centre_of_mass, -pivot.angle) - joint.hinge;
+        joint.captured = 1u;
+        targets[idx] = joint;
+    }
+
+    let driver = drivers[joint.driver];
+    let local = joint.hinge + rotate(joint.rest_offset, driver.angle) + driver.translation;
+
+    // Part of the pivot body: re-pose it inside the body and let the body move it
+    let in_pivot = members[idx].body == joint.pivot_body;
+    if in_pivot && pivot.kind == BODY_DYNAMIC {
+        members[idx].rest_offset = local;
+        members[idx].driven = 1u;
+        let frozen = params.rigid_bodies != RIGID_SHAPE_MATCHING && p.mass > STATIC_MASS;
+        if !frozen {
+            return;
+        }
+    }
+
+    let goal = pivot.centre_of_mass + rotate(local, pivot.angle);
+
+    let dt = params.delta_time;
+    p.vel = select(vec2<f32>(0.0, 0.0), (goal - p.pos) / dt, dt > 0.0);
+    p.pos = goal;
+    particles[idx] = p;
+}
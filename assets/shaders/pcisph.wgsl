@@ -0,0 +1,347 @@
+// PCISPH Pressure Solver (Predictive-Corrective Incompressible SPH)
+// Alternative to the Tait EOS for water. Runs after the forces and bonds passes:
+//   begin -> [predict -> correct_density -> pressure_force -> check] x max_iterations -> apply
+// Each iteration predicts positions with the current pressure guess, measures the
+// predicted density error and corrects water pressure until the largest relative
+// error is below params.pressure_tolerance. predict, correct_density and
+// pressure_force are dispatched indirectly from `iteration_dispatch`: check() zeroes its
+// workgroup count once converged, so the rest of the iteration budget launches no
+// particle work (only the single-thread check dispatches remain).
+
+#import "shaders/sph_kernels.wgsl"::{kernel_w, kernel_gradient}
+
+struct Particle {
+    pos: vec2<f32>,
+    vel: vec2<f32>,
+    mass: f32,
+    density: f32,
+    pressure: f32,
+    z_height: f32,
+    layer_mask: u32,
+    cell_id: u32,
+    _padding: vec2<f32>,
+}
+
+struct GridParams {
+    cell_size: f32,
+    grid_width: u32,
+    grid_height: u32,
+    grid_origin_x: f32,
+    grid_origin_y: f32,
+    wrap: u32,
+    _padding: vec3<f32>,
+}
+
+struct SimParams {
+    delta_time: f32,
+    gravity: f32,
+    smoothing_radius: f32,
+    target_density_water: f32,
+    target_density_air: f32,
+    wind_interaction_threshold: f32,
+    _reserved: f32,
+    sheet_extension: f32,
+    bounds: vec4<f32>,
+    integrator: u32,
+    pressure_solver: u32,
+    pressure_tolerance: f32,
+    pressure_max_iterations: u32,
+    max_velocity: f32,
+    kernel: u32,
+    boundary_handling: u32,
+    rigid_bodies: u32, // 0 = springs, 1 = shape matching
+    open_world: u32, // 1 = bounds follow the fleet, fluid wraps along x instead of walls
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
+}
+
+// Per-particle force accumulator (matches Rust ParticleForce)
+struct ParticleForce {
+    fluid: vec2<f32>,
+    bond: vec2<f32>,        // Bond + bending forces (bond_gather.wgsl)
+    xsph: vec2<f32>,
+    state: vec2<f32>,
+    state_tag: u32,
+    _padding: u32,
+}
+
+// Per-particle solver scratch
+struct SolverParticle {
+    predicted_pos: vec2<f32>,
+    pressure_accel: vec2<f32>,
+}
+
+// Per-step solver state (matches Rust PressureSolverState)
+struct PressureSolverState {
+    max_error_bits: atomic<u32>,  // Relative error >= 0, so f32 bits order like u32
+    iterations: u32,
+    converged: u32,
+    last_max_error: f32,
+}
+
+// Indirect dispatch arguments of the per-iteration passes. Bound as group 1 by
+// begin and check only; the passes it launches read it as their indirect buffer.
+struct DispatchArgs {
+    x: u32,
+    y: u32,
+    z: u32,
+}
+
+@group(0) @binding(0) var<storage, read_write> particles: array<Particle>;
+@group(0) @binding(1) var<storage, read> indices: array<u32>;
+@group(0) @binding(2) var<storage, read> cell_offsets: array<u32>;
+@group(0) @binding(3) var<uniform> grid: GridParams;
+@group(0) @binding(4) var<uniform> params: SimParams;
+@group(0) @binding(5) var<storage, read_write> forces: array<ParticleForce>;
+@group(0) @binding(6) var<storage, read_write> solver: array<SolverParticle>;
+@group(0) @binding(7) var<storage, read_write> state: PressureSolverState;
+@group(0) @binding(8) var<storage, read> boundary_volumes: array<f32>;  // Akinci V_b (density.wgsl)
+@group(1) @binding(0) var<storage, read_write> iteration_dispatch: DispatchArgs;
+
+const BOUNDARY_AKINCI: u32 = 1u;
+
+const PI: f32 = 3.14159265359;
+
+fn is_water(layer_mask: u32) -> bool {
+    return (layer_mask & 1u) != 0u;
+}
+
+fn is_solid(layer_mask: u32) -> bool {
+    return (layer_mask & (4u | 8u | 16u)) != 0u;
+}
+
+const NO_CELL: u32 = 0xFFFFFFFFu;
+
+// Cell (x, y) of a 3x3 neighbourhood, or NO_CELL off the grid. A wrapping grid (the open
+// world) continues on the opposite side along x only. Mirrors
+// reference::NeighbourGrid::neighbours.
+fn neighbor_cell(x_in: i32, y: i32) -> u32 {
+    let width = i32(grid.grid_width);
+    let height = i32(grid.grid_height);
+    var x = x_in;
+    if grid.wrap != 0u {
+        x = (x + width) % width;
+    }
+    if x < 0 || y < 0 || x >= width || y >= height {
+        return NO_CELL;
+    }
+    return u32(y) * grid.grid_width + u32(x);
+}
+
+// b - a, the short way round a wrapping grid along x (mirrors GridParams::offset)
+fn grid_offset(a: vec2<f32>, b: vec2<f32>) -> vec2<f32> {
+    var d = b - a;
+    if grid.wrap != 0u {
+        let width = f32(grid.grid_width) * grid.cell_size;
+        d.x -= round(d.x / width) * width;
+    }
+    return d;
+}
+
+// ==================== BEGIN ====================
+// Reset per-step state and pressure guesses
+@compute @workgroup_size(64)
+fn begin(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let idx = global_id.x;
+    if idx == 0u {
+        atomicStore(&state.max_error_bits, 0u);
+        state.iterations = 0u;
+        state.converged = 0u;
+        state.last_max_error = 0.0;
+        iteration_dispatch = DispatchArgs((arrayLength(&particles) + 63u) / 64u, 1u, 1u);
+    }
+    if idx >= arrayLength(&particles) {
+        return;
+    }
+    solver[idx].pressure_accel = vec2<f32>(0.0, 0.0);
+    if is_water(particles[idx].layer_mask) {
+        particles[idx].pressure = 0.0;
+    }
+}
+
+// ==================== PREDICT ====================
+// Predicted position with every known force plus the current pressure guess
+@compute @workgroup_size(64)
+fn predict(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let idx = global_id.x;
+    if idx >= arrayLength(&particles) || state.converged != 0u {
+        return;
+    }
+
+    let p = particles[idx];
+
+    // Static particles do not move (see physics.wgsl)
+    if p.mass > 10000.0 {
+        solver[idx].predicted_pos = p.pos;
+        return;
+    }
+
+    let accel = (forces[idx].fluid + forces[idx].bond) / p.mass + solver[idx].pressure_accel;
+
+    let dt = params.delta_time;
+    let predicted_vel = p.vel + accel * dt;
+    solver[idx].predicted_pos = p.pos + predicted_vel * dt;
+}
+
+// ==================== CORRECT DENSITY ====================
+// Predicted density error -> pressure correction (water only)
+@compute @workgroup_size(64)
+fn correct_density(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let idx = global_id.x;
+    if idx >= arrayLength(&particles) || state.converged != 0u {
+        return;
+    }
+
+    let p = particles[idx];
+    if !is_water(p.layer_mask) {
+        return;
+    }
+
+    let h = params.smoothing_radius;
+    let rest_density = params.target_density_water;
+    let my_pos = solver[idx].predicted_pos;
+    let cell_x = i32(p.cell_id % grid.grid_width);
+    let cell_y = i32(p.cell_id / grid.grid_width);
+
+    var density = 0.0;
+    var grad_sum = vec2<f32>(0.0, 0.0);
+    var grad_sq_sum = 0.0;
+
+    for (var dy = -1; dy <= 1; dy++) {
+        for (var dx = -1; dx <= 1; dx++) {
+            let neighbor_cell_id = neighbor_cell(cell_x + dx, cell_y + dy);
+            if neighbor_cell_id == NO_CELL {
+                continue;
+            }
+            let cell_start = cell_offsets[neighbor_cell_id];
+            let cell_end = cell_offsets[neighbor_cell_id + 1u];
+
+            for (var j = cell_start; j < cell_end; j++) {
+                let neighbor_idx = indices[j];
+                let neighbor = particles[neighbor_idx];
+
+                // Same filter as density.wgsl
+                if abs(p.z_height - neighbor.z_height) > 0.5 {
+                    continue;
+                }
+                if p.layer_mask != neighbor.layer_mask && !is_solid(neighbor.layer_mask) {
+                    continue;
+                }
+
+                let r = grid_offset(solver[neighbor_idx].predicted_pos, my_pos);
+                let r_sq = dot(r, r);
+                // Same boundary pseudo-mass as density.wgsl
+                var contribution_mass = neighbor.mass;
+                if params.boundary_handling == BOUNDARY_AKINCI && is_solid(neighbor.layer_mask) {
+                    contribution_mass = rest_density * boundary_volumes[neighbor_idx];
+                }
+                density += contribution_mass * kernel_w(params.kernel, sqrt(r_sq), h);
+
+                // Gradient sums for the per-particle PCISPH scaling factor (water pairs)
+                if neighbor_idx != idx && neighbor.layer_mask == p.layer_mask {
+                    let grad = kernel_gradient(params.kernel, r, sqrt(r_sq), h);
+                    grad_sum += grad;
+                    grad_sq_sum += dot(grad, grad);
+                }
+            }
+        }
+    }
+
+    // Only compression is corrected (no tensile pressure)
+    let error = max(density - rest_density, 0.0);
+
+    // δ = 1 / (β (|Σ∇W|² + Σ|∇W|²)), β = dt² m² 2 / ρ0²
+    let dt = params.delta_time;
+    let beta = dt * dt * p.mass * p.mass * 2.0 / (rest_density * rest_density);
+    let denom = beta * (dot(grad_sum, grad_sum) + grad_sq_sum);
+    if denom > 1e-12 {
+        particles[idx].pressure = max(particles[idx].pressure + error / denom, 0.0);
+    }
+
+    atomicMax(&state.max_error_bits, bitcast<u32>(error / rest_density));
+}
+
+// ==================== PRESSURE FORCE ====================
+// Pressure acceleration from the corrected pressures (current positions)
+@compute @workgroup_size(64)
+fn pressure_force(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let idx = global_id.x;
+    if idx >= arrayLength(&particles) || state.converged != 0u {
+        return;
+    }
+
+    let p = particles[idx];
+    if !is_water(p.layer_mask) {
+        return;
+    }
+
+    let h = params.smoothing_radius;
+    let rest_density_sq = params.target_density_water * params.target_density_water;
+    let cell_x = i32(p.cell_id % grid.grid_width);
+    let cell_y = i32(p.cell_id / grid.grid_width);
+
+    var accel = vec2<f32>(0.0, 0.0);
+
+    for (var dy = -1; dy <= 1; dy++) {
+        for (var dx = -1; dx <= 1; dx++) {
+            let neighbor_cell_id = neighbor_cell(cell_x + dx, cell_y + dy);
+            if neighbor_cell_id == NO_CELL {
+                continue;
+            }
+            let cell_start = cell_offsets[neighbor_cell_id];
+            let cell_end = cell_offsets[neighbor_cell_id + 1u];
+
+            for (var j = cell_start; j < cell_end; j++) {
+                let neighbor_idx = indices[j];
+                if neighbor_idx == idx {
+                    continue;
+                }
+                let neighbor = particles[neighbor_idx];
+
+                // Water <-> water only (water <-> solid uses repulsion, see forces.wgsl)
+                if neighbor.layer_mask != p.layer_mask || abs(p.z_height - neighbor.z_height) > 0.5 {
+                    continue;
+                }
+
+                let r = grid_offset(neighbor.pos, p.pos);
+                let grad = kernel_gradient(params.kernel, r, length(r), h);
+                accel -= neighbor.mass * ((p.pressure + neighbor.pressure) / rest_density_sq) * grad;
+            }
+        }
+    }
+
+    solver[idx].pressure_accel = accel;
+}
+
+// ==================== CHECK ====================
+// Single thread: count the iteration and test convergence. Converging stops the
+// remaining iterations by zeroing their indirect workgroup count.
+@compute @workgroup_size(1)
+fn check() {
+    if state.converged != 0u {
+        return;
+    }
+    let max_error = bitcast<f32>(atomicLoad(&state.max_error_bits));
+    atomicStore(&state.max_error_bits, 0u);
+    state.last_max_error = max_error;
+    state.iterations += 1u;
+    if max_error <= params.pressure_tolerance {
+        state.converged = 1u;
+        iteration_dispatch.x = 0u;
+    }
+}
+
+// ==================== APPLY ====================
+// Add the final pressure force to the water force for the integrator
+@compute @workgroup_size(64)
+fn apply(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let idx = global_id.x;
+    if idx >= arrayLength(&particles) {
+        return;
+    }
+    let p = particles[idx];
+    if is_water(p.layer_mask) {
+        forces[idx].fluid += solver[idx].pressure_accel * p.mass;
+    }
+}
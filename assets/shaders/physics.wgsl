@@ -1,6 +1,9 @@
 // Physics Integration Shader
-// Updates velocity and position, applies soft boundary repulsion AND Bond Forces
-// Phase 3: Added Bond Force application from atomic buffer
+// Sums the force buffer (SPH/repulsion/aerodynamics, bonds) plus the soft wall force,
+// and advances velocity and position with the scheme selected in SimParams.integrator.
+// The wall force only depends on the particle's own position, so it is evaluated here
+// instead of being stored in the force buffer.
+// Phase 3: Added Bond Force application (gathered per particle by bond_gather.wgsl)
 // Phase 1: Enhanced with soft boundaries to prevent particle alignment
 
 struct Particle {
@@ -22,24 +25,97 @@ struct SimParams {
     target_density_water: f32,
     target_density_air: f32,
     wind_interaction_threshold: f32,
-    rudder_angle: f32,
+    _reserved: f32,
     sheet_extension: f32,
     bounds: vec4<f32>, // min_x, max_x, min_y, max_y
-    _padding: vec4<f32>,
+    integrator: u32,    // 0=Symplectic Euler, 1=Velocity Verlet, 2=Leapfrog
+    pressure_solver: u32,         // 0=Tait WCSPH, 1=PCISPH
+    pressure_tolerance: f32,      // PCISPH max relative density error
+    pressure_max_iterations: u32, // PCISPH iteration budget
+    max_velocity: f32, // Post-integration speed clamp (0 = off)
+    kernel: u32, // SPH kernel (see SphKernel)
+    boundary_handling: u32, // 0 = repulsion, 1 = Akinci
+    rigid_bodies: u32, // 0 = springs, 1 = shape matching
+    open_world: u32, // 1 = bounds follow the fleet, fluid wraps along x instead of walls
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
+}
+
+// Per-particle force accumulator (matches Rust ParticleForce)
+struct ParticleForce {
+    fluid: vec2<f32>,       // SPH + repulsion + aerodynamics (forces.wgsl)
+    bond: vec2<f32>,        // Bond + bending forces (bond_gather.wgsl)
+    xsph: vec2<f32>,        // XSPH velocity correction (forces.wgsl)
+    state: vec2<f32>,       // Verlet: previous acceleration, Leapfrog: synchronised velocity
+    state_tag: u32,         // Scheme that wrote `state` + 1 (0 = no history)
+    _padding: u32,
+}
+
+// Subset of the interaction table used here (matches Rust InteractionTable)
+struct InteractionProfile {
+    repulsion_strength: f32,
+    repulsion_radius: f32,
+    repulsion_ramp: u32,
+    _padding: u32,
+}
+
+struct InteractionTable {
+    profiles: array<InteractionProfile, 25>,
+    sph_viscosity: f32,
+    sph_pressure_stiffness: f32,
+    sph_close_repulsion: f32,
+    xsph_epsilon: f32,
+    velocity_damping: f32,
+    pressure_cap: f32,
+    _padding: vec2<f32>,
+}
+
+// Rigid body membership and kind (matches Rust RigidMember / RigidBody)
+struct RigidMember {
+    body: u32,
+    driven: u32, // 1 = moved by a kinematic joint of its own body
+    rest_offset: vec2<f32>,
+}
+
+struct RigidBody {
+    first_particle: u32,
+    particle_count: u32,
+    kind: u32,
+    stiffness: f32,
+    centre_of_mass: vec2<f32>,
+    linear_velocity: vec2<f32>,
+    angle: f32,
+    angular_velocity: f32,
+    mass: f32,
+    inertia: f32,
+    captured: u32,
+    _pad0: u32,
+    _pad1: u32,
+    _pad2: u32,
 }
 
 @group(0) @binding(0) var<storage, read_write> particles: array<Particle>;
 @group(0) @binding(1) var<uniform> params: SimParams;
-@group(0) @binding(2) var<storage, read_write> forces: array<atomic<i32>>; // Atomic accumulators [x, y, x, y...]
+@group(0) @binding(2) var<storage, read_write> forces: array<ParticleForce>;
+@group(0) @binding(3) var<uniform> interactions: InteractionTable;
+@group(0) @binding(4) var<storage, read> members: array<RigidMember>;
+@group(0) @binding(5) var<storage, read> bodies: array<RigidBody>;
+
+// Integrator schemes (matches Rust IntegratorScheme)
+const INTEGRATOR_SYMPLECTIC_EULER: u32 = 0u;
+const INTEGRATOR_VELOCITY_VERLET: u32 = 1u;
+const INTEGRATOR_LEAPFROG: u32 = 2u;
 
-const FORCE_SCALER: f32 = 1000.0;
+const NO_BODY: u32 = 0xFFFFFFFFu;
+const RIGID_SHAPE_MATCHING: u32 = 1u;
+const BODY_DYNAMIC: u32 = 0u;
 
 
 // ==================== BOUNDARY PARAMETERS ====================
 const BOUNDARY_STIFFNESS: f32 = 0.0;//500.0;    // Repulsion strength - higher = harder bounce
 const BOUNDARY_RANGE: f32 = 4.0;         // Distance at which repulsion starts
 const BOUNDARY_MARGIN: f32 = 2.0;         // Hard stop margin (safety fallback)
-const MAX_VELOCITY: f32 = 500.0;          // Velocity cap to prevent explosions
 // =============================================================
 
 // Soft boundary repulsion force - increases as particle approaches wall
@@ -69,125 +145,94 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
 
     // ==================== STATIC PARTICLE CHECK ====================
     // If mass is huge (Infinite mass), treat as static obstacle
-    // Exception: Sail particles (layer 8) are kinematic (moved by angle)
-    if p.mass > 10000.0 && (p.layer_mask & 8u) == 0u {
+    // (driven particles are then placed by kinematics.wgsl)
+    // Exception: with shape matching, dynamic rigid bodies integrate normally
+    let body = members[idx].body;
+    let rigid_dynamic = params.rigid_bodies == RIGID_SHAPE_MATCHING
+        && body != NO_BODY
+        && bodies[body].kind == BODY_DYNAMIC;
+    if p.mass > 10000.0 && !rigid_dynamic {
         p.vel = vec2<f32>(0.0, 0.0);
         // Do not update position
         particles[idx] = p;
-        atomicStore(&forces[idx * 2u], 0);
-        atomicStore(&forces[idx * 2u + 1u], 0);
+        forces[idx].state_tag = 0u;
         return;
     }
     // ===============================================================
 
-    // ==================== SAIL ROTATION (Kinematic) ====================
-    let is_sail = (p.layer_mask & 8u) != 0u;
-    if is_sail {
-        // Rotate sail particles around mast center
-        // Mast center from hurricane config: 
-        // hull center = (-80 + 20*8/2, -20 + 5*8/2) = (0.0, 0.0) -> Wait, let's clearer
-        // Hull start: -80, -20. Width 20*8=160. Height 5*8=40. Center = (0, 0).
-        // Mast at center = (0, 0).
-        let mast_center = vec2<f32>(0.0, 0.0); 
-        
-        // We need the *initial* offset to rotate it. 
-        // But p.pos is current position. If we just rotate p.pos, it will spin forever!
-        // We need to know the REST position relative to mast.
-        // Option A: Store rest pos in shader? Too hard.
-        // Option B: Back-calculate from current angle? Hard if angle changes.
-        // Option C: Use bond rest lengths? Complex.
-        // Option D: Just force it based on ID?
-        // Let's assume the sail is INITIALLY spawned at angle 0.
-        // Wait, if we move it every frame based on angle, we need a stable reference.
-        // For now, let's rely on the bonds to pull it? No, user wants DIRECT control.
-        
-        // Revised Approach: 
-        // We don't change position here. We let the BONDS move the sail.
-        // We act on the SPAR. The spar is attached to the mast.
-        // If we rotate the SPAR, the sail will follow.
-        // But wait, I made the spar STATIC (mass 100000).
-        // So I should rotate the SPAR particles here!
-        
-        // Let's rotate SPAR (layer 16 but mass > 10000) and SAIL (layer 8).
-        // Actually spar uses MAST layer (16).
-        
-        // If it's a spar particle (Mass > 10000 AND Layer == MAST AND NOT at (0,0))
-        // Identify spar by being Mast layer but not the central mast cluster?
-        // Or just rotate ALL mast particles around (0,0)? The center ones won't move much.
-        
-        // Better: Rotate based on initial relative position.
-        // But we don't have initial position.
-        // However, if we assume the spar is initially horizontal (y=0) or vertical?
-        // In scenario, spar is to the RIGHT of mast. (Offset positive X).
-        
-        // Let's try to just rotate the velocity? No, kinematic means setting position.
-        
-        // CRITICAL: Without initial position, we can't do absolute rotation.
-        // But we can rotate the VELOCITY to push it towards target angle? No.
-        
-        // Alternative: Rotate the BOND offsets?
-        // Bonds have rest_length (scalar). They don't have orientation.
-        
-        // Okay, we MUST store initial position or have a way to derive it.
-        // Or... we change the "Static" check to allow Spar to move IF it is driven by this rotation.
-        
-        // Let's assume we simply want to set the position of Spar particles.
-        // We can infer their "radius" from the mast center (length(p.pos - center)).
-        // And their "initial angle" was 0 (pointing East).
-        // So new pos = center + radius * (cos(a), sin(a)).
-        
-        // Check scenario: Spar starts at mast_x + spacing. Extends RIGHT.
-        // So yes, initial angle is 0.
-
-        let rel_pos = p.pos - mast_center;
-        let dist = length(rel_pos);
-        
-        // Only apply to Spar (which are Mast layer, but non-zero distance) and Sail
-        // Actually, just Spar. Sail is dynamic and attached to Spar.
-        // Wait, if Sail is dynamic, wind will blow it.
-        // If Spar is kinematic, it acts as the boom.
-        
-        // Let's apply to Spar particles (mass > 10000 && layer == MAST)
-        // Check if dist > 1.0 to avoid rotating the mast itself (which is at 0,0)
-        let is_static_spar = (p.mass > 10000.0) && ((p.layer_mask & 16u) != 0u) && (dist > 2.0);
-
-        if is_static_spar {
-            let angle = params.rudder_angle; // Using this as sail/spar angle
-           
-           // Target position
-            let target_pos = mast_center + vec2<f32>(cos(angle), sin(angle)) * dist;
-           
-           // Move instantly (Kinematic)
-            p.pos = target_pos;
-            p.vel = vec2<f32>(0.0, 0.0);
-
-            particles[idx] = p;
-            atomicStore(&forces[idx * 2u], 0);
-            atomicStore(&forces[idx * 2u + 1u], 0);
-            return;
-        }
+    // ==================== ACCUMULATE FORCES ====================
+    // Fluid force (SPH pressure, viscosity, repulsion, aerodynamics)
+    var force = forces[idx].fluid;
+
+    // Bond and bending forces (gathered every step, so nothing to clear)
+    force += forces[idx].bond;
+
+    // Calculate distances to each boundary
+    let dist_left = p.pos.x - params.bounds.x;
+    let dist_right = params.bounds.y - p.pos.x;
+    let dist_bottom = p.pos.y - params.bounds.z;
+    let dist_top = params.bounds.w - p.pos.y;
+
+    // Soft boundary forces (boundary_force is an acceleration, scale by mass)
+    var boundary_accel = vec2<f32>(0.0, 0.0);
+
+    // Open world: the window follows the fleet along x, so there are no side walls
+    if params.open_world == 0u {
+        // Left wall pushes right (+x)
+        boundary_accel.x += boundary_force(dist_left);
+        // Right wall pushes left (-x)
+        boundary_accel.x -= boundary_force(dist_right);
     }
+    // Bottom wall pushes up (+y)
+    boundary_accel.y += boundary_force(dist_bottom);
+    // Top wall pushes down (-y)
+    boundary_accel.y -= boundary_force(dist_top);
 
-    // ==================== APPLY BOND FORCES ====================
-    // Read accumulated bond forces from atomic buffer and apply to velocity
-    // Forces are stored as fixed-point integers scaled by FORCE_SCALER
-    let fx_int = atomicLoad(&forces[idx * 2u]);
-    let fy_int = atomicLoad(&forces[idx * 2u + 1u]);
-    
-    // Convert back to float
-    let bond_force = vec2<f32>(f32(fx_int), f32(fy_int)) / FORCE_SCALER;
-    
-    // Apply force to velocity: a = F/m, v += a*dt
-    p.vel += (bond_force / p.mass) * params.delta_time;
-    
-    // Clear the force buffer for next frame (reset to 0)
-    atomicStore(&forces[idx * 2u], 0);
-    atomicStore(&forces[idx * 2u + 1u], 0);
+    force += boundary_accel * p.mass;
     // ===========================================================
 
+    // ==================== INTEGRATION ====================
+    // Kick -> velocity filters (XSPH, damping) -> drift, for every scheme.
+    let accel = force / p.mass;
+    let dt = params.delta_time;
+    let tag = params.integrator + 1u;
+    let fresh = forces[idx].state_tag != tag;
+    var drift = vec2<f32>(0.0, 0.0);
+
+    switch params.integrator {
+        case INTEGRATOR_VELOCITY_VERLET: {
+            // Stored velocity is v_{n-1}; complete it with ½(a_{n-1} + a_n)dt.
+            // On the first step it is already v_0, so there is nothing to complete.
+            if !fresh {
+                p.vel += 0.5 * (forces[idx].state + accel) * dt;
+            }
+            drift = 0.5 * accel * dt * dt;
+            forces[idx].state = accel;
+        }
+        case INTEGRATOR_LEAPFROG: {
+            // Stored velocity is v_{n-½}. Bootstrap from synchronised v_0 with a half kick.
+            let v_prev = p.vel;
+            p.vel += accel * select(dt, 0.5 * dt, fresh);
+            forces[idx].state = select(0.5 * (v_prev + p.vel), v_prev, fresh);
+        }
+        default: {
+            // Symplectic Euler
+            p.vel += accel * dt;
+        }
+    }
+    forces[idx].state_tag = tag;
+
+    // XSPH smoothing (reduces oscillation) and damping (prevents energy buildup)
+    p.vel += interactions.xsph_epsilon * forces[idx].xsph;
+    p.vel *= interactions.velocity_damping;
+
+    // Position integration
+    p.pos += p.vel * dt + drift;
+    // =====================================================
+
     // ==================== WIND TUNNEL RECYCLING ====================
     let is_air = (p.layer_mask & 2u) != 0u;
-    if is_air {
+    if is_air && params.open_world == 0u {
         // If Air particle leaves the Right Edge
         if p.pos.x > params.bounds.y {
              // Teleport to Left Edge
@@ -199,34 +244,28 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
              // Reset Density/Pressure for stability? (Will be recalculated next frame anyway)
             p.density = params.target_density_air;
             p.pressure = 0.0;
+
+            // Velocity was replaced, so integrator history no longer applies
+            forces[idx].state_tag = 0u;
         }
     }
     // ===============================================================
 
-    // Calculate distances to each boundary
-    let dist_left = p.pos.x - params.bounds.x;
-    let dist_right = params.bounds.y - p.pos.x;
-    let dist_bottom = p.pos.y - params.bounds.z;
-    let dist_top = params.bounds.w - p.pos.y;
-    
-    // Apply soft boundary forces (before position integration)
-    var boundary_vel = vec2<f32>(0.0, 0.0);
-    
-    // Left wall pushes right (+x)
-    boundary_vel.x += boundary_force(dist_left);
-    // Right wall pushes left (-x)
-    boundary_vel.x -= boundary_force(dist_right);
-    // Bottom wall pushes up (+y)
-    boundary_vel.y += boundary_force(dist_bottom);
-    // Top wall pushes down (-y)
-    boundary_vel.y -= boundary_force(dist_top);
-    
-    // Apply boundary velocity change
-    p.vel += boundary_vel * params.delta_time;
-    
-    // Position integration (velocity already updated by forces shader)
-    p.pos += p.vel * params.delta_time;
-    
+    // ==================== OPEN WORLD WRAP ====================
+    // Fluid leaving the window's left or right side re-enters on the opposite side with
+    // its velocity, so the fixed particle budget always surrounds the fleet (mirrors
+    // WorldWindow::wrap). Other particles (hulls, sails) just fall into the border cells
+    // until the window catches up. The floor and ceiling below clamp as in the tank.
+    if params.open_world != 0u {
+        let is_fluid = (p.layer_mask & 3u) != 0u;
+        if is_fluid {
+            let width = params.bounds.y - params.bounds.x;
+            let rel = p.pos.x - params.bounds.x;
+            p.pos.x = params.bounds.x + rel - floor(rel / width) * width;
+        }
+    }
+    // =========================================================
+
     // Hard boundary fallback (safety - should rarely trigger)
     let min_x = params.bounds.x + BOUNDARY_MARGIN;
     let max_x = params.bounds.y - BOUNDARY_MARGIN;
@@ -250,10 +289,10 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
         p.vel.y = -abs(p.vel.y) * 0.3;
     }
     
-    // Clamp velocity to prevent explosions
+    // Clamp velocity to prevent explosions (off with the adaptive timestep)
     let vel_len = length(p.vel);
-    if vel_len > MAX_VELOCITY {
-        p.vel = normalize(p.vel) * MAX_VELOCITY;
+    if params.max_velocity > 0.0 && vel_len > params.max_velocity {
+        p.vel = normalize(p.vel) * params.max_velocity;
     }
 
     particles[idx] = p;
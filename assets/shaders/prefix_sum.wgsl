@@ -1,6 +1,12 @@
 // Prefix Sum Shader (Pass 2 of Counting Sort)
 // Computes exclusive prefix sum of cell counts -> cell offsets
-// Uses simple sequential approach (runs on single workgroup for small cell counts)
+// Work-efficient (Blelloch) scan over blocks of 512 cells, one workgroup per block:
+//   scan_blocks:       scan each block in workgroup memory, write its total to block_sums
+//   scan_block_sums:   one workgroup scans the block totals (512 at a time, carrying over)
+//   add_block_offsets: add each block's offset, write the end sentinel, and copy the
+//                      offsets into cell_cursor (scatter's write positions)
+// cell_offsets is never modified after this pass, so no restore pass is needed.
+// Mirrors grid::exclusive_scan.
 
 struct GridParams {
     cell_size: f32,
@@ -8,27 +14,130 @@ struct GridParams {
     grid_height: u32,
     grid_origin_x: f32,
     grid_origin_y: f32,
+    wrap: u32,
     _padding: vec3<f32>,
 }
 
 @group(0) @binding(0) var<storage, read> cell_counts: array<u32>;
 @group(0) @binding(1) var<storage, read_write> cell_offsets: array<u32>;
 @group(0) @binding(2) var<uniform> grid: GridParams;
+@group(0) @binding(3) var<storage, read_write> block_sums: array<u32>;
+@group(0) @binding(4) var<storage, read_write> cell_cursor: array<u32>;
 
-// Simple sequential prefix sum (fine for ~2300 cells)
-// Only thread 0 does the work - inefficient but correct
-@compute @workgroup_size(1)
-fn prefix_sum(@builtin(global_invocation_id) global_id: vec3<u32>) {
+// Cells per block: two per thread (matches Rust grid::SCAN_BLOCK_SIZE)
+const SCAN_BLOCK: u32 = 512u;
+
+var<workgroup> scan: array<u32, 512>;
+
+// Exclusive Blelloch scan of `scan` in place; returns the block total.
+// Must be called from uniform control flow by all 256 threads.
+fn scan_workgroup(local: u32) -> u32 {
+    // Up-sweep: build partial sums in place
+    var offset = 1u;
+    for (var d = SCAN_BLOCK >> 1u; d > 0u; d >>= 1u) {
+        workgroupBarrier();
+        if local < d {
+            let ai = offset * (2u * local + 1u) - 1u;
+            let bi = offset * (2u * local + 2u) - 1u;
+            scan[bi] += scan[ai];
+        }
+        offset *= 2u;
+    }
+    workgroupBarrier();
+    let total = scan[SCAN_BLOCK - 1u];
+    workgroupBarrier();
+    if local == 0u {
+        scan[SCAN_BLOCK - 1u] = 0u;
+    }
+
+    // Down-sweep: distribute the sums
+    for (var d = 1u; d < SCAN_BLOCK; d *= 2u) {
+        offset >>= 1u;
+        workgroupBarrier();
+        if local < d {
+            let ai = offset * (2u * local + 1u) - 1u;
+            let bi = offset * (2u * local + 2u) - 1u;
+            let t = scan[ai];
+            scan[ai] = scan[bi];
+            scan[bi] += t;
+        }
+    }
+    workgroupBarrier();
+    return total;
+}
+
+@compute @workgroup_size(256)
+fn scan_blocks(
+    @builtin(local_invocation_id) local_id: vec3<u32>,
+    @builtin(workgroup_id) group_id: vec3<u32>,
+) {
+    let total_cells = grid.grid_width * grid.grid_height;
+    let local = local_id.x;
+    let base = group_id.x * SCAN_BLOCK;
+
+    for (var k = 0u; k < 2u; k++) {
+        let i = base + local * 2u + k;
+        scan[local * 2u + k] = select(0u, cell_counts[i], i < total_cells);
+    }
+
+    let total = scan_workgroup(local);
+
+    for (var k = 0u; k < 2u; k++) {
+        let i = base + local * 2u + k;
+        if i < total_cells {
+            cell_offsets[i] = scan[local * 2u + k];
+        }
+    }
+    if local == 0u {
+        block_sums[group_id.x] = total;
+    }
+}
+
+@compute @workgroup_size(256)
+fn scan_block_sums(@builtin(local_invocation_id) local_id: vec3<u32>) {
     let total_cells = grid.grid_width * grid.grid_height;
-    
-    var running_sum = 0u;
-    
-    for (var i = 0u; i < total_cells; i++) {
-        // Exclusive prefix sum: offset[i] = sum of counts[0..i)
-        cell_offsets[i] = running_sum;
-        running_sum += cell_counts[i];
-    }
-    
-    // Store total at the end (for bounds checking)
-    cell_offsets[total_cells] = running_sum;
+    let block_count = (total_cells + SCAN_BLOCK - 1u) / SCAN_BLOCK;
+    let local = local_id.x;
+
+    // Blocks past 512 * 512 cells: scan in chunks, carrying the running total
+    var carry = 0u;
+    for (var base = 0u; base < block_count; base += SCAN_BLOCK) {
+        for (var k = 0u; k < 2u; k++) {
+            let i = base + local * 2u + k;
+            scan[local * 2u + k] = select(0u, block_sums[i], i < block_count);
+        }
+
+        let total = scan_workgroup(local);
+
+        for (var k = 0u; k < 2u; k++) {
+            let i = base + local * 2u + k;
+            if i < block_count {
+                block_sums[i] = scan[local * 2u + k] + carry;
+            }
+        }
+        carry += total;
+    }
+}
+
+@compute @workgroup_size(256)
+fn add_block_offsets(
+    @builtin(local_invocation_id) local_id: vec3<u32>,
+    @builtin(workgroup_id) group_id: vec3<u32>,
+) {
+    let total_cells = grid.grid_width * grid.grid_height;
+    let block_offset = block_sums[group_id.x];
+
+    for (var k = 0u; k < 2u; k++) {
+        let i = group_id.x * SCAN_BLOCK + local_id.x * 2u + k;
+        if i >= total_cells {
+            continue;
+        }
+        let offset = cell_offsets[i] + block_offset;
+        cell_offsets[i] = offset;
+        cell_cursor[i] = offset;
+        // Store total at the end (for bounds checking)
+        if i == total_cells - 1u {
+            cell_offsets[total_cells] = offset + cell_counts[i];
+        }
+    }
 }
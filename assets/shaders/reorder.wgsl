@@ -0,0 +1,150 @@
+// Particle Reorder (cell order)
+// Every `SimulationSettings::reorder_interval` steps, right after the scatter, the
+// particles are permuted into cell order, so the neighbour loops of the following steps
+// read mostly contiguous memory. Particles owned by a rigid body, driven by a kinematic
+// joint or part of a bending triplet keep their slots (those structures address fixed
+// slots); the others are packed, in cell order, into the slots the movable particles
+// already occupied. Passes:
+//   flag:        one thread per sorted position k, flags[k] = movable[indices[k]]
+//   (prefix_sum.wgsl's blocked scan: flags -> ranks, a movable particle's rank in cell order)
+//   remap:       one thread per sorted position, new_slot[old] and the index buffer
+//   remap_bonds: one thread per bond slot, rewrite both ends of every used slot and
+//                bump BondAllocator.changes so bond_adjacency.wgsl rebuilds the lists
+//   permute:     one thread per old slot, move the particle, its forces (integrator
+//                history) and weld partners, and update the id <-> slot tables
+// `permute` reads the previous state from copies the node makes before the pass.
+// Handles outside the GPU (BondQueue edits, BondBroken, snapshots) keep using the
+// original particle ids: particle_ids maps a slot to its id, particle_slots back.
+// Mirrors reorder::cell_permutation.
+
+struct Particle {
+    pos: vec2<f32>,
+    vel: vec2<f32>,
+    mass: f32,
+    density: f32,
+    pressure: f32,
+    z_height: f32,
+    layer_mask: u32,
+    cell_id: u32,
+    _padding: vec2<f32>,
+}
+
+struct ParticleForce {
+    fluid: vec2<f32>,
+    bond: vec2<f32>,
+    xsph: vec2<f32>,
+    state: vec2<f32>,
+    state_tag: u32,
+    _padding: u32,
+}
+
+struct Bond {
+    particle_a: u32,
+    particle_b: u32,
+    rest_length: f32,
+    stiffness: f32,
+    breaking_strain: f32,
+    bond_type: u32,
+    is_active: u32,
+    yield_strain: f32,
+    damage: f32,
+    compressive_breaking_strain: f32,
+    shear_limit: f32,
+    _padding: u32,
+}
+
+// Matches Rust welding::BondAllocator (only the change counter is used here)
+struct BondAllocator {
+    next_free: u32,
+    dropped: u32,
+    edit_count: u32,
+    free_count: u32,
+    free_taken: u32,
+    changes: u32,
+    rebuilt_changes: u32,
+    _padding: u32,
+}
+
+struct WeldPartners {
+    count: u32,
+    bonds: array<u32, 3>,  // Bond slots: bonds do not move, so these need no remap
+}
+
+// Index side (flag, remap, remap_bonds)
+@group(0) @binding(0) var<storage, read_write> indices: array<u32>;
+@group(0) @binding(1) var<storage, read> movable: array<u32>;        // Per slot: 1 = may move
+@group(0) @binding(2) var<storage, read> movable_slots: array<u32>;  // Movable slots, ascending
+@group(0) @binding(3) var<storage, read_write> flags: array<u32>;
+@group(0) @binding(4) var<storage, read> ranks: array<u32>;          // Exclusive scan of flags
+@group(0) @binding(5) var<storage, read_write> new_slot: array<u32>;
+@group(0) @binding(6) var<storage, read_write> bonds: array<Bond>;
+@group(0) @binding(7) var<storage, read_write> allocator: BondAllocator;
+
+// Data side (permute only; a separate layout, hence the distinct binding numbers)
+@group(0) @binding(8) var<storage, read> permutation: array<u32>;  // new_slot
+@group(0) @binding(9) var<storage, read_write> particles: array<Particle>;
+@group(0) @binding(10) var<storage, read> particles_old: array<Particle>;
+@group(0) @binding(11) var<storage, read_write> forces: array<ParticleForce>;
+@group(0) @binding(12) var<storage, read> forces_old: array<ParticleForce>;
+@group(0) @binding(13) var<storage, read_write> partners: array<WeldPartners>;
+@group(0) @binding(14) var<storage, read> partners_old: array<WeldPartners>;
+@group(0) @binding(15) var<storage, read_write> particle_ids: array<u32>;   // Slot -> original id
+@group(0) @binding(16) var<storage, read> particle_ids_old: array<u32>;
+@group(0) @binding(17) var<storage, read_write> particle_slots: array<u32>; // Original id -> slot
+
+@compute @workgroup_size(64)
+fn flag(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let k = global_id.x;
+    if k >= arrayLength(&indices) {
+        return;
+    }
+    flags[k] = movable[indices[k]];
+}
+
+@compute @workgroup_size(64)
+fn remap(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let k = global_id.x;
+    if k >= arrayLength(&indices) {
+        return;
+    }
+    let old = indices[k];
+    var slot = old;
+    if movable[old] != 0u {
+        slot = movable_slots[ranks[k]];
+    }
+    new_slot[old] = slot;
+    indices[k] = slot;
+}
+
+@compute @workgroup_size(256)
+fn remap_bonds(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let b = global_id.x;
+    if b >= arrayLength(&bonds) {
+        return;
+    }
+    if b == 0u {
+        allocator.changes += 1u;
+    }
+    if bonds[b].rest_length <= 0.0 {
+        return;
+    }
+    bonds[b].particle_a = new_slot[bonds[b].particle_a];
+    bonds[b].particle_b = new_slot[bonds[b].particle_b];
+}
+
+@compute @workgroup_size(64)
+fn permute(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let old = global_id.x;
+    if old >= arrayLength(&permutation) {
+        return;
+    }
+    let slot = permutation[old];
+    particles[slot] = particles_old[old];
+    forces[slot] = forces_old[old];
+
+    partners[slot] = partners_old[old];
+
+    let id = particle_ids_old[old];
+    particle_ids[slot] = id;
+    particle_slots[id] = slot;
+}
@@ -0,0 +1,286 @@
+// Rigid Body Shape Matching
+// Runs after integration, one workgroup per body:
+//   1. reduce mass, centre of mass, linear momentum and the rest centroid
+//   2. reduce the rotation sums (Σ m q×p, Σ m q·p), angular momentum and rest inertia
+//      (q is taken about the rest centroid, which moves when kinematics.wgsl re-poses
+//      part of a body, e.g. the rudder on the hull)
+//   3. fit the rigid transform and update the body state (thread 0 writes it)
+//   4. springs mode, dynamic bodies: pull joint-driven members (the rudder) towards
+//      their pose with a damped spring, and give the rest of the body the opposite
+//      linear and angular impulse
+//   5. shape matching mode, dynamic bodies: project particles onto the rigid pose
+// Rest offsets are captured from the first measured pose (angle 0).
+// Mirrors rigid_body::fit_rigid_transform.
+
+struct Particle {
+    pos: vec2<f32>,
+    vel: vec2<f32>,
+    mass: f32,
+    density: f32,
+    pressure: f32,
+    z_height: f32,
+    layer_mask: u32,
+    cell_id: u32,
+    _padding: vec2<f32>,
+}
+
+struct SimParams {
+    delta_time: f32,
+    gravity: f32,
+    smoothing_radius: f32,
+    target_density_water: f32,
+    target_density_air: f32,
+    wind_interaction_threshold: f32,
+    _reserved: f32,
+    sheet_extension: f32,
+    bounds: vec4<f32>,
+    integrator: u32,
+    pressure_solver: u32,
+    pressure_tolerance: f32,
+    pressure_max_iterations: u32,
+    max_velocity: f32,
+    kernel: u32,
+    boundary_handling: u32,
+    rigid_bodies: u32, // 0 = springs, 1 = shape matching
+    open_world: u32, // 1 = bounds follow the fleet, fluid wraps along x instead of walls
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
+}
+
+// Per-particle body membership (matches Rust RigidMember)
+struct RigidMember {
+    body: u32,
+    driven: u32, // 1 = moved by a kinematic joint of its own body
+    rest_offset: vec2<f32>,
+}
+
+// Body state (matches Rust RigidBody)
+struct RigidBody {
+    first_particle: u32,
+    particle_count: u32,
+    kind: u32,
+    stiffness: f32,
+    centre_of_mass: vec2<f32>,
+    linear_velocity: vec2<f32>,
+    angle: f32,
+    angular_velocity: f32,
+    mass: f32,
+    inertia: f32,
+    captured: u32,
+    _pad0: u32,
+    _pad1: u32,
+    _pad2: u32,
+}
+
+@group(0) @binding(0) var<storage, read_write> particles: array<Particle>;
+@group(0) @binding(1) var<uniform> params: SimParams;
+@group(0) @binding(2) var<storage, read_write> members: array<RigidMember>;
+@group(0) @binding(3) var<storage, read_write> bodies: array<RigidBody>;
+
+const WORKGROUP_SIZE: u32 = 64u;
+const TAU: f32 = 6.28318530718;
+
+// Natural frequency of the springs-mode joint spring (rad/s, critically damped)
+const JOINT_FREQUENCY: f32 = 20.0;
+// Explicit stability cap on JOINT_FREQUENCY * dt
+const MAX_JOINT_STEP: f32 = 0.5;
+// physics.wgsl does not integrate heavier particles outside shape matching
+const STATIC_MASS: f32 = 10000.0;
+
+// Rigid body modes (matches Rust RigidBodyMode)
+const RIGID_SHAPE_MATCHING: u32 = 1u;
+
+// Body kinds (matches Rust RigidBodyKind)
+const BODY_DYNAMIC: u32 = 0u;
+const BODY_KINEMATIC: u32 = 1u;
+
+var<workgroup> shared_a: array<vec4<f32>, 64>;
+var<workgroup> shared_b: array<vec4<f32>, 64>;
+
+fn cross2(a: vec2<f32>, b: vec2<f32>) -> f32 {
+    return a.x * b.y - a.y * b.x;
+}
+
+fn rotate(v: vec2<f32>, angle: f32) -> vec2<f32> {
+    let c = cos(angle);
+    let s = sin(angle);
+    return vec2<f32>(c * v.x - s * v.y, s * v.x + c * v.y);
+}
+
+fn perp(v: vec2<f32>) -> vec2<f32> {
+    return vec2<f32>(-v.y, v.x);
+}
+
+// Velocity change of the joint spring for a particle `error` away from its pose and
+// `slip` slower than the pose moves. Mirrors rigid_body::joint_kick.
+fn joint_kick(error: vec2<f32>, slip: vec2<f32>, dt: f32) -> vec2<f32> {
+    let w = min(JOINT_FREQUENCY * dt, MAX_JOINT_STEP);
+    return select(vec2<f32>(0.0), w * w / dt * error + 2.0 * w * slip, dt > 0.0);
+}
+
+// Tree-sum shared_a/shared_b into element 0 (call from uniform control flow)
+fn reduce_shared(lid: u32) {
+    workgroupBarrier();
+    for (var stride = WORKGROUP_SIZE / 2u; stride > 0u; stride /= 2u) {
+        if lid < stride {
+            shared_a[lid] += shared_a[lid + stride];
+            shared_b[lid] += shared_b[lid + stride];
+        }
+        workgroupBarrier();
+    }
+}
+
+@compute @workgroup_size(64)
+fn shape_match(
+    @builtin(workgroup_id) workgroup_id: vec3<u32>,
+    @builtin(local_invocation_id) local_id: vec3<u32>,
+) {
+    let lid = local_id.x;
+    let body = bodies[workgroup_id.x];
+    let first = body.first_particle;
+    let count = body.particle_count;
+    let captured = body.captured != 0u;
+    // Every thread has read the old state before thread 0 overwrites it
+    storageBarrier();
+
+    // ---- 1. Mass, centre of mass, linear momentum, rest centroid ----
+    var acc_a = vec4<f32>(0.0);
+    var acc_b = vec4<f32>(0.0);
+    for (var i = lid; i < count; i += WORKGROUP_SIZE) {
+        let p = particles[first + i];
+        acc_a += vec4<f32>(p.mass, p.mass * p.pos, 0.0);
+        // Uncaptured bodies take offsets from the centre of mass: centroid 0
+        let rest = select(vec2<f32>(0.0), members[first + i].rest_offset, captured);
+        acc_b += vec4<f32>(p.mass * p.vel, p.mass * rest);
+    }
+    shared_a[lid] = acc_a;
+    shared_b[lid] = acc_b;
+    reduce_shared(lid);
+
+    let mass = shared_a[0].x;
+    let inv_mass = select(0.0, 1.0 / mass, mass > 0.0);
+    let com = shared_a[0].yz * inv_mass;
+    let momentum_vel = shared_b[0].xy * inv_mass;
+    let rest_centre = shared_b[0].zw * inv_mass;
+    workgroupBarrier();
+
+    // ---- 2. Rotation sums, angular momentum, rest inertia ----
+    // Uncaptured bodies take the current pose as rest pose (angle 0)
+    acc_a = vec4<f32>(0.0);
+    for (var i = lid; i < count; i += WORKGROUP_SIZE) {
+        let p = particles[first + i];
+        let offset = p.pos - com;
+        var rest = members[first + i].rest_offset - rest_centre;
+        if !captured {
+            rest = offset;
+            members[first + i].rest_offset = rest;
+        }
+        acc_a += p.mass * vec4<f32>(
+            cross2(rest, offset),
+            dot(rest, offset),
+            cross2(offset, p.vel - momentum_vel),
+            dot(rest, rest),
+        );
+    }
+    shared_a[lid] = acc_a;
+    shared_b[lid] = vec4<f32>(0.0);
+    reduce_shared(lid);
+
+    // ---- 3. Fit (every thread computes the same result) ----
+    let sums = shared_a[0];
+    let turn = atan2(sums.x, sums.y) - body.angle;
+    let angle = body.angle + turn - TAU * round(turn / TAU);
+    let inertia = sums.w;
+    let dt = params.delta_time;
+
+    var linear_velocity = momentum_vel;
+    var angular_velocity = select(0.0, sums.z / inertia, inertia > 0.0);
+    if body.kind == BODY_KINEMATIC {
+        // Moved by something else: velocities from the change in pose
+        linear_velocity = select(vec2<f32>(0.0), (com - body.centre_of_mass) / dt, captured && dt > 0.0);
+        angular_velocity = select(0.0, (angle - body.angle) / dt, captured && dt > 0.0);
+    }
+
+    if lid == 0u {
+        var state = body;
+        state.centre_of_mass = com;
+        state.linear_velocity = linear_velocity;
+        state.angle = angle;
+        state.angular_velocity = angular_velocity;
+        state.mass = mass;
+        state.inertia = inertia;
+        state.captured = select(0u, 1u, mass > 0.0);
+        bodies[workgroup_id.x] = state;
+    }
+
+    // ---- 4. Springs mode: joint spring on driven members, reaction on the rest ----
+    // The reduction runs for every body so the barriers stay in uniform control flow
+    let springs = params.rigid_bodies != RIGID_SHAPE_MATCHING && body.kind == BODY_DYNAMIC && captured;
+    workgroupBarrier();
+    acc_a = vec4<f32>(0.0);
+    acc_b = vec4<f32>(0.0);
+    for (var i = lid; i < count; i += WORKGROUP_SIZE) {
+        let p = particles[first + i];
+        let member = members[first + i];
+        if !springs || p.mass > STATIC_MASS {
+            continue;
+        }
+        let offset = p.pos - com;
+        if member.driven != 0u {
+            let arm = rotate(member.rest_offset - rest_centre, angle);
+            let rigid_vel = linear_velocity + angular_velocity * perp(arm);
+            let impulse = p.mass * joint_kick(arm - offset, rigid_vel - p.vel, dt);
+            acc_a += vec4<f32>(impulse, cross2(offset, impulse), 0.0);
+        } else {
+            acc_a.w += p.mass;
+            acc_b += vec4<f32>(p.mass * offset, p.mass * dot(offset, offset), 0.0);
+        }
+    }
+    shared_a[lid] = acc_a;
+    shared_b[lid] = acc_b;
+    reduce_shared(lid);
+
+    if springs {
+        // Reaction about the centre of the undriven members
+        let impulse = shared_a[0].xy;
+        let rest_mass = shared_a[0].w;
+        let centre = select(vec2<f32>(0.0), shared_b[0].xy / rest_mass, rest_mass > 0.0);
+        let rest_inertia = shared_b[0].z - rest_mass * dot(centre, centre);
+        let torque = shared_a[0].z - cross2(centre, impulse);
+        for (var i = lid; i < count; i += WORKGROUP_SIZE) {
+            var p = particles[first + i];
+            let member = members[first + i];
+            if p.mass > STATIC_MASS {
+                continue;
+            }
+            let offset = p.pos - com;
+            if member.driven != 0u {
+                let arm = rotate(member.rest_offset - rest_centre, angle);
+                let rigid_vel = linear_velocity + angular_velocity * perp(arm);
+                p.vel += joint_kick(arm - offset, rigid_vel - p.vel, dt);
+            } else if rest_mass > 0.0 {
+                let spin = select(0.0, torque / rest_inertia, rest_inertia > 0.0);
+                p.vel -= impulse / rest_mass + spin * perp(offset - centre);
+            }
+            particles[first + i] = p;
+        }
+        return;
+    }
+
+    // ---- 5. Projection onto the rigid pose ----
+    let project = params.rigid_bodies == RIGID_SHAPE_MATCHING && body.kind == BODY_DYNAMIC && captured;
+    if !project {
+        return;
+    }
+    let alpha = clamp(body.stiffness, 0.0, 1.0);
+    for (var i = lid; i < count; i += WORKGROUP_SIZE) {
+        var p = particles[first + i];
+        let arm = rotate(members[first + i].rest_offset - rest_centre, angle);
+        let goal = com + arm;
+        let rigid_vel = linear_velocity + angular_velocity * vec2<f32>(-arm.y, arm.x);
+        p.pos = mix(p.pos, goal, alpha);
+        p.vel = mix(p.vel, rigid_vel, alpha);
+        particles[first + i] = p;
+    }
+}
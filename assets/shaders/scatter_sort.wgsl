@@ -1,5 +1,9 @@
 // Scatter Sort Shader (Pass 3 of Counting Sort)
 // Places each particle index at the correct sorted position
+// Claims slots from cell_cursor (a copy of the cell offsets made by the prefix sum), so
+// cell_offsets stays intact for the neighbour search. The atomics hand out slots in
+// whatever order the invocations run, so `sort_cells` then puts each cell's indices in
+// particle order: neighbour sums and the reorder permutation repeat run to run.
 
 struct Particle {
     pos: vec2<f32>,
@@ -14,8 +18,9 @@ struct Particle {
 }
 
 @group(0) @binding(0) var<storage, read> particles: array<Particle>;
-@group(0) @binding(1) var<storage, read_write> cell_offsets: array<atomic<u32>>;
+@group(0) @binding(1) var<storage, read_write> cell_cursor: array<atomic<u32>>;
 @group(0) @binding(2) var<storage, read_write> sorted_indices: array<u32>;
+@group(0) @binding(3) var<storage, read> cell_offsets: array<u32>;  // Cells + end sentinel
 
 @compute @workgroup_size(64)
 fn scatter(@builtin(global_invocation_id) global_id: vec3<u32>) {
@@ -28,10 +33,30 @@ fn scatter(@builtin(global_invocation_id) global_id: vec3<u32>) {
     
     let p = particles[idx];
     
-    // Atomically get and increment the offset for this cell
+    // Atomically get and increment the cursor for this cell
     // This gives us the position to write this particle's index
-    let write_pos = atomicAdd(&cell_offsets[p.cell_id], 1u);
+    let write_pos = atomicAdd(&cell_cursor[p.cell_id], 1u);
     
     // Write particle index to sorted position
     sorted_indices[write_pos] = idx;
 }
+
+@compute @workgroup_size(256)
+fn sort_cells(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let cell = global_id.x;
+    if cell + 1u >= arrayLength(&cell_offsets) {
+        return;
+    }
+    // Cells hold a few dozen particles at most: insertion sort
+    let start = cell_offsets[cell];
+    let end = cell_offsets[cell + 1u];
+    for (var i = start + 1u; i < end; i++) {
+        let particle = sorted_indices[i];
+        var j = i;
+        while j > start && sorted_indices[j - 1u] > particle {
+            sorted_indices[j] = sorted_indices[j - 1u];
+            j--;
+        }
+        sorted_indices[j] = particle;
+    }
+}
@@ -0,0 +1,92 @@
+// Shared SPH smoothing kernels, imported by density, forces, PCISPH and constraints.
+// `kernel` is SimParams.kernel (matches Rust SphKernel, CPU mirror in kernels.rs).
+// All kernels are 2D-normalised with compact support 2h (the grid cell size).
+const KERNEL_CUBIC_SPLINE: u32 = 0u;
+const KERNEL_WENDLAND_C2: u32 = 1u;
+const KERNEL_WENDLAND_C4: u32 = 2u;
+const KERNEL_POLY6_SPIKY: u32 = 3u;
+
+fn kernel_support(h: f32) -> f32 {
+    return 2.0 * h;
+}
+
+// W(r)
+fn kernel_w(kernel: u32, r: f32, h: f32) -> f32 {
+    let s = kernel_support(h);
+    if r >= s {
+        return 0.0;
+    }
+    let q = r / s;
+    let sigma = 1.0 / (3.14159265359 * s * s);
+    var w = 0.0;
+    switch kernel {
+        case KERNEL_CUBIC_SPLINE: {
+            let t = 1.0 - q;
+            w = select(2.0 * t * t * t, 1.0 - 6.0 * q * q + 6.0 * q * q * q, q < 0.5) * 40.0 / 7.0;
+        }
+        case KERNEL_WENDLAND_C4: {
+            let t2 = (1.0 - q) * (1.0 - q);
+            w = 9.0 * t2 * t2 * t2 * (1.0 + 6.0 * q + 35.0 / 3.0 * q * q);
+        }
+        case KERNEL_POLY6_SPIKY: {
+            let t = 1.0 - q * q;
+            w = 4.0 * t * t * t;
+        }
+        default: {
+            // Wendland C2
+            let t2 = (1.0 - q) * (1.0 - q);
+            w = 7.0 * t2 * t2 * (1.0 + 4.0 * q);
+        }
+    }
+    return sigma * w;
+}
+
+// dW/dr (<= 0); poly6+spiky uses the spiky gradient
+fn kernel_dw(kernel: u32, r: f32, h: f32) -> f32 {
+    let s = kernel_support(h);
+    if r >= s {
+        return 0.0;
+    }
+    let q = r / s;
+    let sigma = 1.0 / (3.14159265359 * s * s * s);
+    let t = 1.0 - q;
+    var dw = 0.0;
+    switch kernel {
+        case KERNEL_CUBIC_SPLINE: {
+            dw = select(-6.0 * t * t, -12.0 * q + 18.0 * q * q, q < 0.5) * 40.0 / 7.0;
+        }
+        case KERNEL_WENDLAND_C4: {
+            let t2 = t * t;
+            dw = 9.0 * (-56.0 / 3.0) * q * (1.0 + 5.0 * q) * t2 * t2 * t;
+        }
+        case KERNEL_POLY6_SPIKY: {
+            dw = -30.0 * t * t;
+        }
+        default: {
+            // Wendland C2
+            dw = 7.0 * -20.0 * q * t * t * t;
+        }
+    }
+    return sigma * dw;
+}
+
+// ∇W for r = x_i - x_j
+fn kernel_gradient(kernel: u32, r: vec2<f32>, r_len: f32, h: f32) -> vec2<f32> {
+    if r_len < 1e-6 {
+        return vec2<f32>(0.0, 0.0);
+    }
+    return kernel_dw(kernel, r_len, h) * (r / r_len);
+}
+
+// Viscosity Laplacian weight: Müller viscosity kernel for poly6+spiky,
+// Brookshaw -2 (dW/dr) / r otherwise
+fn kernel_laplacian(kernel: u32, r: f32, h: f32) -> f32 {
+    let s = kernel_support(h);
+    if r >= s || r < 1e-6 {
+        return 0.0;
+    }
+    if kernel == KERNEL_POLY6_SPIKY {
+        return 40.0 / (3.14159265359 * s * s * s * s * s) * (s - r);
+    }
+    return -2.0 * kernel_dw(kernel, r, h) / r;
+}
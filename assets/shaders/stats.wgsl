@@ -0,0 +1,200 @@
+// Simulation Statistics
+// Runs once per step after the physics stage (integration, kinematics, rigid bodies):
+//   reduce
+// A single workgroup strides over every particle and bond, reduces in shared memory and
+// writes the finished stats (matches Rust StatsState), so no float atomics are needed and
+// the sums do not depend on scheduling. Broken bonds are the growth of the break log head
+// since the previous reduction.
+
+struct Particle {
+    pos: vec2<f32>,
+    vel: vec2<f32>,
+    mass: f32,
+    density: f32,
+    pressure: f32,
+    z_height: f32,
+    layer_mask: u32,
+    cell_id: u32,
+    _padding: vec2<f32>,
+}
+
+struct Bond {
+    particle_a: u32,
+    particle_b: u32,
+    rest_length: f32,
+    stiffness: f32,
+    breaking_strain: f32,
+    bond_type: u32,
+    is_active: u32,
+    yield_strain: f32,
+    damage: f32,
+    compressive_breaking_strain: f32,
+    shear_limit: f32,
+    _padding: u32,
+}
+
+// Head of the bond break log (matches Rust BondBreakLogHeader; the records follow)
+struct BondBreakLogHeader {
+    head: u32,
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
+}
+
+// Per-step statistics (matches Rust StatsState). Indexed by material where per material.
+struct StatsState {
+    kinetic_energy: array<f32, 5>,
+    mean_density: array<f32, 5>,
+    max_density: array<f32, 5>,
+    particle_count: array<u32, 5>,
+    max_speed: f32,
+    mean_speed: f32,
+    min_pressure: f32,
+    max_pressure: f32,
+    active_bonds: u32,
+    broken_bonds: u32,
+    nan_count: u32,
+    break_head: u32,            // Break log head at the last reduction
+    step: u32,                  // Reductions so far (= steps simulated)
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
+}
+
+@group(0) @binding(0) var<storage, read> particles: array<Particle>;
+@group(0) @binding(1) var<storage, read> bonds: array<Bond>;
+@group(0) @binding(2) var<storage, read> break_log: BondBreakLogHeader;
+@group(0) @binding(3) var<storage, read_write> stats: StatsState;
+
+const WORKGROUP_SIZE: u32 = 64u;
+const MATERIAL_COUNT: u32 = 5u;
+const BIG: f32 = 3.0e38;
+
+var<workgroup> shared_energy: array<f32, 320>;
+var<workgroup> shared_density_sum: array<f32, 320>;
+var<workgroup> shared_density_max: array<f32, 320>;
+var<workgroup> shared_count: array<u32, 320>;
+var<workgroup> shared_speed_sum: array<f32, 64>;
+var<workgroup> shared_speed_max: array<f32, 64>;
+var<workgroup> shared_pressure_min: array<f32, 64>;
+var<workgroup> shared_pressure_max: array<f32, 64>;
+var<workgroup> shared_moving: array<u32, 64>;
+var<workgroup> shared_nan: array<u32, 64>;
+var<workgroup> shared_bonds: array<u32, 64>;
+
+// Material index of a layer mask (matches Rust MaterialType::from_layer_mask)
+fn material_index(mask: u32) -> u32 {
+    if (mask & 1u) != 0u { return 0u; }
+    if (mask & 2u) != 0u { return 1u; }
+    if (mask & 4u) != 0u { return 2u; }
+    if (mask & 8u) != 0u { return 3u; }
+    return 4u;
+}
+
+// By bit pattern: `x != x` may be folded away by fast-math shader compilers
+fn is_nan(x: f32) -> bool {
+    return (bitcast<u32>(x) & 0x7fffffffu) > 0x7f800000u;
+}
+
+// ==================== REDUCE ====================
+@compute @workgroup_size(64)
+fn reduce(@builtin(local_invocation_id) local_id: vec3<u32>) {
+    let lid = local_id.x;
+    let base = lid * MATERIAL_COUNT;
+
+    for (var m = 0u; m < MATERIAL_COUNT; m++) {
+        shared_energy[base + m] = 0.0;
+        shared_density_sum[base + m] = 0.0;
+        shared_density_max[base + m] = 0.0;
+        shared_count[base + m] = 0u;
+    }
+    var speed_sum = 0.0;
+    var speed_max = 0.0;
+    var pressure_min = BIG;
+    var pressure_max = -BIG;
+    var nan_count = 0u;
+    var moving = 0u;
+
+    for (var idx = lid; idx < arrayLength(&particles); idx += WORKGROUP_SIZE) {
+        let p = particles[idx];
+        if is_nan(p.pos.x) || is_nan(p.pos.y) || is_nan(p.vel.x) || is_nan(p.vel.y)
+            || is_nan(p.density) || is_nan(p.pressure) {
+            nan_count += 1u;
+            continue;
+        }
+        let m = base + material_index(p.layer_mask);
+        shared_count[m] += 1u;
+        shared_density_sum[m] += p.density;
+        shared_density_max[m] = max(shared_density_max[m], p.density);
+        pressure_min = min(pressure_min, p.pressure);
+        pressure_max = max(pressure_max, p.pressure);
+        // Static obstacles (see physics.wgsl) carry no kinetic energy or speed
+        if p.mass <= 10000.0 {
+            let speed = length(p.vel);
+            shared_energy[m] += 0.5 * p.mass * speed * speed;
+            speed_sum += speed;
+            speed_max = max(speed_max, speed);
+            moving += 1u;
+        }
+    }
+
+    var active_bonds = 0u;
+    for (var idx = lid; idx < arrayLength(&bonds); idx += WORKGROUP_SIZE) {
+        if bonds[idx].is_active != 0u {
+            active_bonds += 1u;
+        }
+    }
+
+    shared_speed_sum[lid] = speed_sum;
+    shared_speed_max[lid] = speed_max;
+    shared_pressure_min[lid] = pressure_min;
+    shared_pressure_max[lid] = pressure_max;
+    shared_moving[lid] = moving;
+    shared_nan[lid] = nan_count;
+    shared_bonds[lid] = active_bonds;
+    workgroupBarrier();
+
+    for (var stride = WORKGROUP_SIZE / 2u; stride > 0u; stride /= 2u) {
+        if lid < stride {
+            let other = (lid + stride) * MATERIAL_COUNT;
+            for (var m = 0u; m < MATERIAL_COUNT; m++) {
+                shared_energy[base + m] += shared_energy[other + m];
+                shared_density_sum[base + m] += shared_density_sum[other + m];
+                shared_density_max[base + m] = max(shared_density_max[base + m], shared_density_max[other + m]);
+                shared_count[base + m] += shared_count[other + m];
+            }
+            shared_speed_sum[lid] += shared_speed_sum[lid + stride];
+            shared_speed_max[lid] = max(shared_speed_max[lid], shared_speed_max[lid + stride]);
+            shared_pressure_min[lid] = min(shared_pressure_min[lid], shared_pressure_min[lid + stride]);
+            shared_pressure_max[lid] = max(shared_pressure_max[lid], shared_pressure_max[lid + stride]);
+            shared_moving[lid] += shared_moving[lid + stride];
+            shared_nan[lid] += shared_nan[lid + stride];
+            shared_bonds[lid] += shared_bonds[lid + stride];
+        }
+        workgroupBarrier();
+    }
+
+    if lid == 0u {
+        for (var m = 0u; m < MATERIAL_COUNT; m++) {
+            let count = shared_count[m];
+            stats.kinetic_energy[m] = shared_energy[m];
+            stats.mean_density[m] = select(0.0, shared_density_sum[m] / f32(count), count > 0u);
+            stats.max_density[m] = shared_density_max[m];
+            stats.particle_count[m] = count;
+        }
+        let moving_total = shared_moving[0];
+        stats.max_speed = shared_speed_max[0];
+        stats.mean_speed = select(0.0, shared_speed_sum[0] / f32(moving_total), moving_total > 0u);
+        // No valid particle: report 0 rather than the sentinels
+        let valid = shared_pressure_min[0] <= shared_pressure_max[0];
+        stats.min_pressure = select(0.0, shared_pressure_min[0], valid);
+        stats.max_pressure = select(0.0, shared_pressure_max[0], valid);
+        stats.active_bonds = shared_bonds[0];
+        stats.nan_count = shared_nan[0];
+
+        let head = break_log.head;
+        stats.broken_bonds = head - stats.break_head;
+        stats.break_head = head;
+        stats.step += 1u;
+    }
+}
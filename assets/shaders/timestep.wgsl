@@ -0,0 +1,249 @@
+// Adaptive Timestep Controller
+// Runs after all forces are known, just before integration:
+//   reduce_particles + reduce_bonds -> control
+// The reductions take the per-workgroup max in shared memory, then one atomicMax per
+// workgroup. `control` turns the maxima into the dt for the next step (copied into
+// SimParams.delta_time by the node) and resets the running maxima.
+
+struct Particle {
+    pos: vec2<f32>,
+    vel: vec2<f32>,
+    mass: f32,
+    density: f32,
+    pressure: f32,
+    z_height: f32,
+    layer_mask: u32,
+    cell_id: u32,
+    _padding: vec2<f32>,
+}
+
+struct Bond {
+    particle_a: u32,
+    particle_b: u32,
+    rest_length: f32,
+    stiffness: f32,
+    breaking_strain: f32,
+    bond_type: u32,
+    is_active: u32,
+    yield_strain: f32,
+    damage: f32,
+    compressive_breaking_strain: f32,
+    shear_limit: f32,
+    _padding: u32,
+}
+
+struct SimParams {
+    delta_time: f32,
+    gravity: f32,
+    smoothing_radius: f32,
+    target_density_water: f32,
+    target_density_air: f32,
+    wind_interaction_threshold: f32,
+    _reserved: f32,
+    sheet_extension: f32,
+    bounds: vec4<f32>,
+    integrator: u32,
+    pressure_solver: u32,
+    pressure_tolerance: f32,
+    pressure_max_iterations: u32,
+    max_velocity: f32,
+    kernel: u32,
+    boundary_handling: u32,
+    rigid_bodies: u32, // 0 = springs, 1 = shape matching
+    open_world: u32, // 1 = bounds follow the fleet, fluid wraps along x instead of walls
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
+}
+
+// Per-particle force accumulator (matches Rust ParticleForce). Read-only here.
+struct ParticleForce {
+    fluid: vec2<f32>,
+    bond: vec2<f32>,        // Bond + bending forces (bond_gather.wgsl)
+    xsph: vec2<f32>,
+    state: vec2<f32>,
+    state_tag: u32,
+    _padding: u32,
+}
+
+// Controller parameters (matches Rust TimestepParams)
+struct TimestepParams {
+    min_delta_time: f32,
+    max_delta_time: f32,
+    cfl_number: f32,
+    force_number: f32,
+    strain_limit: f32,
+    max_growth: f32,
+    _pad0: f32,
+    _pad1: f32,
+}
+
+// Controller state (matches Rust TimestepState)
+struct TimestepState {
+    max_speed_bits: atomic<u32>,   // Maxima are >= 0, so f32 bits order like u32
+    max_accel_bits: atomic<u32>,
+    max_strain_bits: atomic<u32>,
+    delta_time: f32,
+    last_max_speed: f32,
+    last_max_accel: f32,
+    last_max_strain: f32,
+    limiter: u32,
+}
+
+@group(0) @binding(0) var<storage, read> particles: array<Particle>;
+@group(0) @binding(1) var<storage, read> forces: array<ParticleForce>;
+@group(0) @binding(2) var<storage, read> bonds: array<Bond>;
+@group(0) @binding(3) var<uniform> params: SimParams;
+@group(0) @binding(4) var<uniform> timestep: TimestepParams;
+@group(0) @binding(5) var<storage, read_write> state: TimestepState;
+
+const WORKGROUP_SIZE: u32 = 64u;
+
+// Bond types (matches Rust resources::bond_type)
+const BOND_SHEET: u32 = 2u;
+const BOND_ROPE: u32 = 4u;
+
+// Which criterion set dt (matches Rust TimestepLimit)
+const LIMIT_MAX: u32 = 0u;
+const LIMIT_CFL: u32 = 1u;
+const LIMIT_FORCE: u32 = 2u;
+const LIMIT_STRAIN: u32 = 3u;
+const LIMIT_GROWTH: u32 = 4u;
+const LIMIT_MIN: u32 = 5u;
+
+var<workgroup> shared_speed: array<f32, 64>;
+var<workgroup> shared_accel: array<f32, 64>;
+var<workgroup> shared_strain: array<f32, 64>;
+
+// ==================== REDUCE PARTICLES ====================
+@compute @workgroup_size(64)
+fn reduce_particles(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(local_invocation_id) local_id: vec3<u32>,
+) {
+    let idx = global_id.x;
+    let lid = local_id.x;
+
+    var speed = 0.0;
+    var accel = 0.0;
+    if idx < arrayLength(&particles) {
+        let p = particles[idx];
+        // Static particles never move (see physics.wgsl)
+        if p.mass <= 10000.0 {
+            speed = length(p.vel);
+            accel = length((forces[idx].fluid + forces[idx].bond) / p.mass);
+        }
+    }
+    // NaN must not win the max (it would poison the controller)
+    shared_speed[lid] = select(0.0, speed, speed == speed);
+    shared_accel[lid] = select(0.0, accel, accel == accel);
+    workgroupBarrier();
+
+    for (var stride = WORKGROUP_SIZE / 2u; stride > 0u; stride /= 2u) {
+        if lid < stride {
+            shared_speed[lid] = max(shared_speed[lid], shared_speed[lid + stride]);
+            shared_accel[lid] = max(shared_accel[lid], shared_accel[lid + stride]);
+        }
+        workgroupBarrier();
+    }
+
+    if lid == 0u {
+        atomicMax(&state.max_speed_bits, bitcast<u32>(shared_speed[0]));
+        atomicMax(&state.max_accel_bits, bitcast<u32>(shared_accel[0]));
+    }
+}
+
+// ==================== REDUCE BONDS ====================
+@compute @workgroup_size(64)
+fn reduce_bonds(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(local_invocation_id) local_id: vec3<u32>,
+) {
+    let idx = global_id.x;
+    let lid = local_id.x;
+
+    var strain = 0.0;
+    if idx < arrayLength(&bonds) {
+        let bond = bonds[idx];
+        if bond.is_active != 0u && bond.rest_length > 0.0 {
+            let diff = particles[bond.particle_b].pos - particles[bond.particle_a].pos;
+            // Sheets: trimmed rest length. Slack sheets and ropes carry no strain
+            let is_sheet = bond.bond_type == BOND_SHEET;
+            let rest_length = select(bond.rest_length, bond.rest_length * params.sheet_extension, is_sheet);
+            strain = abs(length(diff) - rest_length) / rest_length;
+            let tension_only = is_sheet || bond.bond_type == BOND_ROPE;
+            if tension_only && length(diff) <= rest_length {
+                strain = 0.0;
+            }
+        }
+    }
+    shared_strain[lid] = select(0.0, strain, strain == strain);
+    workgroupBarrier();
+
+    for (var stride = WORKGROUP_SIZE / 2u; stride > 0u; stride /= 2u) {
+        if lid < stride {
+            shared_strain[lid] = max(shared_strain[lid], shared_strain[lid + stride]);
+        }
+        workgroupBarrier();
+    }
+
+    if lid == 0u {
+        atomicMax(&state.max_strain_bits, bitcast<u32>(shared_strain[0]));
+    }
+}
+
+// ==================== CONTROL ====================
+// Single thread: pick the next dt (mirrors timestep::next_delta_time)
+@compute @workgroup_size(1)
+fn control() {
+    let max_speed = bitcast<f32>(atomicLoad(&state.max_speed_bits));
+    let max_accel = bitcast<f32>(atomicLoad(&state.max_accel_bits));
+    let max_strain = bitcast<f32>(atomicLoad(&state.max_strain_bits));
+    let h = params.smoothing_radius;
+    let previous = params.delta_time;
+
+    var dt = timestep.max_delta_time;
+    var limit = LIMIT_MAX;
+
+    if max_speed > 0.0 {
+        let cfl_dt = timestep.cfl_number * h / max_speed;
+        if cfl_dt < dt {
+            dt = cfl_dt;
+            limit = LIMIT_CFL;
+        }
+    }
+    if max_accel > 0.0 {
+        let force_dt = timestep.force_number * sqrt(h / max_accel);
+        if force_dt < dt {
+            dt = force_dt;
+            limit = LIMIT_FORCE;
+        }
+    }
+    if max_strain > timestep.strain_limit {
+        // Based on max dt, not the previous step, so a bond that stays overstretched
+        // holds dt steady instead of shrinking it every step
+        let strain_dt = timestep.max_delta_time * timestep.strain_limit / max_strain;
+        if strain_dt < dt {
+            dt = strain_dt;
+            limit = LIMIT_STRAIN;
+        }
+    }
+    let growth_dt = previous * timestep.max_growth;
+    if growth_dt < dt {
+        dt = growth_dt;
+        limit = LIMIT_GROWTH;
+    }
+    if dt < timestep.min_delta_time {
+        dt = timestep.min_delta_time;
+        limit = LIMIT_MIN;
+    }
+
+    state.delta_time = dt;
+    state.limiter = limit;
+    state.last_max_speed = max_speed;
+    state.last_max_accel = max_accel;
+    state.last_max_strain = max_strain;
+    atomicStore(&state.max_speed_bits, 0u);
+    atomicStore(&state.max_accel_bits, 0u);
+    atomicStore(&state.max_strain_bits, 0u);
+}
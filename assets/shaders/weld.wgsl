@@ -0,0 +1,361 @@
+// Runtime Bond Creation
+// Dispatched in this order, each entry a separate dispatch:
+// apply_repairs: one thread per queued edit (BondQueue), reactivates broken bonds whose
+//   slot still holds the same particle pair.
+// reset_free_list + collect_free: one thread per allocated slot, lists the slots of
+//   broken bonds (rebuilt every step, so repaired slots never stay listed).
+// apply_edits: one thread per queued edit, adds bonds.
+// weld: one thread per particle, bonds touching, slow particle pairs of a weld rule's
+//   materials. The particle with the lower original id owns the weld and remembers its
+//   bond slot, so a pair welds once while the weld holds (bond slots survive reordering).
+//   A weld whose bond broke, or whose slot was reused, is forgotten first, so the pair
+//   can weld again. Pairs already joined by an active bond (scenario, rope or an earlier
+//   weld) are skipped: the bond adjacency is rebuilt just before this entry, so it lists
+//   every bond in the buffer, including this step's edits and reordering.
+//   Neighbours come from the counting-sort grid, so max_distance should not exceed the
+//   cell size.
+// New bonds take listed free slots first, then grow into the padding after next_free;
+// when both run out bonds are dropped. Every written slot counts as a change, which
+// makes bond_adjacency.wgsl rebuild the bond lists (repairs keep the particle pair, so
+// the lists stay valid). Edits name particles by original id (see reorder.wgsl) and are
+// translated to slots through particle_slots. Mirrors welding::WeldRule::welds.
+
+struct Particle {
+    pos: vec2<f32>,
+    vel: vec2<f32>,
+    mass: f32,
+    density: f32,
+    pressure: f32,
+    z_height: f32,
+    layer_mask: u32,
+    cell_id: u32,
+    _padding: vec2<f32>,
+}
+
+struct Bond {
+    particle_a: u32,
+    particle_b: u32,
+    rest_length: f32,
+    stiffness: f32,
+    breaking_strain: f32,
+    bond_type: u32,
+    is_active: u32,
+    yield_strain: f32,
+    damage: f32,
+    compressive_breaking_strain: f32,
+    shear_limit: f32,
+    _padding: u32,
+}
+
+// Matches Rust welding::BondAllocator
+struct BondAllocator {
+    next_free: atomic<u32>,
+    dropped: atomic<u32>,
+    edit_count: u32,
+    free_count: atomic<u32>,  // Slots listed in free_slots this step
+    free_taken: atomic<u32>,  // Listed slots handed out this step (may overshoot free_count)
+    changes: atomic<u32>,     // Bonds written into slots (bond_adjacency.wgsl rebuilds on change)
+    rebuilt_changes: u32,     // Owned by bond_adjacency.wgsl
+    _padding: u32,
+}
+
+// Matches Rust welding::BondEdit
+struct BondEdit {
+    kind: u32,
+    bond_index: u32,
+    _padding0: u32,
+    _padding1: u32,
+    bond: Bond,
+}
+
+// Matches Rust welding::GpuWeldRule
+struct WeldRule {
+    layer_a: u32,
+    layer_b: u32,
+    max_distance: f32,
+    max_relative_speed: f32,
+    bond_type: u32,
+    stiffness: f32,
+    breaking_strain: f32,
+    _padding: u32,
+}
+
+// Matches Rust welding::GpuWeldRules
+struct WeldRules {
+    count: u32,
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
+    rules: array<WeldRule, 4>,
+}
+
+// Matches Rust welding::WeldPartners
+struct WeldPartners {
+    count: u32,
+    bonds: array<u32, 3>,  // Bond slots of the particle's live welds
+}
+
+struct GridParams {
+    cell_size: f32,
+    grid_width: u32,
+    grid_height: u32,
+    grid_origin_x: f32,
+    grid_origin_y: f32,
+    wrap: u32,
+    _padding: vec3<f32>,
+}
+
+// Rigid body membership (matches Rust RigidMember)
+struct RigidMember {
+    body: u32,
+    driven: u32, // 1 = moved by a kinematic joint of its own body
+    rest_offset: vec2<f32>,
+}
+
+@group(0) @binding(0) var<storage, read> particles: array<Particle>;
+@group(0) @binding(1) var<storage, read_write> bonds: array<Bond>;
+@group(0) @binding(2) var<storage, read_write> allocator: BondAllocator;
+@group(0) @binding(3) var<storage, read> edits: array<BondEdit>;
+@group(0) @binding(4) var<uniform> weld_rules: WeldRules;
+@group(0) @binding(5) var<storage, read_write> weld_partners: array<WeldPartners>;
+@group(0) @binding(6) var<storage, read> indices: array<u32>;
+@group(0) @binding(7) var<storage, read> cell_offsets: array<u32>;
+@group(0) @binding(8) var<uniform> grid: GridParams;
+@group(0) @binding(9) var<storage, read> members: array<RigidMember>;
+@group(0) @binding(10) var<storage, read_write> free_slots: array<u32>;
+@group(0) @binding(11) var<storage, read> particle_slots: array<u32>;  // Original id -> slot
+@group(0) @binding(12) var<storage, read> particle_ids: array<u32>;    // Slot -> original id
+// Bonds per particle, CSR packed: particle_count + 1 offsets, then bond slots
+// (bond_adjacency.wgsl)
+@group(0) @binding(13) var<storage, read> bond_adjacency: array<u32>;
+
+const EDIT_ADD: u32 = 0u;
+const EDIT_REPAIR: u32 = 1u;
+const MAX_WELD_PARTNERS: u32 = 3u;
+const NO_BODY: u32 = 0xFFFFFFFFu;
+const NO_SLOT: u32 = 0xFFFFFFFFu;
+const MIN_REST_LENGTH: f32 = 0.001;
+
+// Claim a freed slot, else a never used one, or count the bond as dropped when the
+// buffer is full (returns NO_SLOT)
+fn allocate_bond(bond: Bond) -> u32 {
+    var slot: u32;
+    let taken = atomicAdd(&allocator.free_taken, 1u);
+    if taken < atomicLoad(&allocator.free_count) {
+        slot = free_slots[taken];
+    } else {
+        slot = atomicAdd(&allocator.next_free, 1u);
+        if slot >= arrayLength(&bonds) {
+            atomicAdd(&allocator.dropped, 1u);
+            return NO_SLOT;
+        }
+    }
+    bonds[slot] = bond;
+    atomicAdd(&allocator.changes, 1u);
+    return slot;
+}
+
+const NO_CELL: u32 = 0xFFFFFFFFu;
+
+// Cell (x, y) of a 3x3 neighbourhood, or NO_CELL off the grid. A wrapping grid (the open
+// world) continues on the opposite side along x only. Mirrors
+// reference::NeighbourGrid::neighbours.
+fn neighbor_cell(x_in: i32, y: i32) -> u32 {
+    let width = i32(grid.grid_width);
+    let height = i32(grid.grid_height);
+    var x = x_in;
+    if grid.wrap != 0u {
+        x = (x + width) % width;
+    }
+    if x < 0 || y < 0 || x >= width || y >= height {
+        return NO_CELL;
+    }
+    return u32(y) * grid.grid_width + u32(x);
+}
+
+@compute @workgroup_size(64)
+fn apply_repairs(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let idx = global_id.x;
+    if idx >= allocator.edit_count || idx >= arrayLength(&edits) {
+        return;
+    }
+    let edit = edits[idx];
+    if edit.kind != EDIT_REPAIR || edit.bond_index >= arrayLength(&bonds) {
+        return;
+    }
+    let particle_count = arrayLength(&particles);
+    if edit.bond.particle_a >= particle_count || edit.bond.particle_b >= particle_count {
+        return;
+    }
+    // A reused slot holds another bond now: the repair no longer applies
+    let bond = bonds[edit.bond_index];
+    if bond.is_active == 0u && bond.rest_length > 0.0
+        && bond.particle_a == particle_slots[edit.bond.particle_a]
+        && bond.particle_b == particle_slots[edit.bond.particle_b] {
+        bonds[edit.bond_index].is_active = 1u;
+        bonds[edit.bond_index].damage = 0.0;
+    }
+}
+
+@compute @workgroup_size(1)
+fn reset_free_list() {
+    atomicStore(&allocator.free_count, 0u);
+    atomicStore(&allocator.free_taken, 0u);
+}
+
+// List the slots of broken bonds below next_free for allocate_bond
+@compute @workgroup_size(256)
+fn collect_free(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let idx = global_id.x;
+    if idx >= min(atomicLoad(&allocator.next_free), arrayLength(&bonds)) || bonds[idx].is_active != 0u {
+        return;
+    }
+    free_slots[atomicAdd(&allocator.free_count, 1u)] = idx;
+}
+
+@compute @workgroup_size(64)
+fn apply_edits(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let idx = global_id.x;
+    if idx >= allocator.edit_count || idx >= arrayLength(&edits) {
+        return;
+    }
+    let edit = edits[idx];
+    if edit.kind != EDIT_ADD {
+        return;
+    }
+
+    let particle_count = arrayLength(&particles);
+    var bond = edit.bond;
+    if bond.particle_a >= particle_count || bond.particle_b >= particle_count
+        || bond.particle_a == bond.particle_b {
+        return;
+    }
+    bond.particle_a = particle_slots[bond.particle_a];
+    bond.particle_b = particle_slots[bond.particle_b];
+    // Non-positive rest length: bond at the current distance
+    if bond.rest_length <= 0.0 {
+        let dist = distance(particles[bond.particle_a].pos, particles[bond.particle_b].pos);
+        bond.rest_length = max(dist, MIN_REST_LENGTH);
+    }
+    bond.is_active = 1u;
+    allocate_bond(bond);
+}
+
+// Whether the weld in bond slot `slot` still joins particle `idx` (owner) to `other`
+fn weld_joins(slot: u32, idx: u32, other: u32) -> bool {
+    let bond = bonds[slot];
+    return bond.is_active != 0u && bond.particle_a == idx && bond.particle_b == other;
+}
+
+// Whether an active bond already joins particles `idx` and `other` (mirrors
+// welding::bonded)
+fn bonded(idx: u32, other: u32) -> bool {
+    let entries = arrayLength(&particles) + 1u;
+    let start = entries + bond_adjacency[idx];
+    let end = entries + bond_adjacency[idx + 1u];
+    for (var i = start; i < end; i++) {
+        let bond = bonds[bond_adjacency[i]];
+        if bond.is_active != 0u && (bond.particle_a == other || bond.particle_b == other) {
+            return true;
+        }
+    }
+    return false;
+}
+
+@compute @workgroup_size(64)
+fn weld(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let idx = global_id.x;
+    let particle_count = arrayLength(&particles);
+    if idx >= particle_count || weld_rules.count == 0u {
+        return;
+    }
+
+    let p = particles[idx];
+    // Forget welds that broke or whose slot now holds another bond. Only this thread
+    // writes weld_partners[idx].
+    let old = weld_partners[idx];
+    var partners: WeldPartners;
+    partners.count = 0u;
+    for (var k = 0u; k < min(old.count, MAX_WELD_PARTNERS); k++) {
+        let bond = bonds[old.bonds[k]];
+        if bond.is_active != 0u && bond.particle_a == idx {
+            partners.bonds[partners.count] = old.bonds[k];
+            partners.count += 1u;
+        }
+    }
+    if partners.count != old.count {
+        weld_partners[idx] = partners;
+    }
+    if partners.count >= MAX_WELD_PARTNERS {
+        return;
+    }
+    let body = members[idx].body;
+    let id = particle_ids[idx];
+
+    let cell_x = i32(p.cell_id % grid.grid_width);
+    let cell_y = i32(p.cell_id / grid.grid_width);
+
+    for (var dy = -1; dy <= 1; dy++) {
+        for (var dx = -1; dx <= 1; dx++) {
+            let neighbor_cell_id = neighbor_cell(cell_x + dx, cell_y + dy);
+            if neighbor_cell_id == NO_CELL {
+                continue;
+            }
+            let cell_start = cell_offsets[neighbor_cell_id];
+            let cell_end = cell_offsets[neighbor_cell_id + 1u];
+
+            for (var j = cell_start; j < cell_end; j++) {
+                let other = indices[j];
+                // The lower original id owns the pair
+                if particle_ids[other] <= id {
+                    continue;
+                }
+                // The scenario's bonds (or shape matching) already join one body
+                if body != NO_BODY && members[other].body == body {
+                    continue;
+                }
+                var known = false;
+                for (var k = 0u; k < partners.count; k++) {
+                    known = known || weld_joins(partners.bonds[k], idx, other);
+                }
+                if known || bonded(idx, other) {
+                    continue;
+                }
+
+                let q = particles[other];
+                // Bonds do not wrap, so a pair across the open world's seam never welds
+                let dist = distance(p.pos, q.pos);
+                let relative_speed = length(q.vel - p.vel);
+                for (var r = 0u; r < min(weld_rules.count, 4u); r++) {
+                    let rule = weld_rules.rules[r];
+                    let pair = ((p.layer_mask & rule.layer_a) != 0u && (q.layer_mask & rule.layer_b) != 0u)
+                        || ((p.layer_mask & rule.layer_b) != 0u && (q.layer_mask & rule.layer_a) != 0u);
+                    if !pair || dist >= rule.max_distance || relative_speed >= rule.max_relative_speed {
+                        continue;
+                    }
+
+                    var bond: Bond;
+                    bond.particle_a = idx;
+                    bond.particle_b = other;
+                    bond.rest_length = max(dist, MIN_REST_LENGTH);
+                    bond.stiffness = rule.stiffness;
+                    bond.breaking_strain = rule.breaking_strain;
+                    bond.bond_type = rule.bond_type;
+                    bond.is_active = 1u;
+                    let slot = allocate_bond(bond);
+                    if slot == NO_SLOT {
+                        return;
+                    }
+
+                    partners.bonds[partners.count] = slot;
+                    partners.count += 1u;
+                    weld_partners[idx] = partners;
+                    if partners.count >= MAX_WELD_PARTNERS {
+                        return;
+                    }
+                    break;
+                }
+            }
+        }
+    }
+}
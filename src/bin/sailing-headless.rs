@@ -0,0 +1,285 @@
+//! Headless simulation runner.
+//!
+//! Runs the simulation without a window or the particle renderer, on any wgpu adapter
+//! (software rasterisers included), and writes one CSV row of `SimStats` per step:
+//!
+//! ```text
+//! sailing-headless --scenario hurricane --steps 5000 --out stats.csv
+//! ```
+//!
+//! Steps are released one at a time through `SimulationSettings::max_steps`: the next
+//! step runs once the previous step's stats are back, so no step is missing from the CSV.
+//! `--snapshot` also writes the final particles. `--gpu-timing` turns on the timestamp
+//! queries and logs the mean time of every stage over the last `GpuTimings::window`
+//! steps; `--reorder-interval` overrides the scenario's `reorder_interval` (0 = never);
+//! `--open-world` turns on `SimulationSettings::open_world`.
+//!
+//! Exit status: 0 done, 1 NaN particles in the last step, 2 bad arguments, 3 timed out,
+//! 4 could not write the output. No adapter at all is a panic (101).
+
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use bevy::{
+    app::ScheduleRunnerPlugin,
+    prelude::*,
+    render::{
+        renderer::{initialize_renderer, RenderInstance, WgpuWrapper},
+        settings::{RenderCreation, WgpuSettings},
+        RenderPlugin,
+    },
+    window::ExitCondition,
+    winit::WinitPlugin,
+};
+use sailing::simulation::{
+    GpuStage, GpuTimings, Scenario, SimStats, SimulationPlugin, SimulationSettings, SimulationSnapshot, SnapshotRequests,
+};
+
+const EXIT_NAN: u8 = 1;
+const EXIT_USAGE: u8 = 2;
+const EXIT_TIMEOUT: u8 = 3;
+const EXIT_OUTPUT: u8 = 4;
+
+const USAGE: &str = "usage: sailing-headless [--scenario NAME] [--steps N] [--out stats.csv] \
+[--snapshot particles.csv] [--timeout SECONDS] [--fallback] [--gpu-timing] [--reorder-interval N] \
+[--open-world]";
+
+/// Command line options
+struct Options {
+    scenario: Scenario,
+    steps: u32,
+    out: PathBuf,
+    snapshot: Option<PathBuf>,
+    timeout: Duration,
+    /// Force a software adapter (llvmpipe, WARP, ...)
+    fallback: bool,
+    gpu_timing: bool,
+    /// Overrides the scenario's `reorder_interval`
+    reorder_interval: Option<u32>,
+    open_world: bool,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Options {
+            scenario: Scenario::default(),
+            steps: 1000,
+            out: PathBuf::from("stats.csv"),
+            snapshot: None,
+            timeout: Duration::from_secs(600),
+            fallback: false,
+            gpu_timing: false,
+            reorder_interval: None,
+            open_world: false,
+        };
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("{arg} needs a value"));
+            match arg.as_str() {
+                "--scenario" => options.scenario = value()?.parse()?,
+                "--steps" => options.steps = value()?.parse().map_err(|_| "--steps needs a number")?,
+                "--out" => options.out = value()?.into(),
+                "--snapshot" => options.snapshot = Some(value()?.into()),
+                "--timeout" => {
+                    let seconds: u64 = value()?.parse().map_err(|_| "--timeout needs a number of seconds")?;
+                    options.timeout = Duration::from_secs(seconds);
+                }
+                "--fallback" => options.fallback = true,
+                "--gpu-timing" => options.gpu_timing = true,
+                "--reorder-interval" => {
+                    let interval = value()?.parse().map_err(|_| "--reorder-interval needs a number")?;
+                    options.reorder_interval = Some(interval);
+                }
+                "--open-world" => options.open_world = true,
+                _ => return Err(format!("unknown argument {arg}")),
+            }
+        }
+        if options.steps == 0 {
+            return Err("--steps must be at least 1".into());
+        }
+        Ok(options)
+    }
+
+    /// The scenario's settings with the overrides, stepping held until `advance` releases it
+    fn settings(&self) -> SimulationSettings {
+        let scenario = SimulationSettings::for_scenario(self.scenario);
+        SimulationSettings {
+            reorder_interval: self.reorder_interval.unwrap_or(scenario.reorder_interval),
+            gpu_timing: self.gpu_timing,
+            open_world: self.open_world || scenario.open_world,
+            max_steps: Some(0),
+            ..scenario
+        }
+    }
+}
+
+/// Progress of the run
+#[derive(Resource)]
+struct HeadlessRun {
+    steps: u32,
+    out: PathBuf,
+    snapshot: Option<PathBuf>,
+    deadline: Instant,
+    /// One CSV line per step, in order
+    rows: Vec<String>,
+    last_stats: SimStats,
+    snapshot_id: Option<u32>,
+}
+
+fn main() -> AppExit {
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{message}\n{USAGE}");
+            return AppExit::from_code(EXIT_USAGE);
+        }
+    };
+
+    let render_creation = if options.fallback {
+        fallback_renderer()
+    } else {
+        WgpuSettings::default().into()
+    };
+
+    App::new()
+        .add_plugins(
+            DefaultPlugins
+                .set(WindowPlugin {
+                    primary_window: None,
+                    exit_condition: ExitCondition::DontExit,
+                    close_when_requested: false,
+                })
+                .set(RenderPlugin { render_creation, ..default() })
+                .disable::<WinitPlugin>()
+                .disable::<bevy::audio::AudioPlugin>(),
+        )
+        .add_plugins(ScheduleRunnerPlugin::run_loop(Duration::ZERO))
+        .insert_resource(options.settings())
+        .add_plugins(SimulationPlugin)
+        .insert_resource(HeadlessRun {
+            steps: options.steps,
+            out: options.out,
+            snapshot: options.snapshot,
+            deadline: Instant::now() + options.timeout,
+            rows: Vec::new(),
+            last_stats: SimStats::default(),
+            snapshot_id: None,
+        })
+        .add_systems(Update, (advance, finish).chain())
+        .run()
+}
+
+/// Renderer on the adapter wgpu falls back to when asked for a software one
+fn fallback_renderer() -> RenderCreation {
+    let settings = WgpuSettings::default();
+    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+        backends: settings.backends.unwrap_or(wgpu::Backends::all()),
+        flags: settings.instance_flags,
+        ..default()
+    });
+    let options = wgpu::RequestAdapterOptions { force_fallback_adapter: true, ..default() };
+    let (device, queue, adapter_info, adapter) =
+        bevy::tasks::block_on(initialize_renderer(&instance, &settings, &options));
+    RenderCreation::manual(device, queue, adapter_info, adapter, RenderInstance(Arc::new(WgpuWrapper::new(instance))))
+}
+
+/// Record each new step's stats and release the next step
+fn advance(stats: Res<SimStats>, mut run: ResMut<HeadlessRun>, mut settings: ResMut<SimulationSettings>) {
+    if stats.step > run.last_stats.step {
+        run.rows.push(stats.csv_row());
+        run.last_stats = *stats;
+        if stats.step.is_multiple_of(500) {
+            info!("step {}/{}", stats.step, run.steps);
+        }
+    }
+    let next = (run.last_stats.step + 1).min(run.steps);
+    if settings.max_steps != Some(next) {
+        settings.max_steps = Some(next);
+    }
+}
+
+/// Once the last step is recorded (and the snapshot, if asked for, arrived), write the
+/// output and exit
+fn finish(
+    mut run: ResMut<HeadlessRun>,
+    settings: Res<SimulationSettings>,
+    timings: Res<GpuTimings>,
+    mut requests: ResMut<SnapshotRequests>,
+    mut snapshots: EventReader<SimulationSnapshot>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if Instant::now() > run.deadline {
+        error!("timed out after {} of {} steps", run.last_stats.step, run.steps);
+        let _ = write_stats(&run);
+        exit.send(AppExit::from_code(EXIT_TIMEOUT));
+        return;
+    }
+    if run.last_stats.step < run.steps {
+        return;
+    }
+
+    if let Some(path) = run.snapshot.clone() {
+        // The node has stopped, so any snapshot from now on is of the last step
+        let Some(id) = run.snapshot_id else {
+            run.snapshot_id = Some(requests.request_snapshot(false));
+            return;
+        };
+        let Some(snapshot) = snapshots.read().filter(|snapshot| snapshot.id >= id).last() else {
+            return;
+        };
+        if let Err(error) = std::fs::write(&path, particles_csv(snapshot)) {
+            error!("could not write {}: {error}", path.display());
+            exit.send(AppExit::from_code(EXIT_OUTPUT));
+            return;
+        }
+    }
+
+    if let Err(error) = write_stats(&run) {
+        error!("could not write {}: {error}", run.out.display());
+        exit.send(AppExit::from_code(EXIT_OUTPUT));
+        return;
+    }
+    if settings.gpu_timing {
+        log_timings(&timings);
+    }
+    let stats = run.last_stats;
+    info!("{} steps done, kinetic energy {}, {} NaN particles", stats.step, stats.total_kinetic_energy(), stats.nan_count);
+    exit.send(if stats.nan_count > 0 { AppExit::from_code(EXIT_NAN) } else { AppExit::Success });
+}
+
+/// Mean and slowest time of every stage that ran, then the mean step total
+fn log_timings(timings: &GpuTimings) {
+    if timings.is_empty() {
+        warn!("no GPU timings: the adapter has no timestamp query support");
+        return;
+    }
+    for stage in GpuStage::ALL {
+        if let (Some(mean), Some(max)) = (timings.mean(stage), timings.max(stage)) {
+            info!("{:>18} {:7.3} ms  (max {:.3})", stage.label(), mean, max);
+        }
+    }
+    info!("{:>18} {:7.3} ms over {} steps", "total", timings.mean_total(), timings.len());
+}
+
+fn write_stats(run: &HeadlessRun) -> std::io::Result<()> {
+    let mut csv = SimStats::csv_header();
+    for row in &run.rows {
+        csv.push('\n');
+        csv.push_str(row);
+    }
+    csv.push('\n');
+    std::fs::write(&run.out, csv)
+}
+
+fn particles_csv(snapshot: &SimulationSnapshot) -> String {
+    let mut csv = String::from("index,layer_mask,x,y,vx,vy,mass,density,pressure\n");
+    for (index, p) in snapshot.particles.iter().enumerate() {
+        csv.push_str(&format!(
+            "{index},{},{},{},{},{},{},{},{}\n",
+            p.layer_mask, p.pos[0], p.pos[1], p.vel[0], p.vel[1], p.mass, p.density, p.pressure
+        ));
+    }
+    csv
+}
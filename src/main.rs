@@ -4,7 +4,10 @@
 
 use bevy::prelude::*;
 use sailing::render::ParticleRenderPlugin;
-use sailing::simulation::SimulationPlugin;
+use sailing::simulation::{
+    GpuStage, GpuTimings, PressureSolver, PressureSolverStats, RigidBodies, RigidBodyMode,
+    SimulationPlugin, SimulationSettings, TimestepStats, WorldWindow,
+};
 
 fn main() {
     App::new()
@@ -18,8 +21,8 @@ fn main() {
         }))
         .add_plugins(SimulationPlugin)
         .add_plugins(ParticleRenderPlugin)
-        .add_systems(Startup, setup_camera)
-        .add_systems(Update, log_frame)
+        .add_systems(Startup, (setup_camera, setup_profiling_overlay))
+        .add_systems(Update, (log_frame, follow_window, toggle_profiling, update_profiling_overlay))
         .run();
 }
 
@@ -29,14 +32,118 @@ fn setup_camera(mut commands: Commands) {
     commands.insert_resource(FrameCounter(0));
 }
 
+/// Keep the camera on the simulation window (it only moves in the open world)
+fn follow_window(window: Res<WorldWindow>, mut cameras: Query<&mut Transform, With<Camera2d>>) {
+    for mut transform in &mut cameras {
+        transform.translation = window.centre().extend(transform.translation.z);
+    }
+}
+
+/// GPU pass timing overlay (F3)
+#[derive(Component)]
+struct ProfilingOverlay;
+
+fn setup_profiling_overlay(mut commands: Commands) {
+    commands.spawn((
+        ProfilingOverlay,
+        Text::default(),
+        TextFont { font_size: 14.0, ..default() },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            left: Val::Px(8.0),
+            ..default()
+        },
+        Visibility::Hidden,
+    ));
+}
+
+/// F3 turns GPU timing and its overlay on and off
+fn toggle_profiling(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<SimulationSettings>,
+    mut overlay: Query<&mut Visibility, With<ProfilingOverlay>>,
+) {
+    if !keys.just_pressed(KeyCode::F3) {
+        return;
+    }
+    settings.gpu_timing = !settings.gpu_timing;
+    for mut visibility in &mut overlay {
+        *visibility = if settings.gpu_timing { Visibility::Visible } else { Visibility::Hidden };
+    }
+}
+
+/// Mean (and worst) time per stage over the timing window
+fn update_profiling_overlay(
+    settings: Res<SimulationSettings>,
+    timings: Res<GpuTimings>,
+    mut overlay: Query<&mut Text, With<ProfilingOverlay>>,
+) {
+    if !settings.gpu_timing || !timings.is_changed() {
+        return;
+    }
+    let text = if timings.is_empty() {
+        "GPU timing: waiting for timestamps (unsupported on this adapter?)".to_string()
+    } else {
+        let mut text = format!("GPU {:.2} ms/step ({} steps)\n", timings.mean_total(), timings.len());
+        for stage in GpuStage::ALL {
+            if let (Some(mean), Some(max)) = (timings.mean(stage), timings.max(stage)) {
+                text += &format!("{:>18} {:6.3} ms  (max {:.3})\n", stage.label(), mean, max);
+            }
+        }
+        text
+    };
+    for mut overlay_text in &mut overlay {
+        overlay_text.0.clone_from(&text);
+    }
+}
+
 /// Frame counter for logging
 #[derive(Resource)]
 struct FrameCounter(u32);
 
 /// Log every N frames
-fn log_frame(mut counter: ResMut<FrameCounter>) {
+fn log_frame(
+    mut counter: ResMut<FrameCounter>,
+    settings: Res<SimulationSettings>,
+    solver_stats: Res<PressureSolverStats>,
+    timestep_stats: Res<TimestepStats>,
+    rigid_bodies: Res<RigidBodies>,
+) {
     counter.0 += 1;
-    if counter.0 % 60 == 0 {
+    if counter.0.is_multiple_of(60) {
         info!("Frame {}: Simulation running...", counter.0);
+        if settings.pressure_solver == PressureSolver::Pcisph {
+            info!(
+                "  PCISPH: {} iterations, max density error {:.2}%{}",
+                solver_stats.iterations,
+                solver_stats.max_density_error * 100.0,
+                if solver_stats.converged { "" } else { " (not converged)" }
+            );
+        }
+        if settings.timestep.adaptive {
+            info!(
+                "  dt = {:.4}s ({:?}): max speed {:.1}, max accel {:.0}, max strain {:.3}",
+                timestep_stats.delta_time,
+                timestep_stats.limiter,
+                timestep_stats.max_speed,
+                timestep_stats.max_accel,
+                timestep_stats.max_strain
+            );
+        }
+        if settings.rigid_bodies == RigidBodyMode::ShapeMatching {
+            for body in &rigid_bodies.bodies {
+                info!(
+                    "  {}: com ({:.1}, {:.1}), angle {:.3} rad, v ({:.1}, {:.1}), ω {:.3} rad/s",
+                    body.name,
+                    body.centre_of_mass.x,
+                    body.centre_of_mass.y,
+                    body.angle,
+                    body.linear_velocity.x,
+                    body.linear_velocity.y,
+                    body.angular_velocity
+                );
+            }
+        }
     }
 }
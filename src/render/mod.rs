@@ -322,6 +322,7 @@ pub fn prepare_pipeline(
 }
 
 /// Queue particles and bonds for rendering
+#[allow(clippy::too_many_arguments)]
 pub fn queue_particles(
     mut transparent_phases: ResMut<ViewSortedRenderPhases<Transparent2d>>,
     particle_query: Query<Entity, With<ExtractedParticles>>,
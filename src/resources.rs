@@ -2,6 +2,7 @@
 //!
 //! All structs use `#[repr(C)]` and implement `Pod`/`Zeroable` for GPU buffer compatibility.
 
+use bevy::math::Vec2;
 use bytemuck::{Pod, Zeroable};
 
 /// The fundamental particle in the SPH simulation.
@@ -91,6 +92,22 @@ impl Particle {
             _padding: [0.0; 2],
         }
     }
+
+    /// Create a new rope particle (an intermediate link of a `RopeBuilder` chain). Ropes
+    /// collide as hull material; mass and interaction height come from the rope.
+    pub fn new_rope(pos: [f32; 2], vel: [f32; 2], mass: f32, z_height: f32) -> Self {
+        Self {
+            pos,
+            vel,
+            mass,
+            density: 0.0,
+            pressure: 0.0,
+            z_height,
+            layer_mask: layer::HULL,
+            cell_id: 0,
+            _padding: [0.0; 2],
+        }
+    }
 }
 
 /// Layer mask constants for particle types
@@ -103,6 +120,20 @@ pub mod layer {
     pub const MAST: u32 = 16;
 }
 
+/// Bond type values (`Bond.bond_type`)
+pub mod bond_type {
+    /// Rigid structure: hull, mast, spar
+    pub const HULL: u32 = 0;
+    /// Sail cloth
+    pub const SAIL: u32 = 1;
+    /// Sheet line: tension-only, rest length scaled by `SimParams.sheet_extension`
+    pub const SHEET: u32 = 2;
+    /// Mast step / parrel: very strong joint between structures
+    pub const FUSE: u32 = 3;
+    /// Rope segment: tension-only (mooring, halyards, tow lines; see `ropes`)
+    pub const ROPE: u32 = 4;
+}
+
 /// Grid parameters for spatial indexing (neighbor search)
 #[repr(C)]
 #[derive(Clone, Copy, Pod, Zeroable, Debug)]
@@ -117,9 +148,13 @@ pub struct GridParams {
     pub grid_origin_x: f32,
     /// World Y coordinate of grid origin (bottom edge)
     pub grid_origin_y: f32,
+    /// 1 = the grid wraps along x (the open world, whose window is exactly the grid): the
+    /// neighbour search continues across the left and right edges and x offsets take the
+    /// short way round. The bottom and top edges never wrap.
+    pub wrap: u32,
     /// Padding for WGSL vec3 alignment (total 48 bytes)
-    /// Note: WGSL vec3<f32> has 16-byte alignment, so we need 7 f32s padding
-    pub _padding: [f32; 7],
+    /// Note: WGSL vec3<f32> has 16-byte alignment, so we need 6 f32s padding
+    pub _padding: [f32; 6],
 }
 
 impl Default for GridParams {
@@ -136,8 +171,22 @@ impl Default for GridParams {
             grid_height: (world_height / cell_size).ceil() as u32,
             grid_origin_x,
             grid_origin_y,
-            _padding: [0.0; 7],
+            wrap: 0,
+            _padding: [0.0; 6],
+        }
+    }
+}
+
+impl GridParams {
+    /// `b - a`, the short way round a grid that wraps along x (`grid_offset` in the
+    /// shaders)
+    pub fn offset(&self, a: impl Into<Vec2>, b: impl Into<Vec2>) -> Vec2 {
+        let mut d = b.into() - a.into();
+        if self.wrap != 0 {
+            let width = self.grid_width as f32 * self.cell_size;
+            d.x -= (d.x / width).round() * width;
         }
+        d
     }
 }
 
@@ -157,14 +206,35 @@ pub struct SimParams {
     pub target_density_air: f32,
     /// Wave height threshold for wind-water interaction
     pub wind_interaction_threshold: f32,
-    /// Rudder angle in radians
-    pub rudder_angle: f32,
-    /// Sheet extension multiplier for sail bonds
+    /// Unused (was the rudder angle; the helm drives the `rudder` kinematic joint).
+    /// Keeps `bounds` 16-byte aligned.
+    pub _reserved: f32,
+    /// Sheet extension: multiplier for sheet bond rest lengths (see `SailControl`)
     pub sheet_extension: f32,
-    /// Screen bounds for boundary reflection (min_x, max_x, min_y, max_y)
+    /// Screen bounds for boundary reflection (min_x, max_x, min_y, max_y); the world window
+    /// when `open_world` is set
     pub bounds: [f32; 4],
-    /// Padding for 16-byte alignment (total size: 64 bytes = 16 f32s)
-    pub _padding: [f32; 4],
+    /// Time integration scheme (see `IntegratorScheme`): 0=Symplectic Euler, 1=Velocity Verlet, 2=Leapfrog
+    pub integrator: u32,
+    /// Pressure solver (see `PressureSolver`): 0=Tait WCSPH, 1=PCISPH
+    pub pressure_solver: u32,
+    /// PCISPH: stop iterating once max relative density error is below this
+    pub pressure_tolerance: f32,
+    /// PCISPH: iteration budget per step
+    pub pressure_max_iterations: u32,
+    /// Speed clamp applied after integration (0 = off)
+    pub max_velocity: f32,
+    /// SPH kernel (see `SphKernel`): 0=Cubic spline, 1=Wendland C2, 2=Wendland C4, 3=Poly6+spiky
+    pub kernel: u32,
+    /// Fluid-solid coupling (see `BoundaryHandling`): 0=Repulsion, 1=Akinci boundary particles
+    pub boundary_handling: u32,
+    /// Rigid structures (see `RigidBodyMode`): 0=Springs, 1=Shape matching
+    pub rigid_bodies: u32,
+    /// `bounds` is a window following the fleet: no side walls, fluid wraps across it along x
+    /// (see `WorldWindow`)
+    pub open_world: u32,
+    /// Padding to a 16-byte multiple (total size: 96 bytes)
+    pub _padding: [u32; 3],
 }
 
 impl Default for SimParams {
@@ -176,10 +246,19 @@ impl Default for SimParams {
             target_density_water: 0.2, 
             target_density_air: 0.02,
             wind_interaction_threshold: 0.5,
-            rudder_angle: 0.0,
+            _reserved: 0.0,
             sheet_extension: 1.0,
             bounds: [-640.0, 640.0, -360.0, 360.0], // 1280x720 centered
-            _padding: [0.0; 4],
+            integrator: 0,    // Symplectic Euler (original behaviour)
+            pressure_solver: 0, // Tait WCSPH (original behaviour)
+            pressure_tolerance: 0.01, // 1% density error
+            pressure_max_iterations: 8,
+            max_velocity: 500.0, // Original MAX_VELOCITY clamp
+            kernel: 1,        // Wendland C2 (original density kernel)
+            boundary_handling: 0, // Soft-sphere repulsion (original behaviour)
+            rigid_bodies: 0,  // Bond lattices (original behaviour)
+            open_world: 0,    // Walled 1280x720 tank (original behaviour)
+            _padding: [0; 3],
         }
     }
 }
@@ -198,11 +277,44 @@ pub struct Bond {
     pub stiffness: f32,
     /// Breaking strain (if (current_len - rest)/rest > this, bond breaks)
     pub breaking_strain: f32,
-    /// Bond type: 0=Hull, 1=Sail, 2=Sheet
+    /// Bond type (see `bond_type`): 0=Hull, 1=Sail, 2=Sheet, 3=Fuse, 4=Rope
     pub bond_type: u32,
     /// Active flag: 1=Active, 0=Broken
     pub is_active: u32,
-    /// Padding for 16-byte alignment? Struct size: 4+4+4+4+4+4+4 = 28 bytes.
-    /// Next multiple of 16 is 32. Need 4 bytes padding.
+    /// Strain past which the bond yields: `rest_length` follows the stretch (0 = elastic)
+    pub yield_strain: f32,
+    /// Accumulated fatigue/plastic damage (0..1); lowers the breaking strains
+    pub damage: f32,
+    /// Compression at which the bond crushes, as a positive strain (0 = never)
+    pub compressive_breaking_strain: f32,
+    /// Relative transverse speed of the two particles that shears the bond (0 = never)
+    pub shear_limit: f32,
+    /// Struct size: 12 * 4 = 48 bytes
+    pub _padding: u32,
+}
+
+/// Per-particle force accumulator (the `ForceBuffer` element).
+///
+/// Every force source writes into this struct before the integrator runs, so the
+/// integrator sees one consistent total force per particle:
+/// - `fluid`: SPH pressure, viscosity, soft-sphere repulsion and sail aerodynamics (forces.wgsl)
+/// - `bond`: Peridynamic bond and bending forces, gathered per particle (bond_gather.wgsl)
+///
+/// Boundary forces are added by the integrator itself. `bond` is overwritten by the
+/// gather every step, so nothing needs clearing.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable, Debug, Default)]
+pub struct ParticleForce {
+    /// SPH + repulsion + aerodynamic force (overwritten every step)
+    pub fluid: [f32; 2],
+    /// Bond + bending force (overwritten every step)
+    pub bond: [f32; 2],
+    /// XSPH velocity correction (a velocity filter, not a force; applied after the kick)
+    pub xsph: [f32; 2],
+    /// Integrator history: previous acceleration (Verlet) or synchronised velocity (Leapfrog)
+    pub state: [f32; 2],
+    /// Scheme that wrote `state`, plus one. 0 = no history (integrator bootstraps)
+    pub state_tag: u32,
+    /// Padding to 8-byte alignment (40 bytes total)
     pub _padding: u32,
 }
@@ -0,0 +1,162 @@
+//! Per-particle adjacency (CSR) for gathering bond and bending forces.
+//!
+//! `bonds.wgsl` and `bending.wgsl` write one float force per bond / triplet instead of
+//! scattering fixed-point atomics into the particles. `bond_gather.wgsl` then sums, for
+//! each particle, the forces of the bonds and triplets it belongs to, in ascending index
+//! order, into `ParticleForce.bond`. No atomics, no truncation, the same sum every run.
+//!
+//! The lists are CSR: particle `i`'s items are `entries[offsets[i]..offsets[i + 1]]`.
+//! On the GPU both live in one `array<u32>` (`packed`: offsets, then entries) to stay
+//! within the storage buffer limit. Both lists are built here at startup; bonds added at
+//! runtime (`welding`) rebuild the bond lists on the GPU (`bond_adjacency.wgsl`, which
+//! scans the counts with `prefix_sum.wgsl` and sorts each list to keep the order). The
+//! rebuild only runs when `BondAllocator.changes` moved.
+
+use bevy::math::Vec2;
+
+use super::bending::AngularSpring;
+use super::grid::exclusive_scan;
+use crate::resources::Bond;
+
+/// Compressed per-particle lists of item indices
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Adjacency {
+    /// `particle_count + 1` offsets into `entries`
+    pub offsets: Vec<u32>,
+    /// Item indices, ascending within each particle's list
+    pub entries: Vec<u32>,
+}
+
+impl Adjacency {
+    /// Build from `(particle, item)` links; links to particles out of range are ignored
+    pub fn build(particle_count: usize, links: impl IntoIterator<Item = (u32, u32)>) -> Self {
+        let mut links: Vec<(u32, u32)> = links
+            .into_iter()
+            .filter(|&(particle, _)| (particle as usize) < particle_count)
+            .collect();
+        links.sort_unstable();
+
+        // Counts, then the same blocked scan the GPU rebuild runs
+        let mut counts = vec![0u32; particle_count];
+        for &(particle, _) in &links {
+            counts[particle as usize] += 1;
+        }
+        let offsets = exclusive_scan(&counts);
+        let entries = links.into_iter().map(|(_, item)| item).collect();
+        Self { offsets, entries }
+    }
+
+    /// GPU layout: the `particle_count + 1` offsets, then the entries, zero-padded to
+    /// `capacity` entries
+    pub fn packed(&self, capacity: usize) -> Vec<u32> {
+        let mut packed = self.offsets.clone();
+        packed.extend_from_slice(&self.entries);
+        packed.resize(self.offsets.len() + capacity.max(self.entries.len()), 0);
+        packed
+    }
+
+    /// Items of `particle`
+    pub fn of(&self, particle: usize) -> &[u32] {
+        &self.entries[self.offsets[particle] as usize..self.offsets[particle + 1] as usize]
+    }
+}
+
+/// Bonds per particle. Unused slots (rest length 0) are left out.
+pub fn bond_adjacency(bonds: &[Bond], particle_count: usize) -> Adjacency {
+    let links = bonds
+        .iter()
+        .enumerate()
+        .filter(|(_, bond)| bond.rest_length > 0.0)
+        .flat_map(|(i, bond)| [(bond.particle_a, i as u32), (bond.particle_b, i as u32)]);
+    Adjacency::build(particle_count, links)
+}
+
+/// Bending triplets per particle
+pub fn triplet_adjacency(springs: &[AngularSpring], particle_count: usize) -> Adjacency {
+    let links = springs.iter().enumerate().flat_map(|(i, spring)| {
+        [spring.particle_a, spring.particle_b, spring.particle_c].map(|particle| (particle, i as u32))
+    });
+    Adjacency::build(particle_count, links)
+}
+
+/// Bond force on `particle` from per-bond forces (force on `particle_a`; b gets the
+/// opposite). CPU mirror of bond_gather.wgsl.
+pub fn gather_bond_force(particle: usize, bonds: &[Bond], bond_forces: &[Vec2], adjacency: &Adjacency) -> Vec2 {
+    adjacency
+        .of(particle)
+        .iter()
+        .map(|&bond| {
+            let force = bond_forces[bond as usize];
+            if bonds[bond as usize].particle_a == particle as u32 {
+                force
+            } else {
+                -force
+            }
+        })
+        .fold(Vec2::ZERO, |sum, force| sum + force)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::bond_type;
+    use crate::simulation::grid::SCAN_BLOCK_SIZE;
+    use bytemuck::Zeroable;
+
+    fn bond(a: u32, b: u32) -> Bond {
+        Bond { particle_a: a, particle_b: b, rest_length: 1.0, bond_type: bond_type::HULL, ..Bond::zeroed() }
+    }
+
+    #[test]
+    fn lists_are_sorted_and_skip_unused_slots() {
+        let bonds = [bond(2, 0), bond(0, 1), Bond::zeroed(), bond(1, 2), bond(3, 0)];
+        let adjacency = bond_adjacency(&bonds, 4);
+        assert_eq!(adjacency.offsets, [0, 3, 5, 7, 8]);
+        assert_eq!(adjacency.of(0), [0, 1, 4]);
+        assert_eq!(adjacency.of(2), [0, 3]);
+
+        let packed = adjacency.packed(10);
+        assert_eq!(packed.len(), 5 + 10);
+        assert_eq!(packed[5..13], [0, 1, 4, 1, 3, 0, 3, 4]);
+
+        let springs = [AngularSpring { particle_a: 1, particle_b: 2, particle_c: 3, ..Default::default() }];
+        assert_eq!(triplet_adjacency(&springs, 4).of(2), [0]);
+    }
+
+    #[test]
+    fn lists_span_several_scan_blocks() {
+        // More particles than one scan block, so the block offsets are exercised
+        let particle_count = 3 * SCAN_BLOCK_SIZE + 17;
+        let bonds: Vec<Bond> = (0..4000u32)
+            .map(|i| bond(i * 7 % particle_count as u32, (i * 13 + 5) % particle_count as u32))
+            .collect();
+        let adjacency = bond_adjacency(&bonds, particle_count);
+        assert_eq!(adjacency.offsets[particle_count] as usize, 2 * bonds.len());
+        for particle in 0..particle_count {
+            let expected: Vec<u32> = (0..bonds.len() as u32)
+                .flat_map(|i| {
+                    let bond = &bonds[i as usize];
+                    [bond.particle_a, bond.particle_b].into_iter().filter(move |&p| p == particle as u32).map(move |_| i)
+                })
+                .collect();
+            assert_eq!(adjacency.of(particle), expected);
+        }
+    }
+
+    #[test]
+    fn gather_matches_scatter() {
+        let bonds: Vec<Bond> = (0..40u32).map(|i| bond(i % 7, (i * 3 + 1) % 7)).filter(|b| b.particle_a != b.particle_b).collect();
+        let forces: Vec<Vec2> = (0..bonds.len()).map(|i| Vec2::new(i as f32 * 0.37, 1.0 - i as f32 * 0.11)).collect();
+
+        let mut scattered = [Vec2::ZERO; 7];
+        for (bond, force) in bonds.iter().zip(&forces) {
+            scattered[bond.particle_a as usize] += *force;
+            scattered[bond.particle_b as usize] -= *force;
+        }
+        let adjacency = bond_adjacency(&bonds, 7);
+        for (particle, expected) in scattered.iter().enumerate() {
+            let gathered = gather_bond_force(particle, &bonds, &forces, &adjacency);
+            assert!((gathered - *expected).length() < 1e-4);
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! Angular (bending) springs over particle triplets.
+//!
+//! A triplet `(a, b, c)` bends at `b`. Its angle is the turn from segment `a→b` to
+//! segment `b→c`, `θ = atan2(d1 × d2, d1 · d2)`: 0 for a straight line, positive
+//! counter-clockwise. `bending.wgsl` computes the restoring forces of
+//! `E = ½ k (θ - θ_rest)²` on all three particles (they sum to zero and carry no
+//! net torque) right after the bond pass; they are gathered with the bond forces. A triplet
+//! bent past its break angle deactivates (the spar snaps; cloth never does).
+//!
+//! Stiffness and break angle come from the triplet's family (`BendingFamily`), so
+//! cloth and spars are tuned separately.
+
+use bevy::math::Vec2;
+use bytemuck::{Pod, Zeroable};
+
+use crate::resources::bond_type;
+
+/// Bending response of one structure family
+#[derive(Clone, Copy, Debug)]
+pub struct BendingFamily {
+    /// Bond type of the structure (stored on the triplet)
+    pub bond_type: u32,
+    /// Angular stiffness (force × length per radian)
+    pub stiffness: f32,
+    /// Bend past the rest angle at which the triplet breaks (radians, 0 = never)
+    pub break_angle: f32,
+}
+
+/// Sail cloth: soft, so the sail billows smoothly, and never breaks
+pub const SAIL_BENDING: BendingFamily = BendingFamily {
+    bond_type: bond_type::SAIL,
+    stiffness: 150_000.0,
+    break_angle: 0.0,
+};
+
+/// Spars: stiff enough to flex a little under load, and snap past ~35°
+pub const SPAR_BENDING: BendingFamily = BendingFamily {
+    bond_type: bond_type::HULL,
+    stiffness: 1_500_000.0,
+    break_angle: 0.6,
+};
+
+/// GPU angular spring (matches `AngularSpring` in bending.wgsl). 32 bytes.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable, Debug, Default)]
+pub struct AngularSpring {
+    pub particle_a: u32,
+    /// Vertex particle (the bend is at b)
+    pub particle_b: u32,
+    pub particle_c: u32,
+    /// Bond type of the family it came from
+    pub family: u32,
+    /// Turn angle at rest (0 = straight)
+    pub rest_angle: f32,
+    pub stiffness: f32,
+    /// Break when |θ - θ_rest| exceeds this (0 = unbreakable)
+    pub break_angle: f32,
+    /// 1 = active, 0 = broken
+    pub is_active: u32,
+}
+
+impl AngularSpring {
+    pub fn new(a: u32, b: u32, c: u32, rest_angle: f32, family: &BendingFamily) -> Self {
+        Self {
+            particle_a: a,
+            particle_b: b,
+            particle_c: c,
+            family: family.bond_type,
+            rest_angle,
+            stiffness: family.stiffness,
+            break_angle: family.break_angle,
+            is_active: 1,
+        }
+    }
+}
+
+/// Forces of one triplet this step (matches `TripletForce` in bending.wgsl). 32 bytes.
+/// Written by the bending pass, gathered per particle with the bond forces.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable, Debug, Default)]
+pub struct TripletForce {
+    pub a: [f32; 2],
+    pub b: [f32; 2],
+    pub c: [f32; 2],
+    pub _padding: [f32; 2],
+}
+
+/// Triplets along a polyline that is straight at rest (a spar or a cloth column)
+pub fn bending_chain(indices: &[u32], family: &BendingFamily) -> Vec<AngularSpring> {
+    indices
+        .windows(3)
+        .map(|w| AngularSpring::new(w[0], w[1], w[2], 0.0, family))
+        .collect()
+}
+
+/// Turn angle at `b` (CPU mirror of bending.wgsl)
+pub fn turn_angle(a: Vec2, b: Vec2, c: Vec2) -> f32 {
+    let (d1, d2) = (b - a, c - b);
+    d1.perp_dot(d2).atan2(d1.dot(d2))
+}
+
+/// Forces on `a`, `b`, `c`, or `None` once the triplet breaks (CPU mirror of bending.wgsl)
+pub fn bending_forces(a: Vec2, b: Vec2, c: Vec2, spring: &AngularSpring) -> Option<[Vec2; 3]> {
+    let (d1, d2) = (b - a, c - b);
+    let (len1, len2) = (d1.length_squared(), d2.length_squared());
+    if len1 < 1e-12 || len2 < 1e-12 {
+        return Some([Vec2::ZERO; 3]);
+    }
+    let bend = wrap_angle(turn_angle(a, b, c) - spring.rest_angle);
+    if spring.break_angle > 0.0 && bend.abs() > spring.break_angle {
+        return None;
+    }
+    // F = -k Δθ ∂θ/∂x with ∂θ/∂a = perp(d1)/|d1|², ∂θ/∂c = perp(d2)/|d2|²
+    let torque = -spring.stiffness * bend;
+    let force_a = torque * d1.perp() / len1;
+    let force_c = torque * d2.perp() / len2;
+    Some([force_a, -(force_a + force_c), force_c])
+}
+
+/// Wrap to [-π, π]
+fn wrap_angle(angle: f32) -> f32 {
+    use std::f32::consts::TAU;
+    angle - TAU * (angle / TAU).round()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spring() -> AngularSpring {
+        AngularSpring::new(0, 1, 2, 0.0, &SPAR_BENDING)
+    }
+
+    #[test]
+    fn straight_chain_is_at_rest() {
+        let forces = bending_forces(Vec2::ZERO, Vec2::X * 8.0, Vec2::X * 16.0, &spring()).unwrap();
+        assert!(forces.iter().all(|f| f.length() < 1e-3));
+        assert_eq!(bending_chain(&[4, 5, 6, 7], &SAIL_BENDING).len(), 2);
+    }
+
+    #[test]
+    fn bent_triplet_is_pushed_straight_without_net_force_or_torque() {
+        let (a, b, c) = (Vec2::ZERO, Vec2::new(8.0, 0.0), Vec2::new(15.0, 3.0));
+        let forces = bending_forces(a, b, c, &spring()).unwrap();
+
+        let net: Vec2 = forces.iter().sum();
+        let torque: f32 = [a, b, c].iter().zip(&forces).map(|(x, f)| x.perp_dot(*f)).sum();
+        assert!(net.length() < 1e-2 * forces[0].length());
+        assert!(torque.abs() < 1e-2 * forces[0].length());
+
+        // A small step along the forces reduces the bend
+        let step = 1e-8;
+        let bent = turn_angle(a, b, c);
+        let relaxed = turn_angle(a + forces[0] * step, b + forces[1] * step, c + forces[2] * step);
+        assert!(relaxed.abs() < bent.abs());
+    }
+
+    #[test]
+    fn spar_snaps_cloth_folds() {
+        let (a, b, c) = (Vec2::ZERO, Vec2::new(8.0, 0.0), Vec2::new(8.0, 8.0)); // 90° bend
+        assert!(bending_forces(a, b, c, &spring()).is_none());
+        let cloth = AngularSpring::new(0, 1, 2, 0.0, &SAIL_BENDING);
+        assert!(bending_forces(a, b, c, &cloth).is_some());
+        assert!((wrap_angle(3.0 * std::f32::consts::PI).abs() - std::f32::consts::PI).abs() < 1e-5);
+    }
+}
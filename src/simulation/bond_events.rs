@@ -0,0 +1,195 @@
+//! Bond break events.
+//!
+//! When `bonds.wgsl` breaks a bond it appends a `BondBreakRecord` to a GPU ring
+//! (`BondBreakLog`): it bumps the atomic `head` and writes slot `head % BOND_BREAK_CAPACITY`.
+//! The whole log is read back asynchronously every frame a staging slot is free, and
+//! `emit_bond_break_events` turns the records written since the last readback into
+//! `BondBroken` events in the main world (sound effects, damage HUDs, test assertions).
+//!
+//! Readbacks can be skipped without losing records; only more than
+//! `BOND_BREAK_CAPACITY` breaks between two readbacks overwrite unread ones, which
+//! is reported as `dropped`.
+
+use bevy::prelude::*;
+use bytemuck::{Pod, Zeroable};
+
+use super::readback::{ReadbackChannel, ReadbackResource, ReadbackRing};
+
+/// Records the GPU ring holds (matches bonds.wgsl)
+pub const BOND_BREAK_CAPACITY: usize = 256;
+
+/// Why a bond broke (`BondBreakRecord.cause`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BreakCause {
+    /// Stretched past its (damaged) breaking strain
+    #[default]
+    Stretched,
+    /// Crushed past its compressive breaking strain
+    Crushed,
+    /// Particles slid past each other faster than its shear limit
+    Sheared,
+    /// Fatigue and plastic damage reached 1
+    Worn,
+}
+
+impl BreakCause {
+    pub fn from_u32(value: u32) -> Self {
+        match value {
+            1 => BreakCause::Crushed,
+            2 => BreakCause::Sheared,
+            3 => BreakCause::Worn,
+            _ => BreakCause::Stretched,
+        }
+    }
+}
+
+/// One break as written by bonds.wgsl (matches `BondBreakRecord` there). 32 bytes.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable, Debug, Default)]
+pub struct BondBreakRecord {
+    pub bond_index: u32,
+    pub bond_type: u32,
+    pub particle_a: u32,
+    pub particle_b: u32,
+    /// Midpoint of the two particles when the bond broke
+    pub position: [f32; 2],
+    /// Strain at the break (negative when crushed)
+    pub strain: f32,
+    /// `BreakCause` as u32
+    pub cause: u32,
+}
+
+/// Header of the GPU break log; `BOND_BREAK_CAPACITY` records follow it
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable, Debug, Default)]
+pub struct BondBreakLogHeader {
+    /// Total breaks ever recorded (wraps at u32::MAX)
+    pub head: u32,
+    pub _padding: [u32; 3],
+}
+
+/// Size of the GPU break log in bytes
+pub const BOND_BREAK_LOG_SIZE: u64 = (std::mem::size_of::<BondBreakLogHeader>()
+    + BOND_BREAK_CAPACITY * std::mem::size_of::<BondBreakRecord>()) as u64;
+
+/// A bond broke on the GPU (arrives a few frames after the step it happened in)
+#[derive(Event, Clone, Copy, Debug)]
+pub struct BondBroken {
+    pub bond_index: u32,
+    /// See `resources::bond_type`
+    pub bond_type: u32,
+    pub particle_a: u32,
+    pub particle_b: u32,
+    pub position: Vec2,
+    pub strain: f32,
+    pub cause: BreakCause,
+}
+
+impl From<&BondBreakRecord> for BondBroken {
+    fn from(record: &BondBreakRecord) -> Self {
+        Self {
+            bond_index: record.bond_index,
+            bond_type: record.bond_type,
+            particle_a: record.particle_a,
+            particle_b: record.particle_b,
+            position: Vec2::from(record.position),
+            strain: record.strain,
+            cause: BreakCause::from_u32(record.cause),
+        }
+    }
+}
+
+/// Records written since `last_head` in a read back log, oldest first, and how many
+/// were overwritten before they could be read. Advances `last_head`.
+pub fn unread_breaks(bytes: &[u8], last_head: &mut u32) -> (Vec<BondBreakRecord>, u32) {
+    let header_size = std::mem::size_of::<BondBreakLogHeader>();
+    if bytes.len() < header_size {
+        return (Vec::new(), 0);
+    }
+    let header: BondBreakLogHeader = bytemuck::pod_read_unaligned(&bytes[..header_size]);
+    let records: Vec<BondBreakRecord> = bytes[header_size..]
+        .chunks_exact(std::mem::size_of::<BondBreakRecord>())
+        .map(bytemuck::pod_read_unaligned)
+        .collect();
+    if records.is_empty() {
+        return (Vec::new(), 0);
+    }
+
+    let written = header.head.wrapping_sub(*last_head);
+    let unread = written.min(records.len() as u32);
+    let start = header.head.wrapping_sub(unread);
+    *last_head = header.head;
+    let breaks = (0..unread)
+        .map(|i| records[(start.wrapping_add(i) as usize) % records.len()])
+        .collect();
+    (breaks, written - unread)
+}
+
+/// Main-world end of the break log readback
+#[derive(Resource, Clone, Default)]
+pub struct BondBreakChannel(pub ReadbackChannel);
+
+/// Render-world staging ring for the break log
+#[derive(Resource)]
+pub struct BondBreakReadback(pub ReadbackRing);
+
+impl ReadbackResource for BondBreakReadback {
+    fn ring(&self) -> &ReadbackRing {
+        &self.0
+    }
+}
+
+/// Send a `BondBroken` event for every break recorded since the last readback
+pub fn emit_bond_break_events(
+    channel: Res<BondBreakChannel>,
+    mut last_head: Local<u32>,
+    mut events: EventWriter<BondBroken>,
+) {
+    let Some(bytes) = channel.0.take() else {
+        return;
+    };
+    let (breaks, dropped) = unread_breaks(&bytes, &mut last_head);
+    if dropped > 0 {
+        warn!("{} bond breaks overwrote the log before readback", dropped);
+    }
+    events.send_batch(breaks.iter().map(BondBroken::from));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(head: u32, capacity: usize) -> Vec<u8> {
+        let header = BondBreakLogHeader { head, _padding: [0; 3] };
+        let mut bytes = bytemuck::bytes_of(&header).to_vec();
+        for slot in 0..capacity as u32 {
+            // Slot s holds the latest record with bond_index % capacity == s
+            let bond_index = (0..head).rev().find(|i| i % capacity as u32 == slot).unwrap_or(u32::MAX);
+            let record = BondBreakRecord { bond_index, cause: 2, ..Default::default() };
+            bytes.extend_from_slice(bytemuck::bytes_of(&record));
+        }
+        bytes
+    }
+
+    #[test]
+    fn reads_each_break_once_in_order() {
+        let mut last_head = 0;
+        let (breaks, dropped) = unread_breaks(&log(3, 8), &mut last_head);
+        assert_eq!(breaks.iter().map(|b| b.bond_index).collect::<Vec<_>>(), [0, 1, 2]);
+        assert_eq!((dropped, last_head), (0, 3));
+        assert_eq!(BondBroken::from(&breaks[0]).cause, BreakCause::Sheared);
+
+        // A skipped readback loses nothing; wrapping around the ring keeps the order
+        let (breaks, _) = unread_breaks(&log(10, 8), &mut last_head);
+        assert_eq!(breaks.iter().map(|b| b.bond_index).collect::<Vec<_>>(), [3, 4, 5, 6, 7, 8, 9]);
+        assert!(unread_breaks(&log(10, 8), &mut last_head).0.is_empty());
+    }
+
+    #[test]
+    fn overflow_reports_dropped_breaks() {
+        let mut last_head = 2;
+        let (breaks, dropped) = unread_breaks(&log(20, 8), &mut last_head);
+        assert_eq!(breaks.first().map(|b| b.bond_index), Some(12));
+        assert_eq!((breaks.len(), dropped), (8, 10));
+    }
+}
@@ -0,0 +1,267 @@
+//! Peridynamic bond response.
+//!
+//! `bonds.wgsl` turns every active bond into a spring-damper force between its two
+//! particles. The functions here mirror it on the CPU:
+//!
+//! - the effective rest length: sheets (`bond_type::SHEET`) scale theirs by
+//!   `SimParams.sheet_extension` every step, so easing or hauling a sheet trims the sail
+//!   through the bond force rather than by moving particles
+//! - tension-only bonds (sheets and ropes) go slack when shorter than their rest
+//!   length, so the sail can luff and a mooring line can sag
+//! - a bond breaks once its strain exceeds `breaking_strain`, or is crushed past
+//!   `compressive_breaking_strain`; damage lowers both. A bond with a `shear_limit`
+//!   also breaks when its particles slide past each other faster than that
+//! - bonds with a `yield_strain` are elastic-plastic: strain past the yield point
+//!   permanently moves `rest_length` (a dent), and both plastic flow and strain cycled
+//!   above the endurance limit accumulate `damage`, until the bond cracks at 1
+
+use crate::resources::{bond_type, Bond};
+
+/// Spring force clamp (matches bonds.wgsl)
+pub const SPRING_FORCE_LIMIT: f32 = 2_000_000.0;
+
+/// Damping coefficient along the bond (matches bonds.wgsl)
+pub const BOND_DAMPING: f32 = 200.0;
+
+/// Fatigue damage per unit of strain cycled above the endurance limit (matches bonds.wgsl)
+pub const FATIGUE_RATE: f32 = 0.05;
+
+/// Fraction of the yield strain below which cycling causes no fatigue (matches bonds.wgsl)
+pub const ENDURANCE_FRACTION: f32 = 0.5;
+
+/// Response of one bond this step
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BondResponse {
+    /// Force along a→b (positive pulls the particles together)
+    Force(f32),
+    /// Tension-only bond shorter than its rest length: no force
+    Slack,
+    /// Stretched, crushed or sheared past its limits: the bond deactivates
+    Broken,
+}
+
+/// Rest length this step
+pub fn effective_rest_length(bond: &Bond, sheet_extension: f32) -> f32 {
+    if bond.bond_type == bond_type::SHEET {
+        bond.rest_length * sheet_extension
+    } else {
+        bond.rest_length
+    }
+}
+
+/// Breaking strain lowered by accumulated damage
+pub fn effective_breaking_strain(bond: &Bond) -> f32 {
+    bond.breaking_strain * (1.0 - bond.damage).max(0.0)
+}
+
+/// Whether the bond fails at `strain` with particles sliding past each other at
+/// `transverse_speed` (tension-only bonds cannot be crushed)
+pub fn fractures(bond: &Bond, strain: f32, transverse_speed: f32) -> bool {
+    let intact = (1.0 - bond.damage).max(0.0);
+    let crushed = bond.compressive_breaking_strain > 0.0
+        && !is_tension_only(bond)
+        && -strain > bond.compressive_breaking_strain * intact;
+    let sheared = bond.shear_limit > 0.0 && transverse_speed > bond.shear_limit;
+    strain > effective_breaking_strain(bond) || bond.damage >= 1.0 || crushed || sheared
+}
+
+/// Whether the bond only pulls (ropes and sheets)
+pub fn is_tension_only(bond: &Bond) -> bool {
+    bond.bond_type == bond_type::SHEET || bond.bond_type == bond_type::ROPE
+}
+
+/// Bond response for particle distance `dist` and relative velocity `rel_vel`
+/// along a→b, with no sliding (CPU mirror of bonds.wgsl)
+pub fn bond_response(bond: &Bond, dist: f32, rel_vel: f32, sheet_extension: f32) -> BondResponse {
+    sliding_bond_response(bond, dist, rel_vel, 0.0, sheet_extension)
+}
+
+/// `bond_response` with the particles also sliding past each other at `transverse_speed`
+pub fn sliding_bond_response(
+    bond: &Bond,
+    dist: f32,
+    rel_vel: f32,
+    transverse_speed: f32,
+    sheet_extension: f32,
+) -> BondResponse {
+    let rest = effective_rest_length(bond, sheet_extension);
+    let strain = (dist - rest) / rest;
+    if fractures(bond, strain, transverse_speed) {
+        return BondResponse::Broken;
+    }
+    if is_tension_only(bond) && dist <= rest {
+        return BondResponse::Slack;
+    }
+    let spring = (bond.stiffness * (dist - rest)).clamp(-SPRING_FORCE_LIMIT, SPRING_FORCE_LIMIT);
+    BondResponse::Force(spring + BOND_DAMPING * rel_vel)
+}
+
+/// Yield and fatigue for one step: `strain_change` is the strain cycled this step
+/// (|relative velocity along a→b| × dt / rest length). CPU mirror of bonds.wgsl.
+pub fn deform(bond: &mut Bond, dist: f32, strain_change: f32, sheet_extension: f32) {
+    if bond.yield_strain <= 0.0 {
+        return;
+    }
+    let rest = effective_rest_length(bond, sheet_extension);
+    let strain = (dist - rest) / rest;
+
+    if strain.abs() > ENDURANCE_FRACTION * bond.yield_strain {
+        bond.damage += FATIGUE_RATE * strain_change;
+    }
+
+    // Tension-only bonds are slack in compression and cannot yield there
+    let excess = strain.abs() - bond.yield_strain;
+    if excess > 0.0 && !(is_tension_only(bond) && strain < 0.0) {
+        let yielded_rest = dist / (1.0 + bond.yield_strain * strain.signum());
+        bond.rest_length *= yielded_rest / rest;
+        bond.damage += excess / bond.breaking_strain;
+    }
+    bond.damage = bond.damage.min(1.0);
+}
+
+/// One bond step as bonds.wgsl runs it: fracture, then yield and fatigue, then the
+/// force against the (possibly moved) rest length. Deactivates the bond when it breaks.
+pub fn step_bond(
+    bond: &mut Bond,
+    dist: f32,
+    rel_vel: f32,
+    transverse_speed: f32,
+    dt: f32,
+    sheet_extension: f32,
+) -> BondResponse {
+    let response = sliding_bond_response(bond, dist, rel_vel, transverse_speed, sheet_extension);
+    if response == BondResponse::Broken {
+        bond.is_active = 0;
+        return BondResponse::Broken;
+    }
+    let strain_change = (rel_vel * dt).abs() / effective_rest_length(bond, sheet_extension);
+    deform(bond, dist, strain_change, sheet_extension);
+    if bond.damage >= 1.0 {
+        bond.is_active = 0;
+        return BondResponse::Broken;
+    }
+    bond_response(bond, dist, rel_vel, sheet_extension)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bond(bond_type: u32) -> Bond {
+        Bond {
+            particle_a: 0,
+            particle_b: 1,
+            rest_length: 20.0,
+            stiffness: 1000.0,
+            breaking_strain: 0.5,
+            bond_type,
+            is_active: 1,
+            yield_strain: 0.0,
+            damage: 0.0,
+            compressive_breaking_strain: 0.0,
+            shear_limit: 0.0,
+            _padding: 0,
+        }
+    }
+
+    #[test]
+    fn sheet_is_tension_only_and_follows_extension() {
+        let sheet = bond(bond_type::SHEET);
+        assert_eq!(bond_response(&sheet, 15.0, 0.0, 1.0), BondResponse::Slack);
+        assert_eq!(bond_response(&sheet, 22.0, 0.0, 1.0), BondResponse::Force(2000.0));
+
+        // Eased out: the same distance is slack, hauled in: it pulls harder
+        assert_eq!(bond_response(&sheet, 22.0, 0.0, 1.5), BondResponse::Slack);
+        assert_eq!(bond_response(&sheet, 22.0, 0.0, 0.8), BondResponse::Force(6000.0));
+
+        // Breaking strain is measured against the trimmed length
+        assert_eq!(bond_response(&sheet, 22.0, 0.0, 0.7), BondResponse::Broken);
+    }
+
+    #[test]
+    fn rope_is_tension_only_but_not_trimmed() {
+        let rope = bond(bond_type::ROPE);
+        assert_eq!(bond_response(&rope, 15.0, -3.0, 1.0), BondResponse::Slack);
+        assert_eq!(bond_response(&rope, 22.0, 0.0, 1.5), BondResponse::Force(2000.0));
+    }
+
+    #[test]
+    fn yield_dents_the_bond_instead_of_snapping() {
+        let mut hull = bond(bond_type::HULL);
+        hull.yield_strain = 0.1;
+
+        // Stretched 25%: yields, so the new rest length holds it at the yield strain
+        let response = step_bond(&mut hull, 25.0, 0.0, 0.0, 0.01, 1.0);
+        assert!((hull.rest_length - 25.0 / 1.1).abs() < 1e-4);
+        assert_eq!(response, BondResponse::Force(1000.0 * (25.0 - hull.rest_length)));
+        assert!((hull.damage - 0.15 / 0.5).abs() < 1e-5);
+
+        // Released, it stays longer than before (the dent is permanent)
+        assert!(matches!(step_bond(&mut hull, 20.0, 0.0, 0.0, 0.01, 1.0), BondResponse::Force(f) if f < 0.0));
+        assert!(hull.rest_length > 20.0);
+
+        // An elastic bond stretched the same way keeps its rest length
+        let mut elastic = bond(bond_type::HULL);
+        step_bond(&mut elastic, 25.0, 0.0, 0.0, 0.01, 1.0);
+        assert_eq!(elastic.rest_length, 20.0);
+    }
+
+    #[test]
+    fn cycling_accumulates_fatigue_until_it_cracks() {
+        let mut hull = bond(bond_type::HULL);
+        hull.yield_strain = 0.1;
+
+        // Cycle between 6% and 9% strain: above the endurance limit, below yield
+        let mut steps = 0;
+        while hull.is_active == 1 && steps < 100_000 {
+            let (dist, rel_vel) = if steps % 2 == 0 { (21.8, 60.0) } else { (21.2, -60.0) };
+            step_bond(&mut hull, dist, rel_vel, 0.0, 0.01, 1.0);
+            steps += 1;
+        }
+        assert_eq!(hull.rest_length, 20.0);
+        assert_eq!(hull.is_active, 0);
+        // 0.03 strain cycled per step at 5% damage per unit strain; it cracks once the
+        // lowered breaking strain drops under the 9% peak (damage > 0.82)
+        assert!((500..=600).contains(&steps), "cracked after {steps} steps");
+
+        // Half way there, the breaking strain is already lowered
+        let mut worn = bond(bond_type::HULL);
+        worn.damage = 0.5;
+        assert_eq!(effective_breaking_strain(&worn), 0.25);
+        assert_eq!(bond_response(&worn, 26.0, 0.0, 1.0), BondResponse::Broken);
+    }
+
+    #[test]
+    fn crushing_and_shearing_break_structure_but_not_lines() {
+        let mut mast = bond(bond_type::HULL);
+        mast.compressive_breaking_strain = 0.3;
+        mast.shear_limit = 100.0;
+        assert!(matches!(bond_response(&mast, 15.0, 0.0, 1.0), BondResponse::Force(_)));
+        assert_eq!(bond_response(&mast, 13.0, 0.0, 1.0), BondResponse::Broken);
+
+        // Damage lowers the crushing strain as well
+        mast.damage = 0.5;
+        assert_eq!(bond_response(&mast, 15.0, 0.0, 1.0), BondResponse::Broken);
+        mast.damage = 0.0;
+
+        assert_eq!(sliding_bond_response(&mast, 20.0, 0.0, 150.0, 1.0), BondResponse::Broken);
+        assert_eq!(
+            step_bond(&mut mast, 20.0, 0.0, 150.0, 0.01, 1.0),
+            BondResponse::Broken
+        );
+        assert_eq!(mast.is_active, 0);
+
+        // Without limits (the default) nothing changes; a rope is never crushed
+        assert!(matches!(bond_response(&bond(bond_type::HULL), 2.0, 0.0, 1.0), BondResponse::Force(_)));
+        let mut rope = bond(bond_type::ROPE);
+        rope.compressive_breaking_strain = 0.3;
+        assert_eq!(bond_response(&rope, 2.0, 0.0, 1.0), BondResponse::Slack);
+    }
+
+    #[test]
+    fn structural_bonds_push_and_pull() {
+        let hull = bond(bond_type::HULL);
+        assert_eq!(bond_response(&hull, 15.0, 0.0, 2.0), BondResponse::Force(-5000.0));
+        assert_eq!(bond_response(&hull, 20.0, 1.0, 2.0), BondResponse::Force(BOND_DAMPING));
+    }
+}
@@ -0,0 +1,103 @@
+//! Counting-sort grid (CPU mirror).
+//!
+//! Every step the particles are bucketed by cell: count per cell, exclusive prefix sum
+//! into `cell_offsets` (plus an end sentinel), then scatter the particle indices. The
+//! prefix sum (`prefix_sum.wgsl`) is a work-efficient Blelloch scan over blocks of
+//! `SCAN_BLOCK_SIZE` cells, one workgroup per block, followed by a scan of the block totals
+//! and a pass adding each block's offset. That last pass also copies the offsets into a
+//! cursor buffer, which the scatter consumes with `atomicAdd`, so `cell_offsets` is never
+//! disturbed and needs no restore pass. The atomics leave each cell in an arbitrary order,
+//! so a last pass insertion-sorts every cell's slice by particle index: the result is the
+//! same on every run.
+//!
+//! With `SimulationSettings::reorder_interval`, the particles themselves are periodically
+//! moved into the order the scatter produced (see `reorder`).
+
+/// Cells scanned per workgroup (two per thread; matches prefix_sum.wgsl)
+pub const SCAN_BLOCK_SIZE: usize = 512;
+
+/// Workgroups of the block scan (at least one, so the block sum buffer is never empty)
+pub fn scan_block_count(total_cells: usize) -> usize {
+    total_cells.div_ceil(SCAN_BLOCK_SIZE).max(1)
+}
+
+/// Exclusive prefix sum of per-cell counts, with the total appended (`counts.len() + 1`
+/// entries), computed block by block like prefix_sum.wgsl
+pub fn exclusive_scan(counts: &[u32]) -> Vec<u32> {
+    let mut offsets = vec![0u32; counts.len() + 1];
+
+    // scan_blocks: local scans and block totals
+    let mut block_sums: Vec<u32> = counts
+        .chunks(SCAN_BLOCK_SIZE)
+        .enumerate()
+        .map(|(block, chunk)| {
+            let mut sum = 0u32;
+            for (i, &count) in chunk.iter().enumerate() {
+                offsets[block * SCAN_BLOCK_SIZE + i] = sum;
+                sum = sum.wrapping_add(count);
+            }
+            sum
+        })
+        .collect();
+
+    // scan_block_sums: exclusive scan of the totals
+    let mut carry = 0u32;
+    for sum in block_sums.iter_mut() {
+        let total = *sum;
+        *sum = carry;
+        carry = carry.wrapping_add(total);
+    }
+
+    // add_block_offsets
+    for (i, offset) in offsets.iter_mut().take(counts.len()).enumerate() {
+        *offset = offset.wrapping_add(block_sums[i / SCAN_BLOCK_SIZE]);
+    }
+    offsets[counts.len()] = carry;
+    offsets
+}
+
+/// Count, scan and scatter particle indices by cell. Returns `(cell_offsets, indices)`;
+/// within a cell indices are in particle order, as the GPU leaves them after the cell sort.
+pub fn counting_sort(cell_ids: &[u32], total_cells: usize) -> (Vec<u32>, Vec<u32>) {
+    let mut counts = vec![0u32; total_cells];
+    for &cell in cell_ids {
+        counts[cell as usize] += 1;
+    }
+    let offsets = exclusive_scan(&counts);
+
+    let mut cursor = offsets[..total_cells].to_vec();
+    let mut indices = vec![0u32; cell_ids.len()];
+    for (particle, &cell) in cell_ids.iter().enumerate() {
+        indices[cursor[cell as usize] as usize] = particle as u32;
+        cursor[cell as usize] += 1;
+    }
+    (offsets, indices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocked_scan_matches_sequential_scan() {
+        // Block boundaries, and more blocks than one workgroup scans at once
+        for cells in [0, 1, 511, 512, 513, 2304, SCAN_BLOCK_SIZE * SCAN_BLOCK_SIZE + 700] {
+            let counts: Vec<u32> = (0..cells as u32).map(|i| i.wrapping_mul(2_654_435_761) % 5).collect();
+            let mut expected = vec![0u32];
+            for &count in &counts {
+                expected.push(expected.last().unwrap() + count);
+            }
+            assert_eq!(exclusive_scan(&counts), expected, "{cells} cells");
+        }
+        assert_eq!(scan_block_count(0), 1);
+        assert_eq!(scan_block_count(2304), 5);
+    }
+
+    #[test]
+    fn scatter_leaves_offsets_intact() {
+        let cell_ids = [3, 0, 3, 1, 3, 0];
+        let (offsets, indices) = counting_sort(&cell_ids, 5);
+        assert_eq!(offsets, [0, 2, 3, 3, 6, 6]);
+        assert_eq!(indices, [1, 5, 3, 0, 2, 4]);
+    }
+}
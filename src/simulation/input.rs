@@ -1,31 +1,71 @@
-//! Input handling for sail controls.
+//! Input handling for sail and helm controls.
 //!
-//! Handles keyboard input for sail angle control.
+//! Handles keyboard input for sheet trim (A/D) and rudder angle (Left/Right arrows).
 
 use bevy::prelude::*;
 
-/// Resource tracking the current sail angle.
-#[derive(Resource, Default)]
+use super::kinematics::{KinematicDrivers, RUDDER_DRIVER};
+
+/// Resource tracking the sail trim. Copied into `SimParams.sheet_extension` every frame.
+#[derive(Resource, Clone, Copy, Debug)]
 pub struct SailControl {
-    /// Sail angle in radians. 0 = sail perpendicular to wind, positive = clockwise rotation.
-    pub angle: f32,
+    /// Sheet length as a multiple of its rigged length. < 1 = hauled in, > 1 = eased out.
+    pub sheet_extension: f32,
+}
+
+impl Default for SailControl {
+    fn default() -> Self {
+        Self { sheet_extension: 1.0 }
+    }
 }
 
-/// System to handle keyboard input for sail rotation.
+/// System to handle keyboard input for sheet trim.
 pub fn handle_sail_input(
     keyboard: Res<ButtonInput<KeyCode>>,
     mut sail_control: ResMut<SailControl>,
 ) {
-    const ROTATION_SPEED: f32 = 0.03; // radians per frame
-    const MAX_ANGLE: f32 = 1.5; // ~85 degrees
+    const TRIM_SPEED: f32 = 0.01; // extension per frame
+    const MIN_EXTENSION: f32 = 0.5; // hauled in hard
+    const MAX_EXTENSION: f32 = 3.0; // eased right out
 
     if keyboard.pressed(KeyCode::KeyA) {
-        sail_control.angle -= ROTATION_SPEED;
+        sail_control.sheet_extension -= TRIM_SPEED;
     }
     if keyboard.pressed(KeyCode::KeyD) {
-        sail_control.angle += ROTATION_SPEED;
+        sail_control.sheet_extension += TRIM_SPEED;
     }
     
     // Clamp to valid range
-    sail_control.angle = sail_control.angle.clamp(-MAX_ANGLE, MAX_ANGLE);
+    sail_control.sheet_extension = sail_control.sheet_extension.clamp(MIN_EXTENSION, MAX_EXTENSION);
+}
+
+/// Resource tracking the helm: the rudder angle.
+#[derive(Resource, Default)]
+pub struct HelmControl {
+    /// Rudder angle in radians. 0 = rudder on the centreline, positive = counter-clockwise.
+    pub rudder_angle: f32,
+}
+
+/// System to handle keyboard input for the helm.
+pub fn handle_helm_input(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut helm: ResMut<HelmControl>,
+) {
+    const ROTATION_SPEED: f32 = 0.02; // radians per frame
+    const MAX_ANGLE: f32 = 0.6; // ~35 degrees, past that the rudder stalls
+
+    if keyboard.pressed(KeyCode::ArrowLeft) {
+        helm.rudder_angle -= ROTATION_SPEED;
+    }
+    if keyboard.pressed(KeyCode::ArrowRight) {
+        helm.rudder_angle += ROTATION_SPEED;
+    }
+    
+    // Clamp to valid range
+    helm.rudder_angle = helm.rudder_angle.clamp(-MAX_ANGLE, MAX_ANGLE);
+}
+
+/// System to turn the helm into the rudder kinematic driver.
+pub fn apply_helm_control(helm: Res<HelmControl>, mut drivers: ResMut<KinematicDrivers>) {
+    drivers.set_angle(RUDDER_DRIVER, helm.rudder_angle);
 }
@@ -0,0 +1,185 @@
+//! SPH smoothing kernels.
+//!
+//! The kernel is chosen per run with `SimulationSettings.kernel` and passed to the GPU
+//! in `SimParams.kernel`; density, forces and PCISPH evaluate it. The PBD constraints
+//! only take the contact distance from the shared support radius and do no kernel sums,
+//! so they behave the same under every kernel. Every kernel is 2D-normalised with
+//! compact support `2h` (the grid cell size), written in terms of `q = r / support`.
+//! The functions here mirror the `kernel_*` helpers in `shaders/sph_kernels.wgsl`,
+//! which the shaders import.
+
+use std::f32::consts::PI;
+
+/// SPH kernel family. Must match the `KERNEL_*` constants in `sph_kernels.wgsl`.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SphKernel {
+    /// Monaghan M4 cubic spline
+    CubicSpline = 0,
+    /// Wendland C2 (the original density kernel)
+    #[default]
+    WendlandC2 = 1,
+    /// Wendland C4: smoother, more neighbours needed
+    WendlandC4 = 2,
+    /// Müller et al.: poly6 for values, spiky for gradients, viscosity-kernel Laplacian
+    Poly6Spiky = 3,
+}
+
+impl SphKernel {
+    /// All kernels, in GPU index order
+    pub const ALL: [SphKernel; 4] = [
+        SphKernel::CubicSpline,
+        SphKernel::WendlandC2,
+        SphKernel::WendlandC4,
+        SphKernel::Poly6Spiky,
+    ];
+
+    /// Human-readable name for logs and reports
+    pub fn name(self) -> &'static str {
+        match self {
+            SphKernel::CubicSpline => "Cubic spline",
+            SphKernel::WendlandC2 => "Wendland C2",
+            SphKernel::WendlandC4 => "Wendland C4",
+            SphKernel::Poly6Spiky => "Poly6 + spiky",
+        }
+    }
+}
+
+/// Compact support radius for smoothing radius `h`
+pub fn support(h: f32) -> f32 {
+    2.0 * h
+}
+
+/// Kernel value W(r)
+pub fn w(kernel: SphKernel, r: f32, h: f32) -> f32 {
+    let s = support(h);
+    if r >= s {
+        return 0.0;
+    }
+    let q = r / s;
+    let s2 = s * s;
+    match kernel {
+        SphKernel::CubicSpline => {
+            let sigma = 40.0 / (7.0 * PI * s2);
+            if q < 0.5 {
+                sigma * (1.0 - 6.0 * q * q + 6.0 * q * q * q)
+            } else {
+                sigma * 2.0 * (1.0 - q).powi(3)
+            }
+        }
+        SphKernel::WendlandC2 => 7.0 / (PI * s2) * (1.0 - q).powi(4) * (1.0 + 4.0 * q),
+        SphKernel::WendlandC4 => {
+            9.0 / (PI * s2) * (1.0 - q).powi(6) * (1.0 + 6.0 * q + 35.0 / 3.0 * q * q)
+        }
+        SphKernel::Poly6Spiky => 4.0 / (PI * s2) * (1.0 - q * q).powi(3),
+    }
+}
+
+/// Radial derivative dW/dr (<= 0). Poly6Spiky uses the spiky kernel here.
+pub fn dw_dr(kernel: SphKernel, r: f32, h: f32) -> f32 {
+    let s = support(h);
+    if r >= s {
+        return 0.0;
+    }
+    let q = r / s;
+    let s3 = s * s * s;
+    match kernel {
+        SphKernel::CubicSpline => {
+            let sigma = 40.0 / (7.0 * PI * s3);
+            if q < 0.5 {
+                sigma * (-12.0 * q + 18.0 * q * q)
+            } else {
+                sigma * -6.0 * (1.0 - q).powi(2)
+            }
+        }
+        SphKernel::WendlandC2 => 7.0 / (PI * s3) * -20.0 * q * (1.0 - q).powi(3),
+        SphKernel::WendlandC4 => {
+            9.0 / (PI * s3) * (-56.0 / 3.0) * q * (1.0 + 5.0 * q) * (1.0 - q).powi(5)
+        }
+        SphKernel::Poly6Spiky => -30.0 / (PI * s3) * (1.0 - q).powi(2),
+    }
+}
+
+/// Gradient ∇W at offset `r = x_i - x_j`
+pub fn gradient(kernel: SphKernel, r: [f32; 2], h: f32) -> [f32; 2] {
+    let len = (r[0] * r[0] + r[1] * r[1]).sqrt();
+    if len < 1e-6 {
+        return [0.0, 0.0];
+    }
+    let dw = dw_dr(kernel, len, h);
+    [dw * r[0] / len, dw * r[1] / len]
+}
+
+/// Laplacian weight for viscosity: Müller's viscosity kernel for Poly6Spiky,
+/// otherwise Brookshaw's `-2 (dW/dr) / r` approximation (>= 0 for all kernels).
+pub fn laplacian(kernel: SphKernel, r: f32, h: f32) -> f32 {
+    let s = support(h);
+    if r >= s || r < 1e-6 {
+        return 0.0;
+    }
+    match kernel {
+        SphKernel::Poly6Spiky => 40.0 / (PI * s.powi(5)) * (s - r),
+        _ => -2.0 * dw_dr(kernel, r, h) / r,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const H: f32 = 10.0;
+    const STEPS: usize = 20_000;
+
+    /// ∫ f(r) 2πr dr over the support (midpoint rule)
+    fn integrate_2d(f: impl Fn(f32) -> f32) -> f32 {
+        let dr = support(H) / STEPS as f32;
+        (0..STEPS)
+            .map(|i| {
+                let r = (i as f32 + 0.5) * dr;
+                f(r) * 2.0 * PI * r * dr
+            })
+            .sum()
+    }
+
+    #[test]
+    fn kernels_are_normalised_in_2d() {
+        for kernel in SphKernel::ALL {
+            let integral = integrate_2d(|r| w(kernel, r, H));
+            assert!((integral - 1.0).abs() < 1e-3, "{}: ∫W = {integral}", kernel.name());
+        }
+    }
+
+    #[test]
+    fn gradients_integrate_to_normalised_kernels() {
+        // W_g(r) = -∫_r^s dW/dr' dr' is the kernel the gradient belongs to
+        // (W itself, or spiky for Poly6Spiky); it must be normalised too.
+        let s = support(H);
+        let dr = s / STEPS as f32;
+        for kernel in SphKernel::ALL {
+            let mut w_g = vec![0.0f32; STEPS];
+            let mut acc = 0.0;
+            for i in (0..STEPS).rev() {
+                let r = (i as f32 + 0.5) * dr;
+                acc -= dw_dr(kernel, r, H) * dr;
+                w_g[i] = acc;
+            }
+            let integral: f32 = (0..STEPS)
+                .map(|i| w_g[i] * 2.0 * PI * (i as f32 + 0.5) * dr * dr)
+                .sum();
+            assert!((integral - 1.0).abs() < 2e-3, "{}: ∫W_grad = {integral}", kernel.name());
+            assert!(dw_dr(kernel, 0.5 * s, H) < 0.0);
+            assert_eq!(w(kernel, s, H), 0.0);
+        }
+    }
+
+    #[test]
+    fn wendland_c2_matches_original_density_kernel() {
+        // density.wgsl before kernel selection: 7/(4πh²)(1 - q/2)⁴(2q + 1), q = r/h
+        for i in 0..20 {
+            let r = i as f32;
+            let q = r / H;
+            let original = 7.0 / (4.0 * PI * H * H) * (1.0 - q * 0.5).powi(4) * (2.0 * q + 1.0);
+            assert!((w(SphKernel::WendlandC2, r, H) - original).abs() < 1e-7);
+        }
+    }
+}
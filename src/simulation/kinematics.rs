@@ -0,0 +1,282 @@
+//! Kinematic constraints driven from Rust.
+//!
+//! A scenario declares joints (`Scenario::kinematic_joints`): a contiguous particle
+//! range attached to a named pivot rigid body and moved by a named driver. After
+//! integration, `kinematics.wgsl` places every driven particle at
+//!
+//! `x = c + R(θ_pivot) (h + R(angle) q + translation)`
+//!
+//! where `c`, `θ_pivot` are the pivot body's last fitted pose, `h` the joint's hinge
+//! and `q` the particle's rest offset from it, both in the pivot frame, and
+//! `angle`/`translation` the driver values. Velocities are set from the displacement
+//! so fluids see the motion. Rest offsets are captured on the GPU once the pivot body
+//! has been measured: the spawn pose is the pose at zero drive. Drivers are set on the
+//! `KinematicDrivers` resource and copied to the GPU every frame.
+//!
+//! A joint may drive particles that belong to the pivot body itself (the rudder is
+//! part of the hull body). Those particles are not placed: their rest offsets inside a
+//! dynamic body are re-posed and they are marked `RigidMember::driven`. In shape
+//! matching mode the body's shape match moves them; in springs mode a joint spring
+//! pulls them towards the pose (see `rigid_body::drive_joint_members`). Either way the
+//! water pushing on them turns the whole body. Frozen members (mass above the static
+//! threshold in springs mode) are obstacles and are still placed.
+
+use bevy::prelude::*;
+use bytemuck::{Pod, Zeroable};
+
+use super::rigid_body::RigidBodyDesc;
+
+/// Max number of distinct drivers (size of the GPU driver array)
+pub const MAX_KINEMATIC_DRIVERS: usize = 8;
+
+/// Marks a particle that no driver moves
+pub const NO_DRIVER: u32 = u32::MAX;
+
+/// Driver moved by `HelmControl`
+pub const RUDDER_DRIVER: &str = "rudder";
+
+/// A particle range moved by a driver, relative to a pivot body
+#[derive(Clone, Copy, Debug)]
+pub struct KinematicJointDesc {
+    /// Driver name (joints sharing a name move together)
+    pub driver: &'static str,
+    /// Name of the rigid body the rest offsets are relative to
+    pub pivot_body: &'static str,
+    /// Rotation centre at spawn (world position, e.g. the mast or the rudder pintle)
+    pub hinge: Vec2,
+    pub first_particle: u32,
+    pub particle_count: u32,
+}
+
+/// Driver values (matches `KinematicDriver` in kinematics.wgsl). 16 bytes.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable, Debug, Default)]
+pub struct GpuKinematicDriver {
+    /// Rotation about the joint hinge (radians, counter-clockwise)
+    pub angle: f32,
+    pub _padding: f32,
+    /// Offset in the pivot body frame, applied after the rotation
+    pub translation: [f32; 2],
+}
+
+/// Per-particle joint (matches `KinematicTarget` in kinematics.wgsl). 32 bytes.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable, Debug)]
+pub struct KinematicTarget {
+    /// Driver index, or `NO_DRIVER`
+    pub driver: u32,
+    /// Rigid body index of the pivot
+    pub pivot_body: u32,
+    /// 1 once `rest_offset` was captured
+    pub captured: u32,
+    pub _padding: u32,
+    /// Offset from the hinge in the pivot frame, at zero drive
+    pub rest_offset: [f32; 2],
+    /// Hinge: world spawn position until captured, then offset from the pivot centre
+    /// of mass in the pivot frame
+    pub hinge: [f32; 2],
+}
+
+impl Default for KinematicTarget {
+    fn default() -> Self {
+        Self {
+            driver: NO_DRIVER,
+            pivot_body: 0,
+            captured: 0,
+            _padding: 0,
+            rest_offset: [0.0; 2],
+            hinge: [0.0; 2],
+        }
+    }
+}
+
+/// Current value of one driver
+#[derive(Clone, Copy, Debug)]
+pub struct KinematicDriver {
+    pub name: &'static str,
+    pub angle: f32,
+    pub translation: Vec2,
+}
+
+/// Kinematic drivers of the scenario, in GPU index order. Set values from any system;
+/// they are copied to the GPU every frame.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct KinematicDrivers {
+    drivers: Vec<KinematicDriver>,
+}
+
+impl KinematicDrivers {
+    /// One driver per distinct joint driver name, in first-use order
+    pub fn new(joints: &[KinematicJointDesc]) -> Self {
+        let mut drivers: Vec<KinematicDriver> = Vec::new();
+        for joint in joints {
+            if drivers.iter().all(|driver| driver.name != joint.driver) {
+                drivers.push(KinematicDriver {
+                    name: joint.driver,
+                    angle: 0.0,
+                    translation: Vec2::ZERO,
+                });
+            }
+        }
+        if drivers.len() > MAX_KINEMATIC_DRIVERS {
+            warn!("{} kinematic drivers, only {} are used", drivers.len(), MAX_KINEMATIC_DRIVERS);
+            drivers.truncate(MAX_KINEMATIC_DRIVERS);
+        }
+        Self { drivers }
+    }
+
+    /// GPU index of a driver
+    pub fn index(&self, name: &str) -> Option<usize> {
+        self.drivers.iter().position(|driver| driver.name == name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&KinematicDriver> {
+        self.drivers.iter().find(|driver| driver.name == name)
+    }
+
+    /// Set a driver's rotation. Returns false if the scenario has no such driver.
+    pub fn set_angle(&mut self, name: &str, angle: f32) -> bool {
+        self.get_mut(name).map(|driver| driver.angle = angle).is_some()
+    }
+
+    /// Set a driver's translation. Returns false if the scenario has no such driver.
+    pub fn set_translation(&mut self, name: &str, translation: Vec2) -> bool {
+        self.get_mut(name).map(|driver| driver.translation = translation).is_some()
+    }
+
+    fn get_mut(&mut self, name: &str) -> Option<&mut KinematicDriver> {
+        self.drivers.iter_mut().find(|driver| driver.name == name)
+    }
+
+    /// Driver values for the GPU
+    pub fn gpu_drivers(&self) -> [GpuKinematicDriver; MAX_KINEMATIC_DRIVERS] {
+        let mut gpu = [GpuKinematicDriver::default(); MAX_KINEMATIC_DRIVERS];
+        for (slot, driver) in gpu.iter_mut().zip(&self.drivers) {
+            slot.angle = driver.angle;
+            slot.translation = driver.translation.into();
+        }
+        gpu
+    }
+}
+
+/// Joint table for `particle_count` particles. Joints naming an unknown pivot body,
+/// or a driver past `MAX_KINEMATIC_DRIVERS`, are skipped with a warning.
+pub fn kinematic_targets(
+    joints: &[KinematicJointDesc],
+    bodies: &[RigidBodyDesc],
+    particle_count: usize,
+) -> Vec<KinematicTarget> {
+    let drivers = KinematicDrivers::new(joints);
+    let mut targets = vec![KinematicTarget::default(); particle_count];
+    for joint in joints {
+        let pivot = bodies.iter().position(|body| body.name == joint.pivot_body);
+        let (Some(pivot), Some(driver)) = (pivot, drivers.index(joint.driver)) else {
+            warn!("Kinematic joint '{}' (pivot '{}') skipped", joint.driver, joint.pivot_body);
+            continue;
+        };
+        let start = joint.first_particle as usize;
+        let end = (start + joint.particle_count as usize).min(particle_count);
+        for target in &mut targets[start..end] {
+            target.driver = driver as u32;
+            target.pivot_body = pivot as u32;
+            target.hinge = joint.hinge.into();
+        }
+    }
+    targets
+}
+
+/// Offset of a driven particle from the pivot centre of mass, in the pivot frame
+/// (the re-posed rest offset of a particle inside its pivot body)
+pub fn driven_offset(hinge: Vec2, rest_offset: Vec2, driver: &KinematicDriver) -> Vec2 {
+    hinge + Vec2::from_angle(driver.angle).rotate(rest_offset) + driver.translation
+}
+
+/// Position of a driven particle (CPU mirror of kinematics.wgsl). `hinge` and
+/// `rest_offset` are the captured pivot-frame values.
+pub fn driven_position(
+    pivot_centre: Vec2,
+    pivot_angle: f32,
+    hinge: Vec2,
+    rest_offset: Vec2,
+    driver: &KinematicDriver,
+) -> Vec2 {
+    pivot_centre + Vec2::from_angle(pivot_angle).rotate(driven_offset(hinge, rest_offset, driver))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::rigid_body::RigidBodyKind;
+
+    fn body(name: &'static str, first_particle: u32, particle_count: u32) -> RigidBodyDesc {
+        RigidBodyDesc {
+            name,
+            first_particle,
+            particle_count,
+            kind: RigidBodyKind::Dynamic,
+            stiffness: 1.0,
+        }
+    }
+
+    #[test]
+    fn joints_map_to_drivers_and_pivots() {
+        let bodies = [body("hull", 0, 2), body("mast", 2, 1)];
+        let joints = [
+            KinematicJointDesc { driver: "sail", pivot_body: "mast", hinge: Vec2::ZERO, first_particle: 3, particle_count: 2 },
+            KinematicJointDesc { driver: "rudder", pivot_body: "hull", hinge: Vec2::X, first_particle: 5, particle_count: 1 },
+            KinematicJointDesc { driver: "sail", pivot_body: "mast", hinge: Vec2::ZERO, first_particle: 6, particle_count: 1 },
+            KinematicJointDesc { driver: "flag", pivot_body: "masthead", hinge: Vec2::ZERO, first_particle: 7, particle_count: 1 },
+        ];
+        let targets = kinematic_targets(&joints, &bodies, 8);
+        let drivers: Vec<u32> = targets.iter().map(|t| t.driver).collect();
+        assert_eq!(drivers, [NO_DRIVER, NO_DRIVER, NO_DRIVER, 0, 0, 1, 0, NO_DRIVER]);
+        assert_eq!(targets[3].pivot_body, 1);
+        assert_eq!(targets[5].pivot_body, 0);
+        assert_eq!(targets[5].hinge, [1.0, 0.0]);
+
+        let mut values = KinematicDrivers::new(&joints);
+        assert!(values.set_angle("rudder", 0.4));
+        assert!(!values.set_angle("keel", 0.4));
+        assert_eq!(values.gpu_drivers()[1].angle, 0.4);
+    }
+
+    #[test]
+    fn driven_position_rotates_about_pivot_then_follows_it() {
+        let rest = Vec2::new(10.0, 0.0);
+        let mut driver = KinematicDriver { name: "sail", angle: 0.0, translation: Vec2::ZERO };
+
+        // Zero drive on a resting pivot: the spawn pose
+        assert!((driven_position(Vec2::ZERO, 0.0, Vec2::ZERO, rest, &driver) - rest).length() < 1e-5);
+
+        // Quarter turn of the driver
+        driver.angle = std::f32::consts::FRAC_PI_2;
+        let p = driven_position(Vec2::ZERO, 0.0, Vec2::ZERO, rest, &driver);
+        assert!((p - Vec2::new(0.0, 10.0)).length() < 1e-4);
+
+        // The whole joint follows the pivot body's pose
+        let p = driven_position(Vec2::new(5.0, 5.0), std::f32::consts::FRAC_PI_2, Vec2::ZERO, rest, &driver);
+        assert!((p - Vec2::new(-5.0, 5.0)).length() < 1e-4);
+
+        // Translation is along the pivot frame
+        driver.angle = 0.0;
+        driver.translation = Vec2::new(0.0, -3.0);
+        let p = driven_position(Vec2::ZERO, std::f32::consts::FRAC_PI_2, Vec2::ZERO, rest, &driver);
+        assert!((p - Vec2::new(3.0, 10.0)).length() < 1e-4);
+    }
+
+    #[test]
+    fn rudder_turns_about_its_pintle() {
+        // Pintle 20 aft of the hull centre, blade one unit further aft
+        let hinge = Vec2::new(-20.0, 0.0);
+        let rest = Vec2::new(-1.0, 0.0);
+        let mut driver = KinematicDriver { name: RUDDER_DRIVER, angle: 0.0, translation: Vec2::ZERO };
+        assert!((driven_position(Vec2::ZERO, 0.0, hinge, Vec2::ZERO, &driver) - hinge).length() < 1e-5);
+
+        // Helm over: the blade swings, the pintle stays
+        driver.angle = std::f32::consts::FRAC_PI_2;
+        assert!((driven_position(Vec2::ZERO, 0.0, hinge, Vec2::ZERO, &driver) - hinge).length() < 1e-5);
+        let blade = driven_position(Vec2::ZERO, 0.0, hinge, rest, &driver);
+        assert!((blade - Vec2::new(-20.0, -1.0)).length() < 1e-4);
+        assert_eq!(driven_offset(hinge, rest, &driver), blade);
+    }
+}
@@ -1,97 +1,402 @@
 //! Simulation module - GPU compute pipeline for SPH particle physics.
 
+pub mod adjacency;
+pub mod bending;
+pub mod bond_events;
+pub mod bonds;
+pub mod grid;
 pub mod input;
+pub mod integrator;
+pub mod kernels;
+pub mod kinematics;
+pub mod ping_pong;
+pub mod profiling;
 mod physics_config;
+mod pressure_solver;
+pub mod readback;
+pub mod reference;
+pub mod reorder;
+pub mod rigid_body;
+pub mod ropes;
+pub mod snapshot;
+pub mod stats;
 mod scenarios;
+mod settings;
 mod setup;
 mod systems;
+pub mod timestep;
+pub mod welding;
+pub mod world_window;
 
-pub use physics_config::{InteractionProfile, InteractionTable, MaterialType, default_interaction_table};
-pub use input::SailControl;
+pub use physics_config::{
+    boundary_volume, default_interaction_table, BoundaryHandling, InteractionProfile, InteractionTable,
+    MaterialType,
+};
+pub use bond_events::{BondBroken, BreakCause};
+pub use input::{HelmControl, SailControl};
+pub use integrator::IntegratorScheme;
+pub use kernels::SphKernel;
+pub use kinematics::{KinematicDriver, KinematicDrivers};
+pub use pressure_solver::{PressureSolver, PressureSolverStats};
+pub use profiling::{GpuStage, GpuTimings};
+pub use rigid_body::{RigidBodies, RigidBodyKind, RigidBodyMode, RigidBodyState};
+pub use scenarios::{Scenario, SCENARIOS};
+pub use settings::SimulationSettings;
+pub use snapshot::{SimulationSnapshot, SnapshotRequests};
+pub use stats::SimStats;
+pub use timestep::{TimestepLimit, TimestepSettings, TimestepStats};
+pub use welding::{BondQueue, WeldRule, WeldRules};
+pub use world_window::WorldWindow;
 
 use bevy::{
     prelude::*,
     render::{
         render_graph::{RenderGraph, RenderLabel},
-        render_resource::BufferUsages,
-        renderer::RenderQueue,
+        renderer::{RenderDevice, RenderQueue},
         Extract, Render, RenderApp, RenderSet,
     },
 };
-use crate::resources::SimParams;
+use crate::resources::{GridParams, SimParams};
+use bond_events::{BondBreakChannel, BondBreakReadback, BOND_BREAK_LOG_SIZE};
+use pressure_solver::{PressureSolverReadback, PressureSolverState, PressureSolverStatsChannel};
+use profiling::{GpuProfiler, GpuTimingChannel};
+use readback::ReadbackRing;
+use rigid_body::{RigidBodiesChannel, RigidBodyReadback};
+use snapshot::{SnapshotChannel, SnapshotReadback};
+use stats::{SimStatsChannel, StatsReadback, StatsState};
+use timestep::{TimestepReadback, TimestepState, TimestepStatsChannel};
+use welding::{BondEditsApplied, PendingBondEdits, BOND_ALLOCATOR_EDIT_COUNT_OFFSET};
 
 pub use setup::{
-    BondBuffer, CellCountsBuffer, CellOffsetsBuffer, ForceBuffer, GridParamsBuffer, IndexBuffer,
-    InteractionTableBuffer, ParticleBuffer, SimParamsBuffer, BOND_COUNT, PARTICLE_COUNT,
+    AngularSpringBuffer, BondAdjacencyBuffer, BondAllocatorBuffer, BondBreakLogBuffer, BondBuffer, BondEditBuffer,
+    BondForceBuffer, BondFreeListBuffer, BoundaryVolumeBuffer, CellCountsBuffer, CellCursorBuffer, CellOffsetsBuffer, ForceBuffer,
+    GridParamsBuffer, IndexBuffer, InteractionTableBuffer, KinematicDriverBuffer,
+    KinematicTargetBuffer, ParticleBackBuffer, ParticleBuffer, ParticleIdBuffer, PcisphDispatchBuffer, PressureSolverBuffer,
+    PressureSolverStateBuffer, ReorderBuffer, RigidBodyBuffer, RigidMemberBuffer, ScanBlockSumsBuffer, SimParamsBuffer,
+    StatsStateBuffer, TimestepParamsBuffer, TimestepStateBuffer, WeldPartnerBuffer, WeldRuleBuffer, BOND_COUNT,
+    PARTICLE_COUNT,
 };
 
-/// Extracted sail angle for the render app
-#[derive(Resource, Default)]
-pub struct ExtractedSailAngle(pub f32);
+/// Extract kinematic driver values from main app to render app
+fn extract_kinematic_drivers(
+    mut commands: Commands,
+    drivers: Extract<Option<Res<KinematicDrivers>>>,
+) {
+    if let Some(drivers) = drivers.as_ref() {
+        commands.insert_resource((**drivers).clone());
+    }
+}
 
-/// Extract sail angle from main app to render app
-fn extract_sail_angle(
+/// Extract the sail trim from main app to render app
+fn extract_sail_control(
     mut commands: Commands,
     sail_control: Extract<Option<Res<SailControl>>>,
 ) {
-    if let Some(sail) = sail_control.as_ref() {
-        commands.insert_resource(ExtractedSailAngle(sail.angle));
+    if let Some(sail_control) = sail_control.as_ref() {
+        commands.insert_resource(**sail_control);
     }
 }
 
-/// Update SimParams buffer with sail angle from main app
+/// Extract simulation settings from main app to render app
+fn extract_simulation_settings(
+    mut commands: Commands,
+    settings: Extract<Option<Res<SimulationSettings>>>,
+) {
+    if let Some(settings) = settings.as_ref() {
+        commands.insert_resource(**settings);
+    }
+}
+
+/// Extract the world window from main app to render app
+fn extract_world_window(
+    mut commands: Commands,
+    window: Extract<Option<Res<WorldWindow>>>,
+) {
+    if let Some(window) = window.as_ref() {
+        commands.insert_resource(**window);
+    }
+}
+
+/// Extract the pending snapshot request from main app to render app
+fn extract_snapshot_requests(
+    mut commands: Commands,
+    requests: Extract<Option<Res<SnapshotRequests>>>,
+) {
+    if let Some(requests) = requests.as_ref() {
+        commands.insert_resource(**requests);
+    }
+}
+
+/// Extract this frame's bond edits and the weld rules from main app to render app
+fn extract_bond_edits(
+    mut commands: Commands,
+    edits: Extract<Option<Res<PendingBondEdits>>>,
+    weld_rules: Extract<Option<Res<WeldRules>>>,
+) {
+    if let Some(edits) = edits.as_ref() {
+        commands.insert_resource((**edits).clone());
+    }
+    if let Some(weld_rules) = weld_rules.as_ref() {
+        commands.insert_resource((**weld_rules).clone());
+    }
+}
+
+/// Upload this frame's bond edits and the weld rules
+fn update_bond_edit_buffers(
+    render_queue: Res<RenderQueue>,
+    edit_buffer: Option<Res<BondEditBuffer>>,
+    allocator_buffer: Option<Res<BondAllocatorBuffer>>,
+    weld_rule_buffer: Option<Res<WeldRuleBuffer>>,
+    edits: Option<Res<PendingBondEdits>>,
+    weld_rules: Option<Res<WeldRules>>,
+) {
+    let (Some(edit_buffer), Some(allocator_buffer), Some(weld_rule_buffer)) =
+        (edit_buffer, allocator_buffer, weld_rule_buffer)
+    else {
+        return;
+    };
+    let edits = edits.map(|edits| edits.edits.clone()).unwrap_or_default();
+    if !edits.is_empty() {
+        render_queue.write_buffer(&edit_buffer.0, 0, bytemuck::cast_slice(&edits));
+    }
+    let edit_count = edits.len() as u32;
+    render_queue.write_buffer(&allocator_buffer.0, BOND_ALLOCATOR_EDIT_COUNT_OFFSET, bytemuck::bytes_of(&edit_count));
+    let rules = weld_rules.map(|rules| rules.gpu_rules()).unwrap_or_else(|| WeldRules::default().gpu_rules());
+    render_queue.write_buffer(&weld_rule_buffer.0, 0, bytemuck::bytes_of(&rules));
+}
+
+/// Update SimParams buffer with settings and the sail trim from main app (the helm
+/// reaches the GPU through the kinematic drivers)
 fn update_sim_params_buffer(
     render_queue: Res<RenderQueue>,
     sim_params_buffer: Option<Res<SimParamsBuffer>>,
-    sail_angle: Option<Res<ExtractedSailAngle>>,
+    timestep_params_buffer: Option<Res<TimestepParamsBuffer>>,
+    settings: Option<Res<SimulationSettings>>,
+    sail_control: Option<Res<SailControl>>,
+    window: Option<Res<WorldWindow>>,
 ) {
-    let (Some(buffer), Some(angle)) = (sim_params_buffer, sail_angle) else {
+    let Some(buffer) = sim_params_buffer else {
         return;
     };
     
-    // Create updated SimParams with current sail angle
     let mut params = SimParams::default();
-    params.rudder_angle = angle.0;  // Using rudder_angle field for sail control
+    if let Some(settings) = settings {
+        settings.apply(&mut params);
+        if let Some(timestep_buffer) = timestep_params_buffer {
+            render_queue.write_buffer(&timestep_buffer.0, 0, bytemuck::bytes_of(&settings.timestep.params()));
+        }
+    }
+    if let Some(sail_control) = sail_control {
+        params.sheet_extension = sail_control.sheet_extension;
+    }
+    if let Some(window) = window {
+        params.bounds = window.bounds();
+    }
     
     // Write to buffer
     render_queue.write_buffer(&buffer.0, 0, bytemuck::bytes_of(&params));
 }
 
+/// Move the grid origin with the world window (size and cell count never change); the
+/// open world's grid wraps
+fn update_grid_params_buffer(
+    render_queue: Res<RenderQueue>,
+    grid_params_buffer: Option<Res<GridParamsBuffer>>,
+    window: Option<Res<WorldWindow>>,
+    settings: Res<SimulationSettings>,
+) {
+    let (Some(buffer), Some(window)) = (grid_params_buffer, window) else {
+        return;
+    };
+    let grid = GridParams { wrap: settings.open_world as u32, ..window.grid_params() };
+    render_queue.write_buffer(&buffer.0, 0, bytemuck::bytes_of(&grid));
+}
+
+/// Update the kinematic driver buffer with the driver values from main app
+fn update_kinematic_driver_buffer(
+    render_queue: Res<RenderQueue>,
+    driver_buffer: Option<Res<KinematicDriverBuffer>>,
+    drivers: Option<Res<KinematicDrivers>>,
+) {
+    let (Some(buffer), Some(drivers)) = (driver_buffer, drivers) else {
+        return;
+    };
+    render_queue.write_buffer(&buffer.0, 0, bytemuck::cast_slice(&drivers.gpu_drivers()));
+}
+
 /// Plugin that manages the GPU compute pipeline for SPH particle simulation.
 pub struct SimulationPlugin;
 
 impl Plugin for SimulationPlugin {
     fn build(&self, app: &mut App) {
+        // Settings inserted before the plugin win over the scenario's
+        if !app.world().contains_resource::<SimulationSettings>() {
+            app.insert_resource(SimulationSettings::for_scenario(Scenario::default()));
+        }
+        let scenario = app.world().resource::<SimulationSettings>().scenario;
+
         // Main app: input handling
         app.init_resource::<SailControl>()
-            .add_systems(Update, input::handle_sail_input);
+            .init_resource::<HelmControl>()
+            .init_resource::<PressureSolverStats>()
+            .init_resource::<PressureSolverStatsChannel>()
+            .init_resource::<TimestepStats>()
+            .init_resource::<TimestepStatsChannel>()
+            .init_resource::<GpuTimings>()
+            .init_resource::<GpuTimingChannel>()
+            .init_resource::<SnapshotRequests>()
+            .init_resource::<SnapshotChannel>()
+            .init_resource::<SimStats>()
+            .init_resource::<SimStatsChannel>()
+            .add_event::<SimulationSnapshot>()
+            .insert_resource(RigidBodies::new(&scenario.rigid_bodies()))
+            .init_resource::<RigidBodiesChannel>()
+            .init_resource::<BondBreakChannel>()
+            .add_event::<BondBroken>()
+            .init_resource::<BondQueue>()
+            .init_resource::<PendingBondEdits>()
+            .init_resource::<BondEditsApplied>()
+            .init_resource::<WeldRules>()
+            .init_resource::<WorldWindow>()
+            .insert_resource(KinematicDrivers::new(&scenario.kinematic_joints()))
+            .add_systems(Update, (
+                input::handle_sail_input,
+                (input::handle_helm_input, input::apply_helm_control).chain(),
+                pressure_solver::update_pressure_solver_stats,
+                timestep::update_timestep_stats,
+                profiling::update_gpu_timings,
+                snapshot::emit_snapshots,
+                stats::update_sim_stats,
+                (rigid_body::update_rigid_bodies, world_window::follow_fleet).chain(),
+                bond_events::emit_bond_break_events,
+            ))
+            .add_systems(Last, welding::flush_bond_queue);
         
         let render_app = app.sub_app_mut(RenderApp);
         render_app
-            .init_resource::<ExtractedSailAngle>()
-            .add_systems(bevy::render::ExtractSchedule, extract_sail_angle)
-            .add_systems(Render, update_sim_params_buffer.in_set(RenderSet::Prepare).before(systems::prepare_bind_group))
+            .add_systems(bevy::render::ExtractSchedule, (extract_kinematic_drivers, extract_sail_control, extract_simulation_settings, extract_bond_edits, extract_world_window, extract_snapshot_requests))
+            .add_systems(Render, (update_sim_params_buffer, update_grid_params_buffer, update_kinematic_driver_buffer, update_bond_edit_buffers)
+                .in_set(RenderSet::Prepare)
+                .before(systems::prepare_bind_group))
             .add_systems(Render, systems::prepare_bind_group.in_set(RenderSet::Prepare))
-            .add_systems(Render, systems::queue_compute.in_set(RenderSet::Queue));
+            .add_systems(Render, systems::queue_compute.in_set(RenderSet::Queue))
+            .add_systems(Render, (
+                readback::poll_readback::<PressureSolverReadback>,
+                readback::poll_readback::<TimestepReadback>,
+                readback::poll_readback::<RigidBodyReadback>,
+                readback::poll_readback::<BondBreakReadback>,
+                readback::poll_readback::<GpuProfiler>,
+                readback::poll_readback::<SnapshotReadback>,
+                readback::poll_readback::<StatsReadback>,
+            ).in_set(RenderSet::Cleanup));
 
         // Add SPH compute node to render graph
         let mut render_graph = render_app.world_mut().resource_mut::<RenderGraph>();
-        render_graph.add_node(SphPhysicsLabel, systems::SphPhysicsNode::default());
+        render_graph.add_node(SphPhysicsLabel, systems::SphPhysicsNode);
         render_graph.add_node_edge(SphPhysicsLabel, bevy::render::graph::CameraDriverLabel);
     }
 
     fn finish(&self, app: &mut App) {
+        let solver_channel = app.world().resource::<PressureSolverStatsChannel>().0.clone();
+        let timestep_channel = app.world().resource::<TimestepStatsChannel>().0.clone();
+        let rigid_body_channel = app.world().resource::<RigidBodiesChannel>().0.clone();
+        let bond_break_channel = app.world().resource::<BondBreakChannel>().0.clone();
+        let timing_channel = app.world().resource::<GpuTimingChannel>().0.clone();
+        let snapshot_channel = app.world().resource::<SnapshotChannel>().0.clone();
+        let stats_channel = app.world().resource::<SimStatsChannel>().0.clone();
+        let edits_applied = app.world().resource::<BondEditsApplied>().clone();
+        let settings = *app.world().resource::<SimulationSettings>();
         let render_app = app.sub_app_mut(RenderApp);
+        // The buffers below are built from the scenario before the first extract
+        render_app.insert_resource(settings);
         // Initialize all buffers in the render app
         render_app.init_resource::<ParticleBuffer>();
+        render_app.init_resource::<ParticleBackBuffer>();
         render_app.init_resource::<SimParamsBuffer>();
         render_app.init_resource::<GridParamsBuffer>();
         render_app.init_resource::<IndexBuffer>();
         render_app.init_resource::<CellCountsBuffer>();
         render_app.init_resource::<CellOffsetsBuffer>();
+        render_app.init_resource::<CellCursorBuffer>();
+        render_app.init_resource::<ScanBlockSumsBuffer>();
+        render_app.init_resource::<ParticleIdBuffer>();
+        render_app.init_resource::<ReorderBuffer>();
         render_app.init_resource::<BondBuffer>();
+        render_app.init_resource::<BondAllocatorBuffer>();
+        render_app.init_resource::<BondEditBuffer>();
+        render_app.init_resource::<BondFreeListBuffer>();
+        render_app.insert_resource(edits_applied);
+        render_app.init_resource::<WeldRuleBuffer>();
+        render_app.init_resource::<WeldPartnerBuffer>();
+        render_app.init_resource::<BondForceBuffer>();
+        render_app.init_resource::<BondAdjacencyBuffer>();
+        render_app.init_resource::<BondBreakLogBuffer>();
+        let bond_break_readback = ReadbackRing::new(
+            render_app.world().resource::<RenderDevice>(),
+            "BondBreakLog",
+            BOND_BREAK_LOG_SIZE,
+            3,
+            bond_break_channel,
+        );
+        render_app.insert_resource(BondBreakReadback(bond_break_readback));
         render_app.init_resource::<ForceBuffer>();
         render_app.init_resource::<InteractionTableBuffer>();
+        render_app.init_resource::<BoundaryVolumeBuffer>();
+        render_app.init_resource::<PressureSolverBuffer>();
+        render_app.init_resource::<PressureSolverStateBuffer>();
+        render_app.init_resource::<PcisphDispatchBuffer>();
+        // Readback of the PCISPH iteration count (3 slots: copy, in flight, mapped)
+        let solver_readback = ReadbackRing::new(
+            render_app.world().resource::<RenderDevice>(),
+            "PressureSolverState",
+            std::mem::size_of::<PressureSolverState>() as u64,
+            3,
+            solver_channel,
+        );
+        render_app.insert_resource(PressureSolverReadback(solver_readback));
+        render_app.init_resource::<TimestepParamsBuffer>();
+        render_app.init_resource::<TimestepStateBuffer>();
+        let timestep_readback = ReadbackRing::new(
+            render_app.world().resource::<RenderDevice>(),
+            "TimestepState",
+            std::mem::size_of::<TimestepState>() as u64,
+            3,
+            timestep_channel,
+        );
+        render_app.insert_resource(TimestepReadback(timestep_readback));
+        render_app.init_resource::<AngularSpringBuffer>();
+        render_app.init_resource::<RigidBodyBuffer>();
+        render_app.init_resource::<RigidMemberBuffer>();
+        render_app.init_resource::<KinematicTargetBuffer>();
+        render_app.init_resource::<KinematicDriverBuffer>();
+        let rigid_body_readback = ReadbackRing::new(
+            render_app.world().resource::<RenderDevice>(),
+            "RigidBody",
+            render_app.world().resource::<RigidBodyBuffer>().buffer.size(),
+            3,
+            rigid_body_channel,
+        );
+        render_app.insert_resource(RigidBodyReadback(rigid_body_readback));
+        let snapshot_readback = SnapshotReadback::new(render_app.world().resource::<RenderDevice>(), snapshot_channel);
+        render_app.insert_resource(snapshot_readback);
+        render_app.init_resource::<StatsStateBuffer>();
+        let stats_readback = ReadbackRing::new(
+            render_app.world().resource::<RenderDevice>(),
+            "StatsState",
+            std::mem::size_of::<StatsState>() as u64,
+            3,
+            stats_channel,
+        );
+        render_app.insert_resource(StatsReadback(stats_readback));
+        // Per-pass timestamps, only where the adapter supports them
+        if let Some(profiler) = GpuProfiler::new(
+            render_app.world().resource::<RenderDevice>(),
+            render_app.world().resource::<RenderQueue>(),
+            timing_channel,
+        ) {
+            render_app.insert_resource(profiler);
+        }
+        render_app.init_resource::<systems::StepCounter>();
         // Initialize compute pipelines
         render_app.init_resource::<systems::SphPipelines>();
     }
@@ -6,6 +6,8 @@
 
 use bytemuck::{Pod, Zeroable};
 
+use super::kernels::{self, SphKernel};
+
 /// Material type indices for interaction table lookup.
 /// Must match the order used in shaders.
 #[repr(u32)]
@@ -32,6 +34,29 @@ impl MaterialType {
     }
 }
 
+/// How fluids feel solids (hull, sail, mast). Must match `SimParams.boundary_handling`.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BoundaryHandling {
+    /// Soft-sphere repulsion from the `InteractionTable` profiles; fluid↔solid pairs skip SPH
+    #[default]
+    Repulsion = 0,
+    /// Akinci et al. 2012: solid particles are boundary pseudo-mass `Ψ_b = ρ0·V_b` in fluid
+    /// density, pressure and viscosity, with equal and opposite forces on the solid.
+    /// Scenarios opt in (`Scenario::boundary_handling`).
+    Akinci = 1,
+}
+
+/// Akinci boundary volume `V_b = 1 / Σ_k W(x_b - x_k)` over the solid neighbours of b
+/// (offsets `x_b - x_k`, including b itself). CPU mirror of `boundary_volume` in density.wgsl.
+pub fn boundary_volume(kernel: SphKernel, h: f32, neighbour_offsets: &[[f32; 2]]) -> f32 {
+    let sum: f32 = neighbour_offsets
+        .iter()
+        .map(|o| kernels::w(kernel, (o[0] * o[0] + o[1] * o[1]).sqrt(), h))
+        .sum();
+    1.0 / sum.max(1e-6)
+}
+
 /// Repulsion ramp type constants (u32 for GPU compatibility)
 pub mod repulsion_ramp {
     /// F = k * (1 - r/r0) — immediate stiff resistance
@@ -239,6 +264,46 @@ mod tests {
         assert_eq!(wh.repulsion_strength, hw.repulsion_strength);
     }
 
+    #[test]
+    fn akinci_boundary_restores_wall_density() {
+        // Fluid half-plane (y >= 0) on a solid lattice as thick as the kernel support
+        let (h, d, rho0) = (10.0, 5.0, 0.2);
+        let kernel = SphKernel::WendlandC2;
+        let mass = rho0 * d * d;
+        let range = -8..=8;
+
+        let wall: Vec<[f32; 2]> = range
+            .clone()
+            .flat_map(|i| (1..=4).map(move |j| [i as f32 * d, -j as f32 * d]))
+            .collect();
+        let fluid: Vec<[f32; 2]> = range
+            .clone()
+            .flat_map(|i| (0..8).map(move |j| [i as f32 * d, j as f32 * d]))
+            .collect();
+
+        let density_at = |pos: [f32; 2], with_wall: bool| -> f32 {
+            let r = |q: &[f32; 2]| ((pos[0] - q[0]).powi(2) + (pos[1] - q[1]).powi(2)).sqrt();
+            let mut rho: f32 = fluid.iter().map(|q| mass * kernels::w(kernel, r(q), h)).sum();
+            if with_wall {
+                for b in &wall {
+                    let offsets: Vec<[f32; 2]> = wall.iter().map(|k| [b[0] - k[0], b[1] - k[1]]).collect();
+                    rho += rho0 * boundary_volume(kernel, h, &offsets) * kernels::w(kernel, r(b), h);
+                }
+            }
+            rho
+        };
+
+        let interior = density_at([0.0, 4.0 * d], false);
+        let bare = density_at([0.0, 0.0], false) / interior;
+        let akinci = density_at([0.0, 0.0], true) / interior;
+
+        // Without boundary mass the wall row is badly under-dense (pulled into the wall):
+        // ~0.69 of the interior, ~1.12 with the pseudo-mass
+        assert!(bare < 0.8, "bare wall density ratio {bare}");
+        assert!((akinci - 1.0).abs() < 0.2, "akinci wall density ratio {akinci}");
+        assert!((akinci - 1.0).abs() < (bare - 1.0).abs() * 0.5);
+    }
+
     #[test]
     fn material_type_from_layer_mask() {
         assert_eq!(MaterialType::from_layer_mask(1), MaterialType::Water);
@@ -0,0 +1,128 @@
+//! Double-buffered particle state for the neighbour passes.
+//!
+//! The position constraints (`constraints.wgsl`, `CONSTRAINT_ITERATIONS` times) and the
+//! density pass used to update `particles` in place while other invocations read the same
+//! entries as neighbours, so results depended on scheduling. Each of these passes now reads
+//! the previous state and writes every particle's next state into the other buffer:
+//! `ParticleBuffer` (front) and `ParticleBackBuffer` (back) swap roles by swapping bind
+//! groups. `forces.wgsl` only reads particles (the pressure moved into the density pass).
+//!
+//! `PING_PONG_PASSES` is even, so the state ends each step in the front buffer, which
+//! every other pass and the renderer bind. Same GPU and input, same output.
+
+use bevy::math::Vec2;
+
+/// Position constraint iterations per step
+pub const CONSTRAINT_ITERATIONS: usize = 3;
+
+/// Passes that swap the particle buffers each step: the constraints, then density
+pub const PING_PONG_PASSES: usize = CONSTRAINT_ITERATIONS + 1;
+
+/// Particle buffer holding the current state
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ParticleSlot {
+    /// `ParticleBuffer`
+    #[default]
+    Front = 0,
+    /// `ParticleBackBuffer`
+    Back = 1,
+}
+
+impl ParticleSlot {
+    /// The other buffer (where a ping-pong pass writes)
+    pub fn next(self) -> Self {
+        match self {
+            Self::Front => Self::Back,
+            Self::Back => Self::Front,
+        }
+    }
+
+    /// Bind group of a ping-pong pass reading this slot
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+// Contact distance as a fraction of the kernel support, and the corrected share of the
+// overlap (matches constraints.wgsl)
+pub(crate) const PBD_RADIUS_FACTOR: f32 = 0.25;
+pub(crate) const STIFFNESS: f32 = 0.8;
+
+/// Position of particle `i` after one constraints iteration reading `positions`
+/// (CPU mirror of constraints.wgsl for dynamic particles at one z level)
+pub fn constrained_position(i: usize, positions: &[Vec2], h: f32) -> Vec2 {
+    let collision_dist = 2.0 * h * PBD_RADIUS_FACTOR;
+    let p = positions[i];
+    let (correction, count) = positions
+        .iter()
+        .enumerate()
+        .filter(|&(j, _)| j != i)
+        .map(|(_, &neighbor)| p - neighbor)
+        .filter(|diff| diff.length() >= 0.001 && diff.length() < collision_dist)
+        .fold((Vec2::ZERO, 0.0), |(correction, count), diff| {
+            let dist = diff.length();
+            (correction + diff / dist * (collision_dist - dist), count + 1.0)
+        });
+    if count == 0.0 {
+        return p;
+    }
+    p + (correction / count * STIFFNESS).clamp_length_max(h)
+}
+
+/// One constraints iteration, invocations running in `order`: reads `previous`, returns
+/// the next state
+pub fn constraint_iteration(previous: &[Vec2], h: f32, order: impl IntoIterator<Item = usize>) -> Vec<Vec2> {
+    let mut next = previous.to_vec();
+    for i in order {
+        next[i] = constrained_position(i, previous, h);
+    }
+    next
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const H: f32 = 10.0;
+
+    // A tight cluster: every particle overlaps several others
+    fn cluster() -> Vec<Vec2> {
+        (0..24).map(|i| Vec2::new((i % 6) as f32 * 2.1, (i / 6) as f32 * 1.7 + (i % 2) as f32 * 0.4)).collect()
+    }
+
+    fn step(positions: &[Vec2], order: &[usize]) -> Vec<Vec2> {
+        (0..CONSTRAINT_ITERATIONS).fold(positions.to_vec(), |state, _| constraint_iteration(&state, H, order.iter().copied()))
+    }
+
+    #[test]
+    fn ping_pong_is_independent_of_invocation_order() {
+        let positions = cluster();
+        let forward: Vec<usize> = (0..positions.len()).collect();
+        let reverse: Vec<usize> = forward.iter().rev().copied().collect();
+        let strided: Vec<usize> = (0..positions.len()).map(|i| i * 7 % positions.len()).collect();
+
+        let expected = step(&positions, &forward);
+        assert_ne!(expected, positions);
+        assert_eq!(step(&positions, &reverse), expected);
+        assert_eq!(step(&positions, &strided), expected);
+        // Repeatable run to run
+        assert_eq!(step(&positions, &forward), expected);
+
+        // In place (the old scheme), the result depends on the order
+        let in_place = |order: &[usize]| {
+            let mut state = positions.clone();
+            for &i in order {
+                state[i] = constrained_position(i, &state, H);
+            }
+            state
+        };
+        assert_ne!(in_place(&forward), in_place(&reverse));
+    }
+
+    #[test]
+    fn state_ends_each_step_in_the_front_buffer() {
+        let slot = (0..PING_PONG_PASSES).fold(ParticleSlot::Front, |slot, _| slot.next());
+        assert_eq!(slot, ParticleSlot::Front);
+        assert_eq!((ParticleSlot::Front.index(), ParticleSlot::Back.index()), (0, 1));
+    }
+}
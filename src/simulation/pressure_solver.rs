@@ -0,0 +1,103 @@
+//! Pressure solver selection and reporting.
+//!
+//! The default path is weakly compressible SPH: `forces.wgsl` turns density into
+//! pressure with the Tait EOS. The alternative is PCISPH (`pcisph.wgsl`): water
+//! pressure is found iteratively (predict positions -> predicted density error ->
+//! pressure correction) until the largest relative density error drops below a
+//! tolerance or the iteration budget is spent. Air and solids stay on the Tait EOS —
+//! air is meant to compress, and solids only use pressure to keep their lattice apart.
+
+use bevy::prelude::*;
+use bytemuck::{Pod, Zeroable};
+
+use super::readback::{ReadbackChannel, ReadbackResource, ReadbackRing};
+
+/// Pressure solver choice. Must match `SimParams.pressure_solver` in shaders.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PressureSolver {
+    /// Tait equation of state (weakly compressible)
+    #[default]
+    TaitWcsph = 0,
+    /// Predictive-corrective incompressible SPH for water
+    Pcisph = 1,
+}
+
+/// GPU solver state, one per step (matches `PressureSolverState` in pcisph.wgsl).
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable, Debug, Default)]
+pub struct PressureSolverState {
+    /// Running max relative density error of the current iteration (f32 bits, atomicMax)
+    pub max_error_bits: u32,
+    /// Correction iterations used this step
+    pub iterations: u32,
+    /// 1 once the error dropped below the tolerance
+    pub converged: u32,
+    /// Max relative density error measured by the last iteration
+    pub last_max_error: f32,
+}
+
+/// Per-step PCISPH report, updated from an asynchronous GPU readback.
+/// Lags the simulation by a few frames.
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct PressureSolverStats {
+    /// Iterations used by the most recently read back step
+    pub iterations: u32,
+    /// Largest relative water density error after the last iteration
+    pub max_density_error: f32,
+    /// Whether the tolerance was reached within the iteration budget
+    pub converged: bool,
+}
+
+impl From<PressureSolverState> for PressureSolverStats {
+    fn from(state: PressureSolverState) -> Self {
+        Self {
+            iterations: state.iterations,
+            max_density_error: state.last_max_error,
+            converged: state.converged != 0,
+        }
+    }
+}
+
+/// Main-world end of the solver state readback
+#[derive(Resource, Clone, Default)]
+pub struct PressureSolverStatsChannel(pub ReadbackChannel);
+
+/// Render-world staging ring for the solver state
+#[derive(Resource)]
+pub struct PressureSolverReadback(pub ReadbackRing);
+
+impl ReadbackResource for PressureSolverReadback {
+    fn ring(&self) -> &ReadbackRing {
+        &self.0
+    }
+}
+
+/// Copy the latest read back solver state into `PressureSolverStats`
+pub fn update_pressure_solver_stats(
+    channel: Res<PressureSolverStatsChannel>,
+    mut stats: ResMut<PressureSolverStats>,
+) {
+    let Some(bytes) = channel.0.take() else {
+        return;
+    };
+    let state: PressureSolverState = bytemuck::pod_read_unaligned(&bytes);
+    *stats = state.into();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_report_the_read_back_state() {
+        let state = PressureSolverState { max_error_bits: 0, iterations: 5, converged: 1, last_max_error: 0.004 };
+        let bytes = bytemuck::bytes_of(&state).to_vec();
+        let stats = PressureSolverStats::from(bytemuck::pod_read_unaligned::<PressureSolverState>(&bytes));
+        assert_eq!((stats.iterations, stats.max_density_error, stats.converged), (5, 0.004, true));
+
+        let exhausted = PressureSolverState { iterations: 8, converged: 0, last_max_error: 0.03, ..state };
+        let stats = PressureSolverStats::from(exhausted);
+        assert_eq!((stats.iterations, stats.converged), (8, false));
+    }
+}
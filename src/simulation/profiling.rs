@@ -0,0 +1,367 @@
+//! Per-pass GPU timing.
+//!
+//! With `SimulationSettings::gpu_timing` on an adapter that supports timestamp queries,
+//! every compute pass of `SphPhysicsNode` writes a begin and end timestamp into its
+//! `GpuStage`'s query slot, and the profiler records which slots were written. After the
+//! last pass only the written slots are resolved, each stage into its own block of the
+//! resolve buffer (resolve offsets must be 256-byte aligned), followed by the mask of
+//! written slots and the timestamp period. Resolving a slot nothing wrote is undefined
+//! (GL reports an error for every one), so skipped stages are never touched. The buffer
+//! is read back through a `ReadbackRing` like every other readback: never stalling, only
+//! skipping frames. The main world folds each frame into `GpuTimings`, a rolling window
+//! the overlay (main.rs) and benchmarks read.
+//!
+//! Stages without a pass this frame (PCISPH under Tait, no bond edits, no reorder, ...) are
+//! not decoded, so they are left out of the frame rather than counted as zero.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_resource::{Buffer, BufferDescriptor, BufferUsages, CommandEncoder, WgpuFeatures},
+        renderer::{RenderDevice, RenderQueue},
+    },
+};
+use wgpu::{ComputePassTimestampWrites, QuerySet, QuerySetDescriptor, QueryType, QUERY_RESOLVE_BUFFER_ALIGNMENT};
+
+use super::ping_pong::CONSTRAINT_ITERATIONS;
+use super::readback::{ReadbackChannel, ReadbackResource, ReadbackRing};
+
+/// Timed compute stages, in dispatch order
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GpuStage {
+    CellId,
+    ClearCounts,
+    CountCells,
+    PrefixSum,
+    Scatter,
+    Reorder,
+    Constraints,
+    Density,
+    Forces,
+    Welding,
+    Bonds,
+    Bending,
+    BondGather,
+    Pcisph,
+    Timestep,
+    Integration,
+    Kinematics,
+    RigidBody,
+    Stats,
+}
+
+impl GpuStage {
+    pub const COUNT: usize = 19;
+    pub const ALL: [GpuStage; Self::COUNT] = [
+        Self::CellId,
+        Self::ClearCounts,
+        Self::CountCells,
+        Self::PrefixSum,
+        Self::Scatter,
+        Self::Reorder,
+        Self::Constraints,
+        Self::Density,
+        Self::Forces,
+        Self::Welding,
+        Self::Bonds,
+        Self::Bending,
+        Self::BondGather,
+        Self::Pcisph,
+        Self::Timestep,
+        Self::Integration,
+        Self::Kinematics,
+        Self::RigidBody,
+        Self::Stats,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::CellId => "cell id",
+            Self::ClearCounts => "clear counts",
+            Self::CountCells => "count cells",
+            Self::PrefixSum => "prefix sum",
+            Self::Scatter => "scatter",
+            Self::Reorder => "reorder",
+            Self::Constraints => "constraints",
+            Self::Density => "density",
+            Self::Forces => "forces",
+            Self::Welding => "bond edits + weld",
+            Self::Bonds => "bonds",
+            Self::Bending => "bending",
+            Self::BondGather => "bond gather",
+            Self::Pcisph => "pcisph",
+            Self::Timestep => "timestep",
+            Self::Integration => "integration",
+            Self::Kinematics => "kinematics",
+            Self::RigidBody => "rigid bodies",
+            Self::Stats => "stats",
+        }
+    }
+
+    /// Compute passes per step (each gets its own query pair)
+    pub fn passes(self) -> usize {
+        match self {
+            Self::Constraints => CONSTRAINT_ITERATIONS,
+            _ => 1,
+        }
+    }
+
+    /// First query slot (a begin/end pair) of this stage
+    fn first_slot(self) -> usize {
+        Self::ALL.iter().take_while(|&&stage| stage != self).map(|stage| stage.passes()).sum()
+    }
+
+    /// Passes of this stage that wrote timestamps, given the mask of written slots. Passes
+    /// run in order, so only the leading run counts (and only it is resolved).
+    fn written_passes(self, written: u32) -> usize {
+        let passes = (written >> self.first_slot()) & ((1 << self.passes()) - 1);
+        passes.trailing_ones() as usize
+    }
+
+    /// Offset of this stage's resolved timestamps in the readback
+    fn block_offset(self) -> usize {
+        self as usize * STAGE_BLOCK
+    }
+}
+
+/// Query slots (begin/end pairs) for one step
+pub const TIMED_PASS_COUNT: usize = GpuStage::COUNT + CONSTRAINT_ITERATIONS - 1;
+const QUERY_COUNT: usize = 2 * TIMED_PASS_COUNT;
+// The written slots are tracked in a u32 mask
+const _: () = assert!(TIMED_PASS_COUNT <= 32);
+/// Bytes per stage in the resolve buffer (one aligned block each, u64 begin/end pairs)
+const STAGE_BLOCK: usize = QUERY_RESOLVE_BUFFER_ALIGNMENT as usize;
+/// Resolved readback: the stage blocks, the written slot mask (u32) and the period
+/// (f32, ns/tick)
+const MASK_OFFSET: u64 = (GpuStage::COUNT * STAGE_BLOCK) as u64;
+pub const TIMING_READBACK_SIZE: u64 = MASK_OFFSET + 8;
+
+/// Milliseconds per stage for one step; `None` where the stage did not run
+pub type StageTimes = [Option<f32>; GpuStage::COUNT];
+
+/// Decode one resolved readback (CPU end of `GpuProfiler::finish`). Only the written
+/// slots are read; the blocks of stages that did not run hold stale data.
+pub fn decode_timings(bytes: &[u8]) -> StageTimes {
+    let offset = MASK_OFFSET as usize;
+    let written = u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+    let period = f32::from_le_bytes(bytes[offset + 4..offset + 8].try_into().unwrap());
+
+    GpuStage::ALL.map(|stage| {
+        let passes = stage.written_passes(written);
+        if passes == 0 {
+            return None;
+        }
+        let block = &bytes[stage.block_offset()..];
+        let timestamp = |query: usize| u64::from_le_bytes(block[query * 8..query * 8 + 8].try_into().unwrap());
+        let ticks: u64 = (0..passes)
+            .map(|pass| timestamp(2 * pass + 1).saturating_sub(timestamp(2 * pass)))
+            .sum();
+        Some(ticks as f32 * period * 1e-6)
+    })
+}
+
+/// Rolling per-stage GPU times, updated from an asynchronous readback.
+/// Empty unless `SimulationSettings::gpu_timing` is on and the adapter supports it.
+#[derive(Resource, Clone, Debug)]
+pub struct GpuTimings {
+    /// Steps kept in the window
+    pub window: usize,
+    history: VecDeque<StageTimes>,
+}
+
+impl Default for GpuTimings {
+    fn default() -> Self {
+        Self { window: 120, history: VecDeque::new() }
+    }
+}
+
+impl GpuTimings {
+    pub fn push(&mut self, times: StageTimes) {
+        self.history.push_back(times);
+        while self.history.len() > self.window {
+            self.history.pop_front();
+        }
+    }
+
+    /// Steps in the window
+    pub fn len(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.history.is_empty()
+    }
+
+    /// Mean time of `stage` over the steps it ran in (ms)
+    pub fn mean(&self, stage: GpuStage) -> Option<f32> {
+        let samples: Vec<f32> = self.history.iter().filter_map(|times| times[stage as usize]).collect();
+        (!samples.is_empty()).then(|| samples.iter().sum::<f32>() / samples.len() as f32)
+    }
+
+    /// Slowest time of `stage` in the window (ms)
+    pub fn max(&self, stage: GpuStage) -> Option<f32> {
+        self.history.iter().filter_map(|times| times[stage as usize]).reduce(f32::max)
+    }
+
+    /// Mean GPU time per step over every timed stage (ms)
+    pub fn mean_total(&self) -> f32 {
+        if self.history.is_empty() {
+            return 0.0;
+        }
+        let total: f32 = self.history.iter().flat_map(|times| times.iter().flatten()).sum();
+        total / self.history.len() as f32
+    }
+}
+
+/// Main-world end of the timing readback
+#[derive(Resource, Clone, Default)]
+pub struct GpuTimingChannel(pub ReadbackChannel);
+
+/// Render-world timestamp queries; only created when the device has `TIMESTAMP_QUERY`
+#[derive(Resource)]
+pub struct GpuProfiler {
+    query_set: QuerySet,
+    resolve: Buffer,
+    ring: ReadbackRing,
+    period: f32,
+    /// Query slots (begin/end pairs) written this step
+    written: AtomicU32,
+}
+
+impl ReadbackResource for GpuProfiler {
+    fn ring(&self) -> &ReadbackRing {
+        &self.ring
+    }
+}
+
+impl GpuProfiler {
+    pub fn new(render_device: &RenderDevice, render_queue: &RenderQueue, channel: ReadbackChannel) -> Option<Self> {
+        if !render_device.features().contains(WgpuFeatures::TIMESTAMP_QUERY) {
+            return None;
+        }
+        let query_set = render_device.wgpu_device().create_query_set(&QuerySetDescriptor {
+            label: Some("GPU Timing Queries"),
+            ty: QueryType::Timestamp,
+            count: QUERY_COUNT as u32,
+        });
+        let resolve = render_device.create_buffer(&BufferDescriptor {
+            label: Some("GPU Timing Resolve Buffer"),
+            size: TIMING_READBACK_SIZE,
+            usage: BufferUsages::QUERY_RESOLVE | BufferUsages::COPY_SRC | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        Some(Self {
+            query_set,
+            resolve,
+            ring: ReadbackRing::new(render_device, "GPU Timing", TIMING_READBACK_SIZE, 3, channel),
+            period: render_queue.get_timestamp_period(),
+            written: AtomicU32::new(0),
+        })
+    }
+
+    /// Timestamp writes for `pass` (0-based) of `stage`
+    pub fn timestamp_writes(&self, stage: GpuStage, pass: usize) -> ComputePassTimestampWrites<'_> {
+        let slot = (stage.first_slot() + pass.min(stage.passes() - 1)) as u32;
+        self.written.fetch_or(1 << slot, Ordering::Relaxed);
+        ComputePassTimestampWrites {
+            query_set: &self.query_set,
+            beginning_of_pass_write_index: Some(2 * slot),
+            end_of_pass_write_index: Some(2 * slot + 1),
+        }
+    }
+
+    /// Resolve this step's written queries and queue the readback (after the last timed
+    /// pass)
+    pub fn finish(&self, encoder: &mut CommandEncoder, render_queue: &RenderQueue) {
+        let written = self.written.swap(0, Ordering::Relaxed);
+        // Queue writes land before this frame's command buffers run
+        let mut footer = [0u8; 8];
+        footer[..4].copy_from_slice(&written.to_le_bytes());
+        footer[4..].copy_from_slice(&self.period.to_le_bytes());
+        render_queue.write_buffer(&self.resolve, MASK_OFFSET, &footer);
+        for stage in GpuStage::ALL {
+            let passes = stage.written_passes(written);
+            if passes == 0 {
+                continue;
+            }
+            let first = 2 * stage.first_slot() as u32;
+            let queries = first..first + 2 * passes as u32;
+            encoder.resolve_query_set(&self.query_set, queries, &self.resolve, stage.block_offset() as u64);
+        }
+        self.ring.copy(encoder, &self.resolve, 0);
+    }
+}
+
+/// Fold the latest read back step into `GpuTimings`
+pub fn update_gpu_timings(channel: Res<GpuTimingChannel>, mut timings: ResMut<GpuTimings>) {
+    if let Some(bytes) = channel.0.take() {
+        timings.push(decode_timings(&bytes));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stages_get_disjoint_query_slots() {
+        let mut slots: Vec<usize> = GpuStage::ALL
+            .iter()
+            .flat_map(|stage| stage.first_slot()..stage.first_slot() + stage.passes())
+            .collect();
+        assert_eq!(slots.len(), TIMED_PASS_COUNT);
+        slots.dedup();
+        assert_eq!(slots, (0..TIMED_PASS_COUNT).collect::<Vec<_>>());
+        assert!(GpuStage::ALL.iter().enumerate().all(|(i, &stage)| stage as usize == i));
+    }
+
+    #[test]
+    fn stage_blocks_hold_every_pass() {
+        for stage in GpuStage::ALL {
+            assert!(stage.passes() * 16 <= STAGE_BLOCK);
+            assert_eq!(stage.block_offset() % QUERY_RESOLVE_BUFFER_ALIGNMENT as usize, 0);
+        }
+    }
+
+    #[test]
+    fn readback_decodes_into_rolling_means() {
+        // 2 ns per tick; constraints run three passes, PCISPH did not run but its block
+        // holds a stale frame
+        let mut bytes = vec![0u8; TIMING_READBACK_SIZE as usize];
+        let mut write = |stage: GpuStage, pass: usize, begin: u64, end: u64| {
+            let at = stage.block_offset() + 16 * pass;
+            bytes[at..at + 8].copy_from_slice(&begin.to_le_bytes());
+            bytes[at + 8..at + 16].copy_from_slice(&end.to_le_bytes());
+        };
+        write(GpuStage::Forces, 0, 1_000, 501_000);
+        for pass in 0..3 {
+            write(GpuStage::Constraints, pass, 0, 100_000);
+        }
+        write(GpuStage::Pcisph, 0, 0, 900_000);
+        let slots = |stage: GpuStage| ((1u32 << stage.passes()) - 1) << stage.first_slot();
+        let written = slots(GpuStage::Forces) | slots(GpuStage::Constraints);
+        let offset = MASK_OFFSET as usize;
+        bytes[offset..offset + 4].copy_from_slice(&written.to_le_bytes());
+        bytes[offset + 4..offset + 8].copy_from_slice(&2.0f32.to_le_bytes());
+
+        let times = decode_timings(&bytes);
+        assert_eq!(times[GpuStage::Forces as usize], Some(1.0));
+        assert!((times[GpuStage::Constraints as usize].unwrap() - 0.6).abs() < 1e-6);
+        assert_eq!(times[GpuStage::Pcisph as usize], None);
+
+        let mut timings = GpuTimings { window: 2, ..default() };
+        let mut slower = times;
+        slower[GpuStage::Forces as usize] = Some(3.0);
+        for frame in [times, times, slower] {
+            timings.push(frame);
+        }
+        assert_eq!(timings.len(), 2);
+        assert_eq!(timings.mean(GpuStage::Forces), Some(2.0));
+        assert_eq!(timings.max(GpuStage::Forces), Some(3.0));
+        assert_eq!(timings.mean(GpuStage::Pcisph), None);
+        assert!((timings.mean_total() - 2.6).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,259 @@
+//! Asynchronous GPU -> CPU readback of small buffers.
+//!
+//! A `ReadbackRing` owns a few MAP_READ staging buffers. The compute node records a
+//! copy into a free slot; `poll_readback` (RenderSet::Cleanup, after submission) maps
+//! copied slots and publishes finished ones to a `ReadbackChannel` shared with the
+//! main world. When every slot is still in flight the copy is skipped, so a readback
+//! never stalls the pipeline — it only lowers the update rate.
+
+use std::sync::{
+    atomic::{AtomicU32, AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_resource::{Buffer, BufferDescriptor, BufferUsages, CommandEncoder, Maintain, MapMode},
+        renderer::RenderDevice,
+    },
+};
+
+// Slot states
+const FREE: u32 = 0;
+const COPIED: u32 = 1;
+const MAPPING: u32 = 2;
+const MAPPED: u32 = 3;
+
+/// Latest bytes read back from the GPU, shared between the render and main worlds.
+#[derive(Clone, Default)]
+pub struct ReadbackChannel(Arc<Mutex<Option<Vec<u8>>>>);
+
+impl ReadbackChannel {
+    fn publish(&self, bytes: Vec<u8>) {
+        if let Ok(mut latest) = self.0.lock() {
+            *latest = Some(bytes);
+        }
+    }
+
+    /// Take the most recent readback, if a new one arrived since the last call
+    pub fn take(&self) -> Option<Vec<u8>> {
+        self.0.lock().ok().and_then(|mut latest| latest.take())
+    }
+}
+
+/// What `ReadbackRing::poll` does with a slot's staging buffer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SlotAction {
+    /// Free, or a map is still in flight
+    Wait,
+    /// A copy was recorded: start mapping it
+    Map,
+    /// The map finished: read the bytes, unmap and `release` the slot
+    Publish,
+}
+
+/// Slot states of a ring, kept apart from the staging buffers:
+/// FREE -> COPIED (`claim`) -> MAPPING (`poll`) -> MAPPED (map callback) -> FREE (`release`).
+/// A failed map returns the slot to FREE.
+struct SlotStates {
+    states: Vec<Arc<AtomicU32>>,
+    next: AtomicUsize,
+}
+
+impl SlotStates {
+    fn new(count: usize) -> Self {
+        Self {
+            states: (0..count).map(|_| Arc::new(AtomicU32::new(FREE))).collect(),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Claim a free slot for a copy recorded this frame, round robin
+    fn claim(&self) -> Option<usize> {
+        let count = self.states.len();
+        let start = self.next.load(Ordering::Relaxed);
+        (0..count).map(|i| (start + i) % count).find(|&index| {
+            let claimed = self.states[index]
+                .compare_exchange(FREE, COPIED, Ordering::AcqRel, Ordering::Relaxed)
+                .is_ok();
+            if claimed {
+                self.next.store((index + 1) % count, Ordering::Relaxed);
+            }
+            claimed
+        })
+    }
+
+    /// Advance slot `index` for this poll and say what to do with its buffer
+    fn poll(&self, index: usize) -> SlotAction {
+        let state = &self.states[index];
+        match state.load(Ordering::Acquire) {
+            MAPPED => SlotAction::Publish,
+            COPIED => {
+                state.store(MAPPING, Ordering::Release);
+                SlotAction::Map
+            }
+            _ => SlotAction::Wait,
+        }
+    }
+
+    /// Map callback of a MAPPING slot
+    fn map_finished(state: &AtomicU32, ok: bool) {
+        state.store(if ok { MAPPED } else { FREE }, Ordering::Release);
+    }
+
+    /// The published slot's buffer was unmapped: it can take the next copy
+    fn release(&self, index: usize) {
+        self.states[index].store(FREE, Ordering::Release);
+    }
+}
+
+/// Ring of staging buffers for non-blocking readback of `size` bytes.
+pub struct ReadbackRing {
+    buffers: Vec<Buffer>,
+    slots: SlotStates,
+    size: u64,
+    channel: ReadbackChannel,
+}
+
+impl ReadbackRing {
+    pub fn new(
+        render_device: &RenderDevice,
+        label: &str,
+        size: u64,
+        slot_count: usize,
+        channel: ReadbackChannel,
+    ) -> Self {
+        let buffers = (0..slot_count)
+            .map(|i| {
+                render_device.create_buffer(&BufferDescriptor {
+                    label: Some(&format!("{label} Readback {i}")),
+                    size,
+                    usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                })
+            })
+            .collect();
+
+        Self {
+            buffers,
+            slots: SlotStates::new(slot_count),
+            size,
+            channel,
+        }
+    }
+
+    /// Size in bytes of each readback
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Record a copy of `src[offset..offset + size]` into a free slot.
+    /// Returns false (and records nothing) when every slot is busy.
+    pub fn copy(&self, encoder: &mut CommandEncoder, src: &Buffer, offset: u64) -> bool {
+        let Some(index) = self.slots.claim() else {
+            return false;
+        };
+        encoder.copy_buffer_to_buffer(src, offset, &self.buffers[index], 0, self.size);
+        true
+    }
+
+    /// Record copies of the first `len` bytes of each source, back to back, into one
+    /// free slot (the lengths must add up to at most `size`). Returns false when every
+    /// slot is busy.
+    pub fn copy_concatenated(&self, encoder: &mut CommandEncoder, sources: &[(&Buffer, u64)]) -> bool {
+        debug_assert!(sources.iter().map(|&(_, len)| len).sum::<u64>() <= self.size);
+        let Some(index) = self.slots.claim() else {
+            return false;
+        };
+        let mut offset = 0;
+        for &(src, len) in sources {
+            encoder.copy_buffer_to_buffer(src, 0, &self.buffers[index], offset, len);
+            offset += len;
+        }
+        true
+    }
+
+    /// Publish finished maps and start mapping newly copied slots.
+    /// Must run after the frame's command buffers were submitted.
+    pub fn poll(&self) {
+        for (index, buffer) in self.buffers.iter().enumerate() {
+            match self.slots.poll(index) {
+                SlotAction::Publish => {
+                    let bytes = buffer.slice(..).get_mapped_range().to_vec();
+                    buffer.unmap();
+                    self.slots.release(index);
+                    self.channel.publish(bytes);
+                }
+                SlotAction::Map => {
+                    let state = self.slots.states[index].clone();
+                    buffer.slice(..).map_async(MapMode::Read, move |result| {
+                        SlotStates::map_finished(&state, result.is_ok());
+                    });
+                }
+                SlotAction::Wait => {}
+            }
+        }
+    }
+}
+
+/// Render-world resources that own a `ReadbackRing`
+pub trait ReadbackResource: Resource {
+    fn ring(&self) -> &ReadbackRing;
+}
+
+/// Drive one readback ring: map copies recorded this frame, publish finished ones.
+pub fn poll_readback<T: ReadbackResource>(readback: Option<Res<T>>, render_device: Res<RenderDevice>) {
+    let Some(readback) = readback else {
+        return;
+    };
+    readback.ring().poll();
+    render_device.poll(Maintain::Poll);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(slots: &SlotStates, index: usize) -> u32 {
+        slots.states[index].load(Ordering::Acquire)
+    }
+
+    #[test]
+    fn slot_cycles_free_copied_mapping_mapped_free() {
+        let slots = SlotStates::new(3);
+        assert_eq!(slots.poll(0), SlotAction::Wait);
+
+        assert_eq!(slots.claim(), Some(0));
+        assert_eq!(state(&slots, 0), COPIED);
+        assert_eq!(slots.poll(0), SlotAction::Map);
+        assert_eq!(state(&slots, 0), MAPPING);
+        // Map still in flight
+        assert_eq!(slots.poll(0), SlotAction::Wait);
+
+        SlotStates::map_finished(&slots.states[0], true);
+        assert_eq!(state(&slots, 0), MAPPED);
+        assert_eq!(slots.poll(0), SlotAction::Publish);
+        slots.release(0);
+        assert_eq!(state(&slots, 0), FREE);
+        assert_eq!(slots.poll(0), SlotAction::Wait);
+    }
+
+    #[test]
+    fn claims_go_round_robin_and_skip_when_every_slot_is_busy() {
+        let slots = SlotStates::new(3);
+        assert_eq!((slots.claim(), slots.claim(), slots.claim()), (Some(0), Some(1), Some(2)));
+        assert_eq!(slots.claim(), None);
+
+        // A failed map frees the slot without publishing
+        assert_eq!(slots.poll(1), SlotAction::Map);
+        SlotStates::map_finished(&slots.states[1], false);
+        assert_eq!(state(&slots, 1), FREE);
+        assert_eq!(slots.claim(), Some(1));
+
+        assert_eq!(slots.poll(2), SlotAction::Map);
+        SlotStates::map_finished(&slots.states[2], true);
+        slots.release(2);
+        assert_eq!(slots.claim(), Some(2));
+    }
+}
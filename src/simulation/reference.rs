@@ -0,0 +1,958 @@
+//! CPU reference implementation of the physics step.
+//!
+//! Mirrors the fluid and bond stages of the render graph node, in the same order and on
+//! the same `Particle`, `Bond`, `SimParams`, `GridParams` and `InteractionTable` values
+//! the GPU buffers hold, so physics can be tested in plain `cargo test` and GPU results
+//! can be checked against it (`tests/gpu_reference.rs`):
+//!
+//! 1. cell ids (cell_id.wgsl) and the counting sort (`grid::counting_sort`)
+//! 2. `CONSTRAINT_ITERATIONS` position constraint passes (constraints.wgsl)
+//! 3. Akinci boundary volumes, density and Tait pressure (density.wgsl)
+//! 4. SPH, repulsion and sail forces (forces.wgsl)
+//! 5. bond forces with fracture, yield and fatigue (bonds.wgsl via `bonds::step_bond`),
+//!    gathered per particle (bond_gather.wgsl)
+//! 6. PCISPH water pressure when selected (pcisph.wgsl)
+//! 7. integration, velocity filters, walls and the speed clamp (physics.wgsl)
+//! 8. statistics (`stats::reduce_stats`)
+//!
+//! Each stage reads the previous state and returns or writes the next one, like the
+//! ping-pong passes, so the result does not depend on iteration order. Neighbour sums run
+//! in cell order with particles in index order within a cell, the order the GPU's cell
+//! sort leaves. GPU float arithmetic still differs in rounding, so parity checks need a
+//! float tolerance. `reorder_interval` only
+//! relabels particles (see `reorder`) and is ignored.
+//!
+//! Scope: scenes without the stages below, such as `water_only` and `pressure_washer`.
+//! Not mirrored: the adaptive timestep (`delta_time` is used as is), runtime bond edits
+//! and welding, bending springs, kinematic drivers and rigid bodies (static particles
+//! stay put and every bond is a spring). Scenarios with hulls, rudders, masts or sails
+//! use those stages, so the reference only approximates them and is not a parity
+//! target there.
+
+use bevy::math::Vec2;
+
+use super::adjacency::{bond_adjacency, gather_bond_force, Adjacency};
+use super::bonds::{step_bond, BondResponse, SPRING_FORCE_LIMIT};
+use super::grid::counting_sort;
+use super::integrator::{kick, IntegratorScheme, IntegratorState};
+use super::kernels::{self, SphKernel};
+use super::physics_config::{boundary_volume, BoundaryHandling, InteractionTable, MaterialType};
+use super::ping_pong::{CONSTRAINT_ITERATIONS, PBD_RADIUS_FACTOR, STIFFNESS};
+use super::pressure_solver::{PressureSolver, PressureSolverState};
+use super::stats::{reduce_stats, StatsState, STATIC_MASS};
+use crate::resources::{layer, Bond, GridParams, Particle, ParticleForce, SimParams};
+
+/// Tait exponent (matches density.wgsl)
+const PRESSURE_GAMMA: i32 = 7;
+
+// Pair force limits (match forces.wgsl)
+const MIN_DISTANCE: f32 = 0.003;
+const CLOSE_RANGE: f32 = 0.3;
+const MAX_ACCELERATION: f32 = 50000.0;
+
+// Sail aerodynamics (match forces.wgsl)
+const WIND_VELOCITY: Vec2 = Vec2::new(150.0, 0.0);
+const SAIL_WIND_COUPLING: f32 = 0.15;
+const SAIL_DRAG: f32 = 0.3;
+
+// Walls (match physics.wgsl)
+const BOUNDARY_STIFFNESS: f32 = 0.0;
+const BOUNDARY_RANGE: f32 = 4.0;
+const BOUNDARY_MARGIN: f32 = 2.0;
+
+/// Particles closer than this in z do not interact
+const Z_LEVEL_TOLERANCE: f32 = 0.5;
+
+fn is_solid(layer_mask: u32) -> bool {
+    layer_mask & (layer::HULL | layer::SAIL | layer::MAST) != 0
+}
+
+fn kernel_of(params: &SimParams) -> SphKernel {
+    // Unknown values fall through to the shaders' default case
+    SphKernel::ALL.get(params.kernel as usize).copied().unwrap_or_default()
+}
+
+fn rest_density(params: &SimParams, layer_mask: u32) -> f32 {
+    if layer_mask & layer::WATER != 0 {
+        params.target_density_water
+    } else {
+        params.target_density_air
+    }
+}
+
+/// Grid cell of a position, clamped to the grid (cell_id.wgsl)
+pub fn cell_id(pos: [f32; 2], grid: &GridParams) -> u32 {
+    let cell_x = (((pos[0] - grid.grid_origin_x) / grid.cell_size) as u32).min(grid.grid_width - 1);
+    let cell_y = (((pos[1] - grid.grid_origin_y) / grid.cell_size) as u32).min(grid.grid_height - 1);
+    cell_y * grid.grid_width + cell_x
+}
+
+/// Counting-sorted particle indices for the 3×3 cell neighbour loops
+#[derive(Clone, Debug)]
+pub struct NeighbourGrid {
+    pub grid: GridParams,
+    /// Start of every cell in `indices`, plus an end sentinel
+    pub cell_offsets: Vec<u32>,
+    pub indices: Vec<u32>,
+}
+
+impl NeighbourGrid {
+    /// Sort the particles by their current `cell_id`
+    pub fn build(particles: &[Particle], grid: GridParams) -> Self {
+        let cell_ids: Vec<u32> = particles.iter().map(|p| p.cell_id).collect();
+        let total_cells = (grid.grid_width * grid.grid_height) as usize;
+        let (cell_offsets, indices) = counting_sort(&cell_ids, total_cells);
+        Self { grid, cell_offsets, indices }
+    }
+
+    /// Particles in the 3×3 cells around `cell_id` (the particle itself included), in the
+    /// shaders' loop order. A wrapping grid continues on the opposite side along x.
+    pub fn neighbours(&self, cell_id: u32) -> impl Iterator<Item = usize> + '_ {
+        let (width, height) = (self.grid.grid_width as i32, self.grid.grid_height as i32);
+        let (cell_x, cell_y) = ((cell_id % self.grid.grid_width) as i32, (cell_id / self.grid.grid_width) as i32);
+        let wrap = self.grid.wrap != 0;
+        (-1..=1)
+            .flat_map(move |dy| (-1..=1).map(move |dx| (cell_x + dx, cell_y + dy)))
+            .map(move |(x, y)| if wrap { (x.rem_euclid(width), y) } else { (x, y) })
+            .filter(move |&(x, y)| x >= 0 && y >= 0 && x < width && y < height)
+            .flat_map(move |(x, y)| {
+                let cell = (y * width + x) as usize;
+                self.cell_offsets[cell] as usize..self.cell_offsets[cell + 1] as usize
+            })
+            .map(move |j| self.indices[j] as usize)
+    }
+}
+
+/// One position constraint pass: pushes overlapping particles at the same z level apart
+/// (constraints.wgsl). Static particles are copied unchanged.
+pub fn constraints(particles: &[Particle], neighbours: &NeighbourGrid, params: &SimParams) -> Vec<Particle> {
+    let h = params.smoothing_radius;
+    let collision_dist = kernels::support(h) * PBD_RADIUS_FACTOR;
+    particles
+        .iter()
+        .enumerate()
+        .map(|(i, &p)| {
+            if p.mass > STATIC_MASS {
+                return p;
+            }
+            let pos = Vec2::from(p.pos);
+            let (mut correction, mut count) = (Vec2::ZERO, 0.0);
+            for j in neighbours.neighbours(p.cell_id).filter(|&j| j != i) {
+                let neighbour = &particles[j];
+                if (p.z_height - neighbour.z_height).abs() > Z_LEVEL_TOLERANCE {
+                    continue;
+                }
+                let diff = neighbours.grid.offset(neighbour.pos, pos);
+                let dist = diff.length();
+                if dist < 0.001 || dist >= collision_dist {
+                    continue;
+                }
+                correction += diff / dist * (collision_dist - dist);
+                count += 1.0;
+            }
+            if count > 0.0 {
+                let moved = pos + (correction / count * STIFFNESS).clamp_length_max(h);
+                return Particle { pos: moved.into(), ..p };
+            }
+            p
+        })
+        .collect()
+}
+
+/// Akinci boundary volume of every solid particle, 0 for fluids (`boundary_volume` in
+/// density.wgsl)
+pub fn boundary_volumes(particles: &[Particle], neighbours: &NeighbourGrid, params: &SimParams) -> Vec<f32> {
+    let kernel = kernel_of(params);
+    particles
+        .iter()
+        .map(|p| {
+            if !is_solid(p.layer_mask) {
+                return 0.0;
+            }
+            let offsets: Vec<[f32; 2]> = neighbours
+                .neighbours(p.cell_id)
+                .map(|j| &particles[j])
+                .filter(|n| is_solid(n.layer_mask) && (p.z_height - n.z_height).abs() <= Z_LEVEL_TOLERANCE)
+                .map(|n| neighbours.grid.offset(n.pos, p.pos).into())
+                .collect();
+            boundary_volume(kernel, params.smoothing_radius, &offsets)
+        })
+        .collect()
+}
+
+/// Density and Tait pressure of every particle (density.wgsl). `volumes` are only read
+/// with Akinci boundary handling.
+pub fn density(
+    particles: &[Particle],
+    neighbours: &NeighbourGrid,
+    params: &SimParams,
+    interactions: &InteractionTable,
+    volumes: &[f32],
+) -> Vec<Particle> {
+    let kernel = kernel_of(params);
+    let h = params.smoothing_radius;
+    particles
+        .iter()
+        .map(|&p| {
+            let akinci = params.boundary_handling == BoundaryHandling::Akinci as u32 && !is_solid(p.layer_mask);
+            let rest = rest_density(params, p.layer_mask);
+            let mut density = 0.0;
+            for j in neighbours.neighbours(p.cell_id) {
+                let neighbour = &particles[j];
+                if (p.z_height - neighbour.z_height).abs() > Z_LEVEL_TOLERANCE {
+                    continue;
+                }
+                let neighbour_is_solid = is_solid(neighbour.layer_mask);
+                if p.layer_mask != neighbour.layer_mask && !is_solid(p.layer_mask) && !neighbour_is_solid {
+                    continue;
+                }
+                let mass = if akinci && neighbour_is_solid { rest * volumes[j] } else { neighbour.mass };
+                density += mass * kernels::w(kernel, neighbours.grid.offset(neighbour.pos, p.pos).length(), h);
+            }
+            let density = f32::max(density, 0.1);
+            let pressure = interactions.sph_pressure_stiffness * ((density / rest).powi(PRESSURE_GAMMA) - 1.0);
+            Particle { density, pressure: pressure.clamp(0.0, interactions.pressure_cap), ..p }
+        })
+        .collect()
+}
+
+/// SPH pressure, viscosity, solid-fluid coupling and sail aerodynamics (forces.wgsl).
+/// Writes `fluid` (as a force) and `xsph` of every particle.
+pub fn fluid_forces(
+    particles: &[Particle],
+    neighbours: &NeighbourGrid,
+    params: &SimParams,
+    interactions: &InteractionTable,
+    volumes: &[f32],
+    forces: &mut [ParticleForce],
+) {
+    let kernel = kernel_of(params);
+    let h = params.smoothing_radius;
+    let akinci = params.boundary_handling == BoundaryHandling::Akinci as u32;
+    let pcisph = params.pressure_solver == PressureSolver::Pcisph as u32;
+
+    for (i, p) in particles.iter().enumerate() {
+        let pos = Vec2::from(p.pos);
+        let vel = Vec2::from(p.vel);
+        let is_fluid = p.layer_mask & (layer::WATER | layer::AIR) != 0;
+        // Under PCISPH water pressure comes from `pcisph` after the bonds
+        let pcisph_pressure = pcisph && p.layer_mask & layer::WATER != 0;
+        let target_density = rest_density(params, p.layer_mask);
+        let (mut pressure_force, mut viscosity_force, mut xsph) = (Vec2::ZERO, Vec2::ZERO, Vec2::ZERO);
+
+        for j in neighbours.neighbours(p.cell_id).filter(|&j| j != i) {
+            let neighbour = &particles[j];
+            let r = neighbours.grid.offset(neighbour.pos, pos);
+            let r_len = r.length();
+            if r_len >= kernels::support(h) || r_len < MIN_DISTANCE {
+                continue;
+            }
+            if (p.z_height - neighbour.z_height).abs() >= Z_LEVEL_TOLERANCE {
+                continue;
+            }
+            let same_layer = p.layer_mask == neighbour.layer_mask;
+            let neighbour_is_fluid = neighbour.layer_mask & (layer::WATER | layer::AIR) != 0;
+            if !same_layer && is_fluid && neighbour_is_fluid {
+                continue;
+            }
+            let vel_diff = Vec2::from(neighbour.vel) - vel;
+
+            if is_fluid != neighbour_is_fluid {
+                if akinci {
+                    // Boundary pseudo-mass; the solid side takes the equal and opposite force
+                    let grad = Vec2::from(kernels::gradient(kernel, r.into(), h));
+                    let lap = kernels::laplacian(kernel, r_len, h);
+                    if is_fluid {
+                        let volume = volumes[j];
+                        pressure_force -= target_density * volume * (p.pressure / p.density) * grad;
+                        viscosity_force += interactions.sph_viscosity * volume * vel_diff * lap;
+                    } else {
+                        let fluid_rest_density = rest_density(params, neighbour.layer_mask);
+                        let volume = volumes[i];
+                        let to_density = p.density / p.mass;
+                        let fluid_accel_per_mass = neighbour.mass / (neighbour.density * neighbour.density);
+                        pressure_force -= to_density * fluid_rest_density * volume * neighbour.pressure
+                            * fluid_accel_per_mass
+                            * grad;
+                        viscosity_force += to_density * (neighbour.mass / neighbour.density)
+                            * interactions.sph_viscosity
+                            * volume
+                            * vel_diff
+                            * lap;
+                    }
+                } else {
+                    // Soft-sphere repulsion only, no SPH between fluid and solid
+                    let profile = interactions.get(
+                        MaterialType::from_layer_mask(p.layer_mask),
+                        MaterialType::from_layer_mask(neighbour.layer_mask),
+                    );
+                    if r_len < profile.repulsion_radius && profile.repulsion_strength > 0.0 {
+                        let t = 1.0 - r_len / profile.repulsion_radius;
+                        let ramp = if profile.repulsion_ramp == 1 { t * t } else { t };
+                        pressure_force += profile.repulsion_strength * ramp * (r / r_len);
+                    }
+                }
+                continue;
+            }
+
+            if !pcisph_pressure {
+                let pressure_term = (p.pressure + neighbour.pressure) / (2.0 * neighbour.density);
+                pressure_force -= neighbour.mass * pressure_term * Vec2::from(kernels::gradient(kernel, r.into(), h));
+            }
+
+            let close_threshold = h * CLOSE_RANGE;
+            if r_len < close_threshold {
+                let close_factor = (close_threshold - r_len) / close_threshold;
+                pressure_force += interactions.sph_close_repulsion * close_factor * close_factor * (r / r_len);
+            }
+
+            viscosity_force += interactions.sph_viscosity * neighbour.mass * (vel_diff / neighbour.density)
+                * kernels::laplacian(kernel, r_len, h);
+
+            if same_layer {
+                let avg_density = (p.density + neighbour.density) * 0.5;
+                xsph += (neighbour.mass / avg_density) * vel_diff * kernels::w(kernel, r_len, h);
+            }
+        }
+
+        let mut acceleration = ((pressure_force + viscosity_force) / p.density).clamp_length_max(MAX_ACCELERATION);
+        if p.layer_mask & layer::SAIL != 0 {
+            acceleration += SAIL_WIND_COUPLING * (WIND_VELOCITY - vel);
+            let speed = vel.length();
+            if speed > 0.1 {
+                acceleration -= SAIL_DRAG * speed * vel;
+            }
+        }
+        forces[i].fluid = (acceleration * p.mass).into();
+        forces[i].xsph = xsph.into();
+    }
+}
+
+/// Bond step (bonds.wgsl): the force of every bond on its `particle_a` (b gets the
+/// opposite), and how many bonds broke. Broken bonds are deactivated in `bonds`.
+pub fn bond_forces(particles: &[Particle], bonds: &mut [Bond], params: &SimParams) -> (Vec<Vec2>, u32) {
+    let mut broken = 0;
+    let forces = bonds
+        .iter_mut()
+        .map(|bond| {
+            if bond.is_active == 0 {
+                return Vec2::ZERO;
+            }
+            let (a, b) = (&particles[bond.particle_a as usize], &particles[bond.particle_b as usize]);
+            let diff = Vec2::from(b.pos) - Vec2::from(a.pos);
+            let dist = diff.length();
+            if dist < 0.001 {
+                return Vec2::ZERO;
+            }
+            let dir = diff / dist;
+            let vel_diff = Vec2::from(b.vel) - Vec2::from(a.vel);
+            let rel_vel = vel_diff.dot(dir);
+            let transverse_speed = (vel_diff - dir * rel_vel).length();
+            match step_bond(bond, dist, rel_vel, transverse_speed, params.delta_time, params.sheet_extension) {
+                BondResponse::Force(force) => {
+                    (dir * force).clamp(Vec2::splat(-SPRING_FORCE_LIMIT), Vec2::splat(SPRING_FORCE_LIMIT))
+                }
+                BondResponse::Slack => Vec2::ZERO,
+                BondResponse::Broken => {
+                    broken += 1;
+                    Vec2::ZERO
+                }
+            }
+        })
+        .collect();
+    (forces, broken)
+}
+
+/// PCISPH water pressure (pcisph.wgsl): predict positions with the current pressure
+/// guess, correct water pressure from the predicted density error and recompute the
+/// pressure acceleration, until the largest relative error is within
+/// `pressure_tolerance` or `pressure_max_iterations` are spent. The final pressure force
+/// is added to `fluid`. Returns the solver state as the GPU reports it.
+pub fn pcisph(
+    particles: &mut [Particle],
+    neighbours: &NeighbourGrid,
+    params: &SimParams,
+    volumes: &[f32],
+    forces: &mut [ParticleForce],
+) -> PressureSolverState {
+    let kernel = kernel_of(params);
+    let h = params.smoothing_radius;
+    let rest = params.target_density_water;
+    let dt = params.delta_time;
+    let akinci = params.boundary_handling == BoundaryHandling::Akinci as u32;
+    let is_water = |p: &Particle| p.layer_mask & layer::WATER != 0;
+
+    // begin
+    let mut state = PressureSolverState::default();
+    let mut pressure_accel = vec![Vec2::ZERO; particles.len()];
+    for p in particles.iter_mut().filter(|p| is_water(p)) {
+        p.pressure = 0.0;
+    }
+
+    for _ in 0..params.pressure_max_iterations {
+        // predict
+        let predicted: Vec<Vec2> = particles
+            .iter()
+            .zip(forces.iter())
+            .zip(&pressure_accel)
+            .map(|((p, f), &accel)| {
+                if p.mass > STATIC_MASS {
+                    return Vec2::from(p.pos);
+                }
+                let accel = (Vec2::from(f.fluid) + Vec2::from(f.bond)) / p.mass + accel;
+                Vec2::from(p.pos) + (Vec2::from(p.vel) + accel * dt) * dt
+            })
+            .collect();
+
+        // correct_density: each particle only writes its own pressure
+        let mut max_error = 0.0f32;
+        for i in 0..particles.len() {
+            let p = particles[i];
+            if !is_water(&p) {
+                continue;
+            }
+            let (mut density, mut grad_sum, mut grad_sq_sum) = (0.0, Vec2::ZERO, 0.0);
+            for j in neighbours.neighbours(p.cell_id) {
+                let neighbour = &particles[j];
+                if (p.z_height - neighbour.z_height).abs() > Z_LEVEL_TOLERANCE {
+                    continue;
+                }
+                if p.layer_mask != neighbour.layer_mask && !is_solid(neighbour.layer_mask) {
+                    continue;
+                }
+                let r = neighbours.grid.offset(predicted[j], predicted[i]);
+                let mass = if akinci && is_solid(neighbour.layer_mask) { rest * volumes[j] } else { neighbour.mass };
+                density += mass * kernels::w(kernel, r.length(), h);
+                if j != i && neighbour.layer_mask == p.layer_mask {
+                    let grad = Vec2::from(kernels::gradient(kernel, r.into(), h));
+                    grad_sum += grad;
+                    grad_sq_sum += grad.length_squared();
+                }
+            }
+            // Only compression is corrected
+            let error = f32::max(density - rest, 0.0);
+            let beta = dt * dt * p.mass * p.mass * 2.0 / (rest * rest);
+            let denom = beta * (grad_sum.length_squared() + grad_sq_sum);
+            if denom > 1e-12 {
+                particles[i].pressure = f32::max(p.pressure + error / denom, 0.0);
+            }
+            max_error = max_error.max(error / rest);
+        }
+
+        // pressure_force: water <-> water at the current positions
+        for (i, p) in particles.iter().enumerate().filter(|(_, p)| is_water(p)) {
+            let mut accel = Vec2::ZERO;
+            for j in neighbours.neighbours(p.cell_id).filter(|&j| j != i) {
+                let neighbour = &particles[j];
+                if neighbour.layer_mask != p.layer_mask || (p.z_height - neighbour.z_height).abs() > Z_LEVEL_TOLERANCE {
+                    continue;
+                }
+                let grad = Vec2::from(kernels::gradient(kernel, neighbours.grid.offset(neighbour.pos, p.pos).into(), h));
+                accel -= neighbour.mass * ((p.pressure + neighbour.pressure) / (rest * rest)) * grad;
+            }
+            pressure_accel[i] = accel;
+        }
+
+        // check
+        state.last_max_error = max_error;
+        state.iterations += 1;
+        if max_error <= params.pressure_tolerance {
+            state.converged = 1;
+            break;
+        }
+    }
+
+    // apply
+    for ((p, f), accel) in particles.iter().zip(forces.iter_mut()).zip(pressure_accel) {
+        if is_water(p) {
+            f.fluid = (Vec2::from(f.fluid) + accel * p.mass).into();
+        }
+    }
+    state
+}
+
+/// Soft wall acceleration at `distance` from a wall (physics.wgsl)
+fn boundary_force(distance: f32) -> f32 {
+    if distance >= BOUNDARY_RANGE {
+        return 0.0;
+    }
+    if distance <= 0.0 {
+        return BOUNDARY_STIFFNESS;
+    }
+    let t = 1.0 - distance / BOUNDARY_RANGE;
+    BOUNDARY_STIFFNESS * t * t
+}
+
+/// Integrate every particle from its accumulated forces (physics.wgsl): kick, XSPH and
+/// damping, drift, then air recycling or open world wrap, the floor and ceiling, and the
+/// speed clamp
+pub fn integrate(
+    particles: &mut [Particle],
+    forces: &mut [ParticleForce],
+    params: &SimParams,
+    interactions: &InteractionTable,
+) {
+    let scheme = IntegratorScheme::ALL.get(params.integrator as usize).copied().unwrap_or_default();
+    let [min_x, max_x, min_y, max_y] = params.bounds;
+    let dt = params.delta_time;
+    let open_world = params.open_world != 0;
+
+    for (p, f) in particles.iter_mut().zip(forces.iter_mut()) {
+        if p.mass > STATIC_MASS {
+            p.vel = [0.0, 0.0];
+            f.state_tag = 0;
+            continue;
+        }
+
+        let mut force = Vec2::from(f.fluid) + Vec2::from(f.bond);
+        // The open world has no side walls; floor and ceiling stay
+        let side_walls = if open_world {
+            0.0
+        } else {
+            boundary_force(p.pos[0] - min_x) - boundary_force(max_x - p.pos[0])
+        };
+        let wall_accel = Vec2::new(side_walls, boundary_force(p.pos[1] - min_y) - boundary_force(max_y - p.pos[1]));
+        force += wall_accel * p.mass;
+
+        let mut state = IntegratorState { history: f.state, tag: f.state_tag };
+        let drift = Vec2::from(kick(scheme, &mut p.vel, (force / p.mass).into(), &mut state, dt));
+        (f.state, f.state_tag) = (state.history, state.tag);
+
+        let vel = (Vec2::from(p.vel) + interactions.xsph_epsilon * Vec2::from(f.xsph)) * interactions.velocity_damping;
+        let mut pos = Vec2::from(p.pos) + vel * dt + drift;
+        let mut vel = vel;
+
+        if p.layer_mask & layer::AIR != 0 && !open_world && pos.x > max_x {
+            // Wind tunnel: air leaving on the right re-enters on the left at wind speed
+            pos.x = min_x + 5.0;
+            vel = WIND_VELOCITY;
+            p.density = params.target_density_air;
+            p.pressure = 0.0;
+            f.state_tag = 0;
+        }
+        if open_world && p.layer_mask & (layer::WATER | layer::AIR) != 0 {
+            let width = max_x - min_x;
+            let rel = pos.x - min_x;
+            pos.x = min_x + rel - (rel / width).floor() * width;
+        }
+        if pos.y < min_y + BOUNDARY_MARGIN {
+            pos.y = min_y + BOUNDARY_MARGIN;
+            vel.y = vel.y.abs() * 0.3;
+        }
+        if pos.y > max_y - BOUNDARY_MARGIN {
+            pos.y = max_y - BOUNDARY_MARGIN;
+            vel.y = -vel.y.abs() * 0.3;
+        }
+        if params.max_velocity > 0.0 {
+            vel = vel.clamp_length_max(params.max_velocity);
+        }
+        (p.pos, p.vel) = (pos.into(), vel.into());
+    }
+}
+
+/// The whole simulation state, stepped on the CPU
+#[derive(Clone, Debug)]
+pub struct ReferenceSim {
+    pub particles: Vec<Particle>,
+    pub bonds: Vec<Bond>,
+    pub params: SimParams,
+    pub grid: GridParams,
+    pub interactions: InteractionTable,
+    /// Force accumulator and integrator history (the `ForceBuffer`)
+    pub forces: Vec<ParticleForce>,
+    /// Bonds broken so far (the break log head)
+    pub break_head: u32,
+    /// Statistics of the last step, as stats.wgsl reduces them
+    pub stats: StatsState,
+    /// PCISPH state of the last step (untouched with the Tait EOS)
+    pub pressure_solver: PressureSolverState,
+    adjacency: Adjacency,
+}
+
+impl ReferenceSim {
+    pub fn new(
+        particles: Vec<Particle>,
+        bonds: Vec<Bond>,
+        params: SimParams,
+        grid: GridParams,
+        interactions: InteractionTable,
+    ) -> Self {
+        let adjacency = bond_adjacency(&bonds, particles.len());
+        Self {
+            forces: vec![ParticleForce::default(); particles.len()],
+            particles,
+            bonds,
+            params,
+            grid,
+            interactions,
+            break_head: 0,
+            stats: StatsState::default(),
+            pressure_solver: PressureSolverState::default(),
+            adjacency,
+        }
+    }
+
+    /// Advance one step of `params.delta_time`
+    pub fn step(&mut self) {
+        for p in &mut self.particles {
+            p.cell_id = cell_id(p.pos, &self.grid);
+        }
+        // The open world's grid wraps, as the render world uploads it
+        let grid = GridParams { wrap: self.params.open_world, ..self.grid };
+        let neighbours = NeighbourGrid::build(&self.particles, grid);
+
+        for _ in 0..CONSTRAINT_ITERATIONS {
+            self.particles = constraints(&self.particles, &neighbours, &self.params);
+        }
+
+        let volumes = if self.params.boundary_handling == BoundaryHandling::Akinci as u32 {
+            boundary_volumes(&self.particles, &neighbours, &self.params)
+        } else {
+            vec![0.0; self.particles.len()]
+        };
+        self.particles = density(&self.particles, &neighbours, &self.params, &self.interactions, &volumes);
+        fluid_forces(&self.particles, &neighbours, &self.params, &self.interactions, &volumes, &mut self.forces);
+
+        let (bond_forces, broken) = bond_forces(&self.particles, &mut self.bonds, &self.params);
+        self.break_head = self.break_head.wrapping_add(broken);
+        for (i, force) in self.forces.iter_mut().enumerate() {
+            force.bond = gather_bond_force(i, &self.bonds, &bond_forces, &self.adjacency).into();
+        }
+
+        if self.params.pressure_solver == PressureSolver::Pcisph as u32 {
+            self.pressure_solver = pcisph(&mut self.particles, &neighbours, &self.params, &volumes, &mut self.forces);
+        }
+
+        integrate(&mut self.particles, &mut self.forces, &self.params, &self.interactions);
+        self.stats = reduce_stats(&self.particles, &self.bonds, self.break_head, &self.stats);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::bond_type;
+    use crate::simulation::default_interaction_table;
+    use crate::simulation::ping_pong::constraint_iteration;
+    use crate::simulation::kinematics::kinematic_targets;
+    use crate::simulation::reorder::{
+        cell_permutation, movable_particles, movable_slots, permute, permute_ids, remap_bond,
+    };
+    use crate::simulation::rigid_body::rigid_members;
+    use crate::simulation::setup::{scenario_bonds, PARTICLE_COUNT};
+    use crate::simulation::Scenario;
+    use bytemuck::Zeroable;
+
+    fn sim(particles: Vec<Particle>, bonds: Vec<Bond>) -> ReferenceSim {
+        ReferenceSim::new(particles, bonds, SimParams::default(), GridParams::default(), default_interaction_table())
+    }
+
+    #[test]
+    fn cell_ids_clamp_to_the_grid() {
+        let grid = GridParams::default();
+        assert_eq!(cell_id([-640.0, -360.0], &grid), 0);
+        assert_eq!(cell_id([-615.0, -335.0], &grid), grid.grid_width + 1);
+        assert_eq!(cell_id([-9000.0, 0.0], &grid), cell_id([-630.0, 0.0], &grid));
+        assert_eq!(cell_id([9000.0, 9000.0], &grid), grid.grid_width * grid.grid_height - 1);
+    }
+
+    #[test]
+    fn constraints_match_the_all_pairs_mirror() {
+        let positions: Vec<Vec2> =
+            (0..24).map(|i| Vec2::new((i % 6) as f32 * 2.1, (i / 6) as f32 * 1.7 + (i % 2) as f32 * 0.4)).collect();
+        let mut particles: Vec<Particle> = positions.iter().map(|&pos| Particle::new_water(pos.into(), [0.0; 2])).collect();
+        let grid = GridParams::default();
+        for p in &mut particles {
+            p.cell_id = cell_id(p.pos, &grid);
+        }
+        let neighbours = NeighbourGrid::build(&particles, grid);
+        let params = SimParams::default();
+
+        let next = constraints(&particles, &neighbours, &params);
+        let expected = constraint_iteration(&positions, params.smoothing_radius, 0..positions.len());
+        for (p, e) in next.iter().zip(&expected) {
+            assert!(Vec2::from(p.pos).distance(*e) < 1e-4, "{:?} != {e}", p.pos);
+        }
+        assert_ne!(next[7].pos, particles[7].pos);
+    }
+
+    #[test]
+    fn density_sums_same_layer_neighbours_only() {
+        let water = Particle::new_water([0.0, 0.0], [0.0; 2]);
+        // Outside each other's collision distance, so the constraints leave them be
+        let other = Particle::new_water([4.2, 3.0], [0.0; 2]);
+        let air = Particle { z_height: 0.0, ..Particle::new_air([-6.0, 0.0], [0.0; 2]) };
+        let mut sim = sim(vec![water, other, air], vec![]);
+        sim.step();
+
+        let kernel = SphKernel::default();
+        let distance = Vec2::new(4.2, 3.0).length();
+        let expected = water.mass * (kernels::w(kernel, 0.0, 10.0) + kernels::w(kernel, distance, 10.0));
+        assert!((sim.particles[0].density - expected).abs() < 1e-3 * expected);
+        assert!(sim.particles[0].pressure > 0.0);
+        assert!(sim.particles[0].pressure <= sim.interactions.pressure_cap);
+        // Pushed apart by pressure
+        assert!(sim.particles[0].vel[0] < 0.0 && sim.particles[1].vel[0] > 0.0);
+    }
+
+    #[test]
+    fn open_world_neighbours_reach_across_the_seam() {
+        // 4.2 apart across the window's left/right edge, and the same pair mid-window
+        let seam_pair = vec![Particle::new_water([-638.0, 0.0], [0.0; 2]), Particle::new_water([637.8, 0.0], [0.0; 2])];
+        let middle_pair = vec![Particle::new_water([2.1, 0.0], [0.0; 2]), Particle::new_water([-2.1, 0.0], [0.0; 2])];
+        let run = |particles: Vec<Particle>, open_world: u32| {
+            let mut sim = sim(particles, vec![]);
+            sim.params.open_world = open_world;
+            sim.step();
+            sim
+        };
+        let seam = run(seam_pair.clone(), 1);
+        let middle = run(middle_pair, 1);
+        for (actual, expected) in seam.particles.iter().zip(&middle.particles) {
+            assert!((actual.density - expected.density).abs() < 1e-3 * expected.density);
+            assert!((actual.vel[0] - expected.vel[0]).abs() < 1e-3 * expected.vel[0].abs());
+        }
+        // Pushed apart the short way: the left particle to the right and vice versa
+        assert!(seam.particles[0].vel[0] > 0.0 && seam.particles[1].vel[0] < 0.0);
+
+        // Walled in, the pair is a window apart
+        let walled = run(seam_pair, 0);
+        assert!(walled.particles[0].density < 0.9 * seam.particles[0].density);
+    }
+
+    #[test]
+    fn open_world_fluid_crosses_the_side_seam_but_not_the_floor() {
+        let mut alone = sim(vec![Particle::new_water([100.0, 0.0], [0.0; 2])], vec![]);
+        alone.step();
+        let lone_density = alone.particles[0].density;
+
+        let mut world = sim(
+            vec![
+                // About to leave on the right, and sinking through the floor
+                Particle::new_water([639.0, 0.0], [300.0, 0.0]),
+                Particle::new_water([0.0, -357.0], [0.0, -300.0]),
+                // 4.2 apart across the bottom/top edge
+                Particle::new_water([100.0, -358.0], [0.0; 2]),
+                Particle::new_water([100.0, 357.8], [0.0; 2]),
+            ],
+            vec![],
+        );
+        world.params.open_world = 1;
+        world.step();
+
+        // Re-entered on the left, still moving right
+        let crossed = world.particles[0];
+        assert!(crossed.pos[0] < -630.0, "x = {}", crossed.pos[0]);
+        assert!(crossed.vel[0] > 250.0);
+
+        // The floor clamps and bounces as in the tank
+        assert_eq!(world.particles[1].pos[1], world.params.bounds[2] + BOUNDARY_MARGIN);
+        assert!(world.particles[1].vel[1] > 0.0);
+
+        // The bottom and top edges are not neighbours
+        for p in &world.particles[2..] {
+            assert!((p.density - lone_density).abs() < 1e-3 * lone_density);
+        }
+    }
+
+    #[test]
+    fn overstretched_bond_breaks_once_and_is_counted() {
+        let a = Particle::new_mast([0.0, 0.0], [0.0; 2]);
+        // Beyond the kernel support, so only the bond acts between them
+        let b = Particle::new_mast([40.0, 0.0], [0.0; 2]);
+        let bond = Bond {
+            particle_a: 0,
+            particle_b: 1,
+            rest_length: 25.0,
+            stiffness: 10000.0,
+            breaking_strain: 0.2,
+            bond_type: bond_type::HULL,
+            is_active: 1,
+            ..Bond::zeroed()
+        };
+        let mut stretched = sim(vec![a, b], vec![bond]);
+        stretched.step();
+        assert_eq!(stretched.bonds[0].is_active, 0);
+        assert_eq!((stretched.break_head, stretched.stats.broken_bonds, stretched.stats.active_bonds), (1, 1, 0));
+        stretched.step();
+        assert_eq!((stretched.break_head, stretched.stats.broken_bonds, stretched.stats.step), (1, 0, 2));
+
+        // Within its breaking strain it pulls the particles together instead
+        let mut held = sim(vec![a, Particle { pos: [27.0, 0.0], ..b }], vec![bond]);
+        held.step();
+        assert_eq!(held.bonds[0].is_active, 1);
+        assert!(held.particles[0].vel[0] > 0.0 && held.particles[1].vel[0] < 0.0);
+    }
+
+    /// A resting square block of water, 5% denser than `target_density_water` inside
+    fn compressed_water_block(side: usize) -> (Vec<Particle>, NeighbourGrid, SimParams) {
+        let grid = GridParams::default();
+        let mut particles: Vec<Particle> = (0..side * side)
+            .map(|i| Particle::new_water([(i % side) as f32 * 10.0, (i / side) as f32 * 10.0], [0.0; 2]))
+            .collect();
+        for p in &mut particles {
+            p.cell_id = cell_id(p.pos, &grid);
+        }
+        let neighbours = NeighbourGrid::build(&particles, grid);
+        let mut params = SimParams {
+            pressure_solver: PressureSolver::Pcisph as u32,
+            pressure_max_iterations: 64,
+            ..SimParams::default()
+        };
+        let volumes = vec![0.0; particles.len()];
+        let lattice = density(&particles, &neighbours, &params, &default_interaction_table(), &volumes);
+        params.target_density_water = lattice[side / 2 * side + side / 2].density / 1.05;
+        (particles, neighbours, params)
+    }
+
+    fn solve(particles: &[Particle], neighbours: &NeighbourGrid, params: &SimParams) -> (Vec<Particle>, Vec<ParticleForce>, PressureSolverState) {
+        let mut particles = particles.to_vec();
+        let mut forces = vec![ParticleForce::default(); particles.len()];
+        let volumes = vec![0.0; particles.len()];
+        let state = pcisph(&mut particles, neighbours, params, &volumes, &mut forces);
+        (particles, forces, state)
+    }
+
+    #[test]
+    fn pcisph_iterates_until_the_density_error_is_within_tolerance() {
+        let (particles, neighbours, params) = compressed_water_block(8);
+
+        let (_, _, first) = solve(&particles, &neighbours, &SimParams { pressure_max_iterations: 1, ..params });
+        assert_eq!((first.iterations, first.converged), (1, 0));
+        assert!(first.last_max_error > params.pressure_tolerance, "{first:?}");
+
+        let (solved, forces, state) = solve(&particles, &neighbours, &params);
+        assert_eq!(state.converged, 1, "{state:?}");
+        assert!(state.last_max_error <= params.pressure_tolerance);
+        assert!(state.iterations > 1 && state.iterations < params.pressure_max_iterations, "{state:?}");
+        assert!(solved.iter().all(|p| p.pressure >= 0.0) && solved.iter().any(|p| p.pressure > 0.0));
+        // The pressure force pushes the block apart: the corners accelerate outwards
+        let (first_corner, last_corner) = (Vec2::from(forces[0].fluid), Vec2::from(forces[63].fluid));
+        assert!(first_corner.x < 0.0 && first_corner.y < 0.0, "{first_corner}");
+        assert!(last_corner.x > 0.0 && last_corner.y > 0.0, "{last_corner}");
+    }
+
+    #[test]
+    fn pcisph_reports_an_exhausted_iteration_budget() {
+        let (particles, neighbours, params) = compressed_water_block(8);
+        let tight = SimParams { pressure_tolerance: 1e-7, pressure_max_iterations: 3, ..params };
+        let (_, _, state) = solve(&particles, &neighbours, &tight);
+        assert_eq!((state.iterations, state.converged, state.max_error_bits), (3, 0, 0));
+        assert!(state.last_max_error > tight.pressure_tolerance);
+
+        // Nothing compressed: converged after the first check, no pressure force
+        let sparse: Vec<Particle> = particles
+            .iter()
+            .map(|&p| {
+                let pos = [p.pos[0] * 4.0, p.pos[1] * 4.0];
+                Particle { pos, cell_id: cell_id(pos, &neighbours.grid), ..p }
+            })
+            .collect();
+        let relaxed = SimParams { target_density_water: 1.0, ..params };
+        let (_, forces, state) = solve(&sparse, &NeighbourGrid::build(&sparse, neighbours.grid), &relaxed);
+        assert_eq!((state.iterations, state.converged, state.last_max_error), (1, 1, 0.0));
+        assert!(forces.iter().all(|f| f.fluid == [0.0, 0.0]));
+    }
+
+    #[test]
+    fn static_particles_stay_put() {
+        let wall = Particle { mass: 1.0e6, layer_mask: layer::HULL, vel: [5.0, 0.0], ..Particle::zeroed() };
+        let water = Particle::new_water([1.0, 0.0], [-20.0, 0.0]);
+        let mut sim = sim(vec![wall, water], vec![]);
+        for _ in 0..10 {
+            sim.step();
+        }
+        assert_eq!((sim.particles[0].pos, sim.particles[0].vel), ([0.0, 0.0], [0.0, 0.0]));
+        assert_eq!(sim.stats.nan_count, 0);
+    }
+
+    #[test]
+    fn reordering_only_relabels_the_particles() {
+        // A splashing water block with a bonded pair of hull particles dropped in
+        let mut particles: Vec<Particle> = (0..36)
+            .map(|i| Particle::new_water([(i % 6) as f32 * 6.0, (i / 6) as f32 * 6.0], [(i % 5) as f32 - 2.0, 3.0]))
+            .collect();
+        particles.push(Particle::new_mast([8.0, 40.0], [0.0, -10.0]));
+        particles.push(Particle::new_mast([20.0, 40.0], [0.0, -10.0]));
+        let bond = Bond {
+            particle_a: 36,
+            particle_b: 37,
+            rest_length: 12.0,
+            stiffness: 10000.0,
+            breaking_strain: 0.5,
+            bond_type: bond_type::HULL,
+            is_active: 1,
+            ..Bond::zeroed()
+        };
+        let mut original = sim(particles, vec![bond]);
+        original.step();
+
+        // Permute into the order the last step's sort left them in, as reorder.wgsl does
+        let indices = NeighbourGrid::build(&original.particles, original.grid).indices;
+        let movable = vec![1; original.particles.len()];
+        let new_slot = cell_permutation(&indices, &movable, &movable_slots(&movable));
+        assert_ne!(new_slot, (0..38).collect::<Vec<u32>>());
+        let mut bonds = original.bonds.clone();
+        for bond in &mut bonds {
+            remap_bond(bond, &new_slot);
+        }
+        let mut reordered = sim(permute(&original.particles, &new_slot), bonds);
+        reordered.forces = permute(&original.forces, &new_slot);
+
+        for _ in 0..5 {
+            original.step();
+            reordered.step();
+        }
+        // Same physics up to the summation order within a cell
+        for (expected, actual) in permute(&original.particles, &new_slot).iter().zip(&reordered.particles) {
+            let (expected, actual) = (Vec2::from(expected.pos), Vec2::from(actual.pos));
+            assert!(expected.distance(actual) < 1e-3, "{expected} vs {actual}");
+        }
+        assert_eq!(reordered.stats.active_bonds, original.stats.active_bonds);
+    }
+
+    #[test]
+    fn one_reorder_of_the_default_scenario_keeps_ids_and_bonds() {
+        let scenario = Scenario::default();
+        let (particles, _) = scenario.spawn_particles(PARTICLE_COUNT);
+        let mut sim = sim(particles, scenario_bonds(scenario));
+        sim.step();
+
+        let bodies = scenario.rigid_bodies();
+        let movable = movable_particles(
+            &rigid_members(&bodies, PARTICLE_COUNT),
+            &kinematic_targets(&scenario.kinematic_joints(), &bodies, PARTICLE_COUNT),
+            &scenario.angular_springs(),
+        );
+        let indices = NeighbourGrid::build(&sim.particles, sim.grid).indices;
+        let new_slot = cell_permutation(&indices, &movable, &movable_slots(&movable));
+        let identity: Vec<u32> = (0..PARTICLE_COUNT as u32).collect();
+        assert_ne!(new_slot, identity);
+
+        // Both tables are bijections, inverse to each other, and fixed particles stay put
+        let (ids, slots) = permute_ids(&identity, &new_slot);
+        let mut sorted = ids.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, identity);
+        for (slot, &id) in ids.iter().enumerate() {
+            assert_eq!(slots[id as usize], slot as u32);
+            assert_eq!(new_slot[id as usize], slot as u32);
+            if movable[slot] == 0 {
+                assert_eq!(id, slot as u32);
+            }
+        }
+
+        // Every bond still joins the same two particles, now at their new slots
+        let moved = permute(&sim.particles, &new_slot);
+        let mut active = 0;
+        for original in sim.bonds.iter().filter(|bond| bond.rest_length > 0.0) {
+            let mut bond = *original;
+            remap_bond(&mut bond, &new_slot);
+            assert_eq!((ids[bond.particle_a as usize], ids[bond.particle_b as usize]), (original.particle_a, original.particle_b));
+            assert_eq!(moved[bond.particle_a as usize].pos, sim.particles[original.particle_a as usize].pos);
+            assert_eq!(moved[bond.particle_b as usize].pos, sim.particles[original.particle_b as usize].pos);
+            active += bond.is_active;
+        }
+        assert!(active > 0);
+    }
+}
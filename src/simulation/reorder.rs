@@ -0,0 +1,165 @@
+//! Periodic permutation of the particle data into cell order (CPU mirror of reorder.wgsl).
+//!
+//! The counting sort only orders the index buffer, so the neighbour loops read each
+//! neighbour through `particles[indices[j]]`, scattered over the whole buffer. Every
+//! `SimulationSettings::reorder_interval` steps the particles themselves are moved into
+//! cell order, with an old -> new slot table (`cell_permutation`) that the bonds and
+//! index buffer are remapped through.
+//!
+//! Particles owned by a rigid body, driven by a kinematic joint or part of a bending
+//! triplet never move: body ranges, kinematic targets and the triplet adjacency address
+//! fixed slots. The others are packed, in cell order, into the slots movable particles
+//! already held. Handles outside the GPU (`BondQueue`, `BondBroken`, snapshots) keep the
+//! original particle ids; the GPU keeps an id <-> slot table for them.
+//!
+//! Every pass reads the previous state and writes the next (see `ping_pong`), so relabelling
+//! the particles between steps does not change the physics, only the memory order.
+
+use super::bending::AngularSpring;
+use super::kinematics::{KinematicTarget, NO_DRIVER};
+use super::rigid_body::{RigidMember, NO_BODY};
+use crate::resources::Bond;
+
+/// Per-slot flag (1 = may move) for particles outside rigid bodies, kinematic joints and
+/// bending triplets
+pub fn movable_particles(
+    members: &[RigidMember],
+    targets: &[KinematicTarget],
+    springs: &[AngularSpring],
+) -> Vec<u32> {
+    let mut movable: Vec<u32> = members
+        .iter()
+        .zip(targets)
+        .map(|(member, target)| (member.body == NO_BODY && target.driver == NO_DRIVER) as u32)
+        .collect();
+    for spring in springs {
+        for particle in [spring.particle_a, spring.particle_b, spring.particle_c] {
+            if let Some(flag) = movable.get_mut(particle as usize) {
+                *flag = 0;
+            }
+        }
+    }
+    movable
+}
+
+/// Slots of the movable particles, ascending (the slots the permutation fills)
+pub fn movable_slots(movable: &[u32]) -> Vec<u32> {
+    (0..movable.len() as u32).filter(|&slot| movable[slot as usize] != 0).collect()
+}
+
+/// New slot of every particle: movable particles take the movable slots in the order
+/// `indices` (the scatter's cell order) visits them, the others stay. Mirrors the `flag`,
+/// scan and `remap` passes.
+pub fn cell_permutation(indices: &[u32], movable: &[u32], movable_slots: &[u32]) -> Vec<u32> {
+    let mut new_slot: Vec<u32> = (0..movable.len() as u32).collect();
+    let mut rank = 0;
+    for &old in indices {
+        if movable[old as usize] != 0 {
+            new_slot[old as usize] = movable_slots[rank];
+            rank += 1;
+        }
+    }
+    new_slot
+}
+
+/// `items` moved to their new slots (`permute` in reorder.wgsl)
+pub fn permute<T: Copy>(items: &[T], new_slot: &[u32]) -> Vec<T> {
+    let mut permuted = items.to_vec();
+    for (old, item) in items.iter().enumerate() {
+        permuted[new_slot[old] as usize] = *item;
+    }
+    permuted
+}
+
+/// Slot -> id and id -> slot tables after the permutation, from the slot -> id table
+/// before it (`permute` in reorder.wgsl)
+pub fn permute_ids(ids: &[u32], new_slot: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    let ids = permute(ids, new_slot);
+    let mut slots = vec![0; ids.len()];
+    for (slot, &id) in ids.iter().enumerate() {
+        slots[id as usize] = slot as u32;
+    }
+    (ids, slots)
+}
+
+/// Both ends of a used bond slot remapped (`remap_bonds` in reorder.wgsl)
+pub fn remap_bond(bond: &mut Bond, new_slot: &[u32]) {
+    if bond.rest_length > 0.0 {
+        bond.particle_a = new_slot[bond.particle_a as usize];
+        bond.particle_b = new_slot[bond.particle_b as usize];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::grid::counting_sort;
+    use bytemuck::Zeroable;
+
+    fn members(bodies: &[u32]) -> Vec<RigidMember> {
+        bodies.iter().map(|&body| RigidMember { body, ..RigidMember::default() }).collect()
+    }
+
+    #[test]
+    fn movable_particles_skip_bodies_joints_and_triplets() {
+        let members = members(&[NO_BODY, 0, 0, NO_BODY, NO_BODY, NO_BODY, NO_BODY]);
+        let mut targets = vec![KinematicTarget::default(); 7];
+        targets[3].driver = 0;
+        let springs = [AngularSpring { particle_a: 4, particle_b: 5, particle_c: 1, ..AngularSpring::default() }];
+
+        let movable = movable_particles(&members, &targets, &springs);
+        assert_eq!(movable, [1, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(movable_slots(&movable), [0, 6]);
+    }
+
+    #[test]
+    fn permutation_puts_movable_particles_in_cell_order() {
+        let cell_ids = [3, 0, 3, 1, 3, 0, 2, 1];
+        let movable = [1, 1, 0, 1, 1, 1, 0, 1];
+        let (offsets, indices) = counting_sort(&cell_ids, 4);
+        let new_slot = cell_permutation(&indices, &movable, &movable_slots(&movable));
+
+        // A bijection that leaves the fixed particles alone
+        let mut sorted = new_slot.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..8).collect::<Vec<u32>>());
+        assert_eq!((new_slot[2], new_slot[6]), (2, 6));
+
+        // Read in slot order, the movable particles' cells never decrease
+        let cells = permute(&cell_ids, &new_slot);
+        let moved: Vec<u32> = (0..8).filter(|&slot| movable[slot] != 0).map(|slot| cells[slot]).collect();
+        assert!(moved.windows(2).all(|pair| pair[0] <= pair[1]), "{moved:?}");
+
+        // The index buffer remapped through the table is the sort of the permuted cells
+        let remapped: Vec<u32> = indices.iter().map(|&i| new_slot[i as usize]).collect();
+        for cell in 0..4 {
+            for &slot in &remapped[offsets[cell] as usize..offsets[cell + 1] as usize] {
+                assert_eq!(cells[slot as usize], cell as u32);
+            }
+        }
+    }
+
+    #[test]
+    fn remapped_bonds_and_ids_follow_their_particles() {
+        let new_slot = [2, 0, 1, 3];
+        let positions = [10.0, 11.0, 12.0, 13.0];
+        let moved = permute(&positions, &new_slot);
+
+        let mut bond = Bond { particle_a: 0, particle_b: 3, rest_length: 1.0, ..Bond::zeroed() };
+        remap_bond(&mut bond, &new_slot);
+        assert_eq!((moved[bond.particle_a as usize], moved[bond.particle_b as usize]), (10.0, 13.0));
+
+        // Unused slots keep their (meaningless) ends
+        let mut unused = Bond { particle_a: 1, particle_b: 2, rest_length: 0.0, ..Bond::zeroed() };
+        remap_bond(&mut unused, &new_slot);
+        assert_eq!((unused.particle_a, unused.particle_b), (1, 2));
+
+        // Permuted along, the slot -> id table still names every particle after two reorders
+        let second = [1, 2, 3, 0];
+        let ids = permute(&permute(&[0u32, 1, 2, 3], &new_slot), &second);
+        let moved = permute(&moved, &second);
+        for slot in 0..4 {
+            assert_eq!(moved[slot], positions[ids[slot] as usize]);
+        }
+    }
+}
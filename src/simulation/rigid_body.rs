@@ -0,0 +1,532 @@
+//! Shape-matching rigid bodies.
+//!
+//! A rigid body is a contiguous range of particles declared by the scenario
+//! (`Scenario::rigid_bodies`). After integration, `rigid_body.wgsl` runs one workgroup
+//! per body and fits the best rigid transform to the particles (2D shape matching,
+//! Müller et al. 2005):
+//!
+//! - centre of mass `c = Σ m x / Σ m`, linear velocity `V = Σ m v / Σ m`
+//! - rotation `θ = atan2(Σ m q × p, Σ m q · p)` for rest offsets `q` (taken about their
+//!   mass-weighted mean, which moves when a joint re-poses part of a body) and current
+//!   offsets `p`
+//! - angular velocity `ω = Σ m p × (v - V) / Σ m |q|²` (rest inertia, so projecting
+//!   onto the rigid pose conserves angular momentum)
+//!
+//! With `RigidBodyMode::ShapeMatching`, dynamic bodies are integrated like any other
+//! particle (the mass > 10000 freeze no longer applies to them), their internal bonds
+//! are skipped, and their particles are projected onto `c + R(θ) q` with the rigid
+//! velocity `V + ω × R(θ) q`. Kinematic bodies are moved by something else and only
+//! measured. Rest offsets are captured from the first measured pose, so a body's
+//! angle is relative to its spawn orientation. Body state is read back into `RigidBodies`.
+//!
+//! In `RigidBodyMode::Springs` the bond lattice keeps the shape, but members driven by a
+//! joint of their own body (the rudder, see `kinematics`) still follow their re-posed
+//! rest offsets: a critically damped spring (`joint_kick`) pulls each towards its pose,
+//! and the rest of the body takes the opposite linear and angular impulse. Loads on the
+//! driven part (the water on the blade) deflect it against the spring and reach the body.
+
+use bevy::prelude::*;
+use bytemuck::{Pod, Zeroable};
+
+use super::readback::{ReadbackChannel, ReadbackResource, ReadbackRing};
+use super::stats::STATIC_MASS;
+
+/// Natural frequency of the springs-mode joint spring (rad/s, matches rigid_body.wgsl)
+pub const JOINT_FREQUENCY: f32 = 20.0;
+
+/// Cap on `JOINT_FREQUENCY * dt`, so the explicit spring stays stable at large steps
+const MAX_JOINT_STEP: f32 = 0.5;
+
+/// How rigid structures keep their shape. Must match `SimParams.rigid_bodies` in shaders.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RigidBodyMode {
+    /// Stiff bond lattices and frozen (mass > 10000) particles; bodies are only measured
+    #[default]
+    Springs = 0,
+    /// Dynamic bodies are projected onto their best-fit rigid transform every step
+    ShapeMatching = 1,
+}
+
+/// How a body moves (matches `BODY_*` in rigid_body.wgsl)
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RigidBodyKind {
+    /// Moved by forces, kept rigid by shape matching
+    #[default]
+    Dynamic = 0,
+    /// Moved by something else (frozen or scripted); state is measured only
+    Kinematic = 1,
+}
+
+/// A rigid body declared by a scenario
+#[derive(Clone, Copy, Debug)]
+pub struct RigidBodyDesc {
+    pub name: &'static str,
+    /// First particle of the body's contiguous particle range
+    pub first_particle: u32,
+    pub particle_count: u32,
+    pub kind: RigidBodyKind,
+    /// Fraction of the way particles are moved onto the rigid pose per step (1 = fully rigid)
+    pub stiffness: f32,
+}
+
+/// Marks a particle that belongs to no body
+pub const NO_BODY: u32 = u32::MAX;
+
+/// GPU body state (matches `RigidBody` in rigid_body.wgsl). 64 bytes.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable, Debug, Default)]
+pub struct RigidBody {
+    pub first_particle: u32,
+    pub particle_count: u32,
+    /// `RigidBodyKind`
+    pub kind: u32,
+    pub stiffness: f32,
+    pub centre_of_mass: [f32; 2],
+    pub linear_velocity: [f32; 2],
+    /// Orientation relative to the captured rest pose (unwrapped, radians)
+    pub angle: f32,
+    pub angular_velocity: f32,
+    pub mass: f32,
+    /// Rest inertia Σ m |q|² about the centre of mass
+    pub inertia: f32,
+    /// 1 once the rest offsets were captured
+    pub captured: u32,
+    pub _padding: [u32; 3],
+}
+
+impl From<&RigidBodyDesc> for RigidBody {
+    fn from(desc: &RigidBodyDesc) -> Self {
+        Self {
+            first_particle: desc.first_particle,
+            particle_count: desc.particle_count,
+            kind: desc.kind as u32,
+            stiffness: desc.stiffness,
+            ..Default::default()
+        }
+    }
+}
+
+/// Per-particle body membership (matches `RigidMember` in rigid_body.wgsl). 16 bytes.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable, Debug)]
+pub struct RigidMember {
+    /// Owning body, or `NO_BODY`
+    pub body: u32,
+    /// 1 once a kinematic joint pivoting on the owning body drives the particle (set by
+    /// kinematics.wgsl)
+    pub driven: u32,
+    /// Offset from the centre of mass in the rest pose (captured on the GPU)
+    pub rest_offset: [f32; 2],
+}
+
+impl Default for RigidMember {
+    fn default() -> Self {
+        Self {
+            body: NO_BODY,
+            driven: 0,
+            rest_offset: [0.0; 2],
+        }
+    }
+}
+
+/// Membership table for `particle_count` particles
+pub fn rigid_members(descs: &[RigidBodyDesc], particle_count: usize) -> Vec<RigidMember> {
+    let mut members = vec![RigidMember::default(); particle_count];
+    for (body, desc) in descs.iter().enumerate() {
+        let start = desc.first_particle as usize;
+        let end = (start + desc.particle_count as usize).min(particle_count);
+        for member in &mut members[start..end] {
+            member.body = body as u32;
+        }
+    }
+    members
+}
+
+/// Best-fit rigid motion of a particle set
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RigidFit {
+    pub centre_of_mass: Vec2,
+    /// Mass-weighted mean of the rest offsets (zero unless a joint re-posed part of the body)
+    pub rest_centre: Vec2,
+    pub angle: f32,
+    pub linear_velocity: Vec2,
+    pub angular_velocity: f32,
+}
+
+fn cross(a: Vec2, b: Vec2) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+/// Fit a rigid transform to particles with rest offsets `rest`
+/// (CPU mirror of `shape_match` in rigid_body.wgsl, dynamic bodies).
+/// The angle is unwrapped to the branch closest to `previous_angle`.
+pub fn fit_rigid_transform(
+    rest: &[Vec2],
+    positions: &[Vec2],
+    velocities: &[Vec2],
+    masses: &[f32],
+    previous_angle: f32,
+) -> RigidFit {
+    let mass: f32 = masses.iter().sum();
+    if mass <= 0.0 {
+        return RigidFit::default();
+    }
+    let com = positions.iter().zip(masses).map(|(x, m)| *x * *m).sum::<Vec2>() / mass;
+    let vel = velocities.iter().zip(masses).map(|(v, m)| *v * *m).sum::<Vec2>() / mass;
+    let rest_centre = rest.iter().zip(masses).map(|(q, m)| *q * *m).sum::<Vec2>() / mass;
+
+    let (mut sin_sum, mut cos_sum, mut momentum, mut inertia) = (0.0, 0.0, 0.0, 0.0);
+    for i in 0..masses.len() {
+        let p = positions[i] - com;
+        let q = rest[i] - rest_centre;
+        let m = masses[i];
+        sin_sum += m * cross(q, p);
+        cos_sum += m * q.dot(p);
+        momentum += m * cross(p, velocities[i] - vel);
+        inertia += m * q.length_squared();
+    }
+
+    let turn = sin_sum.atan2(cos_sum) - previous_angle;
+    let turn = turn - std::f32::consts::TAU * (turn / std::f32::consts::TAU).round();
+    RigidFit {
+        centre_of_mass: com,
+        rest_centre,
+        angle: previous_angle + turn,
+        linear_velocity: vel,
+        angular_velocity: if inertia > 0.0 { momentum / inertia } else { 0.0 },
+    }
+}
+
+impl RigidFit {
+    /// Position of the particle with rest offset `rest` in the fitted pose
+    pub fn goal_position(&self, rest: Vec2) -> Vec2 {
+        self.centre_of_mass + Vec2::from_angle(self.angle).rotate(rest - self.rest_centre)
+    }
+
+    /// Rigid-body velocity of the particle with rest offset `rest`
+    pub fn rigid_velocity(&self, rest: Vec2) -> Vec2 {
+        let arm = Vec2::from_angle(self.angle).rotate(rest - self.rest_centre);
+        self.linear_velocity + self.angular_velocity * arm.perp()
+    }
+}
+
+/// Velocity change of the joint spring for a particle `error` away from its pose and
+/// `slip` slower than the pose moves (critically damped)
+pub fn joint_kick(error: Vec2, slip: Vec2, dt: f32) -> Vec2 {
+    if dt <= 0.0 {
+        return Vec2::ZERO;
+    }
+    let w = (JOINT_FREQUENCY * dt).min(MAX_JOINT_STEP);
+    w * w / dt * error + 2.0 * w * slip
+}
+
+/// Springs-mode joint drive (CPU mirror of step 4 of rigid_body.wgsl). Returns the new
+/// velocities: driven members are kicked towards their pose in `fit`, the other movable
+/// members take the opposite impulse about their own centre of mass, so the body's
+/// momentum and angular momentum are unchanged.
+pub fn drive_joint_members(
+    fit: &RigidFit,
+    rest: &[Vec2],
+    positions: &[Vec2],
+    velocities: &[Vec2],
+    masses: &[f32],
+    driven: &[bool],
+    dt: f32,
+) -> Vec<Vec2> {
+    let com = fit.centre_of_mass;
+    let kick = |i: usize| {
+        let arm = fit.goal_position(rest[i]) - com;
+        joint_kick(arm - (positions[i] - com), fit.rigid_velocity(rest[i]) - velocities[i], dt)
+    };
+
+    let (mut impulse, mut moment, mut rest_mass, mut first, mut second) = (Vec2::ZERO, 0.0, 0.0, Vec2::ZERO, 0.0);
+    for i in 0..masses.len() {
+        let (m, offset) = (masses[i], positions[i] - com);
+        if m > STATIC_MASS {
+            continue;
+        }
+        if driven[i] {
+            let j = m * kick(i);
+            impulse += j;
+            moment += cross(offset, j);
+        } else {
+            rest_mass += m;
+            first += m * offset;
+            second += m * offset.length_squared();
+        }
+    }
+    let centre = if rest_mass > 0.0 { first / rest_mass } else { Vec2::ZERO };
+    let rest_inertia = second - rest_mass * centre.length_squared();
+    let torque = moment - cross(centre, impulse);
+    let spin = if rest_inertia > 0.0 { torque / rest_inertia } else { 0.0 };
+
+    (0..masses.len())
+        .map(|i| {
+            let v = velocities[i];
+            if masses[i] > STATIC_MASS {
+                v
+            } else if driven[i] {
+                v + kick(i)
+            } else if rest_mass > 0.0 {
+                v - impulse / rest_mass - spin * (positions[i] - com - centre).perp()
+            } else {
+                v
+            }
+        })
+        .collect()
+}
+
+/// Measured state of one rigid body
+#[derive(Clone, Copy, Debug)]
+pub struct RigidBodyState {
+    pub name: &'static str,
+    pub kind: RigidBodyKind,
+    pub centre_of_mass: Vec2,
+    /// Orientation relative to the spawn pose (radians, unwrapped)
+    pub angle: f32,
+    pub linear_velocity: Vec2,
+    pub angular_velocity: f32,
+    pub mass: f32,
+}
+
+/// Latest body states, updated from an asynchronous GPU readback.
+/// Lags the simulation by a few frames; empty until the first readback lands.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct RigidBodies {
+    pub bodies: Vec<RigidBodyState>,
+    names: Vec<(&'static str, RigidBodyKind)>,
+}
+
+impl RigidBodies {
+    pub fn new(descs: &[RigidBodyDesc]) -> Self {
+        Self {
+            bodies: Vec::new(),
+            names: descs.iter().map(|desc| (desc.name, desc.kind)).collect(),
+        }
+    }
+
+    /// Look up a body by scenario name
+    pub fn get(&self, name: &str) -> Option<&RigidBodyState> {
+        self.bodies.iter().find(|body| body.name == name)
+    }
+}
+
+/// Main-world end of the body state readback
+#[derive(Resource, Clone, Default)]
+pub struct RigidBodiesChannel(pub ReadbackChannel);
+
+/// Render-world staging ring for the body states
+#[derive(Resource)]
+pub struct RigidBodyReadback(pub ReadbackRing);
+
+impl ReadbackResource for RigidBodyReadback {
+    fn ring(&self) -> &ReadbackRing {
+        &self.0
+    }
+}
+
+/// Copy the latest read back body states into `RigidBodies`
+pub fn update_rigid_bodies(channel: Res<RigidBodiesChannel>, mut rigid_bodies: ResMut<RigidBodies>) {
+    let Some(bytes) = channel.0.take() else {
+        return;
+    };
+    let states: Vec<RigidBody> = bytes
+        .chunks_exact(std::mem::size_of::<RigidBody>())
+        .map(bytemuck::pod_read_unaligned)
+        .collect();
+    let bodies = rigid_bodies
+        .names
+        .iter()
+        .zip(&states)
+        .filter(|(_, state)| state.captured != 0)
+        .map(|(&(name, kind), state)| RigidBodyState {
+            name,
+            kind,
+            centre_of_mass: Vec2::from(state.centre_of_mass),
+            angle: state.angle,
+            linear_velocity: Vec2::from(state.linear_velocity),
+            angular_velocity: state.angular_velocity,
+            mass: state.mass,
+        })
+        .collect();
+    rigid_bodies.bodies = bodies;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 4x3 lattice (spacing 8) as offsets from its centre of mass, with unequal masses
+    fn lattice() -> (Vec<Vec2>, Vec<f32>) {
+        let mut points = Vec::new();
+        let mut masses = Vec::new();
+        for y in 0..3 {
+            for x in 0..4 {
+                points.push(Vec2::new(x as f32, y as f32) * 8.0);
+                masses.push(1.0 + (x + y) as f32);
+            }
+        }
+        let mass: f32 = masses.iter().sum();
+        let com = points.iter().zip(&masses).map(|(p, m)| *p * *m).sum::<Vec2>() / mass;
+        (points.into_iter().map(|p| p - com).collect(), masses)
+    }
+
+    #[test]
+    fn fit_recovers_rigid_motion() {
+        let (rest, masses) = lattice();
+        let truth = RigidFit {
+            centre_of_mass: Vec2::new(30.0, -12.0),
+            rest_centre: Vec2::ZERO,
+            angle: 0.7,
+            linear_velocity: Vec2::new(5.0, 2.0),
+            angular_velocity: -1.5,
+        };
+        let positions: Vec<Vec2> = rest.iter().map(|q| truth.goal_position(*q)).collect();
+        let velocities: Vec<Vec2> = rest.iter().map(|q| truth.rigid_velocity(*q)).collect();
+
+        let fit = fit_rigid_transform(&rest, &positions, &velocities, &masses, 0.0);
+        assert!((fit.centre_of_mass - truth.centre_of_mass).length() < 1e-4);
+        assert!((fit.angle - truth.angle).abs() < 1e-5);
+        assert!((fit.linear_velocity - truth.linear_velocity).length() < 1e-4);
+        assert!((fit.angular_velocity - truth.angular_velocity).abs() < 1e-4);
+    }
+
+    #[test]
+    fn projection_restores_shape_and_keeps_momentum() {
+        let (rest, masses) = lattice();
+        // Rotated, stretched and jittered: what a step of stiff springs might leave behind
+        let positions: Vec<Vec2> = rest
+            .iter()
+            .enumerate()
+            .map(|(i, q)| Vec2::from_angle(0.3).rotate(*q * Vec2::new(1.1, 0.95)) + Vec2::splat((i % 3) as f32 - 1.0))
+            .collect();
+        let velocities: Vec<Vec2> = (0..rest.len()).map(|i| Vec2::new(i as f32, -(i as f32) * 0.5)).collect();
+
+        let fit = fit_rigid_transform(&rest, &positions, &velocities, &masses, 0.0);
+        let projected: Vec<Vec2> = rest.iter().map(|q| fit.goal_position(*q)).collect();
+        let projected_vel: Vec<Vec2> = rest.iter().map(|q| fit.rigid_velocity(*q)).collect();
+
+        // Rigid: every pairwise distance is back at its rest value
+        for i in 0..rest.len() {
+            for j in 0..rest.len() {
+                let d = projected[i].distance(projected[j]);
+                assert!((d - rest[i].distance(rest[j])).abs() < 1e-3);
+            }
+        }
+
+        // Linear and angular momentum are unchanged by the projection
+        let momentum = |vels: &[Vec2]| vels.iter().zip(&masses).map(|(v, m)| *v * *m).sum::<Vec2>();
+        assert!((momentum(&velocities) - momentum(&projected_vel)).length() < 1e-2);
+        let com = fit.centre_of_mass;
+        let angular = |pos: &[Vec2], vels: &[Vec2]| -> f32 {
+            (0..masses.len()).map(|i| masses[i] * cross(pos[i] - com, vels[i])).sum()
+        };
+        let before = angular(&positions, &velocities);
+        let after = angular(&projected, &projected_vel);
+        assert!((before - after).abs() < 1e-3 * before.abs().max(1.0), "{before} vs {after}");
+    }
+
+    #[test]
+    fn reposed_part_does_not_shift_the_body() {
+        let (mut rest, masses) = lattice();
+        let positions = rest.clone();
+        let velocities = vec![Vec2::ZERO; rest.len()];
+
+        // Swing the last particle (a "rudder") about its neighbour: the rest offsets no
+        // longer average to zero, but the body itself must stay put
+        let hinge = rest[10];
+        rest[11] = hinge + Vec2::from_angle(0.5).rotate(rest[11] - hinge);
+        let fit = fit_rigid_transform(&rest, &positions, &velocities, &masses, 0.0);
+        let projected: Vec<Vec2> = rest.iter().map(|q| fit.goal_position(*q)).collect();
+        let mass: f32 = masses.iter().sum();
+        let com = projected.iter().zip(&masses).map(|(p, m)| *p * *m).sum::<Vec2>() / mass;
+        assert!((com - fit.centre_of_mass).length() < 1e-4);
+        assert!(projected[0].distance(positions[0]) < 0.5);
+    }
+
+    #[test]
+    fn joint_drive_keeps_momentum() {
+        let (mut rest, masses) = lattice();
+        let positions = rest.clone();
+        let velocities: Vec<Vec2> = (0..rest.len()).map(|i| Vec2::new(i as f32, 1.0 - i as f32 * 0.3)).collect();
+        // The last particle is driven a quarter turn about its neighbour
+        let hinge = rest[10];
+        rest[11] = hinge + Vec2::from_angle(1.5).rotate(rest[11] - hinge);
+        let mut driven = vec![false; rest.len()];
+        driven[11] = true;
+
+        let fit = fit_rigid_transform(&rest, &positions, &velocities, &masses, 0.0);
+        let after = drive_joint_members(&fit, &rest, &positions, &velocities, &masses, &driven, 0.01);
+
+        // The blade is kicked towards its pose
+        let goal = fit.goal_position(rest[11]);
+        assert!((after[11] - velocities[11]).dot(goal - positions[11]) > 0.0);
+
+        // Internal: linear and angular momentum are unchanged
+        let momentum = |vels: &[Vec2]| vels.iter().zip(&masses).map(|(v, m)| *v * *m).sum::<Vec2>();
+        assert!((momentum(&velocities) - momentum(&after)).length() < 1e-3);
+        let angular = |vels: &[Vec2]| -> f32 { (0..masses.len()).map(|i| masses[i] * cross(positions[i], vels[i])).sum() };
+        assert!((angular(&velocities) - angular(&after)).abs() < 1e-2);
+    }
+
+    #[test]
+    fn load_on_a_driven_blade_deflects_it_and_reaches_the_body() {
+        let (rest, masses) = lattice();
+        let mut driven = vec![false; rest.len()];
+        driven[11] = true;
+        let mut positions = rest.clone();
+        let mut velocities = vec![Vec2::ZERO; rest.len()];
+        // A steady sideways load on the blade only, like water on a rudder
+        let load = Vec2::new(0.0, 40.0);
+        let dt = 0.01;
+        let mut angle = 0.0;
+        for _ in 0..400 {
+            velocities[11] += load / masses[11] * dt;
+            for (x, v) in positions.iter_mut().zip(&velocities) {
+                *x += *v * dt;
+            }
+            let fit = fit_rigid_transform(&rest, &positions, &velocities, &masses, angle);
+            angle = fit.angle;
+            velocities = drive_joint_members(&fit, &rest, &positions, &velocities, &masses, &driven, dt);
+        }
+
+        // The whole load went into the body's momentum
+        let momentum: Vec2 = velocities.iter().zip(&masses).map(|(v, m)| *v * *m).sum();
+        assert!((momentum - load * 4.0).length() < 1e-2 * load.length() * 4.0, "{momentum}");
+        // The load sits off the centre of mass, so it turns the body
+        assert!(angle.abs() > 1e-3, "{angle}");
+
+        // The blade gives way along the load by about load / (m ω²), no more
+        let fit = fit_rigid_transform(&rest, &positions, &velocities, &masses, angle);
+        let deflection = positions[11] - fit.goal_position(rest[11]);
+        let expected = load.length() / (masses[11] * JOINT_FREQUENCY * JOINT_FREQUENCY);
+        assert!(deflection.dot(load.normalize()) > 0.0, "{deflection}");
+        assert!(deflection.length() < 3.0 * expected, "{deflection} vs {expected}");
+    }
+
+    #[test]
+    fn angle_unwraps_past_pi() {
+        let (rest, masses) = lattice();
+        let truth = RigidFit {
+            angle: 3.3,
+            ..Default::default()
+        };
+        let positions: Vec<Vec2> = rest.iter().map(|q| truth.goal_position(*q)).collect();
+        let velocities = vec![Vec2::ZERO; rest.len()];
+
+        let fit = fit_rigid_transform(&rest, &positions, &velocities, &masses, 3.0);
+        assert!((fit.angle - 3.3).abs() < 1e-5, "{}", fit.angle);
+    }
+
+    #[test]
+    fn members_cover_body_ranges() {
+        let descs = [
+            RigidBodyDesc { name: "a", first_particle: 2, particle_count: 3, kind: RigidBodyKind::Dynamic, stiffness: 1.0 },
+            RigidBodyDesc { name: "b", first_particle: 6, particle_count: 2, kind: RigidBodyKind::Kinematic, stiffness: 1.0 },
+        ];
+        let bodies: Vec<u32> = rigid_members(&descs, 9).iter().map(|m| m.body).collect();
+        assert_eq!(bodies, [NO_BODY, NO_BODY, 0, 0, 0, NO_BODY, 1, 1, NO_BODY]);
+    }
+}
@@ -0,0 +1,290 @@
+//! Multi-segment ropes.
+//!
+//! A rope is a chain of tension-only bonds (`bond_type::ROPE`) between two named
+//! particles, with intermediate particles so it can sag, wrap and drag through water.
+//! Scenarios name the particles ropes attach to (`Scenario::named_particles`) and
+//! describe ropes with `RopeBuilder` (`Scenario::ropes`), e.g.
+//! `RopeBuilder::new("mooring", "stern", "anchor").segments(12).slack(1.1)`. Sheets are
+//! ropes too (`RopeBuilder::sheet`): their segments trim with `SimParams.sheet_extension`.
+//!
+//! Intermediate particles take the tail of the particle buffer, so spawning and bond
+//! generation agree on indices without sharing state: both call `build_ropes` with the
+//! same inputs. Scenarios spawn their structures first and fill the rest with water or
+//! air, so the tail is fill: every rope particle replaces one fill particle, and a
+//! scenario with ropes has that many fewer fluid particles. `place_rope_particles`
+//! panics if a rope would overwrite anything but fill.
+
+use bevy::prelude::*;
+
+use crate::resources::{bond_type, layer, Bond, Particle};
+
+/// A particle ropes can attach to
+#[derive(Clone, Copy, Debug)]
+pub struct NamedParticle {
+    pub name: &'static str,
+    pub index: u32,
+    /// Spawn position (the rope is laid out straight between its ends)
+    pub position: Vec2,
+}
+
+/// Why a rope could not be built
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RopeError {
+    /// No named particle with this name
+    UnknownParticle(&'static str),
+    /// A rope needs at least one segment
+    NoSegments,
+    /// Both ends are the same particle
+    SameEnds,
+}
+
+impl std::fmt::Display for RopeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RopeError::UnknownParticle(name) => write!(f, "no particle named '{name}'"),
+            RopeError::NoSegments => write!(f, "a rope needs at least one segment"),
+            RopeError::SameEnds => write!(f, "both ends are the same particle"),
+        }
+    }
+}
+
+/// Describes a rope between two named particles
+#[derive(Clone, Debug)]
+pub struct RopeBuilder {
+    name: &'static str,
+    from: &'static str,
+    to: &'static str,
+    segments: u32,
+    slack: f32,
+    stiffness: f32,
+    breaking_strain: f32,
+    mass: f32,
+    z_height: f32,
+    bond_type: u32,
+}
+
+impl RopeBuilder {
+    /// A rope from `from` to `to` (named particles). Defaults: 8 segments laid taut,
+    /// stiffness 20000, breaking strain 2.0, particle mass 50 at water level.
+    pub fn new(name: &'static str, from: &'static str, to: &'static str) -> Self {
+        Self {
+            name,
+            from,
+            to,
+            segments: 8,
+            slack: 1.0,
+            stiffness: 20_000.0,
+            breaking_strain: 2.0,
+            mass: 50.0,
+            z_height: 0.0,
+            bond_type: bond_type::ROPE,
+        }
+    }
+
+    /// Number of bonds (`segments - 1` intermediate particles)
+    pub fn segments(mut self, segments: u32) -> Self {
+        self.segments = segments;
+        self
+    }
+
+    /// Rope length as a multiple of the distance between the ends (> 1 = slack)
+    pub fn slack(mut self, slack: f32) -> Self {
+        self.slack = slack;
+        self
+    }
+
+    /// Spring constant of every segment
+    pub fn stiffness(mut self, stiffness: f32) -> Self {
+        self.stiffness = stiffness;
+        self
+    }
+
+    /// Segment strain at which the rope parts
+    pub fn breaking_strain(mut self, breaking_strain: f32) -> Self {
+        self.breaking_strain = breaking_strain;
+        self
+    }
+
+    /// Mass of each intermediate particle
+    pub fn mass(mut self, mass: f32) -> Self {
+        self.mass = mass;
+        self
+    }
+
+    /// Interaction layer height of the intermediate particles (0 = water, 2 = air)
+    pub fn z_height(mut self, z_height: f32) -> Self {
+        self.z_height = z_height;
+        self
+    }
+
+    /// Make the segments sheet bonds (`bond_type::SHEET`): still tension-only, with rest
+    /// lengths scaled by `SimParams.sheet_extension`, so the whole line eases and hauls
+    pub fn sheet(mut self) -> Self {
+        self.bond_type = bond_type::SHEET;
+        self
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Intermediate particles this rope needs
+    pub fn intermediate_count(&self) -> u32 {
+        self.segments.saturating_sub(1)
+    }
+
+    /// Build the chain with its intermediate particles at `first_particle..`
+    pub fn build(&self, named: &[NamedParticle], first_particle: u32) -> Result<Rope, RopeError> {
+        let find = |name: &'static str| {
+            named.iter().find(|p| p.name == name).ok_or(RopeError::UnknownParticle(name))
+        };
+        let (from, to) = (find(self.from)?, find(self.to)?);
+        if self.segments == 0 {
+            return Err(RopeError::NoSegments);
+        }
+        if from.index == to.index {
+            return Err(RopeError::SameEnds);
+        }
+
+        let segments = self.segments as f32;
+        let particles: Vec<Particle> = (1..self.segments)
+            .map(|i| {
+                let pos = from.position.lerp(to.position, i as f32 / segments);
+                Particle::new_rope(pos.into(), [0.0, 0.0], self.mass, self.z_height)
+            })
+            .collect();
+
+        // Chain: from -> intermediates -> to
+        let chain: Vec<u32> = std::iter::once(from.index)
+            .chain(first_particle..first_particle + self.intermediate_count())
+            .chain(std::iter::once(to.index))
+            .collect();
+        let rest_length = self.slack * from.position.distance(to.position) / segments;
+        let bonds = chain
+            .windows(2)
+            .map(|pair| Bond {
+                particle_a: pair[0],
+                particle_b: pair[1],
+                rest_length,
+                stiffness: self.stiffness,
+                breaking_strain: self.breaking_strain,
+                bond_type: self.bond_type,
+                is_active: 1,
+                yield_strain: 0.0,
+                damage: 0.0,
+                compressive_breaking_strain: 0.0,
+                shear_limit: 0.0,
+                _padding: 0,
+            })
+            .collect();
+
+        Ok(Rope { name: self.name, first_particle, particles, bonds })
+    }
+}
+
+/// A built rope: intermediate particles (at `first_particle..`) and its bond chain
+#[derive(Clone, Debug)]
+pub struct Rope {
+    pub name: &'static str,
+    pub first_particle: u32,
+    pub particles: Vec<Particle>,
+    pub bonds: Vec<Bond>,
+}
+
+/// Build every rope, packing intermediate particles at the tail of a buffer of
+/// `particle_count` particles. Ropes that fail to build are skipped with a warning.
+pub fn build_ropes(builders: &[RopeBuilder], named: &[NamedParticle], particle_count: usize) -> Vec<Rope> {
+    let total: u32 = builders.iter().map(RopeBuilder::intermediate_count).sum();
+    let Some(mut next) = (particle_count as u32).checked_sub(total) else {
+        warn!("Ropes need {} particles, only {} exist; no ropes built", total, particle_count);
+        return Vec::new();
+    };
+    let mut ropes = Vec::new();
+    for builder in builders {
+        match builder.build(named, next) {
+            Ok(rope) => {
+                next += rope.particles.len() as u32;
+                ropes.push(rope);
+            }
+            Err(err) => warn!("Rope '{}' skipped: {}", builder.name(), err),
+        }
+    }
+    ropes
+}
+
+/// Write rope particles into the particle list (spawn side of `build_ropes`), replacing
+/// the fill particles at the tail
+pub fn place_rope_particles(particles: &mut [Particle], ropes: &[Rope]) {
+    for rope in ropes {
+        let start = rope.first_particle as usize;
+        let tail = &mut particles[start..start + rope.particles.len()];
+        if let Some(offset) = tail.iter().position(|p| p.layer_mask & (layer::WATER | layer::AIR) == 0) {
+            panic!(
+                "rope '{}' would overwrite structural particle {}; spawn fewer structures or more fill",
+                rope.name,
+                start + offset
+            );
+        }
+        tail.copy_from_slice(&rope.particles);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named() -> Vec<NamedParticle> {
+        vec![
+            NamedParticle { name: "bow", index: 3, position: Vec2::new(0.0, 0.0) },
+            NamedParticle { name: "anchor", index: 7, position: Vec2::new(40.0, 0.0) },
+        ]
+    }
+
+    #[test]
+    fn rope_chains_between_named_particles() {
+        let rope = RopeBuilder::new("mooring", "bow", "anchor")
+            .segments(4)
+            .slack(1.5)
+            .build(&named(), 90)
+            .unwrap();
+
+        assert_eq!(rope.particles.len(), 3);
+        let chain: Vec<(u32, u32)> = rope.bonds.iter().map(|b| (b.particle_a, b.particle_b)).collect();
+        assert_eq!(chain, [(3, 90), (90, 91), (91, 92), (92, 7)]);
+        assert!(rope.bonds.iter().all(|b| b.bond_type == bond_type::ROPE && b.rest_length == 15.0));
+        assert_eq!(rope.particles[1].pos, [20.0, 0.0]);
+    }
+
+    #[test]
+    fn ropes_fill_the_tail_and_skip_bad_ends() {
+        let builders = [
+            RopeBuilder::new("a", "bow", "anchor").segments(3),
+            RopeBuilder::new("b", "bow", "buoy"),
+            RopeBuilder::new("c", "anchor", "bow").segments(2),
+        ];
+        assert_eq!(
+            builders[1].build(&named(), 0).unwrap_err(),
+            RopeError::UnknownParticle("buoy")
+        );
+
+        // 2 + 7 + 1 intermediates reserved, "b" skipped
+        let ropes = build_ropes(&builders, &named(), 100);
+        assert_eq!(ropes.len(), 2);
+        assert_eq!(ropes[0].first_particle, 90);
+        assert_eq!(ropes[1].first_particle, 92);
+
+        let mut particles = vec![Particle::new_air([0.0, 0.0], [0.0, 0.0]); 100];
+        place_rope_particles(&mut particles, &ropes);
+        assert_eq!(particles[92].layer_mask, layer::HULL);
+        assert_eq!(particles[89].layer_mask, layer::AIR);
+    }
+
+    #[test]
+    #[should_panic(expected = "would overwrite structural particle 99")]
+    fn ropes_never_overwrite_structures() {
+        let ropes = build_ropes(&[RopeBuilder::new("a", "bow", "anchor").segments(3)], &named(), 100);
+        let mut particles = vec![Particle::new_water([0.0, 0.0], [0.0, 0.0]); 100];
+        particles[99] = Particle::new_mast([0.0, 0.0], [0.0, 0.0]);
+        place_rope_particles(&mut particles, &ropes);
+    }
+}
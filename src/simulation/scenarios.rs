@@ -1,28 +1,133 @@
 //! Test Scenarios for the Sailing SPH Simulation
 //!
-//! To switch scenarios, comment/uncomment the desired scenario in `spawn_particles()`.
+//! `Scenario` picks one at run time: set `SimulationSettings::scenario` (see
+//! `SimulationSettings::for_scenario`) before adding `SimulationPlugin`, or pass
+//! `--scenario` to `sailing-headless`. Every per-scenario table (particles, bodies,
+//! joints, bending, ropes, boundary handling, bonds) is selected from it.
 
-use rand::Rng;
+use std::str::FromStr;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use crate::resources::Particle;
+use bevy::math::Vec2;
+use super::bending::{bending_chain, AngularSpring, SAIL_BENDING, SPAR_BENDING};
+use super::kinematics::{KinematicJointDesc, RUDDER_DRIVER};
+use super::physics_config::BoundaryHandling;
+use super::ropes::{build_ropes, place_rope_particles, NamedParticle, Rope, RopeBuilder};
+use super::rigid_body::{RigidBodyDesc, RigidBodyKind};
 
-// ==================== SCENARIO SELECTION ====================
-// Uncomment ONE scenario function call in spawn_particles() below.
-// Each scenario returns (particles, hull_bounds) for exclusion zones.
-// =============================================================
+/// Names of the scenarios, as `Scenario::from_str` accepts them
+pub const SCENARIOS: [&str; 4] = ["dry_dock", "water_only", "pressure_washer", "hurricane"];
 
 /// Hull bounding box for exclusion zones (min_x, max_x, min_y, max_y)
 pub type HullBounds = Option<(f32, f32, f32, f32)>;
 
-/// Main entry point - calls the selected scenario
-pub fn spawn_particles(particle_count: usize) -> (Vec<Particle>, HullBounds) {
-    // ========================================
-    // SELECT YOUR SCENARIO HERE:
-    // ========================================
-    
-    // scenario_dry_dock(particle_count)          // Hull + Water (default)
-    // scenario_water_only(particle_count)     // Just water particles
-    // scenario_pressure_washer(particle_count) // Wind hitting a wall
-    scenario_hurricane(particle_count)
+/// The scene a run starts from
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Scenario {
+    /// Hull + rudder floating in a water current, moored upstream
+    DryDock,
+    /// Just water particles (SPH tuning)
+    WaterOnly,
+    /// Wind hitting a static wall
+    PressureWasher,
+    /// Hull, mast, spar and sail in a gale (top-down)
+    #[default]
+    Hurricane,
+}
+
+impl Scenario {
+    /// In `SCENARIOS` order
+    pub const ALL: [Scenario; 4] = [Self::DryDock, Self::WaterOnly, Self::PressureWasher, Self::Hurricane];
+
+    /// Name as listed in `SCENARIOS`
+    pub fn name(self) -> &'static str {
+        SCENARIOS[self as usize]
+    }
+
+    /// Spawn the scenario's particles, then lay out its ropes
+    pub fn spawn_particles(self, particle_count: usize) -> (Vec<Particle>, HullBounds) {
+        let (mut particles, hull_bounds) = match self {
+            Self::DryDock => scenario_dry_dock(particle_count),
+            Self::WaterOnly => scenario_water_only(particle_count),
+            Self::PressureWasher => scenario_pressure_washer(particle_count),
+            Self::Hurricane => scenario_hurricane(particle_count),
+        };
+        place_rope_particles(&mut particles, &self.built_ropes(particle_count));
+        (particles, hull_bounds)
+    }
+
+    /// Rigid bodies of the scenario
+    pub fn rigid_bodies(self) -> Vec<RigidBodyDesc> {
+        match self {
+            Self::DryDock => dry_dock_rigid_bodies(),
+            Self::WaterOnly | Self::PressureWasher => Vec::new(),
+            Self::Hurricane => hurricane_rigid_bodies(),
+        }
+    }
+
+    /// Kinematic joints of the scenario
+    pub fn kinematic_joints(self) -> Vec<KinematicJointDesc> {
+        match self {
+            Self::DryDock => dry_dock_kinematic_joints(),
+            Self::WaterOnly | Self::PressureWasher => Vec::new(),
+            Self::Hurricane => hurricane_kinematic_joints(),
+        }
+    }
+
+    /// Bending triplets of the scenario
+    pub fn angular_springs(self) -> Vec<AngularSpring> {
+        match self {
+            Self::DryDock | Self::WaterOnly | Self::PressureWasher => Vec::new(),
+            Self::Hurricane => hurricane_angular_springs(),
+        }
+    }
+
+    /// Particles the scenario's ropes can attach to
+    pub fn named_particles(self) -> Vec<NamedParticle> {
+        match self {
+            Self::DryDock => dry_dock_named_particles(),
+            Self::WaterOnly | Self::PressureWasher => Vec::new(),
+            Self::Hurricane => hurricane_named_particles(),
+        }
+    }
+
+    /// Fluid-solid coupling. Scenarios opt into Akinci boundary particles; the others
+    /// keep the tuned repulsion.
+    pub fn boundary_handling(self) -> BoundaryHandling {
+        match self {
+            Self::DryDock | Self::WaterOnly | Self::PressureWasher => BoundaryHandling::Repulsion,
+            // Buoyancy and sail drag from fluid pressure
+            Self::Hurricane => BoundaryHandling::Akinci,
+        }
+    }
+
+    /// Ropes of the scenario
+    pub fn ropes(self) -> Vec<RopeBuilder> {
+        match self {
+            Self::DryDock => dry_dock_ropes(),
+            Self::WaterOnly | Self::PressureWasher => Vec::new(),
+            Self::Hurricane => hurricane_ropes(),
+        }
+    }
+
+    /// Ropes of the scenario with their particles and bonds. Rope particles overwrite
+    /// the tail of the particle buffer; `BondBuffer` appends the bonds.
+    pub fn built_ropes(self, particle_count: usize) -> Vec<Rope> {
+        build_ropes(&self.ropes(), &self.named_particles(), particle_count)
+    }
+}
+
+impl FromStr for Scenario {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        SCENARIOS
+            .iter()
+            .position(|&scenario| scenario == name)
+            .map(|index| Self::ALL[index])
+            .ok_or_else(|| format!("unknown scenario {name} (one of {})", SCENARIOS.join(", ")))
+    }
 }
 
 // ==================== SCENARIO CONFIGS ====================
@@ -35,6 +140,24 @@ pub mod config {
     pub const HULL_START_Y: f32 = 100.0;
     pub const HULL_MASS: f32 = 8000.0;
     
+    // Rudder Configuration (a blade behind the stern, spawned right after the hull)
+    pub const RUDDER_LENGTH: usize = 4;
+    pub const HULL_START_X: f32 = -(HULL_WIDTH as f32 * HULL_SPACING) / 2.0;
+    /// Pintle: one spacing aft of the stern, on the hull centreline
+    pub const RUDDER_PINTLE: [f32; 2] = [
+        HULL_START_X - HULL_SPACING,
+        HULL_START_Y + (HULL_HEIGHT - 1) as f32 * HULL_SPACING / 2.0,
+    ];
+    
+    // Mooring: a static anchor upstream of the stern (the current flows +x), spawned
+    // after the rudder. The rope leaves from the top stern corner, clear of the rudder.
+    pub const MOORING_ROW: usize = HULL_HEIGHT - 1;
+    pub const MOORING_ANCHOR: [f32; 2] = [
+        HULL_START_X - 150.0,
+        HULL_START_Y + MOORING_ROW as f32 * HULL_SPACING,
+    ];
+    pub const MOORING_SEGMENTS: u32 = 12;
+    
     // Water Configuration
     pub const WATER_SPAWN_X_MIN: f32 = -600.0;
     pub const WATER_SPAWN_X_MAX: f32 = 600.0;
@@ -48,15 +171,23 @@ pub mod config {
 
 // ==================== SCENARIOS ====================
 
+/// Seed of the random fluid fill, so every run of a scenario starts from the same
+/// particles (the GPU determinism check compares whole runs)
+const SPAWN_SEED: u64 = 0x5A11_1465;
+
+fn spawn_rng() -> StdRng {
+    StdRng::seed_from_u64(SPAWN_SEED)
+}
+
 /// Scenario: Dry Dock
 /// Hull grid floating in water. Tests buoyancy and rigid body behavior.
 pub fn scenario_dry_dock(particle_count: usize) -> (Vec<Particle>, HullBounds) {
     use config::*;
-    let mut rng = rand::thread_rng();
+    let mut rng = spawn_rng();
     let mut particles = Vec::with_capacity(particle_count);
     
     // Spawn Hull Grid
-    let start_x = -(HULL_WIDTH as f32 * HULL_SPACING) / 2.0;
+    let start_x = HULL_START_X;
     
     for y in 0..HULL_HEIGHT {
         for x in 0..HULL_WIDTH {
@@ -68,10 +199,17 @@ pub fn scenario_dry_dock(particle_count: usize) -> (Vec<Particle>, HullBounds) {
             particles.push(p);
         }
     }
+    spawn_rudder(&mut particles, RUDDER_PINTLE, RUDDER_LENGTH, HULL_SPACING, HULL_MASS);
+    
+    // Mooring anchor (static; the mooring rope is laid out by spawn_particles)
+    let mut anchor = Particle::new_water(MOORING_ANCHOR, [0.0, 0.0]);
+    anchor.layer_mask = crate::resources::layer::HULL;
+    anchor.mass = 100000.0; // Static - physics shader treats mass > 10000 as immovable
+    particles.push(anchor);
     
-    // Calculate hull bounding box (with margin)
+    // Calculate hull bounding box (with margin, including the rudder)
     let hull_bounds = Some((
-        start_x - HULL_SPACING * 2.0,
+        start_x - HULL_SPACING * (RUDDER_LENGTH as f32 + 2.0),
         start_x + (HULL_WIDTH as f32) * HULL_SPACING + HULL_SPACING * 2.0,
         HULL_START_Y - HULL_SPACING * 2.0,
         HULL_START_Y + (HULL_HEIGHT as f32) * HULL_SPACING + HULL_SPACING * 2.0,
@@ -97,12 +235,72 @@ pub fn scenario_dry_dock(particle_count: usize) -> (Vec<Particle>, HullBounds) {
     (particles, hull_bounds)
 }
 
+/// Dry dock: the hull lattice and its rudder are one dynamic body
+pub fn dry_dock_rigid_bodies() -> Vec<RigidBodyDesc> {
+    use config::*;
+    vec![RigidBodyDesc {
+        name: "hull",
+        first_particle: 0,
+        particle_count: (HULL_WIDTH * HULL_HEIGHT + RUDDER_LENGTH) as u32,
+        kind: RigidBodyKind::Dynamic,
+        stiffness: 1.0,
+    }]
+}
+
+/// Dry dock: the rudder turns about its pintle with the helm
+pub fn dry_dock_kinematic_joints() -> Vec<KinematicJointDesc> {
+    use config::*;
+    vec![KinematicJointDesc {
+        driver: RUDDER_DRIVER,
+        pivot_body: "hull",
+        hinge: Vec2::from(RUDDER_PINTLE),
+        first_particle: (HULL_WIDTH * HULL_HEIGHT) as u32,
+        particle_count: RUDDER_LENGTH as u32,
+    }]
+}
+
+/// Dry dock: the mooring point on the stern and the mooring anchor
+pub fn dry_dock_named_particles() -> Vec<NamedParticle> {
+    use config::*;
+    let hull_count = HULL_WIDTH * HULL_HEIGHT;
+    vec![
+        NamedParticle {
+            name: "stern",
+            index: (MOORING_ROW * HULL_WIDTH) as u32,
+            position: Vec2::new(HULL_START_X, HULL_START_Y + MOORING_ROW as f32 * HULL_SPACING),
+        },
+        NamedParticle {
+            name: "anchor",
+            index: (hull_count + RUDDER_LENGTH) as u32,
+            position: Vec2::from(MOORING_ANCHOR),
+        },
+    ]
+}
+
+/// Dry dock: a slack mooring line holds the hull against the current
+pub fn dry_dock_ropes() -> Vec<RopeBuilder> {
+    vec![RopeBuilder::new("mooring", "stern", "anchor")
+        .segments(config::MOORING_SEGMENTS)
+        .slack(1.1)]
+}
+
+/// Rudder blade: `length` hull particles in a line running aft (-x) from the pintle.
+/// Rudder particles sit at water level with the hull, so the water pushes on them.
+fn spawn_rudder(particles: &mut Vec<Particle>, pintle: [f32; 2], length: usize, spacing: f32, mass: f32) {
+    for i in 0..length {
+        let px = pintle[0] - (i as f32) * spacing;
+        let mut p = Particle::new_water([px, pintle[1]], [0.0, 0.0]);
+        p.layer_mask = crate::resources::layer::HULL;
+        p.mass = mass;
+        particles.push(p);
+    }
+}
+
 /// Scenario: Water Only
 /// Pure water simulation with no hull. Good for tuning SPH parameters.
-#[allow(dead_code)]
 pub fn scenario_water_only(particle_count: usize) -> (Vec<Particle>, HullBounds) {
     use config::*;
-    let mut rng = rand::thread_rng();
+    let mut rng = spawn_rng();
     let mut particles = Vec::with_capacity(particle_count);
     
     while particles.len() < particle_count {
@@ -120,9 +318,8 @@ pub fn scenario_water_only(particle_count: usize) -> (Vec<Particle>, HullBounds)
 
 /// Scenario: Pressure Washer
 /// Wind (air) blasting against a wall of static particles.
-#[allow(dead_code)]
 pub fn scenario_pressure_washer(particle_count: usize) -> (Vec<Particle>, HullBounds) {
-    let mut rng = rand::thread_rng();
+    let mut rng = spawn_rng();
     let mut particles = Vec::with_capacity(particle_count);
     
     // Create vertical wall of static hull particles
@@ -154,9 +351,9 @@ pub fn scenario_pressure_washer(particle_count: usize) -> (Vec<Particle>, HullBo
 /// Scenario: Hurricane (Top-Down View)
 /// High-speed wind test for sail billow and mast fracture verification.
 /// Hull, mast, and sail OVERLAP in x,y but have different z_heights.
-#[allow(dead_code)]
 pub fn scenario_hurricane(particle_count: usize) -> (Vec<Particle>, HullBounds) {
-    let mut rng = rand::thread_rng();
+    use hurricane_config::{HULL_START_X, HULL_START_Y, RUDDER_LENGTH, RUDDER_PINTLE};
+    let mut rng = spawn_rng();
     let mut particles = Vec::with_capacity(particle_count);
     
     // ==================== HURRICANE SCENARIO CONFIG ====================
@@ -176,8 +373,8 @@ pub fn scenario_hurricane(particle_count: usize) -> (Vec<Particle>, HullBounds)
     
     // 1. Spawn Hull (rectangular deck - anchored/locked)
     // The hull is a grid at z=0 (water level)
-    let hull_start_x = -80.0;
-    let hull_start_y = -20.0;
+    let hull_start_x = HULL_START_X;
+    let hull_start_y = HULL_START_Y;
     let hull_width = 20usize;
     let hull_height = 5usize;
     let hull_spacing = 8.0;
@@ -196,6 +393,13 @@ pub fn scenario_hurricane(particle_count: usize) -> (Vec<Particle>, HullBounds)
     let hull_particle_count = particles.len();
     println!("  Hull particles: 0..{}", hull_particle_count);
     
+    // 1b. Spawn Rudder (blade behind the stern, turned by the helm)
+    spawn_rudder(&mut particles, RUDDER_PINTLE, RUDDER_LENGTH, hull_spacing, 100000.0);
+    for p in &mut particles[hull_particle_count..] {
+        p.z_height = HULL_Z;
+    }
+    println!("  Rudder particles: {}..{}", hull_particle_count, particles.len());
+    
     // 2. Spawn Mast (single column of particles at center of hull, at high z)
     // In top-down view, mast is a single point but we make it a small cluster
     let mast_x = hull_start_x + (hull_width as f32 * hull_spacing) / 2.0;
@@ -217,8 +421,9 @@ pub fn scenario_hurricane(particle_count: usize) -> (Vec<Particle>, HullBounds)
     let mast_end_idx = particles.len();
     println!("  Mast particles: {}..{}", mast_start_idx, mast_end_idx);
     
-    // 3. Spawn Spar (boom extending from mast along sail length)
-    // The spar is a 10x2 grid of mast particles that the sail attaches to
+    // 3. Spawn Spar (yard crossing the mast along sail length)
+    // The spar is a 10x2 grid of mast particles that the sail attaches to. It pivots
+    // freely on the mast; the sheets (see setup.rs) set how far it swings.
     let spar_start_x = mast_x + SAIL_SPACING; // One spacing unit right of mast
     let spar_start_y = mast_y - (SAIL_HEIGHT as f32 * SAIL_SPACING) / 2.0;
     let spar_start_idx = particles.len();
@@ -228,9 +433,8 @@ pub fn scenario_hurricane(particle_count: usize) -> (Vec<Particle>, HullBounds)
         for x in 0..SPAR_DEPTH {
             let px = spar_start_x + (x as f32) * SAIL_SPACING;
             let py = spar_start_y + (y as f32) * SAIL_SPACING;
-            let mut p = Particle::new_mast([px, py], [0.0, 0.0]); // Same as mast (heavy wood)
+            let mut p = Particle::new_mast([px, py], [0.0, 0.0]); // Same as mast (heavy wood), but free to move
             p.z_height = MAST_Z; // Spar at mast level (z=1) - doesn't interact with air
-            p.mass = 100000.0; // Static - physics shader treats mass > 10000 as immovable
             particles.push(p);
         }
     }
@@ -264,7 +468,7 @@ pub fn scenario_hurricane(particle_count: usize) -> (Vec<Particle>, HullBounds)
     
     // Return bounds (for water exclusion if needed)
     let hull_bounds = Some((
-        hull_start_x - hull_spacing,
+        hull_start_x - hull_spacing * (RUDDER_LENGTH as f32 + 1.0),
         hull_start_x + (hull_width as f32) * hull_spacing + (SAIL_WIDTH as f32) * SAIL_SPACING + hull_spacing,
         hull_start_y - hull_spacing,
         hull_start_y + (hull_height as f32) * hull_spacing + hull_spacing,
@@ -273,6 +477,139 @@ pub fn scenario_hurricane(particle_count: usize) -> (Vec<Particle>, HullBounds)
     (particles, hull_bounds)
 }
 
+/// Hurricane: hull (with its rudder), mast and spar are dynamic bodies. The spar swings
+/// on the mast, held by the sheets.
+pub fn hurricane_rigid_bodies() -> Vec<RigidBodyDesc> {
+    use hurricane_config::*;
+    let hull_count = HULL_WIDTH * HULL_HEIGHT + RUDDER_LENGTH;
+    let mast_count = MAST_GRID_SIZE * MAST_GRID_SIZE;
+    let spar_count = SPAR_LENGTH * SPAR_DEPTH;
+    vec![
+        RigidBodyDesc {
+            name: "hull",
+            first_particle: 0,
+            particle_count: hull_count as u32,
+            kind: RigidBodyKind::Dynamic,
+            stiffness: 1.0,
+        },
+        RigidBodyDesc {
+            name: "mast",
+            first_particle: hull_count as u32,
+            particle_count: mast_count as u32,
+            kind: RigidBodyKind::Dynamic,
+            stiffness: 1.0,
+        },
+        RigidBodyDesc {
+            name: "spar",
+            first_particle: (hull_count + mast_count) as u32,
+            particle_count: spar_count as u32,
+            kind: RigidBodyKind::Dynamic,
+            stiffness: 1.0,
+        },
+    ]
+}
+
+/// Hurricane: the rudder turns about its pintle with the helm (the sail is trimmed by
+/// its sheets, not driven)
+pub fn hurricane_kinematic_joints() -> Vec<KinematicJointDesc> {
+    use hurricane_config::*;
+    vec![KinematicJointDesc {
+        driver: RUDDER_DRIVER,
+        pivot_body: "hull",
+        hinge: Vec2::from(RUDDER_PINTLE),
+        first_particle: (HULL_WIDTH * HULL_HEIGHT) as u32,
+        particle_count: RUDDER_LENGTH as u32,
+    }]
+}
+
+/// Hurricane: bending along each spar column (flex, snaps when overloaded) and each
+/// sail column (smooth billow)
+pub fn hurricane_angular_springs() -> Vec<AngularSpring> {
+    use hurricane_config::*;
+    let spar_start = HULL_WIDTH * HULL_HEIGHT + RUDDER_LENGTH + MAST_GRID_SIZE * MAST_GRID_SIZE;
+    let sail_start = spar_start + SPAR_LENGTH * SPAR_DEPTH;
+    let column = |start: usize, width: usize, length: usize, x: usize| -> Vec<u32> {
+        (0..length).map(|y| (start + y * width + x) as u32).collect()
+    };
+    let mut springs = Vec::new();
+    for x in 0..SPAR_DEPTH {
+        springs.extend(bending_chain(&column(spar_start, SPAR_DEPTH, SPAR_LENGTH, x), &SPAR_BENDING));
+    }
+    for x in 0..SAIL_WIDTH {
+        springs.extend(bending_chain(&column(sail_start, SAIL_WIDTH, SAIL_HEIGHT, x), &SAIL_BENDING));
+    }
+    springs
+}
+
+/// Hurricane: attachment points for ropes: bow, stern and mast, and for each sheet the
+/// sail clew and the deck block on the hull edge below it
+pub fn hurricane_named_particles() -> Vec<NamedParticle> {
+    use hurricane_config::*;
+    let row = HULL_HEIGHT / 2;
+    let row_y = HULL_START_Y + row as f32 * HULL_SPACING;
+    let mast_centre = HULL_WIDTH * HULL_HEIGHT + RUDDER_LENGTH + MAST_GRID_SIZE * MAST_GRID_SIZE / 2;
+
+    // Clews: the outer sail column at both ends of the yard. Blocks: the hull particle
+    // in the clews' column on the first and last hull row.
+    let sail_start = HULL_WIDTH * HULL_HEIGHT + RUDDER_LENGTH + MAST_GRID_SIZE * MAST_GRID_SIZE + SPAR_LENGTH * SPAR_DEPTH;
+    let clew_x = MAST_X + (SPAR_DEPTH + SAIL_WIDTH) as f32 * SAIL_SPACING;
+    let yard_start_y = MAST_Y - (SAIL_HEIGHT as f32 * SAIL_SPACING) / 2.0;
+    let block_column = ((clew_x - HULL_START_X) / HULL_SPACING).round() as usize;
+    let clew = |name, sail_row: usize| NamedParticle {
+        name,
+        index: (sail_start + sail_row * SAIL_WIDTH + SAIL_WIDTH - 1) as u32,
+        position: Vec2::new(clew_x, yard_start_y + sail_row as f32 * SAIL_SPACING),
+    };
+    let block = |name, hull_row: usize| NamedParticle {
+        name,
+        index: (hull_row * HULL_WIDTH + block_column) as u32,
+        position: Vec2::new(
+            HULL_START_X + block_column as f32 * HULL_SPACING,
+            HULL_START_Y + hull_row as f32 * HULL_SPACING,
+        ),
+    };
+
+    vec![
+        clew("starboard_clew", 0),
+        clew("port_clew", SAIL_HEIGHT - 1),
+        block("starboard_block", 0),
+        block("port_block", HULL_HEIGHT - 1),
+        NamedParticle {
+            name: "bow",
+            index: (row * HULL_WIDTH + HULL_WIDTH - 1) as u32,
+            position: Vec2::new(HULL_START_X + (HULL_WIDTH - 1) as f32 * HULL_SPACING, row_y),
+        },
+        NamedParticle {
+            name: "stern",
+            index: (row * HULL_WIDTH) as u32,
+            position: Vec2::new(HULL_START_X, row_y),
+        },
+        NamedParticle {
+            name: "mast",
+            index: mast_centre as u32,
+            position: Vec2::new(MAST_X, MAST_Y),
+        },
+    ]
+}
+
+/// Hurricane: one sheet from each end of the yard to the deck, each stopping the yard
+/// swinging its way. Tension-only sheet segments, so the sail luffs when eased; the
+/// line sags through the air between clew and block.
+pub fn hurricane_ropes() -> Vec<RopeBuilder> {
+    use hurricane_config::*;
+    [("starboard_sheet", "starboard_block", "starboard_clew"), ("port_sheet", "port_block", "port_clew")]
+        .into_iter()
+        .map(|(name, block, clew)| {
+            RopeBuilder::new(name, block, clew)
+                .sheet()
+                .segments(SHEET_SEGMENTS)
+                .stiffness(SHEET_STIFFNESS)
+                .breaking_strain(10.0) // Trimming must not part the sheet
+                .z_height(2.0) // Sail and air level
+        })
+        .collect()
+}
+
 /// Hurricane scenario configuration (exported for bond generation)
 /// NOTE: Must match values in scenario_hurricane()!
 pub mod hurricane_config {
@@ -280,6 +617,20 @@ pub mod hurricane_config {
     pub const HULL_WIDTH: usize = 20;
     pub const HULL_HEIGHT: usize = 5;
     pub const HULL_SPACING: f32 = 8.0;
+    pub const HULL_START_X: f32 = -80.0;
+    pub const HULL_START_Y: f32 = -20.0;
+    
+    // Rudder: 3 particles behind the stern, spawned right after the hull
+    pub const RUDDER_LENGTH: usize = 3;
+    /// Pintle: one spacing aft of the stern, on the centre hull row
+    pub const RUDDER_PINTLE: [f32; 2] = [
+        HULL_START_X - HULL_SPACING,
+        HULL_START_Y + (HULL_HEIGHT / 2) as f32 * HULL_SPACING,
+    ];
+    
+    // Mast position (centre of the hull rectangle)
+    pub const MAST_X: f32 = HULL_START_X + HULL_WIDTH as f32 * HULL_SPACING / 2.0;
+    pub const MAST_Y: f32 = HULL_START_Y + HULL_HEIGHT as f32 * HULL_SPACING / 2.0;
     
     // Mast: 3x3 = 9 particles (cross-section in top-down view)
     pub const MAST_GRID_SIZE: usize = 3;
@@ -293,6 +644,84 @@ pub mod hurricane_config {
     pub const SAIL_WIDTH: usize = 2;
     pub const SAIL_HEIGHT: usize = 10;
     pub const SAIL_SPACING: f32 = 8.0;
+    
+    // Sheets: ropes from the deck blocks to the clews
+    pub const SHEET_SEGMENTS: u32 = 4;
+    pub const SHEET_STIFFNESS: f32 = 30_000.0;
 }
 
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::bond_type;
+    use crate::simulation::bonds::is_tension_only;
+    use crate::simulation::setup::{scenario_bonds, PARTICLE_COUNT};
+
+    #[test]
+    fn scenarios_parse_from_their_names() {
+        for scenario in Scenario::ALL {
+            assert_eq!(scenario.name().parse::<Scenario>(), Ok(scenario));
+        }
+        assert_eq!(Scenario::default().name(), "hurricane");
+        assert!("lava_lamp".parse::<Scenario>().unwrap_err().contains("dry_dock"));
+    }
+
+    #[test]
+    fn scenario_tables_match_the_spawned_particles() {
+        for scenario in Scenario::ALL {
+            let (particles, _) = scenario.spawn_particles(PARTICLE_COUNT);
+            assert_eq!(particles.len(), PARTICLE_COUNT, "{scenario:?}");
+
+            for named in scenario.named_particles() {
+                let spawned = Vec2::from(particles[named.index as usize].pos);
+                assert_eq!(spawned, named.position, "{scenario:?} {}", named.name);
+            }
+            for body in scenario.rigid_bodies() {
+                assert!((body.first_particle + body.particle_count) as usize <= PARTICLE_COUNT, "{scenario:?}");
+            }
+
+            // Structure bonds join the particles they were laid out for: the hull lattice
+            // (and rudder) come first, at their spawn distances
+            let bonds = scenario_bonds(scenario);
+            assert!(bonds.iter().all(|bond| bond.particle_a.max(bond.particle_b) < PARTICLE_COUNT as u32));
+            for bond in bonds.iter().take_while(|bond| bond.bond_type == bond_type::HULL) {
+                let a = Vec2::from(particles[bond.particle_a as usize].pos);
+                let b = Vec2::from(particles[bond.particle_b as usize].pos);
+                assert!((a.distance(b) - bond.rest_length).abs() < 1e-3, "{scenario:?} {bond:?}");
+            }
+        }
+        assert!(scenario_bonds(Scenario::WaterOnly).is_empty());
+    }
+
+    #[test]
+    fn hurricane_sheets_are_tension_only_ropes_from_deck_to_clew() {
+        use hurricane_config::{SHEET_SEGMENTS, SHEET_STIFFNESS};
+        let named = Scenario::Hurricane.named_particles();
+        let find = |name: &str| *named.iter().find(|p| p.name == name).unwrap();
+        let ropes = Scenario::Hurricane.built_ropes(PARTICLE_COUNT);
+        assert_eq!(ropes.len(), 2);
+
+        for (rope, (block, clew)) in ropes.iter().zip([("starboard_block", "starboard_clew"), ("port_block", "port_clew")]) {
+            let (block, clew) = (find(block), find(clew));
+            assert_eq!(rope.bonds.len(), SHEET_SEGMENTS as usize);
+            assert_eq!(rope.particles.len(), SHEET_SEGMENTS as usize - 1);
+            assert_eq!(rope.bonds[0].particle_a, block.index);
+            assert_eq!(rope.bonds.last().unwrap().particle_b, clew.index);
+
+            // Taut at full extension: the segments add up to the block-clew distance
+            let segment = block.position.distance(clew.position) / SHEET_SEGMENTS as f32;
+            for bond in &rope.bonds {
+                assert!((bond.rest_length - segment).abs() < 1e-4, "{bond:?}");
+                assert_eq!((bond.bond_type, bond.stiffness), (bond_type::SHEET, SHEET_STIFFNESS));
+                assert!(is_tension_only(bond));
+            }
+        }
+
+        // The ropes are rigged: their bonds are among the scenario's
+        let bonds = scenario_bonds(Scenario::Hurricane);
+        let sheet_bonds = bonds.iter().filter(|bond| bond.bond_type == bond_type::SHEET).count();
+        assert_eq!(sheet_bonds, 2 * SHEET_SEGMENTS as usize);
+    }
+}
@@ -0,0 +1,121 @@
+//! Per-run simulation settings.
+//!
+//! `SimulationSettings` lives in the main world and is copied into the render world
+//! every frame, where it is folded into the `SimParams` uniform. Insert it before
+//! adding `SimulationPlugin` to choose the scenario and settings for a run; otherwise
+//! `SimulationSettings::for_scenario(Scenario::default())` is used.
+
+use bevy::prelude::*;
+
+use super::integrator::IntegratorScheme;
+use super::kernels::SphKernel;
+use super::physics_config::BoundaryHandling;
+use super::pressure_solver::PressureSolver;
+use super::rigid_body::RigidBodyMode;
+use super::scenarios::Scenario;
+use super::timestep::TimestepSettings;
+use crate::resources::SimParams;
+
+/// Solver choices that can be changed without editing shaders.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct SimulationSettings {
+    /// Scene the buffers are built from (read once, when the plugin starts)
+    pub scenario: Scenario,
+    /// Time integration scheme used by physics.wgsl
+    pub integrator: IntegratorScheme,
+    /// SPH smoothing kernel used by density, forces and PCISPH
+    pub kernel: SphKernel,
+    /// Fluid-solid coupling: Akinci boundary particles or the old soft-sphere repulsion
+    pub boundary_handling: BoundaryHandling,
+    /// Water pressure solver: Tait EOS or PCISPH
+    pub pressure_solver: PressureSolver,
+    /// PCISPH: max relative density error (0.01 = 1%) at which iteration stops
+    pub pressure_tolerance: f32,
+    /// PCISPH: max correction iterations per step
+    pub pressure_max_iterations: u32,
+    /// Adaptive timestep controller (off = fixed `SimParams.delta_time`)
+    pub timestep: TimestepSettings,
+    /// Speed clamp for the fixed timestep. Ignored with the adaptive timestep, which
+    /// shrinks dt to keep fast particles resolved instead of clamping them.
+    pub max_velocity: f32,
+    /// Rigid structures: bond lattices or shape-matched rigid bodies
+    pub rigid_bodies: RigidBodyMode,
+    /// Permute the particle data into cell order every this many steps, so the
+    /// neighbour loops read mostly contiguous memory (0 = never; see `reorder`)
+    pub reorder_interval: u32,
+    /// No side walls: the grid and bounds follow the fleet along x and fluid wraps across
+    /// the window's left and right edges (see `world_window`). Off by default: the stock
+    /// scenarios are walled tanks.
+    pub open_world: bool,
+    /// Time every compute pass with GPU timestamp queries into `GpuTimings` (ignored
+    /// when the adapter has no timestamp support)
+    pub gpu_timing: bool,
+    /// Stop stepping after this many steps; the end-of-step readbacks (`SimStats`,
+    /// snapshots) keep arriving. `None` steps forever.
+    pub max_steps: Option<u32>,
+}
+
+impl Default for SimulationSettings {
+    fn default() -> Self {
+        Self {
+            scenario: Scenario::default(),
+            integrator: IntegratorScheme::default(),
+            kernel: SphKernel::default(),
+            boundary_handling: BoundaryHandling::default(),
+            pressure_solver: PressureSolver::default(),
+            pressure_tolerance: 0.01,
+            pressure_max_iterations: 8,
+            timestep: TimestepSettings::default(),
+            max_velocity: 500.0,
+            rigid_bodies: RigidBodyMode::default(),
+            reorder_interval: 0,
+            open_world: false,
+            gpu_timing: false,
+            max_steps: None,
+        }
+    }
+}
+
+impl SimulationSettings {
+    /// Defaults plus the solver choices `scenario` opts into. Every scenario reorders
+    /// every 10 steps: on llvmpipe that takes 39% off a hurricane step and 16% off a
+    /// water-only step, the reorder pass included.
+    pub fn for_scenario(scenario: Scenario) -> Self {
+        Self {
+            scenario,
+            boundary_handling: scenario.boundary_handling(),
+            reorder_interval: 10,
+            ..Self::default()
+        }
+    }
+
+    /// Write these settings into the GPU parameter block
+    pub fn apply(&self, params: &mut SimParams) {
+        params.integrator = self.integrator as u32;
+        params.kernel = self.kernel as u32;
+        params.boundary_handling = self.boundary_handling as u32;
+        params.pressure_solver = self.pressure_solver as u32;
+        params.pressure_tolerance = self.pressure_tolerance;
+        params.pressure_max_iterations = self.pressure_max_iterations;
+        params.rigid_bodies = self.rigid_bodies as u32;
+        params.open_world = self.open_world as u32;
+        params.max_velocity = if self.timestep.adaptive { 0.0 } else { self.max_velocity };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_settings_match_default_params() {
+        let mut params = SimParams::default();
+        SimulationSettings::default().apply(&mut params);
+        let defaults = SimParams::default();
+        assert_eq!(params.boundary_handling, BoundaryHandling::Repulsion as u32);
+        assert_eq!(
+            (params.integrator, params.kernel, params.boundary_handling, params.pressure_solver),
+            (defaults.integrator, defaults.kernel, defaults.boundary_handling, defaults.pressure_solver)
+        );
+    }
+}
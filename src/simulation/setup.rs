@@ -8,9 +8,21 @@ use bevy::{
     },
 };
 
-use crate::resources::{Bond, GridParams, SimParams};
-use super::scenarios;
+use crate::resources::{bond_type, Bond, GridParams, Particle, ParticleForce, SimParams};
+use super::scenarios::{self, Scenario};
+use super::settings::SimulationSettings;
+use super::adjacency::{bond_adjacency, triplet_adjacency};
+use super::grid::scan_block_count;
+use super::bending::{AngularSpring, TripletForce};
+use super::bond_events::BOND_BREAK_LOG_SIZE;
 use super::physics_config::{default_interaction_table, InteractionTable};
+use super::kinematics::{kinematic_targets, GpuKinematicDriver, MAX_KINEMATIC_DRIVERS};
+use super::pressure_solver::PressureSolverState;
+use super::reorder::{movable_particles, movable_slots};
+use super::rigid_body::{rigid_members, RigidBody};
+use super::stats::StatsState;
+use super::timestep::{TimestepParams, TimestepState};
+use super::welding::{BondAllocator, BondEdit, WeldPartners, WeldRules, MAX_BOND_EDITS};
 
 // ==================== SIMULATION CONFIG ====================
 /// Number of particles in the simulation
@@ -19,7 +31,6 @@ pub const PARTICLE_COUNT: usize = 10000;
 pub const BOND_COUNT: usize = 20_000;
 
 // Bond Configuration (used by BondBuffer)
-const BOND_STIFFNESS: f32 = 30_000.0;
 const BOND_BREAKING_STRAIN: f32 = 2.0;
 // =============================================================
 
@@ -30,14 +41,36 @@ pub struct ParticleBuffer(pub Buffer);
 impl FromWorld for ParticleBuffer {
     fn from_world(world: &mut World) -> Self {
         let render_device = world.resource::<RenderDevice>();
+        let scenario = world.resource::<SimulationSettings>().scenario;
 
         // Use the scenarios module to spawn particles
-        let (particles, _hull_bounds) = scenarios::spawn_particles(PARTICLE_COUNT);
+        let (particles, _hull_bounds) = scenario.spawn_particles(PARTICLE_COUNT);
 
         let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
             label: Some("Particle Buffer"),
             contents: bytemuck::cast_slice(&particles),
-            usage: BufferUsages::STORAGE | BufferUsages::COPY_DST | BufferUsages::VERTEX,
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_DST | BufferUsages::COPY_SRC | BufferUsages::VERTEX,
+        });
+
+        Self(buffer)
+    }
+}
+
+/// Resource holding the second particle state buffer. Ping-pong passes read one particle
+/// buffer and write the other (see `ping_pong`); the state is back in `ParticleBuffer`
+/// at the end of every step.
+#[derive(Resource)]
+pub struct ParticleBackBuffer(pub Buffer);
+
+impl FromWorld for ParticleBackBuffer {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("ParticleBack Buffer"),
+            size: (PARTICLE_COUNT * std::mem::size_of::<Particle>()) as u64,
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
         });
 
         Self(buffer)
@@ -149,6 +182,182 @@ impl FromWorld for CellOffsetsBuffer {
     }
 }
 
+/// Resource holding the particle id tables: `ids` maps a slot to the original id of the
+/// particle in it, `slots` maps an original id to its slot. Identity until the first
+/// reorder; handles outside the GPU keep original ids (see `reorder`).
+#[derive(Resource)]
+pub struct ParticleIdBuffer {
+    pub ids: Buffer,
+    pub slots: Buffer,
+}
+
+impl FromWorld for ParticleIdBuffer {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let identity: Vec<u32> = (0..PARTICLE_COUNT as u32).collect();
+        let ids = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("ParticleId Buffer"),
+            contents: bytemuck::cast_slice(&identity),
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC,
+        });
+        let slots = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("ParticleSlot Buffer"),
+            contents: bytemuck::cast_slice(&identity),
+            usage: BufferUsages::STORAGE,
+        });
+
+        Self { ids, slots }
+    }
+}
+
+/// Resource holding reorder.wgsl's inputs and scratch. The movable particles and their
+/// slots are fixed by the scenario; the rank scan reuses prefix_sum.wgsl like the bond
+/// adjacency rebuild. The particles' previous state goes to `ParticleBackBuffer`, which
+/// the next ping-pong pass overwrites anyway.
+#[derive(Resource)]
+pub struct ReorderBuffer {
+    /// Per-slot flag, 1 = may move (`reorder::movable_particles`)
+    pub movable: Buffer,
+    /// Slots of the movable particles, ascending
+    pub movable_slots: Buffer,
+    /// Movable flag per sorted position (the counts of the scan)
+    pub flags: Buffer,
+    /// `PARTICLE_COUNT + 1` ranks: exclusive scan of `flags`
+    pub ranks: Buffer,
+    /// Write cursors of the scan (not used after it)
+    pub cursor: Buffer,
+    /// Per-block totals of the scan
+    pub block_sums: Buffer,
+    /// `GridParams` uniform sizing the scan to `PARTICLE_COUNT` flags
+    pub scan_params: Buffer,
+    /// Old -> new slot of the last reorder
+    pub new_slot: Buffer,
+    /// `ParticleForce`s, `WeldPartners` and ids before the permutation
+    pub forces_old: Buffer,
+    pub partners_old: Buffer,
+    pub ids_old: Buffer,
+}
+
+impl FromWorld for ReorderBuffer {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let scenario = world.resource::<SimulationSettings>().scenario;
+
+        let bodies = scenario.rigid_bodies();
+        let movable = movable_particles(
+            &rigid_members(&bodies, PARTICLE_COUNT),
+            &kinematic_targets(&scenario.kinematic_joints(), &bodies, PARTICLE_COUNT),
+            &scenario.angular_springs(),
+        );
+        // At least one slot so the binding is never zero-sized
+        let mut slots = movable_slots(&movable);
+        println!("Reorder: {} of {} particles movable", slots.len(), PARTICLE_COUNT);
+        if slots.is_empty() {
+            slots.push(0);
+        }
+
+        let storage = |label: &'static str, size: usize, usage: BufferUsages| {
+            render_device.create_buffer(&BufferDescriptor {
+                label: Some(label),
+                size: size as u64,
+                usage: BufferUsages::STORAGE | usage,
+                mapped_at_creation: false,
+            })
+        };
+        let word = std::mem::size_of::<u32>();
+
+        let scan_grid = GridParams {
+            grid_width: PARTICLE_COUNT as u32,
+            grid_height: 1,
+            ..GridParams::default()
+        };
+
+        Self {
+            movable: render_device.create_buffer_with_data(&BufferInitDescriptor {
+                label: Some("Reorder Movable Buffer"),
+                contents: bytemuck::cast_slice(&movable),
+                usage: BufferUsages::STORAGE,
+            }),
+            movable_slots: render_device.create_buffer_with_data(&BufferInitDescriptor {
+                label: Some("Reorder MovableSlots Buffer"),
+                contents: bytemuck::cast_slice(&slots),
+                usage: BufferUsages::STORAGE,
+            }),
+            flags: storage("Reorder Flags Buffer", PARTICLE_COUNT * word, BufferUsages::empty()),
+            ranks: storage("Reorder Ranks Buffer", (PARTICLE_COUNT + 1) * word, BufferUsages::empty()),
+            cursor: storage("Reorder Cursor Buffer", (PARTICLE_COUNT + 1) * word, BufferUsages::empty()),
+            block_sums: storage(
+                "Reorder BlockSums Buffer",
+                scan_block_count(PARTICLE_COUNT) * word,
+                BufferUsages::empty(),
+            ),
+            scan_params: render_device.create_buffer_with_data(&BufferInitDescriptor {
+                label: Some("Reorder ScanParams Buffer"),
+                contents: bytemuck::bytes_of(&scan_grid),
+                usage: BufferUsages::UNIFORM,
+            }),
+            new_slot: storage("Reorder NewSlot Buffer", PARTICLE_COUNT * word, BufferUsages::empty()),
+            forces_old: storage(
+                "Reorder ForcesOld Buffer",
+                PARTICLE_COUNT * std::mem::size_of::<ParticleForce>(),
+                BufferUsages::COPY_DST,
+            ),
+            partners_old: storage(
+                "Reorder PartnersOld Buffer",
+                PARTICLE_COUNT * std::mem::size_of::<WeldPartners>(),
+                BufferUsages::COPY_DST,
+            ),
+            ids_old: storage("Reorder IdsOld Buffer", PARTICLE_COUNT * word, BufferUsages::COPY_DST),
+        }
+    }
+}
+
+/// Resource holding the scatter's write cursors (one u32 per cell), copied from the cell
+/// offsets by the prefix sum so the scatter leaves `CellOffsetsBuffer` intact
+#[derive(Resource)]
+pub struct CellCursorBuffer(pub Buffer);
+
+impl FromWorld for CellCursorBuffer {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let grid_params = GridParams::default();
+        let total_cells = (grid_params.grid_width * grid_params.grid_height) as usize;
+
+        let buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("CellCursor Buffer"),
+            size: (total_cells * 4) as u64,
+            usage: BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+
+        Self(buffer)
+    }
+}
+
+/// Resource holding the prefix sum's per-block totals (one u32 per `SCAN_BLOCK_SIZE` cells)
+#[derive(Resource)]
+pub struct ScanBlockSumsBuffer(pub Buffer);
+
+impl FromWorld for ScanBlockSumsBuffer {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let grid_params = GridParams::default();
+        let total_cells = (grid_params.grid_width * grid_params.grid_height) as usize;
+
+        let buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("ScanBlockSums Buffer"),
+            size: (scan_block_count(total_cells) * 4) as u64,
+            usage: BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+
+        Self(buffer)
+    }
+}
+
 /// Sort parameters for bitonic sort
 #[repr(C)]
 #[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
@@ -160,6 +369,7 @@ pub struct SortParams {
 }
 
 /// Resource holding sort parameters uniform buffer
+#[allow(dead_code)]
 #[derive(Resource)]
 pub struct SortParamsBuffer(pub Buffer);
 
@@ -190,424 +400,802 @@ pub struct BondBuffer(pub Buffer);
 impl FromWorld for BondBuffer {
     fn from_world(world: &mut World) -> Self {
         let render_device = world.resource::<RenderDevice>();
+        let scenario = world.resource::<SimulationSettings>().scenario;
+
+        let mut bonds = scenario_bonds(scenario);
+        println!("Generated {} bonds ({} free slots for runtime bonds)", bonds.len(), BOND_COUNT - bonds.len());
         
-        // ==================== BOND STIFFNESS CONFIG ====================
-        const HULL_STIFFNESS: f32 = 30_000.0;      // Rigid hull
-        const SAIL_STIFFNESS: f32 = 15_000.0;      // Rigid sail panel
-        const MAST_STIFFNESS: f32 = 20_000.0;      // Stiff mast
-        const FUSE_STIFFNESS: f32 = 100_000.0;     // Mast-hull connection (extremely strong)
-        const FUSE_BREAKING_STRAIN: f32 = 10.0;    // Essentially unbreakable
-        // ===============================================================
-        
-        use scenarios::config::*;
-        use scenarios::hurricane_config;
-        
-        let mut bonds = Vec::new();
-        let diagonal_length = HULL_SPACING * 1.4142; // sqrt(2) for diagonal bonds
-        
-        // =========================== HULL BONDS ===========================
-        // Note: These may be for dry_dock or hurricane scenario
-        // For hurricane, hull is just a single row, but we still generate bonds
-        for y in 0..HULL_HEIGHT {
-            for x in 0..HULL_WIDTH {
-                let idx = y * HULL_WIDTH + x;
-                
-                // Horizontal bond (right neighbor)
-                if x + 1 < HULL_WIDTH {
-                    let right_idx = y * HULL_WIDTH + (x + 1);
-                    bonds.push(Bond {
-                        particle_a: idx as u32,
-                        particle_b: right_idx as u32,
-                        rest_length: HULL_SPACING,
-                        stiffness: HULL_STIFFNESS,
-                        breaking_strain: BOND_BREAKING_STRAIN,
-                        bond_type: 0, // Hull
-                        is_active: 1,
-                        _padding: 0,
-                    });
-                }
-                
-                // Vertical bond (top neighbor)
-                if y + 1 < HULL_HEIGHT {
-                    let top_idx = (y + 1) * HULL_WIDTH + x;
-                    bonds.push(Bond {
-                        particle_a: idx as u32,
-                        particle_b: top_idx as u32,
-                        rest_length: HULL_SPACING,
-                        stiffness: HULL_STIFFNESS,
-                        breaking_strain: BOND_BREAKING_STRAIN,
-                        bond_type: 0, // Hull
-                        is_active: 1,
-                        _padding: 0,
-                    });
-                }
-                
-                // Diagonal bond (top-right)
-                if x + 1 < HULL_WIDTH && y + 1 < HULL_HEIGHT {
-                    let tr_idx = (y + 1) * HULL_WIDTH + (x + 1);
-                    bonds.push(Bond {
-                        particle_a: idx as u32,
-                        particle_b: tr_idx as u32,
-                        rest_length: diagonal_length,
-                        stiffness: HULL_STIFFNESS,
-                        breaking_strain: BOND_BREAKING_STRAIN,
-                        bond_type: 0, // Hull
-                        is_active: 1,
-                        _padding: 0,
-                    });
-                }
-                
-                // Diagonal bond (top-left)
-                if x > 0 && y + 1 < HULL_HEIGHT {
-                    let tl_idx = (y + 1) * HULL_WIDTH + (x - 1);
-                    bonds.push(Bond {
-                        particle_a: idx as u32,
-                        particle_b: tl_idx as u32,
-                        rest_length: diagonal_length,
-                        stiffness: HULL_STIFFNESS,
-                        breaking_strain: BOND_BREAKING_STRAIN,
-                        bond_type: 0, // Hull
-                        is_active: 1,
-                        _padding: 0,
-                    });
-                }
-            }
+        // Pad to BOND_COUNT
+        while bonds.len() < BOND_COUNT {
+            bonds.push(Bond {
+                particle_a: 0,
+                particle_b: 0,
+                rest_length: 0.0,
+                stiffness: 0.0,
+                breaking_strain: 0.0,
+                bond_type: 0,
+                is_active: 0,
+                yield_strain: 0.0,
+                damage: 0.0,
+                compressive_breaking_strain: 0.0,
+                shear_limit: 0.0,
+                _padding: 0,
+            });
         }
-        
-        let hull_bond_count = bonds.len();
-        
-        // =========================== HURRICANE SCENARIO BONDS ===========================
-        // These are for the hurricane test scenario with sail and mast (TOP-DOWN VIEW)
-        // Indices must match scenario_hurricane() particle ordering:
-        //   Hull: 0..100 (20x5 grid)
-        //   Mast: 100..109 (3x3 grid cross-section)
-        //   Sail: 109..157 (8x6 grid)
-        
-        let hurricane_hull_count = hurricane_config::HULL_WIDTH * hurricane_config::HULL_HEIGHT; // 100
-        let mast_particle_count = hurricane_config::MAST_GRID_SIZE * hurricane_config::MAST_GRID_SIZE; // 9
-        let mast_start_idx = hurricane_hull_count;
-        let mast_end_idx = mast_start_idx + mast_particle_count;
-        let sail_start_idx = mast_end_idx;
-        
-        // --- MAST BONDS (3x3 grid with horizontal/vertical + diagonal for rigidity) ---
-        let mast_size = hurricane_config::MAST_GRID_SIZE;
-        for y in 0..mast_size {
-            for x in 0..mast_size {
-                let idx = mast_start_idx + y * mast_size + x;
-                
-                // Horizontal bond
-                if x + 1 < mast_size {
-                    let right_idx = mast_start_idx + y * mast_size + (x + 1);
-                    bonds.push(Bond {
-                        particle_a: idx as u32,
-                        particle_b: right_idx as u32,
-                        rest_length: hurricane_config::MAST_SPACING,
-                        stiffness: MAST_STIFFNESS,
-                        breaking_strain: BOND_BREAKING_STRAIN,
-                        bond_type: 0, // Mast (rigid)
-                        is_active: 1,
-                        _padding: 0,
-                    });
-                }
-                
-                // Vertical bond  
-                if y + 1 < mast_size {
-                    let top_idx = mast_start_idx + (y + 1) * mast_size + x;
-                    bonds.push(Bond {
-                        particle_a: idx as u32,
-                        particle_b: top_idx as u32,
-                        rest_length: hurricane_config::MAST_SPACING,
-                        stiffness: MAST_STIFFNESS,
-                        breaking_strain: BOND_BREAKING_STRAIN,
-                        bond_type: 0,
-                        is_active: 1,
-                        _padding: 0,
-                    });
-                }
-                
-                // Diagonal bond (for rigidity)
-                if x + 1 < mast_size && y + 1 < mast_size {
-                    let diag_idx = mast_start_idx + (y + 1) * mast_size + (x + 1);
-                    bonds.push(Bond {
-                        particle_a: idx as u32,
-                        particle_b: diag_idx as u32,
-                        rest_length: hurricane_config::MAST_SPACING * 1.4142,
-                        stiffness: MAST_STIFFNESS,
-                        breaking_strain: BOND_BREAKING_STRAIN,
-                        bond_type: 0,
-                        is_active: 1,
-                        _padding: 0,
-                    });
-                }
+
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("Bond Buffer"),
+            contents: bytemuck::cast_slice(&bonds),
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_DST | BufferUsages::COPY_SRC,
+        });
+
+        Self(buffer)
+    }
+}
+
+// ==================== BOND STIFFNESS CONFIG ====================
+const HULL_STIFFNESS: f32 = 30_000.0;      // Rigid hull
+const SAIL_STIFFNESS: f32 = 15_000.0;      // Rigid sail panel
+const MAST_STIFFNESS: f32 = 20_000.0;      // Stiff mast
+const FUSE_STIFFNESS: f32 = 100_000.0;     // Mast-hull connection (extremely strong)
+const FUSE_BREAKING_STRAIN: f32 = 10.0;    // Essentially unbreakable
+const HULL_YIELD_STRAIN: f32 = 0.1;        // Hull planking dents past 10% strain
+const CRUSH_STRAIN: f32 = 0.5;             // Hull, mast and spar crush at 50% compression
+const HULL_SHEAR_LIMIT: f32 = 300.0;       // Transverse speed that shears hull/mast/spar bonds
+// ===============================================================
+
+/// Bonds the scenario starts with (hull, rig, ropes and sheets), before padding to `BOND_COUNT`.
+/// Runtime bonds (`welding`) are allocated after these.
+pub(crate) fn scenario_bonds(scenario: Scenario) -> Vec<Bond> {
+    use scenarios::{config, hurricane_config};
+
+    let mut bonds = match scenario {
+        Scenario::DryDock => hull_lattice_bonds(config::HULL_WIDTH, config::HULL_HEIGHT, config::HULL_SPACING),
+        Scenario::Hurricane => {
+            let mut bonds = hull_lattice_bonds(
+                hurricane_config::HULL_WIDTH,
+                hurricane_config::HULL_HEIGHT,
+                hurricane_config::HULL_SPACING,
+            );
+            bonds.extend(hurricane_rig_bonds());
+            bonds
+        }
+        Scenario::WaterOnly | Scenario::PressureWasher => Vec::new(),
+    };
+    let structure_bond_count = bonds.len();
+
+    // =========================== ROPES ===========================
+    for rope in scenario.built_ropes(PARTICLE_COUNT) {
+        debug!("Rope '{}': {} segments", rope.name, rope.bonds.len());
+        bonds.extend(rope.bonds);
+    }
+
+    debug!("{} structure bonds + {} rope bonds", structure_bond_count, bonds.len() - structure_bond_count);
+    bonds
+}
+
+/// Hull lattice: a `width` x `height` grid of hull particles from index 0, bonded to its
+/// right, upper and both upper diagonal neighbours
+fn hull_lattice_bonds(width: usize, height: usize, spacing: f32) -> Vec<Bond> {
+    let mut bonds = Vec::new();
+    let diagonal_length = spacing * std::f32::consts::SQRT_2; // diagonal bonds
+    
+    // =========================== HULL BONDS ===========================
+    for y in 0..height {
+        for x in 0..width {
+            let idx = y * width + x;
+            
+            // Horizontal bond (right neighbor)
+            if x + 1 < width {
+                let right_idx = y * width + (x + 1);
+                bonds.push(Bond {
+                    particle_a: idx as u32,
+                    particle_b: right_idx as u32,
+                    rest_length: spacing,
+                    stiffness: HULL_STIFFNESS,
+                    breaking_strain: BOND_BREAKING_STRAIN,
+                    bond_type: 0, // Hull
+                    is_active: 1,
+                    yield_strain: HULL_YIELD_STRAIN,
+                    damage: 0.0,
+                    compressive_breaking_strain: CRUSH_STRAIN,
+                    shear_limit: HULL_SHEAR_LIMIT,
+                    _padding: 0,
+                });
+            }
+            
+            // Vertical bond (top neighbor)
+            if y + 1 < height {
+                let top_idx = (y + 1) * width + x;
+                bonds.push(Bond {
+                    particle_a: idx as u32,
+                    particle_b: top_idx as u32,
+                    rest_length: spacing,
+                    stiffness: HULL_STIFFNESS,
+                    breaking_strain: BOND_BREAKING_STRAIN,
+                    bond_type: 0, // Hull
+                    is_active: 1,
+                    yield_strain: HULL_YIELD_STRAIN,
+                    damage: 0.0,
+                    compressive_breaking_strain: CRUSH_STRAIN,
+                    shear_limit: HULL_SHEAR_LIMIT,
+                    _padding: 0,
+                });
+            }
+            
+            // Diagonal bond (top-right)
+            if x + 1 < width && y + 1 < height {
+                let tr_idx = (y + 1) * width + (x + 1);
+                bonds.push(Bond {
+                    particle_a: idx as u32,
+                    particle_b: tr_idx as u32,
+                    rest_length: diagonal_length,
+                    stiffness: HULL_STIFFNESS,
+                    breaking_strain: BOND_BREAKING_STRAIN,
+                    bond_type: 0, // Hull
+                    is_active: 1,
+                    yield_strain: HULL_YIELD_STRAIN,
+                    damage: 0.0,
+                    compressive_breaking_strain: CRUSH_STRAIN,
+                    shear_limit: HULL_SHEAR_LIMIT,
+                    _padding: 0,
+                });
+            }
+            
+            // Diagonal bond (top-left)
+            if x > 0 && y + 1 < height {
+                let tl_idx = (y + 1) * width + (x - 1);
+                bonds.push(Bond {
+                    particle_a: idx as u32,
+                    particle_b: tl_idx as u32,
+                    rest_length: diagonal_length,
+                    stiffness: HULL_STIFFNESS,
+                    breaking_strain: BOND_BREAKING_STRAIN,
+                    bond_type: 0, // Hull
+                    is_active: 1,
+                    yield_strain: HULL_YIELD_STRAIN,
+                    damage: 0.0,
+                    compressive_breaking_strain: CRUSH_STRAIN,
+                    shear_limit: HULL_SHEAR_LIMIT,
+                    _padding: 0,
+                });
             }
         }
-        
-        // --- MAST-HULL FUSE BONDS (breakable connection) ---
-        // Connect center mast particle(s) to hull particles underneath
-        // Hull center is at index (HULL_HEIGHT/2 * HULL_WIDTH + HULL_WIDTH/2)
-        let hull_w = hurricane_config::HULL_WIDTH;
-        let hull_h = hurricane_config::HULL_HEIGHT;
-        let hull_center_idx = (hull_h / 2) * hull_w + (hull_w / 2);
-        let mast_center_idx = mast_start_idx + (mast_size / 2) * mast_size + (mast_size / 2);
-        
-        // Connect mast center to hull center (main fuse)
+    }
+    
+    bonds
+}
+
+/// Hurricane rig: rudder, mast, mast-hull fuse, sail and spar
+fn hurricane_rig_bonds() -> Vec<Bond> {
+    use scenarios::hurricane_config;
+
+    let mut bonds = Vec::new();
+
+    // =========================== HURRICANE SCENARIO BONDS ===========================
+    // These are for the hurricane test scenario with sail and mast (TOP-DOWN VIEW)
+    // Indices must match scenario_hurricane() particle ordering:
+    //   Hull: 0..100 (20x5 grid)
+    //   Rudder: 100..103 (line aft of the stern)
+    //   Mast: 103..112 (3x3 grid cross-section)
+    //   Spar: 112..132 (2x10 grid)
+    //   Sail: 132..152 (2x10 grid)
+    
+    let hurricane_hull_count = hurricane_config::HULL_WIDTH * hurricane_config::HULL_HEIGHT; // 100
+    let mast_particle_count = hurricane_config::MAST_GRID_SIZE * hurricane_config::MAST_GRID_SIZE; // 9
+    let mast_start_idx = hurricane_hull_count + hurricane_config::RUDDER_LENGTH;
+    let mast_end_idx = mast_start_idx + mast_particle_count;
+    let spar_particle_count = hurricane_config::SPAR_LENGTH * hurricane_config::SPAR_DEPTH; // 20
+    let sail_start_idx = mast_end_idx + spar_particle_count;
+    
+    // --- RUDDER BONDS (blade chain + pintle to the three centre stern particles) ---
+    let rudder_start_idx = hurricane_hull_count;
+    let rudder_len = hurricane_config::RUDDER_LENGTH;
+    let hull_spacing = hurricane_config::HULL_SPACING;
+    for i in 0..rudder_len.saturating_sub(1) {
         bonds.push(Bond {
-            particle_a: hull_center_idx as u32,
-            particle_b: mast_center_idx as u32,
-            rest_length: 0.1, // Very short - they are overlapping!
-            stiffness: FUSE_STIFFNESS,
-            breaking_strain: FUSE_BREAKING_STRAIN,
-            bond_type: 3, // MastStep/Fuse
+            particle_a: (rudder_start_idx + i) as u32,
+            particle_b: (rudder_start_idx + i + 1) as u32,
+            rest_length: hull_spacing,
+            stiffness: HULL_STIFFNESS,
+            breaking_strain: BOND_BREAKING_STRAIN,
+            bond_type: 0, // Hull
             is_active: 1,
+            yield_strain: HULL_YIELD_STRAIN,
+            damage: 0.0,
+            compressive_breaking_strain: CRUSH_STRAIN,
+            shear_limit: HULL_SHEAR_LIMIT,
             _padding: 0,
         });
-        
-        // Connect mast edges to nearby hull particles for stability
-        for m_offset in [0, mast_size - 1] {
-            for h_offset in [0isize, 1, -1] {
-                let mast_idx = mast_start_idx + m_offset;
-                let hull_idx = (hull_center_idx as isize + h_offset) as usize;
-                if hull_idx < hurricane_hull_count {
-                    bonds.push(Bond {
-                        particle_a: hull_idx as u32,
-                        particle_b: mast_idx as u32,
-                        rest_length: hurricane_config::MAST_SPACING,
-                        stiffness: FUSE_STIFFNESS,
-                        breaking_strain: FUSE_BREAKING_STRAIN,
-                        bond_type: 3,
-                        is_active: 1,
-                        _padding: 0,
-                    });
-                }
+    }
+    let stern_centre_row = hurricane_config::HULL_HEIGHT / 2;
+    for row in stern_centre_row - 1..=stern_centre_row + 1 {
+        let dy = (row as f32 - stern_centre_row as f32) * hull_spacing;
+        bonds.push(Bond {
+            particle_a: (row * hurricane_config::HULL_WIDTH) as u32, // Stern column
+            particle_b: rudder_start_idx as u32,                    // Pintle
+            rest_length: (hull_spacing * hull_spacing + dy * dy).sqrt(),
+            stiffness: HULL_STIFFNESS,
+            breaking_strain: BOND_BREAKING_STRAIN,
+            bond_type: 0, // Hull
+            is_active: 1,
+            yield_strain: HULL_YIELD_STRAIN,
+            damage: 0.0,
+            compressive_breaking_strain: CRUSH_STRAIN,
+            shear_limit: HULL_SHEAR_LIMIT,
+            _padding: 0,
+        });
+    }
+    
+    // --- MAST BONDS (3x3 grid with horizontal/vertical + diagonal for rigidity) ---
+    let mast_size = hurricane_config::MAST_GRID_SIZE;
+    for y in 0..mast_size {
+        for x in 0..mast_size {
+            let idx = mast_start_idx + y * mast_size + x;
+            
+            // Horizontal bond
+            if x + 1 < mast_size {
+                let right_idx = mast_start_idx + y * mast_size + (x + 1);
+                bonds.push(Bond {
+                    particle_a: idx as u32,
+                    particle_b: right_idx as u32,
+                    rest_length: hurricane_config::MAST_SPACING,
+                    stiffness: MAST_STIFFNESS,
+                    breaking_strain: BOND_BREAKING_STRAIN,
+                    bond_type: 0, // Mast (rigid)
+                    is_active: 1,
+                    yield_strain: 0.0,
+                    damage: 0.0,
+                    compressive_breaking_strain: CRUSH_STRAIN,
+                    shear_limit: HULL_SHEAR_LIMIT,
+                    _padding: 0,
+                });
             }
-        }
-        
-        // --- SAIL BONDS (with diagonals for rigidity - holds shape better) ---
-        let sail_w = hurricane_config::SAIL_WIDTH;
-        let sail_h = hurricane_config::SAIL_HEIGHT;
-        let sail_diag_length = hurricane_config::SAIL_SPACING * 1.4142;
-        
-        for y in 0..sail_h {
-            for x in 0..sail_w {
-                let idx = sail_start_idx + y * sail_w + x;
-                
-                // Horizontal bond (right neighbor)
-                if x + 1 < sail_w {
-                    let right_idx = sail_start_idx + y * sail_w + (x + 1);
-                    bonds.push(Bond {
-                        particle_a: idx as u32,
-                        particle_b: right_idx as u32,
-                        rest_length: hurricane_config::SAIL_SPACING,
-                        stiffness: SAIL_STIFFNESS,
-                        breaking_strain: BOND_BREAKING_STRAIN,
-                        bond_type: 1, // Sail
-                        is_active: 1,
-                        _padding: 0,
-                    });
-                }
-                
-                // Vertical bond (top neighbor)
-                if y + 1 < sail_h {
-                    let top_idx = sail_start_idx + (y + 1) * sail_w + x;
-                    bonds.push(Bond {
-                        particle_a: idx as u32,
-                        particle_b: top_idx as u32,
-                        rest_length: hurricane_config::SAIL_SPACING,
-                        stiffness: SAIL_STIFFNESS,
-                        breaking_strain: BOND_BREAKING_STRAIN,
-                        bond_type: 1, // Sail
-                        is_active: 1,
-                        _padding: 0,
-                    });
-                }
-                
-                // Diagonal bond (top-right) - provides shear resistance
-                if x + 1 < sail_w && y + 1 < sail_h {
-                    let diag_idx = sail_start_idx + (y + 1) * sail_w + (x + 1);
-                    bonds.push(Bond {
-                        particle_a: idx as u32,
-                        particle_b: diag_idx as u32,
-                        rest_length: sail_diag_length,
-                        stiffness: SAIL_STIFFNESS,
-                        breaking_strain: BOND_BREAKING_STRAIN,
-                        bond_type: 1, // Sail
-                        is_active: 1,
-                        _padding: 0,
-                    });
-                }
-                
-                // Diagonal bond (top-left) - provides shear resistance
-                if x > 0 && y + 1 < sail_h {
-                    let diag_idx = sail_start_idx + (y + 1) * sail_w + (x - 1);
-                    bonds.push(Bond {
-                        particle_a: idx as u32,
-                        particle_b: diag_idx as u32,
-                        rest_length: sail_diag_length,
-                        stiffness: SAIL_STIFFNESS,
-                        breaking_strain: BOND_BREAKING_STRAIN,
-                        bond_type: 1, // Sail
-                        is_active: 1,
-                        _padding: 0,
-                    });
-                }
+            
+            // Vertical bond  
+            if y + 1 < mast_size {
+                let top_idx = mast_start_idx + (y + 1) * mast_size + x;
+                bonds.push(Bond {
+                    particle_a: idx as u32,
+                    particle_b: top_idx as u32,
+                    rest_length: hurricane_config::MAST_SPACING,
+                    stiffness: MAST_STIFFNESS,
+                    breaking_strain: BOND_BREAKING_STRAIN,
+                    bond_type: 0,
+                    is_active: 1,
+                    yield_strain: 0.0,
+                    damage: 0.0,
+                    compressive_breaking_strain: CRUSH_STRAIN,
+                    shear_limit: HULL_SHEAR_LIMIT,
+                    _padding: 0,
+                });
+            }
+            
+            // Diagonal bond (for rigidity)
+            if x + 1 < mast_size && y + 1 < mast_size {
+                let diag_idx = mast_start_idx + (y + 1) * mast_size + (x + 1);
+                bonds.push(Bond {
+                    particle_a: idx as u32,
+                    particle_b: diag_idx as u32,
+                    rest_length: hurricane_config::MAST_SPACING * std::f32::consts::SQRT_2,
+                    stiffness: MAST_STIFFNESS,
+                    breaking_strain: BOND_BREAKING_STRAIN,
+                    bond_type: 0,
+                    is_active: 1,
+                    yield_strain: 0.0,
+                    damage: 0.0,
+                    compressive_breaking_strain: CRUSH_STRAIN,
+                    shear_limit: HULL_SHEAR_LIMIT,
+                    _padding: 0,
+                });
             }
         }
-        
-        // --- SPAR BONDS (10x2 grid) ---
-        // Spar particles start after mast: mast_start_idx + 9 = spar_start
-        let spar_start_idx = mast_start_idx + mast_size * mast_size; // 100 + 9 = 109
-        let spar_len = hurricane_config::SPAR_LENGTH;
-        let spar_depth = hurricane_config::SPAR_DEPTH;
-        let spar_diag = hurricane_config::SAIL_SPACING * 1.4142;
-        
-        // Spar internal bonds (grid with horizontal, vertical, diagonal)
-        for y in 0..spar_len {
-            for x in 0..spar_depth {
-                let idx = spar_start_idx + y * spar_depth + x;
-                
-                // Horizontal bond (right neighbor)
-                if x + 1 < spar_depth {
-                    let right_idx = spar_start_idx + y * spar_depth + (x + 1);
-                    bonds.push(Bond {
-                        particle_a: idx as u32,
-                        particle_b: right_idx as u32,
-                        rest_length: hurricane_config::SAIL_SPACING,
-                        stiffness: MAST_STIFFNESS,
-                        breaking_strain: BOND_BREAKING_STRAIN,
-                        bond_type: 2,
-                        is_active: 1,
-                        _padding: 0,
-                    });
-                }
-                
-                // Vertical bond (next y)
-                if y + 1 < spar_len {
-                    let next_y_idx = spar_start_idx + (y + 1) * spar_depth + x;
-                    bonds.push(Bond {
-                        particle_a: idx as u32,
-                        particle_b: next_y_idx as u32,
-                        rest_length: hurricane_config::SAIL_SPACING,
-                        stiffness: MAST_STIFFNESS,
-                        breaking_strain: BOND_BREAKING_STRAIN,
-                        bond_type: 2,
-                        is_active: 1,
-                        _padding: 0,
-                    });
-                }
-                
-                // Diagonal bonds for rigidity
-                if x + 1 < spar_depth && y + 1 < spar_len {
-                    let diag_idx = spar_start_idx + (y + 1) * spar_depth + (x + 1);
-                    bonds.push(Bond {
-                        particle_a: idx as u32,
-                        particle_b: diag_idx as u32,
-                        rest_length: spar_diag,
-                        stiffness: MAST_STIFFNESS,
-                        breaking_strain: BOND_BREAKING_STRAIN,
-                        bond_type: 2,
-                        is_active: 1,
-                        _padding: 0,
-                    });
-                }
-                if x > 0 && y + 1 < spar_len {
-                    let diag_idx = spar_start_idx + (y + 1) * spar_depth + (x - 1);
-                    bonds.push(Bond {
-                        particle_a: idx as u32,
-                        particle_b: diag_idx as u32,
-                        rest_length: spar_diag,
-                        stiffness: MAST_STIFFNESS,
-                        breaking_strain: BOND_BREAKING_STRAIN,
-                        bond_type: 2,
-                        is_active: 1,
-                        _padding: 0,
-                    });
-                }
-                
-                // Skip-2 vertical bonds for bending resistance (keeps spar straight)
-                if y + 2 < spar_len {
-                    let skip_idx = spar_start_idx + (y + 2) * spar_depth + x;
-                    bonds.push(Bond {
-                        particle_a: idx as u32,
-                        particle_b: skip_idx as u32,
-                        rest_length: hurricane_config::SAIL_SPACING * 2.0,
-                        stiffness: MAST_STIFFNESS * 2.0, // Extra stiff for bending resistance
-                        breaking_strain: BOND_BREAKING_STRAIN,
-                        bond_type: 2,
-                        is_active: 1,
-                        _padding: 0,
-                    });
-                }
+    }
+    
+    // --- MAST-HULL FUSE BONDS (breakable connection) ---
+    // Connect center mast particle(s) to hull particles underneath
+    // Hull center is at index (HULL_HEIGHT/2 * HULL_WIDTH + HULL_WIDTH/2)
+    let hull_w = hurricane_config::HULL_WIDTH;
+    let hull_h = hurricane_config::HULL_HEIGHT;
+    let hull_center_idx = (hull_h / 2) * hull_w + (hull_w / 2);
+    let mast_center_idx = mast_start_idx + (mast_size / 2) * mast_size + (mast_size / 2);
+    
+    // Connect mast center to hull center (main fuse)
+    bonds.push(Bond {
+        particle_a: hull_center_idx as u32,
+        particle_b: mast_center_idx as u32,
+        rest_length: 0.1, // Very short - they are overlapping!
+        stiffness: FUSE_STIFFNESS,
+        breaking_strain: FUSE_BREAKING_STRAIN,
+        bond_type: 3, // MastStep/Fuse
+        is_active: 1,
+        yield_strain: 0.0,
+        damage: 0.0,
+        compressive_breaking_strain: 0.0,
+        shear_limit: 0.0,
+        _padding: 0,
+    });
+    
+    // Connect mast edges to nearby hull particles for stability
+    for m_offset in [0, mast_size - 1] {
+        for h_offset in [0isize, 1, -1] {
+            let mast_idx = mast_start_idx + m_offset;
+            let hull_idx = (hull_center_idx as isize + h_offset) as usize;
+            if hull_idx < hurricane_hull_count {
+                bonds.push(Bond {
+                    particle_a: hull_idx as u32,
+                    particle_b: mast_idx as u32,
+                    rest_length: hurricane_config::MAST_SPACING,
+                    stiffness: FUSE_STIFFNESS,
+                    breaking_strain: FUSE_BREAKING_STRAIN,
+                    bond_type: 3,
+                    is_active: 1,
+                    yield_strain: 0.0,
+                    damage: 0.0,
+                    compressive_breaking_strain: 0.0,
+                    shear_limit: 0.0,
+                    _padding: 0,
+                });
             }
         }
-        
-        // Mast-center to Spar left column bonds (connect mast to spar)
-        let mast_center = mast_start_idx + (mast_size / 2) * mast_size + (mast_size / 2);
-        for y in 0..spar_len {
-            let spar_left_idx = spar_start_idx + y * spar_depth + 0; // Left column of spar
-            let y_offset = (y as f32 - (spar_len as f32 / 2.0)).abs() * hurricane_config::SAIL_SPACING;
-            let rest_len = (hurricane_config::SAIL_SPACING.powi(2) + y_offset.powi(2)).sqrt();
+    }
+    
+    // --- SAIL BONDS (with diagonals for rigidity - holds shape better) ---
+    let sail_w = hurricane_config::SAIL_WIDTH;
+    let sail_h = hurricane_config::SAIL_HEIGHT;
+    let sail_diag_length = hurricane_config::SAIL_SPACING * std::f32::consts::SQRT_2;
+    
+    for y in 0..sail_h {
+        for x in 0..sail_w {
+            let idx = sail_start_idx + y * sail_w + x;
             
-            bonds.push(Bond {
-                particle_a: mast_center as u32,
-                particle_b: spar_left_idx as u32,
-                rest_length: rest_len.max(hurricane_config::SAIL_SPACING),
-                stiffness: FUSE_STIFFNESS, // Same as mast-hull (extremely strong)
-                breaking_strain: BOND_BREAKING_STRAIN,
-                bond_type: 2,
-                is_active: 1,
-                _padding: 0,
-            });
+            // Horizontal bond (right neighbor)
+            if x + 1 < sail_w {
+                let right_idx = sail_start_idx + y * sail_w + (x + 1);
+                bonds.push(Bond {
+                    particle_a: idx as u32,
+                    particle_b: right_idx as u32,
+                    rest_length: hurricane_config::SAIL_SPACING,
+                    stiffness: SAIL_STIFFNESS,
+                    breaking_strain: BOND_BREAKING_STRAIN,
+                    bond_type: 1, // Sail
+                    is_active: 1,
+                    yield_strain: 0.0,
+                    damage: 0.0,
+                    compressive_breaking_strain: 0.0,
+                    shear_limit: 0.0,
+                    _padding: 0,
+                });
+            }
+            
+            // Vertical bond (top neighbor)
+            if y + 1 < sail_h {
+                let top_idx = sail_start_idx + (y + 1) * sail_w + x;
+                bonds.push(Bond {
+                    particle_a: idx as u32,
+                    particle_b: top_idx as u32,
+                    rest_length: hurricane_config::SAIL_SPACING,
+                    stiffness: SAIL_STIFFNESS,
+                    breaking_strain: BOND_BREAKING_STRAIN,
+                    bond_type: 1, // Sail
+                    is_active: 1,
+                    yield_strain: 0.0,
+                    damage: 0.0,
+                    compressive_breaking_strain: 0.0,
+                    shear_limit: 0.0,
+                    _padding: 0,
+                });
+            }
+            
+            // Diagonal bond (top-right) - provides shear resistance
+            if x + 1 < sail_w && y + 1 < sail_h {
+                let diag_idx = sail_start_idx + (y + 1) * sail_w + (x + 1);
+                bonds.push(Bond {
+                    particle_a: idx as u32,
+                    particle_b: diag_idx as u32,
+                    rest_length: sail_diag_length,
+                    stiffness: SAIL_STIFFNESS,
+                    breaking_strain: BOND_BREAKING_STRAIN,
+                    bond_type: 1, // Sail
+                    is_active: 1,
+                    yield_strain: 0.0,
+                    damage: 0.0,
+                    compressive_breaking_strain: 0.0,
+                    shear_limit: 0.0,
+                    _padding: 0,
+                });
+            }
+            
+            // Diagonal bond (top-left) - provides shear resistance
+            if x > 0 && y + 1 < sail_h {
+                let diag_idx = sail_start_idx + (y + 1) * sail_w + (x - 1);
+                bonds.push(Bond {
+                    particle_a: idx as u32,
+                    particle_b: diag_idx as u32,
+                    rest_length: sail_diag_length,
+                    stiffness: SAIL_STIFFNESS,
+                    breaking_strain: BOND_BREAKING_STRAIN,
+                    bond_type: 1, // Sail
+                    is_active: 1,
+                    yield_strain: 0.0,
+                    damage: 0.0,
+                    compressive_breaking_strain: 0.0,
+                    shear_limit: 0.0,
+                    _padding: 0,
+                });
+            }
         }
-        
-        // --- SAIL-SPAR BONDS (connect sail left edge to spar right column) ---
-        let spar_total = spar_len * spar_depth; // 10 * 2 = 20
-        let sail_start_idx_fixed = spar_start_idx + spar_total; // 109 + 20 = 129
-        
-        for y in 0..sail_h {
-            let spar_right_idx = spar_start_idx + y * spar_depth + (spar_depth - 1); // Right column of spar
-            let sail_left_idx = sail_start_idx_fixed + y * sail_w; // Left edge of sail
+    }
+    
+    // --- SPAR BONDS (10x2 grid; bending stiffness comes from angular springs) ---
+    // Spar particles start after mast: mast_start_idx + 9 = spar_start
+    let spar_start_idx = mast_start_idx + mast_size * mast_size; // 103 + 9 = 112
+    let spar_len = hurricane_config::SPAR_LENGTH;
+    let spar_depth = hurricane_config::SPAR_DEPTH;
+    let spar_diag = hurricane_config::SAIL_SPACING * std::f32::consts::SQRT_2;
+    
+    // Spar internal bonds (grid with horizontal, vertical, diagonal)
+    for y in 0..spar_len {
+        for x in 0..spar_depth {
+            let idx = spar_start_idx + y * spar_depth + x;
             
-            bonds.push(Bond {
-                particle_a: spar_right_idx as u32,
-                particle_b: sail_left_idx as u32,
-                rest_length: hurricane_config::SAIL_SPACING,
-                stiffness: SAIL_STIFFNESS * 4.0, // Doubled from 2.0 -> 4.0
-                breaking_strain: BOND_BREAKING_STRAIN,
-                bond_type: 1,
-                is_active: 1,
-                _padding: 0,
-            });
+            // Horizontal bond (right neighbor)
+            if x + 1 < spar_depth {
+                let right_idx = spar_start_idx + y * spar_depth + (x + 1);
+                bonds.push(Bond {
+                    particle_a: idx as u32,
+                    particle_b: right_idx as u32,
+                    rest_length: hurricane_config::SAIL_SPACING,
+                    stiffness: MAST_STIFFNESS,
+                    breaking_strain: BOND_BREAKING_STRAIN,
+                    bond_type: bond_type::HULL, // Spar (rigid wood, like the mast)
+                    is_active: 1,
+                    yield_strain: 0.0,
+                    damage: 0.0,
+                    compressive_breaking_strain: CRUSH_STRAIN,
+                    shear_limit: HULL_SHEAR_LIMIT,
+                    _padding: 0,
+                });
+            }
+            
+            // Vertical bond (next y)
+            if y + 1 < spar_len {
+                let next_y_idx = spar_start_idx + (y + 1) * spar_depth + x;
+                bonds.push(Bond {
+                    particle_a: idx as u32,
+                    particle_b: next_y_idx as u32,
+                    rest_length: hurricane_config::SAIL_SPACING,
+                    stiffness: MAST_STIFFNESS,
+                    breaking_strain: BOND_BREAKING_STRAIN,
+                    bond_type: bond_type::HULL, // Spar (rigid wood, like the mast)
+                    is_active: 1,
+                    yield_strain: 0.0,
+                    damage: 0.0,
+                    compressive_breaking_strain: CRUSH_STRAIN,
+                    shear_limit: HULL_SHEAR_LIMIT,
+                    _padding: 0,
+                });
+            }
+            
+            // Diagonal bonds for rigidity
+            if x + 1 < spar_depth && y + 1 < spar_len {
+                let diag_idx = spar_start_idx + (y + 1) * spar_depth + (x + 1);
+                bonds.push(Bond {
+                    particle_a: idx as u32,
+                    particle_b: diag_idx as u32,
+                    rest_length: spar_diag,
+                    stiffness: MAST_STIFFNESS,
+                    breaking_strain: BOND_BREAKING_STRAIN,
+                    bond_type: bond_type::HULL, // Spar (rigid wood, like the mast)
+                    is_active: 1,
+                    yield_strain: 0.0,
+                    damage: 0.0,
+                    compressive_breaking_strain: CRUSH_STRAIN,
+                    shear_limit: HULL_SHEAR_LIMIT,
+                    _padding: 0,
+                });
+            }
+            if x > 0 && y + 1 < spar_len {
+                let diag_idx = spar_start_idx + (y + 1) * spar_depth + (x - 1);
+                bonds.push(Bond {
+                    particle_a: idx as u32,
+                    particle_b: diag_idx as u32,
+                    rest_length: spar_diag,
+                    stiffness: MAST_STIFFNESS,
+                    breaking_strain: BOND_BREAKING_STRAIN,
+                    bond_type: bond_type::HULL, // Spar (rigid wood, like the mast)
+                    is_active: 1,
+                    yield_strain: 0.0,
+                    damage: 0.0,
+                    compressive_breaking_strain: CRUSH_STRAIN,
+                    shear_limit: HULL_SHEAR_LIMIT,
+                    _padding: 0,
+                });
+            }
         }
+    }
+    
+    // Mast-center to Spar left column bonds (connect mast to spar).
+    // All measured from one point, so they hold the yard on the mast but let it swing.
+    let mast_center = mast_start_idx + (mast_size / 2) * mast_size + (mast_size / 2);
+    for y in 0..spar_len {
+        let spar_left_idx = spar_start_idx + y * spar_depth; // Left column of spar
+        let y_offset = (y as f32 - (spar_len as f32 / 2.0)).abs() * hurricane_config::SAIL_SPACING;
+        let rest_len = (hurricane_config::SAIL_SPACING.powi(2) + y_offset.powi(2)).sqrt();
         
-        let total_active_bonds = bonds.len();
-        println!("Generated {} hull bonds + {} sail/mast bonds = {} total", 
-                 hull_bond_count, total_active_bonds - hull_bond_count, total_active_bonds);
+        bonds.push(Bond {
+            particle_a: mast_center as u32,
+            particle_b: spar_left_idx as u32,
+            rest_length: rest_len.max(hurricane_config::SAIL_SPACING),
+            stiffness: FUSE_STIFFNESS, // Same as mast-hull (extremely strong)
+            breaking_strain: BOND_BREAKING_STRAIN,
+            bond_type: bond_type::FUSE, // Parrel: the yard pivots about the mast
+            is_active: 1,
+            yield_strain: 0.0,
+            damage: 0.0,
+            compressive_breaking_strain: 0.0,
+            shear_limit: 0.0,
+            _padding: 0,
+        });
+    }
+    
+    // --- SAIL-SPAR BONDS (connect sail left edge to spar right column) ---
+    for y in 0..sail_h {
+        let spar_right_idx = spar_start_idx + y * spar_depth + (spar_depth - 1); // Right column of spar
+        let sail_left_idx = sail_start_idx + y * sail_w; // Left edge of sail
         
-        // Pad to BOND_COUNT
-        while bonds.len() < BOND_COUNT {
-            bonds.push(Bond {
-                particle_a: 0,
-                particle_b: 0,
-                rest_length: 0.0,
-                stiffness: 0.0,
-                breaking_strain: 0.0,
-                bond_type: 0,
-                is_active: 0,
-                _padding: 0,
-            });
-        }
+        bonds.push(Bond {
+            particle_a: spar_right_idx as u32,
+            particle_b: sail_left_idx as u32,
+            rest_length: hurricane_config::SAIL_SPACING,
+            stiffness: SAIL_STIFFNESS * 4.0, // Doubled from 2.0 -> 4.0
+            breaking_strain: BOND_BREAKING_STRAIN,
+            bond_type: 1,
+            is_active: 1,
+            yield_strain: 0.0,
+            damage: 0.0,
+            compressive_breaking_strain: 0.0,
+            shear_limit: 0.0,
+            _padding: 0,
+        });
+    }
+    
+    // Sheets (sail clews to the hull edges) are ropes: see `hurricane_ropes`
+    
+    bonds
+}
+
+/// Resource holding the runtime bond slot allocator (`BondAllocator`).
+/// `next_free` starts right after the scenario's bonds.
+#[derive(Resource)]
+pub struct BondAllocatorBuffer(pub Buffer);
+
+impl FromWorld for BondAllocatorBuffer {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let scenario = world.resource::<SimulationSettings>().scenario;
 
+        let allocator = BondAllocator {
+            next_free: scenario_bonds(scenario).len() as u32,
+            ..Default::default()
+        };
         let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
-            label: Some("Bond Buffer"),
-            contents: bytemuck::cast_slice(&bonds),
+            label: Some("BondAllocator Buffer"),
+            contents: bytemuck::bytes_of(&allocator),
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_DST | BufferUsages::COPY_SRC,
+        });
+
+        Self(buffer)
+    }
+}
+
+/// Resource holding the slots of broken bonds that new bonds reuse (`collect_free` in
+/// weld.wgsl, rebuilt every step that creates bonds)
+#[derive(Resource)]
+pub struct BondFreeListBuffer(pub Buffer);
+
+impl FromWorld for BondFreeListBuffer {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("BondFreeList Buffer"),
+            size: (BOND_COUNT * 4) as u64,
+            usage: BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+
+        Self(buffer)
+    }
+}
+
+/// Resource holding this frame's queued bond edits (`BondEdit`, up to `MAX_BOND_EDITS`)
+#[derive(Resource)]
+pub struct BondEditBuffer(pub Buffer);
+
+impl FromWorld for BondEditBuffer {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("BondEdit Buffer"),
+            size: (MAX_BOND_EDITS * std::mem::size_of::<BondEdit>()) as u64,
             usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self(buffer)
+    }
+}
+
+/// Resource holding the weld rules uniform (`GpuWeldRules`), rewritten from `WeldRules`
+#[derive(Resource)]
+pub struct WeldRuleBuffer(pub Buffer);
+
+impl FromWorld for WeldRuleBuffer {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("WeldRules Buffer"),
+            contents: bytemuck::bytes_of(&WeldRules::default().gpu_rules()),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+
+        Self(buffer)
+    }
+}
+
+/// Resource holding the live welds each particle owns (`WeldPartners` per particle)
+#[derive(Resource)]
+pub struct WeldPartnerBuffer(pub Buffer);
+
+impl FromWorld for WeldPartnerBuffer {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let partners = vec![WeldPartners::default(); PARTICLE_COUNT];
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("WeldPartner Buffer"),
+            contents: bytemuck::cast_slice(&partners),
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC,
+        });
+
+        Self(buffer)
+    }
+}
+
+/// Resource holding the GPU bond break log (`BondBreakLogHeader` + ring of
+/// `BondBreakRecord`s), appended to by the bonds pass and read back every frame
+#[derive(Resource)]
+pub struct BondBreakLogBuffer(pub Buffer);
+
+impl FromWorld for BondBreakLogBuffer {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("BondBreakLog Buffer"),
+            contents: &vec![0u8; BOND_BREAK_LOG_SIZE as usize],
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC,
+        });
+
+        Self(buffer)
+    }
+}
+
+/// Resource holding the per-bond forces (`vec2<f32>` per bond slot, force on `particle_a`).
+/// Written by the bonds pass, summed per particle by the bond gather pass.
+#[derive(Resource)]
+pub struct BondForceBuffer(pub Buffer);
+
+impl FromWorld for BondForceBuffer {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("BondForce Buffer"),
+            size: (BOND_COUNT * std::mem::size_of::<[f32; 2]>()) as u64,
+            usage: BufferUsages::STORAGE,
+            mapped_at_creation: false,
         });
 
         Self(buffer)
     }
 }
 
-/// Resource holding the atomic force accumulation buffer
+/// Byte offsets of the bond list rebuild's indirect dispatch args in
+/// `BondAdjacencyBuffer::dispatch` (written by `plan` in bond_adjacency.wgsl)
+pub mod adjacency_dispatch {
+    /// One thread per particle (clear, sort)
+    pub const PARTICLES: u64 = 0;
+    /// One thread per bond slot (count, scatter)
+    pub const BONDS: u64 = 12;
+    /// One workgroup per scan block (scan_blocks, add_block_offsets)
+    pub const SCAN_BLOCKS: u64 = 24;
+    /// Single workgroup (scan_block_sums)
+    pub const SCAN_BLOCK_SUMS: u64 = 36;
+    /// Size of the args buffer
+    pub const SIZE: u64 = 48;
+}
+
+/// Resource holding the per-particle bond lists (`Adjacency::packed`, room for both ends
+/// of every bond slot) and the scratch buffers `bond_adjacency.wgsl` rebuilds them with.
+/// The offsets come from prefix_sum.wgsl's blocked scan, run over the per-particle counts
+/// as a grid one row of `PARTICLE_COUNT` cells high.
+#[derive(Resource)]
+pub struct BondAdjacencyBuffer {
+    pub buffer: Buffer,
+    /// `PARTICLE_COUNT` bond counts
+    pub counts: Buffer,
+    /// `PARTICLE_COUNT + 1` write cursors
+    pub cursor: Buffer,
+    /// Per-block totals of the scan
+    pub block_sums: Buffer,
+    /// `GridParams` uniform sizing the scan to `PARTICLE_COUNT` counts
+    pub scan_params: Buffer,
+    /// Indirect dispatch args of the rebuild (`adjacency_dispatch`)
+    pub dispatch: Buffer,
+}
+
+impl FromWorld for BondAdjacencyBuffer {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let scenario = world.resource::<SimulationSettings>().scenario;
+
+        let adjacency = bond_adjacency(&scenario_bonds(scenario), PARTICLE_COUNT);
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("BondAdjacency Buffer"),
+            contents: bytemuck::cast_slice(&adjacency.packed(2 * BOND_COUNT)),
+            usage: BufferUsages::STORAGE,
+        });
+
+        let counts = render_device.create_buffer(&BufferDescriptor {
+            label: Some("BondAdjacency Counts Buffer"),
+            size: (PARTICLE_COUNT * std::mem::size_of::<u32>()) as u64,
+            usage: BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+
+        let cursor = render_device.create_buffer(&BufferDescriptor {
+            label: Some("BondAdjacency Cursor Buffer"),
+            size: ((PARTICLE_COUNT + 1) * std::mem::size_of::<u32>()) as u64,
+            usage: BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+
+        let block_sums = render_device.create_buffer(&BufferDescriptor {
+            label: Some("BondAdjacency BlockSums Buffer"),
+            size: (scan_block_count(PARTICLE_COUNT) * std::mem::size_of::<u32>()) as u64,
+            usage: BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+
+        let scan_grid = GridParams {
+            grid_width: PARTICLE_COUNT as u32,
+            grid_height: 1,
+            ..GridParams::default()
+        };
+        let scan_params = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("BondAdjacency ScanParams Buffer"),
+            contents: bytemuck::bytes_of(&scan_grid),
+            usage: BufferUsages::UNIFORM,
+        });
+
+        // Zero until the first plan: nothing to rebuild
+        let dispatch = render_device.create_buffer(&BufferDescriptor {
+            label: Some("BondAdjacency Dispatch Buffer"),
+            size: adjacency_dispatch::SIZE,
+            usage: BufferUsages::STORAGE | BufferUsages::INDIRECT,
+            mapped_at_creation: false,
+        });
+
+        Self { buffer, counts, cursor, block_sums, scan_params, dispatch }
+    }
+}
+
+/// Resource holding the per-particle force accumulation buffer (`ParticleForce` per particle).
+/// Written by the forces and bond gather passes, consumed by the integrator.
 #[derive(Resource)]
 pub struct ForceBuffer(pub Buffer);
 
@@ -615,10 +1203,11 @@ impl FromWorld for ForceBuffer {
     fn from_world(world: &mut World) -> Self {
         let render_device = world.resource::<RenderDevice>();
         
-        let size = PARTICLE_COUNT * 2 * 4;
+        // Zero-initialised: no bond force, no integrator history (state_tag = 0)
+        let size = PARTICLE_COUNT * std::mem::size_of::<ParticleForce>();
         
         let buffer = render_device.create_buffer(&BufferDescriptor {
-            label: Some("Atomic Force Buffer"),
+            label: Some("Force Buffer"),
             size: size as u64,
             usage: BufferUsages::STORAGE | BufferUsages::COPY_DST | BufferUsages::COPY_SRC,
             mapped_at_creation: false,
@@ -651,3 +1240,296 @@ impl FromWorld for InteractionTableBuffer {
         Self(buffer)
     }
 }
+
+/// Resource holding PCISPH per-particle scratch (predicted position + pressure acceleration)
+#[derive(Resource)]
+pub struct PressureSolverBuffer(pub Buffer);
+
+impl FromWorld for PressureSolverBuffer {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        
+        // vec2 predicted_pos + vec2 pressure_accel per particle
+        let size = PARTICLE_COUNT * 4 * 4;
+        
+        let buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("PressureSolver Buffer"),
+            size: size as u64,
+            usage: BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+        
+        Self(buffer)
+    }
+}
+
+/// Indirect dispatch arguments (x, y, z workgroups) of the PCISPH iteration passes.
+/// `begin` sets the particle workgroup count, `check` zeroes it once converged.
+#[derive(Resource)]
+pub struct PcisphDispatchBuffer(pub Buffer);
+
+impl FromWorld for PcisphDispatchBuffer {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("PCISPH Dispatch Buffer"),
+            size: 3 * 4,
+            usage: BufferUsages::STORAGE | BufferUsages::INDIRECT,
+            mapped_at_creation: false,
+        });
+
+        Self(buffer)
+    }
+}
+
+/// Resource holding the PCISPH per-step state (iterations, convergence, max error).
+/// Copied to a staging buffer each step for `PressureSolverStats`.
+#[derive(Resource)]
+pub struct PressureSolverStateBuffer(pub Buffer);
+
+impl FromWorld for PressureSolverStateBuffer {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        
+        let buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("PressureSolverState Buffer"),
+            size: std::mem::size_of::<PressureSolverState>() as u64,
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        
+        Self(buffer)
+    }
+}
+
+/// Resource holding the adaptive timestep controller parameters uniform.
+/// Rewritten every frame from `SimulationSettings.timestep`.
+#[derive(Resource)]
+pub struct TimestepParamsBuffer(pub Buffer);
+
+impl FromWorld for TimestepParamsBuffer {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("TimestepParams Buffer"),
+            contents: bytemuck::bytes_of(&TimestepParams::default()),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+        
+        Self(buffer)
+    }
+}
+
+/// Resource holding the adaptive timestep controller state (running maxima + next dt).
+/// `delta_time` is copied into the SimParams buffer at the start of each adaptive step.
+#[derive(Resource)]
+pub struct TimestepStateBuffer(pub Buffer);
+
+impl FromWorld for TimestepStateBuffer {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        
+        // First adaptive step starts from the fixed default dt
+        let state = TimestepState {
+            delta_time: SimParams::default().delta_time,
+            ..Default::default()
+        };
+        
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("TimestepState Buffer"),
+            contents: bytemuck::bytes_of(&state),
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC,
+        });
+        
+        Self(buffer)
+    }
+}
+
+/// Resource holding the per-step statistics (`StatsState`), written by the stats pass
+/// and read back every frame
+#[derive(Resource)]
+pub struct StatsStateBuffer(pub Buffer);
+
+impl FromWorld for StatsStateBuffer {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("StatsState Buffer"),
+            contents: bytemuck::bytes_of(&StatsState::default()),
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC,
+        });
+
+        Self(buffer)
+    }
+}
+
+/// Resource holding the Akinci boundary volume per particle (`V_b`, 0 for fluids).
+/// Written by the boundary volume pass in density.wgsl, read by density, forces and PCISPH.
+#[derive(Resource)]
+pub struct BoundaryVolumeBuffer(pub Buffer);
+
+impl FromWorld for BoundaryVolumeBuffer {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        
+        let size = PARTICLE_COUNT * std::mem::size_of::<f32>();
+        
+        let buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("BoundaryVolume Buffer"),
+            size: size as u64,
+            usage: BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+        
+        Self(buffer)
+    }
+}
+
+/// Resource holding the rigid body states (`RigidBody` per scenario body).
+/// Copied to a staging buffer each step for `RigidBodies`.
+#[derive(Resource)]
+pub struct RigidBodyBuffer {
+    pub buffer: Buffer,
+    /// Number of bodies (one shape-matching workgroup each)
+    pub count: u32,
+}
+
+impl FromWorld for RigidBodyBuffer {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let scenario = world.resource::<SimulationSettings>().scenario;
+
+        // At least one (empty) body so the binding is never zero-sized
+        let mut bodies: Vec<RigidBody> = scenario.rigid_bodies().iter().map(RigidBody::from).collect();
+        let count = bodies.len() as u32;
+        if bodies.is_empty() {
+            bodies.push(RigidBody::default());
+        }
+
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("RigidBody Buffer"),
+            contents: bytemuck::cast_slice(&bodies),
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC,
+        });
+
+        Self { buffer, count }
+    }
+}
+
+/// Resource holding the bending triplets (`AngularSpring` per triplet)
+#[derive(Resource)]
+pub struct AngularSpringBuffer {
+    pub buffer: Buffer,
+    /// Number of triplets (0 skips the bending pass)
+    pub count: u32,
+    /// `TripletForce` per triplet, written by the bending pass
+    pub forces: Buffer,
+    /// Per-particle triplet lists (`Adjacency::packed`)
+    pub adjacency: Buffer,
+}
+
+impl FromWorld for AngularSpringBuffer {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let scenario = world.resource::<SimulationSettings>().scenario;
+
+        // At least one (inactive) triplet so the binding is never zero-sized
+        let mut springs = scenario.angular_springs();
+        let count = springs.len() as u32;
+        println!("Generated {} bending triplets", count);
+        let adjacency = triplet_adjacency(&springs, PARTICLE_COUNT);
+        if springs.is_empty() {
+            springs.push(AngularSpring::default());
+        }
+
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("AngularSpring Buffer"),
+            contents: bytemuck::cast_slice(&springs),
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
+        });
+
+        // Zeroed: a skipped bending pass contributes nothing to the gather
+        let forces = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("TripletForce Buffer"),
+            contents: bytemuck::cast_slice(&vec![TripletForce::default(); springs.len()]),
+            usage: BufferUsages::STORAGE,
+        });
+
+        let adjacency = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("TripletAdjacency Buffer"),
+            contents: bytemuck::cast_slice(&adjacency.packed(1)),
+            usage: BufferUsages::STORAGE,
+        });
+
+        Self { buffer, count, forces, adjacency }
+    }
+}
+
+/// Resource holding per-particle body membership and rest offsets (`RigidMember` per particle)
+#[derive(Resource)]
+pub struct RigidMemberBuffer(pub Buffer);
+
+impl FromWorld for RigidMemberBuffer {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let scenario = world.resource::<SimulationSettings>().scenario;
+
+        // Rest offsets are captured on the GPU from the first measured pose
+        let members = rigid_members(&scenario.rigid_bodies(), PARTICLE_COUNT);
+
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("RigidMember Buffer"),
+            contents: bytemuck::cast_slice(&members),
+            usage: BufferUsages::STORAGE,
+        });
+
+        Self(buffer)
+    }
+}
+
+/// Resource holding per-particle kinematic joints (`KinematicTarget` per particle)
+#[derive(Resource)]
+pub struct KinematicTargetBuffer(pub Buffer);
+
+impl FromWorld for KinematicTargetBuffer {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let scenario = world.resource::<SimulationSettings>().scenario;
+
+        // Rest offsets are captured on the GPU once the pivot body was measured
+        let targets = kinematic_targets(
+            &scenario.kinematic_joints(),
+            &scenario.rigid_bodies(),
+            PARTICLE_COUNT,
+        );
+
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("KinematicTarget Buffer"),
+            contents: bytemuck::cast_slice(&targets),
+            usage: BufferUsages::STORAGE,
+        });
+
+        Self(buffer)
+    }
+}
+
+/// Resource holding the kinematic driver values, rewritten every frame from `KinematicDrivers`
+#[derive(Resource)]
+pub struct KinematicDriverBuffer(pub Buffer);
+
+impl FromWorld for KinematicDriverBuffer {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("KinematicDriver Buffer"),
+            contents: bytemuck::cast_slice(&[GpuKinematicDriver::default(); MAX_KINEMATIC_DRIVERS]),
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
+        });
+
+        Self(buffer)
+    }
+}
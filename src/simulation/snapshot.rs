@@ -0,0 +1,279 @@
+//! Snapshots of the particle and bond state for main-world systems.
+//!
+//! `SnapshotRequests::request_snapshot` asks for `ParticleBuffer` and `BondBuffer`, and
+//! optionally `ForceBuffer`, as they are at the end of a step. The compute node copies
+//! them and the slot -> particle id table behind a small header into a slot of a
+//! `ReadbackRing`, and once the slot is mapped `emit_snapshots` decodes it into a
+//! `SimulationSnapshot` event. Decoding undoes any reordering (see `reorder`): particles
+//! and forces come back in original id order, and bonds name original ids.
+//!
+//! Throttling: a slot is about 1.9 MB, so only two are in flight. Requests made before
+//! the pending one was delivered are merged into it, and the copy waits while both slots
+//! are busy: a request is delayed, never the pipeline. A newer snapshot landing in the
+//! same poll as an older one replaces it.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_resource::{Buffer, BufferDescriptor, BufferUsages, CommandEncoder},
+        renderer::{RenderDevice, RenderQueue},
+    },
+};
+use bytemuck::{Pod, Zeroable};
+
+use super::readback::{ReadbackChannel, ReadbackResource, ReadbackRing};
+use super::reorder::{permute, remap_bond};
+use super::setup::{BOND_COUNT, PARTICLE_COUNT};
+use crate::resources::{Bond, Particle, ParticleForce};
+
+/// Leads every snapshot readback
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable, Debug, Default)]
+pub struct SnapshotHeader {
+    /// Id of the request this snapshot answers
+    pub id: u32,
+    /// 1 if the forces follow the bonds
+    pub forces: u32,
+    pub _padding: [u32; 2],
+}
+
+const HEADER_SIZE: u64 = std::mem::size_of::<SnapshotHeader>() as u64;
+const PARTICLES_SIZE: u64 = (PARTICLE_COUNT * std::mem::size_of::<Particle>()) as u64;
+const BONDS_SIZE: u64 = (BOND_COUNT * std::mem::size_of::<Bond>()) as u64;
+const IDS_SIZE: u64 = (PARTICLE_COUNT * std::mem::size_of::<u32>()) as u64;
+const FORCES_SIZE: u64 = (PARTICLE_COUNT * std::mem::size_of::<ParticleForce>()) as u64;
+/// Staging slot size: header, particles, bonds, particle ids, forces
+pub const SNAPSHOT_SIZE: u64 = HEADER_SIZE + PARTICLES_SIZE + BONDS_SIZE + IDS_SIZE + FORCES_SIZE;
+
+/// A requested snapshot (ids start at 1)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SnapshotRequest {
+    pub id: u32,
+    /// Include `ForceBuffer`
+    pub forces: bool,
+}
+
+/// Snapshot requests from main-world systems (copied to the render world every frame)
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct SnapshotRequests {
+    last_id: u32,
+    pending: Option<SnapshotRequest>,
+}
+
+impl SnapshotRequests {
+    /// Ask for the state at the end of an upcoming step. Returns the id the
+    /// `SimulationSnapshot` will carry; merges with a request not delivered yet.
+    pub fn request_snapshot(&mut self, forces: bool) -> u32 {
+        self.last_id = self.last_id.wrapping_add(1).max(1);
+        let forces = forces || self.pending.is_some_and(|pending| pending.forces);
+        self.pending = Some(SnapshotRequest { id: self.last_id, forces });
+        self.last_id
+    }
+
+    /// The request waiting for its snapshot, if any
+    pub fn pending(&self) -> Option<SnapshotRequest> {
+        self.pending
+    }
+
+    /// A snapshot arrived: it answers the pending request if it is at least as new
+    fn delivered(&mut self, id: u32) {
+        if self.pending.is_some_and(|pending| pending.id <= id) {
+            self.pending = None;
+        }
+    }
+}
+
+/// Particle and bond state at the end of one step (arrives a few frames later)
+#[derive(Event, Clone, Debug)]
+pub struct SimulationSnapshot {
+    /// Id returned by `request_snapshot`
+    pub id: u32,
+    /// In original id order
+    pub particles: Vec<Particle>,
+    /// All `BOND_COUNT` slots, including unused and broken ones
+    pub bonds: Vec<Bond>,
+    /// Only when requested; in original id order
+    pub forces: Option<Vec<ParticleForce>>,
+}
+
+/// Decode one staging slot (CPU end of `SnapshotReadback::record`)
+pub fn decode_snapshot(bytes: &[u8]) -> Option<SimulationSnapshot> {
+    if (bytes.len() as u64) < SNAPSHOT_SIZE {
+        return None;
+    }
+    let header: SnapshotHeader = bytemuck::pod_read_unaligned(&bytes[..HEADER_SIZE as usize]);
+    let section = |start: u64, len: u64| &bytes[start as usize..(start + len) as usize];
+    let particles = section(HEADER_SIZE, PARTICLES_SIZE);
+    let bonds = section(HEADER_SIZE + PARTICLES_SIZE, BONDS_SIZE);
+    let ids = section(HEADER_SIZE + PARTICLES_SIZE + BONDS_SIZE, IDS_SIZE);
+    let forces = section(HEADER_SIZE + PARTICLES_SIZE + BONDS_SIZE + IDS_SIZE, FORCES_SIZE);
+
+    // Slot -> original id: moving every slot to its id undoes the reordering
+    let ids: Vec<u32> = ids.chunks_exact(4).map(bytemuck::pod_read_unaligned).collect();
+    let particles: Vec<Particle> =
+        particles.chunks_exact(std::mem::size_of::<Particle>()).map(bytemuck::pod_read_unaligned).collect();
+    let mut bonds: Vec<Bond> = bonds.chunks_exact(std::mem::size_of::<Bond>()).map(bytemuck::pod_read_unaligned).collect();
+    for bond in &mut bonds {
+        remap_bond(bond, &ids);
+    }
+    Some(SimulationSnapshot {
+        id: header.id,
+        particles: permute(&particles, &ids),
+        bonds,
+        forces: (header.forces != 0).then(|| {
+            let forces: Vec<ParticleForce> =
+                forces.chunks_exact(std::mem::size_of::<ParticleForce>()).map(bytemuck::pod_read_unaligned).collect();
+            permute(&forces, &ids)
+        }),
+    })
+}
+
+/// Main-world end of the snapshot readback
+#[derive(Resource, Clone, Default)]
+pub struct SnapshotChannel(pub ReadbackChannel);
+
+/// Render-world staging ring and header for snapshots
+#[derive(Resource)]
+pub struct SnapshotReadback {
+    ring: ReadbackRing,
+    header: Buffer,
+    /// Id of the last request copied, so a pending request is copied once
+    last_copied: AtomicU32,
+}
+
+impl ReadbackResource for SnapshotReadback {
+    fn ring(&self) -> &ReadbackRing {
+        &self.ring
+    }
+}
+
+impl SnapshotReadback {
+    pub fn new(render_device: &RenderDevice, channel: ReadbackChannel) -> Self {
+        Self {
+            ring: ReadbackRing::new(render_device, "Snapshot", SNAPSHOT_SIZE, 2, channel),
+            header: render_device.create_buffer(&BufferDescriptor {
+                label: Some("Snapshot Header Buffer"),
+                size: HEADER_SIZE,
+                usage: BufferUsages::COPY_SRC | BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }),
+            last_copied: AtomicU32::new(0),
+        }
+    }
+
+    /// Copy the end-of-step state for `request`, unless it was copied already or every
+    /// slot is busy (it is retried next frame)
+    #[allow(clippy::too_many_arguments)]
+    pub fn record(
+        &self,
+        encoder: &mut CommandEncoder,
+        render_queue: &RenderQueue,
+        request: SnapshotRequest,
+        particles: &Buffer,
+        bonds: &Buffer,
+        particle_ids: &Buffer,
+        forces: &Buffer,
+    ) {
+        if self.last_copied.load(Ordering::Relaxed) == request.id {
+            return;
+        }
+        let header = SnapshotHeader { id: request.id, forces: request.forces as u32, _padding: [0; 2] };
+        let mut sources = vec![
+            (&self.header, HEADER_SIZE),
+            (particles, PARTICLES_SIZE),
+            (bonds, BONDS_SIZE),
+            (particle_ids, IDS_SIZE),
+        ];
+        if request.forces {
+            sources.push((forces, FORCES_SIZE));
+        }
+        if self.ring.copy_concatenated(encoder, &sources) {
+            // Queue writes land before this frame's copies run
+            render_queue.write_buffer(&self.header, 0, bytemuck::bytes_of(&header));
+            self.last_copied.store(request.id, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Send a `SimulationSnapshot` event for every snapshot that arrived
+pub fn emit_snapshots(
+    channel: Res<SnapshotChannel>,
+    mut requests: ResMut<SnapshotRequests>,
+    mut events: EventWriter<SimulationSnapshot>,
+) {
+    let Some(snapshot) = channel.0.take().as_deref().and_then(decode_snapshot) else {
+        return;
+    };
+    requests.delivered(snapshot.id);
+    events.send(snapshot);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_merge_until_delivered() {
+        let mut requests = SnapshotRequests::default();
+        assert_eq!(requests.pending(), None);
+        let first = requests.request_snapshot(true);
+        let second = requests.request_snapshot(false);
+        assert_eq!((first, second), (1, 2));
+        assert_eq!(requests.pending(), Some(SnapshotRequest { id: 2, forces: true }));
+
+        // An older snapshot does not answer a newer request
+        requests.delivered(1);
+        assert!(requests.pending().is_some());
+        requests.delivered(2);
+        assert_eq!(requests.pending(), None);
+    }
+
+    #[test]
+    fn staging_bytes_decode_into_a_snapshot() {
+        let particle = Particle { pos: [3.0, -4.0], mass: 2.0, layer_mask: 1, ..Particle::zeroed() };
+        let bond = Bond { particle_a: 7, particle_b: 9, rest_length: 1.5, ..Bond::zeroed() };
+        let force = ParticleForce { fluid: [1.0, 2.0], ..Default::default() };
+        let ids: Vec<u32> = (0..PARTICLE_COUNT as u32).collect();
+
+        let mut bytes = bytemuck::bytes_of(&SnapshotHeader { id: 5, forces: 1, _padding: [0; 2] }).to_vec();
+        bytes.extend(std::iter::repeat_n(bytemuck::bytes_of(&particle), PARTICLE_COUNT).flatten());
+        bytes.extend(std::iter::repeat_n(bytemuck::bytes_of(&bond), BOND_COUNT).flatten());
+        bytes.extend(bytemuck::cast_slice(&ids));
+        bytes.extend(std::iter::repeat_n(bytemuck::bytes_of(&force), PARTICLE_COUNT).flatten());
+
+        let snapshot = decode_snapshot(&bytes).unwrap();
+        assert_eq!(snapshot.id, 5);
+        assert_eq!((snapshot.particles.len(), snapshot.bonds.len()), (PARTICLE_COUNT, BOND_COUNT));
+        assert_eq!(snapshot.particles[PARTICLE_COUNT - 1].pos, [3.0, -4.0]);
+        assert_eq!(snapshot.bonds[0].particle_b, 9);
+        assert_eq!(snapshot.forces.unwrap()[0].fluid, [1.0, 2.0]);
+
+        // Without forces the stale tail is ignored
+        bytes[4..8].copy_from_slice(&0u32.to_le_bytes());
+        assert!(decode_snapshot(&bytes).unwrap().forces.is_none());
+        assert!(decode_snapshot(&bytes[..100]).is_none());
+    }
+
+    #[test]
+    fn reordered_snapshots_decode_in_id_order() {
+        // Slots 0 and 1 hold particles 1 and 0
+        let mut ids: Vec<u32> = (0..PARTICLE_COUNT as u32).collect();
+        ids.swap(0, 1);
+        let mut particles = vec![Particle::zeroed(); PARTICLE_COUNT];
+        particles[0].pos = [1.0, 1.0];
+        let mut bonds = vec![Bond::zeroed(); BOND_COUNT];
+        bonds[0] = Bond { particle_a: 0, particle_b: 2, rest_length: 1.0, ..Bond::zeroed() };
+
+        let mut bytes = bytemuck::bytes_of(&SnapshotHeader::default()).to_vec();
+        bytes.extend(bytemuck::cast_slice(&particles));
+        bytes.extend(bytemuck::cast_slice(&bonds));
+        bytes.extend(bytemuck::cast_slice(&ids));
+        bytes.resize(SNAPSHOT_SIZE as usize, 0);
+
+        let snapshot = decode_snapshot(&bytes).unwrap();
+        assert_eq!((snapshot.particles[0].pos, snapshot.particles[1].pos), ([0.0, 0.0], [1.0, 1.0]));
+        assert_eq!((snapshot.bonds[0].particle_a, snapshot.bonds[0].particle_b), (1, 2));
+    }
+}
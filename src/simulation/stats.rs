@@ -0,0 +1,253 @@
+//! Per-step simulation statistics.
+//!
+//! After the physics stage `stats.wgsl` reduces the particle and bond buffers into a
+//! 128-byte `StatsState`: kinetic energy and density per material, speed and pressure
+//! extremes, active bonds, bonds broken this step and NaN particles. It runs as one
+//! workgroup striding over the buffers and reducing in shared memory, so there are no float
+//! atomics and the result is the same every run. Only the state is read back (every frame a
+//! staging slot is free) into the `SimStats` resource, never the buffers themselves.
+//!
+//! Particles with a NaN position, velocity, density or pressure are only counted in
+//! `nan_count`. Static obstacles (mass above 10000) count towards density and pressure but
+//! not kinetic energy or speed.
+
+use bevy::prelude::*;
+use bytemuck::{Pod, Zeroable};
+
+use super::physics_config::MaterialType;
+use super::readback::{ReadbackChannel, ReadbackResource, ReadbackRing};
+use crate::resources::{Bond, Particle};
+
+/// Particles above this mass are static obstacles (matches physics.wgsl)
+pub(crate) const STATIC_MASS: f32 = 10000.0;
+
+/// GPU statistics (matches `StatsState` in stats.wgsl). 128 bytes.
+/// Per-material arrays are indexed by `MaterialType as usize`.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable, Debug, Default, PartialEq)]
+pub struct StatsState {
+    pub kinetic_energy: [f32; MaterialType::COUNT],
+    pub mean_density: [f32; MaterialType::COUNT],
+    pub max_density: [f32; MaterialType::COUNT],
+    pub particle_count: [u32; MaterialType::COUNT],
+    pub max_speed: f32,
+    pub mean_speed: f32,
+    pub min_pressure: f32,
+    pub max_pressure: f32,
+    pub active_bonds: u32,
+    pub broken_bonds: u32,
+    pub nan_count: u32,
+    /// Break log head at the last reduction (`BondBreakLogHeader::head`)
+    pub break_head: u32,
+    /// Reductions so far, i.e. steps simulated
+    pub step: u32,
+    pub _padding: [u32; 3],
+}
+
+/// Reduce one step (CPU mirror of stats.wgsl). `break_head` is the break log head now,
+/// `previous` the result of the previous reduction.
+pub fn reduce_stats(particles: &[Particle], bonds: &[Bond], break_head: u32, previous: &StatsState) -> StatsState {
+    let mut state = StatsState::zeroed();
+    let mut density_sum = [0.0f32; MaterialType::COUNT];
+    let (mut speed_sum, mut moving) = (0.0f32, 0u32);
+    let (mut min_pressure, mut max_pressure) = (f32::MAX, f32::MIN);
+
+    for p in particles {
+        let values = [p.pos[0], p.pos[1], p.vel[0], p.vel[1], p.density, p.pressure];
+        if values.iter().any(|value| value.is_nan()) {
+            state.nan_count += 1;
+            continue;
+        }
+        let m = MaterialType::from_layer_mask(p.layer_mask) as usize;
+        state.particle_count[m] += 1;
+        density_sum[m] += p.density;
+        state.max_density[m] = state.max_density[m].max(p.density);
+        min_pressure = min_pressure.min(p.pressure);
+        max_pressure = max_pressure.max(p.pressure);
+        if p.mass <= STATIC_MASS {
+            let speed = Vec2::from(p.vel).length();
+            state.kinetic_energy[m] += 0.5 * p.mass * speed * speed;
+            speed_sum += speed;
+            state.max_speed = state.max_speed.max(speed);
+            moving += 1;
+        }
+    }
+
+    state.mean_density = std::array::from_fn(|m| {
+        let count = state.particle_count[m];
+        if count > 0 { density_sum[m] / count as f32 } else { 0.0 }
+    });
+    state.mean_speed = if moving > 0 { speed_sum / moving as f32 } else { 0.0 };
+    if min_pressure <= max_pressure {
+        (state.min_pressure, state.max_pressure) = (min_pressure, max_pressure);
+    }
+    state.active_bonds = bonds.iter().filter(|bond| bond.is_active != 0).count() as u32;
+    state.broken_bonds = break_head.wrapping_sub(previous.break_head);
+    state.break_head = break_head;
+    state.step = previous.step + 1;
+    state
+}
+
+/// Per-step simulation statistics, updated from an asynchronous GPU readback
+/// (a few frames behind). Per-material arrays are indexed by `MaterialType as usize`.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub struct SimStats {
+    /// Steps simulated when these stats were taken (0 before the first readback)
+    pub step: u32,
+    /// Σ ½·m·|v|² per material (static obstacles excluded)
+    pub kinetic_energy: [f32; MaterialType::COUNT],
+    /// Particles per material (NaN particles excluded)
+    pub particle_count: [u32; MaterialType::COUNT],
+    pub mean_density: [f32; MaterialType::COUNT],
+    pub max_density: [f32; MaterialType::COUNT],
+    pub max_speed: f32,
+    pub mean_speed: f32,
+    pub min_pressure: f32,
+    pub max_pressure: f32,
+    pub active_bonds: u32,
+    /// Bonds broken in the step these stats describe
+    pub broken_bonds: u32,
+    /// Particles with a NaN position, velocity, density or pressure
+    pub nan_count: u32,
+}
+
+impl SimStats {
+    pub fn from_state(state: &StatsState) -> Self {
+        Self {
+            step: state.step,
+            kinetic_energy: state.kinetic_energy,
+            particle_count: state.particle_count,
+            mean_density: state.mean_density,
+            max_density: state.max_density,
+            max_speed: state.max_speed,
+            mean_speed: state.mean_speed,
+            min_pressure: state.min_pressure,
+            max_pressure: state.max_pressure,
+            active_bonds: state.active_bonds,
+            broken_bonds: state.broken_bonds,
+            nan_count: state.nan_count,
+        }
+    }
+
+    /// Kinetic energy of all materials
+    pub fn total_kinetic_energy(&self) -> f32 {
+        self.kinetic_energy.iter().sum()
+    }
+
+    /// Column names matching `csv_row`
+    pub fn csv_header() -> String {
+        let per_material = |prefix: &str| MATERIAL_NAMES.map(|name| format!("{prefix}_{name}")).join(",");
+        format!(
+            "step,{},{},{},{},max_speed,mean_speed,min_pressure,max_pressure,active_bonds,broken_bonds,nan_count",
+            per_material("kinetic_energy"),
+            per_material("particles"),
+            per_material("mean_density"),
+            per_material("max_density"),
+        )
+    }
+
+    /// One CSV line (no newline)
+    pub fn csv_row(&self) -> String {
+        let join = |values: &[String]| values.join(",");
+        format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{}",
+            self.step,
+            join(&self.kinetic_energy.map(|value| value.to_string())),
+            join(&self.particle_count.map(|value| value.to_string())),
+            join(&self.mean_density.map(|value| value.to_string())),
+            join(&self.max_density.map(|value| value.to_string())),
+            self.max_speed,
+            self.mean_speed,
+            self.min_pressure,
+            self.max_pressure,
+            self.active_bonds,
+            self.broken_bonds,
+            self.nan_count,
+        )
+    }
+}
+
+/// Column name suffixes by `MaterialType as usize`
+const MATERIAL_NAMES: [&str; MaterialType::COUNT] = ["water", "air", "hull", "sail", "mast"];
+
+/// Main-world end of the statistics readback
+#[derive(Resource, Clone, Default)]
+pub struct SimStatsChannel(pub ReadbackChannel);
+
+/// Render-world staging ring for the statistics
+#[derive(Resource)]
+pub struct StatsReadback(pub ReadbackRing);
+
+impl ReadbackResource for StatsReadback {
+    fn ring(&self) -> &ReadbackRing {
+        &self.0
+    }
+}
+
+/// Copy the latest read back statistics into `SimStats`
+pub fn update_sim_stats(channel: Res<SimStatsChannel>, mut stats: ResMut<SimStats>) {
+    let Some(bytes) = channel.0.take() else {
+        return;
+    };
+    let state: StatsState = bytemuck::pod_read_unaligned(&bytes);
+    *stats = SimStats::from_state(&state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reduction_splits_by_material_and_skips_nan() {
+        let mut water = Particle::new_water([0.0, 0.0], [3.0, 4.0]);
+        water.density = 1000.0;
+        water.pressure = 50.0;
+        let mut slow_water = Particle::new_water([1.0, 0.0], [0.0, 1.0]);
+        slow_water.density = 900.0;
+        slow_water.pressure = -20.0;
+        let mut air = Particle::new_air([2.0, 0.0], [0.0, 0.0]);
+        air.density = 1.2;
+        let hull = Particle { mass: 1.0e6, layer_mask: 4, density: 5.0, vel: [9.0, 0.0], ..Particle::zeroed() };
+        let broken = Particle { vel: [f32::NAN, 0.0], ..water };
+        let particles = [water, slow_water, air, hull, broken];
+
+        let bonds = [Bond { is_active: 1, ..Bond::zeroed() }, Bond::zeroed(), Bond { is_active: 1, ..Bond::zeroed() }];
+        let previous = StatsState { break_head: 4, step: 9, ..Default::default() };
+        let state = reduce_stats(&particles, &bonds, 7, &previous);
+        let water_index = MaterialType::Water as usize;
+
+        assert_eq!(state.nan_count, 1);
+        assert_eq!(state.particle_count, [2, 1, 1, 0, 0]);
+        assert_eq!(state.kinetic_energy[water_index], 0.5 * water.mass * 25.0 + 0.5 * water.mass);
+        assert_eq!(state.kinetic_energy[MaterialType::Hull as usize], 0.0);
+        assert_eq!(state.mean_density[water_index], 950.0);
+        assert_eq!(state.max_density[water_index], 1000.0);
+        assert_eq!((state.min_pressure, state.max_pressure), (-20.0, 50.0));
+        // The static hull is ignored: max and mean over the three others
+        assert_eq!(state.max_speed, 5.0);
+        assert_eq!(state.mean_speed, 2.0);
+        assert_eq!((state.active_bonds, state.broken_bonds, state.break_head, state.step), (2, 3, 7, 10));
+
+        // Nothing valid: zeros, not the min/max sentinels
+        let empty = reduce_stats(&[broken], &[], u32::MAX, &StatsState { break_head: u32::MAX - 1, ..state });
+        assert_eq!((empty.min_pressure, empty.max_pressure, empty.broken_bonds), (0.0, 0.0, 1));
+        let wrapped = reduce_stats(&[], &[], 2, &empty);
+        assert_eq!(wrapped.broken_bonds, 3);
+    }
+
+    #[test]
+    fn readback_bytes_update_sim_stats() {
+        assert_eq!(std::mem::size_of::<StatsState>(), 128);
+        let particles = [Particle::new_water([0.0, 0.0], [2.0, 0.0]), Particle::new_air([5.0, 0.0], [0.0, 0.0])];
+        let state = reduce_stats(&particles, &[], 0, &StatsState::default());
+        let stats = SimStats::from_state(&bytemuck::pod_read_unaligned(bytemuck::bytes_of(&state)));
+        assert_eq!(stats.step, 1);
+        assert_eq!(stats.total_kinetic_energy(), 2.0 * particles[0].mass);
+        assert_eq!(stats.max_speed, 2.0);
+        assert_eq!(stats.mean_speed, 1.0);
+
+        let columns = |line: String| line.split(',').count();
+        assert_eq!(columns(SimStats::csv_header()), columns(stats.csv_row()));
+        assert!(stats.csv_row().starts_with("1,100,0,"));
+    }
+}
@@ -4,25 +4,56 @@
 //! 1. Cell ID calculation
 //! 2. Clear cell counts
 //! 3. Count cells
-//! 4. Prefix sum
-//! 5. Scatter sort
-//! 6. Density calculation
-//! 7. Force calculation
-//! 8. Position integration
+//! 4. Prefix sum (multi-workgroup Blelloch scan; also seeds the scatter cursors)
+//! 5. Scatter sort, each cell then sorted by particle index (optionally followed by a
+//!    permutation of the particles into cell order)
+//! 6. Density calculation (Akinci boundary volumes first, optional)
+//! 7. Force calculation (SPH + repulsion, written to the force buffer)
+//! 8. Queued bond edits and welds (runtime bonds, rebuilding the bond adjacency), then
+//!    per-bond and per-triplet forces (breaks are logged), gathered per particle into
+//!    the force buffer
+//! 9. PCISPH pressure iterations (optional, water only)
+//! 10. Adaptive timestep reduction + controller (optional, picks the next dt)
+//! 11. Integration (selectable scheme, consumes the force buffer)
+//! 12. Kinematic joints (driven particles placed relative to their pivot body)
+//! 13. Rigid bodies (fit + optional shape-matching projection, one workgroup per body)
+//! 14. Statistics reduction (energy, density, pressure, bonds, NaNs -> SimStats)
+
+use std::sync::atomic::{AtomicU32, Ordering};
 
 use bevy::{
+    ecs::system::SystemParam,
     prelude::*,
     render::{
         render_graph::{self, RenderGraphContext},
         render_resource::*,
-        renderer::{RenderContext, RenderDevice},
+        renderer::{RenderContext, RenderDevice, RenderQueue},
     },
 };
 
+use super::bond_events::BondBreakReadback;
+use super::grid::scan_block_count;
+use super::physics_config::BoundaryHandling;
+use super::ping_pong::{ParticleSlot, CONSTRAINT_ITERATIONS};
+use super::profiling::{GpuProfiler, GpuStage};
+use super::pressure_solver::{PressureSolver, PressureSolverReadback};
+use super::readback::ReadbackResource;
+use super::rigid_body::RigidBodyReadback;
+use super::settings::SimulationSettings;
+use super::snapshot::{SnapshotReadback, SnapshotRequests};
+use super::stats::StatsReadback;
 use super::setup::{
-    BondBuffer, CellCountsBuffer, CellOffsetsBuffer, ForceBuffer, GridParamsBuffer, IndexBuffer,
-    InteractionTableBuffer, ParticleBuffer, SimParamsBuffer, BOND_COUNT, PARTICLE_COUNT,
+    AngularSpringBuffer, BondAdjacencyBuffer, BondAllocatorBuffer, BondBreakLogBuffer, BondBuffer,
+    BondEditBuffer, BondForceBuffer, BondFreeListBuffer, BoundaryVolumeBuffer, CellCountsBuffer, CellCursorBuffer, CellOffsetsBuffer,
+    ForceBuffer, GridParamsBuffer, IndexBuffer, InteractionTableBuffer, KinematicDriverBuffer,
+    KinematicTargetBuffer, ParticleBackBuffer, ParticleBuffer, PcisphDispatchBuffer, PressureSolverBuffer,
+    PressureSolverStateBuffer,
+    ParticleIdBuffer, ReorderBuffer, RigidBodyBuffer, RigidMemberBuffer, ScanBlockSumsBuffer, SimParamsBuffer,
+    StatsStateBuffer, TimestepParamsBuffer, TimestepStateBuffer, WeldPartnerBuffer, WeldRuleBuffer, BOND_COUNT,
+    PARTICLE_COUNT, adjacency_dispatch,
 };
+use super::timestep::{TimestepReadback, TIMESTEP_STATE_DT_OFFSET};
+use super::welding::{BondEditsApplied, PendingBondEdits, WeldRules};
 use crate::resources::GridParams;
 
 // ==================== Pipeline Resources ====================
@@ -33,22 +64,70 @@ pub struct SphPipelines {
     pub cell_id: CachedComputePipelineId,
     pub clear_counts: CachedComputePipelineId,
     pub count_cells: CachedComputePipelineId,
-    pub prefix_sum: CachedComputePipelineId,
+    pub prefix_scan_blocks: CachedComputePipelineId,
+    pub prefix_scan_block_sums: CachedComputePipelineId,
+    pub prefix_add_block_offsets: CachedComputePipelineId,
     pub scatter: CachedComputePipelineId,
+    pub cell_sort: CachedComputePipelineId,
+    // Particle reorder stages
+    pub reorder_flag: CachedComputePipelineId,
+    pub reorder_remap: CachedComputePipelineId,
+    pub reorder_remap_bonds: CachedComputePipelineId,
+    pub reorder_permute: CachedComputePipelineId,
     pub density: CachedComputePipelineId,
+    pub boundary_volume: CachedComputePipelineId, // Akinci boundary volumes (density.wgsl)
     pub forces: CachedComputePipelineId,
     pub bonds: CachedComputePipelineId,
     pub constraints: CachedComputePipelineId, // New PBD constraints
     pub physics: CachedComputePipelineId,
+    // PCISPH pressure solver stages
+    pub pcisph_begin: CachedComputePipelineId,
+    pub pcisph_predict: CachedComputePipelineId,
+    pub pcisph_correct_density: CachedComputePipelineId,
+    pub pcisph_pressure_force: CachedComputePipelineId,
+    pub pcisph_check: CachedComputePipelineId,
+    pub pcisph_apply: CachedComputePipelineId,
+    // Adaptive timestep stages
+    pub timestep_reduce_particles: CachedComputePipelineId,
+    pub timestep_reduce_bonds: CachedComputePipelineId,
+    pub timestep_control: CachedComputePipelineId,
+    pub kinematics: CachedComputePipelineId,
+    pub rigid_body: CachedComputePipelineId,
+    pub bending: CachedComputePipelineId,
+    pub bond_edits: CachedComputePipelineId,
+    pub bond_repairs: CachedComputePipelineId,
+    pub bond_reset_free_list: CachedComputePipelineId,
+    pub bond_collect_free: CachedComputePipelineId,
+    pub weld: CachedComputePipelineId,
+    pub bond_gather: CachedComputePipelineId,
+    pub adjacency_clear: CachedComputePipelineId,
+    pub adjacency_count: CachedComputePipelineId,
+    pub adjacency_plan: CachedComputePipelineId,
+    pub adjacency_scatter: CachedComputePipelineId,
+    pub adjacency_sort: CachedComputePipelineId,
+    pub stats: CachedComputePipelineId,
     // Bind group layouts
     pub cell_id_layout: BindGroupLayout,
     pub count_layout: BindGroupLayout,
     pub prefix_layout: BindGroupLayout,
     pub scatter_layout: BindGroupLayout,
+    pub reorder_layout: BindGroupLayout,
+    pub reorder_permute_layout: BindGroupLayout,
     pub density_layout: BindGroupLayout, // Also used for constraints
     pub forces_layout: BindGroupLayout,  // Separate layout for forces (has InteractionTable)
     pub bonds_layout: BindGroupLayout,
     pub physics_layout: BindGroupLayout,
+    pub pcisph_layout: BindGroupLayout,
+    pub pcisph_dispatch_layout: BindGroupLayout,
+    pub timestep_layout: BindGroupLayout,
+    pub kinematics_layout: BindGroupLayout,
+    pub rigid_body_layout: BindGroupLayout,
+    pub bending_layout: BindGroupLayout,
+    pub weld_layout: BindGroupLayout,
+    pub bond_gather_layout: BindGroupLayout,
+    pub bond_adjacency_layout: BindGroupLayout,
+    pub bond_adjacency_plan_layout: BindGroupLayout,
+    pub stats_layout: BindGroupLayout,
 }
 
 impl FromWorld for SphPipelines {
@@ -76,68 +155,266 @@ impl FromWorld for SphPipelines {
             ],
         );
 
-        // Prefix sum layout: cell_counts (read), cell_offsets (rw), grid params
+        // Prefix sum layout: cell_counts (read), cell_offsets (rw), grid params, block sums (rw),
+        // cell_cursor (rw)
         let prefix_layout = render_device.create_bind_group_layout(
             Some("Prefix Sum Layout"),
             &[
                 storage_buffer_entry(0, true),  // cell_counts read
                 storage_buffer_entry(1, false), // cell_offsets rw
                 uniform_buffer_entry(2),        // grid params
+                storage_buffer_entry(3, false), // block sums rw
+                storage_buffer_entry(4, false), // cell_cursor rw
             ],
         );
 
-        // Scatter layout: particles (read), cell_offsets (rw atomic), sorted_indices (rw)
+        // Scatter layout: particles (read), cell_cursor (rw atomic), sorted_indices (rw),
+        // cell_offsets (read, for the per-cell sort)
         let scatter_layout = render_device.create_bind_group_layout(
             Some("Scatter Layout"),
             &[
                 storage_buffer_entry(0, true),  // particles read
-                storage_buffer_entry(1, false), // cell_offsets rw (atomic)
+                storage_buffer_entry(1, false), // cell_cursor rw (atomic)
                 storage_buffer_entry(2, false), // sorted_indices rw
+                storage_buffer_entry(3, true),  // cell_offsets read
+            ],
+        );
+
+        // Reorder layout (flag, remap, remap_bonds): sorted_indices (rw), movable flags and
+        // slots, scan flags and ranks, new slots, bonds (rw), allocator (rw)
+        let reorder_layout = render_device.create_bind_group_layout(
+            Some("Reorder Layout"),
+            &[
+                storage_buffer_entry(0, false), // sorted_indices rw
+                storage_buffer_entry(1, true),  // movable flags read
+                storage_buffer_entry(2, true),  // movable slots read
+                storage_buffer_entry(3, false), // flags rw
+                storage_buffer_entry(4, true),  // ranks read
+                storage_buffer_entry(5, false), // new slots rw
+                storage_buffer_entry(6, false), // bonds rw
+                storage_buffer_entry(7, false), // bond allocator rw
+            ],
+        );
+
+        // Reorder permute layout (bindings 8-17 of reorder.wgsl): new slots, then each
+        // permuted buffer (rw) followed by its copy from before the pass, and the id tables
+        let reorder_permute_layout = render_device.create_bind_group_layout(
+            Some("Reorder Permute Layout"),
+            &[
+                storage_buffer_entry(8, true),   // new slots read
+                storage_buffer_entry(9, false),  // particles rw
+                storage_buffer_entry(10, true),  // previous particles read
+                storage_buffer_entry(11, false), // forces rw
+                storage_buffer_entry(12, true),  // previous forces read
+                storage_buffer_entry(13, false), // weld partners rw
+                storage_buffer_entry(14, true),  // previous weld partners read
+                storage_buffer_entry(15, false), // particle ids rw
+                storage_buffer_entry(16, true),  // previous particle ids read
+                storage_buffer_entry(17, false), // particle slots rw
             ],
         );
 
-        // Density layout: particles, indices, cell_offsets, grid, sim params
+        // Density layout: particles (previous state), indices, cell_offsets, grid, sim params,
+        // boundary volumes, particles (next state), InteractionTable
         let density_layout = render_device.create_bind_group_layout(
             Some("Density Layout"),
             &[
-                storage_buffer_entry(0, false), // particles rw
+                storage_buffer_entry(0, true),  // particles read (previous state)
                 storage_buffer_entry(1, true),  // indices read
                 storage_buffer_entry(2, true),  // cell_offsets read
                 uniform_buffer_entry(3),        // grid params
                 uniform_buffer_entry(4),        // sim params
+                storage_buffer_entry(5, false), // boundary volumes rw
+                storage_buffer_entry(6, false), // particles rw (next state)
+                uniform_buffer_entry(7),        // interaction table
             ],
         );
 
-        // Forces layout: same as density + InteractionTable at binding 5
+        // Forces layout: density bindings 0-4 + InteractionTable at 5, forces at 6, boundary volumes at 7
         let forces_layout = render_device.create_bind_group_layout(
             Some("Forces Layout"),
             &[
-                storage_buffer_entry(0, false), // particles rw
+                storage_buffer_entry(0, true),  // particles read
                 storage_buffer_entry(1, true),  // indices read
                 storage_buffer_entry(2, true),  // cell_offsets read
                 uniform_buffer_entry(3),        // grid params
                 uniform_buffer_entry(4),        // sim params
                 uniform_buffer_entry(5),        // interaction table
+                storage_buffer_entry(6, false), // forces rw
+                storage_buffer_entry(7, true),  // boundary volumes read
             ],
         );
 
-        // Bonds layout: particles (rw), bonds (rw), forces (atomic rw)
+        // Bonds layout: particles (rw), bonds (rw), bond forces (rw), sim params, body membership,
+        // break log (rw), particle ids
         let bonds_layout = render_device.create_bind_group_layout(
             Some("Bonds Layout"),
             &[
                 storage_buffer_entry(0, false), // particles rw
                 storage_buffer_entry(1, false), // bonds rw
-                storage_buffer_entry(2, false), // forces atomic rw
+                storage_buffer_entry(2, false), // bond forces rw
+                uniform_buffer_entry(3),        // sim params
+                storage_buffer_entry(4, true),  // rigid members read
+                storage_buffer_entry(5, false), // bond break log rw
+                storage_buffer_entry(6, true),  // particle ids read
             ],
         );
 
-        // Physics layout: particles (rw), sim params, forces (atomic rw)
+        // Physics layout: particles (rw), sim params, forces (atomic rw), interaction table, bodies
         let physics_layout = render_device.create_bind_group_layout(
             Some("Physics Layout"),
             &[
                 storage_buffer_entry(0, false), // particles rw
                 uniform_buffer_entry(1),        // sim params
                 storage_buffer_entry(2, false), // forces atomic rw
+                uniform_buffer_entry(3),        // interaction table (damping, XSPH)
+                storage_buffer_entry(4, true),  // rigid members read
+                storage_buffer_entry(5, true),  // rigid bodies read
+            ],
+        );
+
+        // PCISPH layout: density bindings + forces, solver scratch, solver state
+        let pcisph_layout = render_device.create_bind_group_layout(
+            Some("PCISPH Layout"),
+            &[
+                storage_buffer_entry(0, false), // particles rw
+                storage_buffer_entry(1, true),  // indices read
+                storage_buffer_entry(2, true),  // cell_offsets read
+                uniform_buffer_entry(3),        // grid params
+                uniform_buffer_entry(4),        // sim params
+                storage_buffer_entry(5, false), // forces rw
+                storage_buffer_entry(6, false), // solver scratch rw
+                storage_buffer_entry(7, false), // solver state rw
+                storage_buffer_entry(8, true),  // boundary volumes read
+            ],
+        );
+
+        // PCISPH group 1 (begin and check only): indirect args of the iteration passes
+        let pcisph_dispatch_layout = render_device.create_bind_group_layout(
+            Some("PCISPH Dispatch Layout"),
+            &[
+                storage_buffer_entry(0, false), // iteration dispatch args rw
+            ],
+        );
+
+        // Timestep layout: read-only simulation state + controller params/state
+        let timestep_layout = render_device.create_bind_group_layout(
+            Some("Timestep Layout"),
+            &[
+                storage_buffer_entry(0, true),  // particles read
+                storage_buffer_entry(1, true),  // forces read
+                storage_buffer_entry(2, true),  // bonds read
+                uniform_buffer_entry(3),        // sim params
+                uniform_buffer_entry(4),        // timestep params
+                storage_buffer_entry(5, false), // timestep state rw
+            ],
+        );
+
+        // Kinematics layout: particles (rw), sim params, joints (rest offsets rw), bodies, drivers,
+        // body members (rest offsets re-posed for joints inside their pivot body)
+        let kinematics_layout = render_device.create_bind_group_layout(
+            Some("Kinematics Layout"),
+            &[
+                storage_buffer_entry(0, false), // particles rw
+                uniform_buffer_entry(1),        // sim params
+                storage_buffer_entry(2, false), // kinematic targets rw
+                storage_buffer_entry(3, true),  // rigid bodies read
+                storage_buffer_entry(4, true),  // kinematic drivers read
+                storage_buffer_entry(5, false), // rigid members rw
+            ],
+        );
+
+        // Rigid body layout: particles (rw), sim params, members (rest offsets rw), bodies (rw)
+        let rigid_body_layout = render_device.create_bind_group_layout(
+            Some("Rigid Body Layout"),
+            &[
+                storage_buffer_entry(0, false), // particles rw
+                uniform_buffer_entry(1),        // sim params
+                storage_buffer_entry(2, false), // rigid members rw
+                storage_buffer_entry(3, false), // rigid bodies rw
+            ],
+        );
+
+        // Stats layout: read-only particles, bonds and break log head + stats state
+        let stats_layout = render_device.create_bind_group_layout(
+            Some("Stats Layout"),
+            &[
+                storage_buffer_entry(0, true),  // particles read
+                storage_buffer_entry(1, true),  // bonds read
+                storage_buffer_entry(2, true),  // bond break log read
+                storage_buffer_entry(3, false), // stats state rw
+            ],
+        );
+
+        // Bending layout: particles (read), angular springs (rw), triplet forces (rw), sim params, body membership
+        let bending_layout = render_device.create_bind_group_layout(
+            Some("Bending Layout"),
+            &[
+                storage_buffer_entry(0, true),  // particles read
+                storage_buffer_entry(1, false), // angular springs rw
+                storage_buffer_entry(2, false), // triplet forces rw
+                uniform_buffer_entry(3),        // sim params
+                storage_buffer_entry(4, true),  // rigid members read
+            ],
+        );
+
+        // Weld layout: particles (read), bonds (rw), allocator (atomic rw), edits, weld rules,
+        // weld partners (rw), grid (indices, offsets, params), body membership, free slots,
+        // particle id tables, bond adjacency
+        let weld_layout = render_device.create_bind_group_layout(
+            Some("Weld Layout"),
+            &[
+                storage_buffer_entry(0, true),  // particles read
+                storage_buffer_entry(1, false), // bonds rw
+                storage_buffer_entry(2, false), // allocator atomic rw
+                storage_buffer_entry(3, true),  // bond edits read
+                uniform_buffer_entry(4),        // weld rules
+                storage_buffer_entry(5, false), // weld partners rw
+                storage_buffer_entry(6, true),  // indices read
+                storage_buffer_entry(7, true),  // cell offsets read
+                uniform_buffer_entry(8),        // grid params
+                storage_buffer_entry(9, true),  // rigid members read
+                storage_buffer_entry(10, false), // free bond slots rw
+                storage_buffer_entry(11, true),  // particle slots read
+                storage_buffer_entry(12, true),  // particle ids read
+                storage_buffer_entry(13, true),  // bond adjacency read
+            ],
+        );
+
+        // Bond gather layout: bonds, bond forces, bond adjacency, angular springs, triplet forces,
+        // triplet adjacency (all read), forces (rw)
+        let bond_gather_layout = render_device.create_bind_group_layout(
+            Some("Bond Gather Layout"),
+            &[
+                storage_buffer_entry(0, true),  // bonds read
+                storage_buffer_entry(1, true),  // bond forces read
+                storage_buffer_entry(2, true),  // bond adjacency read
+                storage_buffer_entry(3, true),  // angular springs read
+                storage_buffer_entry(4, true),  // triplet forces read
+                storage_buffer_entry(5, true),  // triplet adjacency read
+                storage_buffer_entry(6, false), // forces rw
+            ],
+        );
+
+        // Bond adjacency layout: bonds (read), bond adjacency (rw), cursors (atomic rw),
+        // counts (atomic rw)
+        let bond_adjacency_layout = render_device.create_bind_group_layout(
+            Some("Bond Adjacency Layout"),
+            &[
+                storage_buffer_entry(0, true),  // bonds read
+                storage_buffer_entry(1, false), // bond adjacency rw
+                storage_buffer_entry(2, false), // cursors atomic rw
+                storage_buffer_entry(3, false), // counts atomic rw
+            ],
+        );
+
+        // Bond adjacency group 1 (plan only): allocator change counters, indirect args of
+        // the rebuild passes
+        let bond_adjacency_plan_layout = render_device.create_bind_group_layout(
+            Some("Bond Adjacency Plan Layout"),
+            &[
+                storage_buffer_entry(0, false), // bond allocator rw
+                storage_buffer_entry(1, false), // rebuild dispatch args rw
             ],
         );
 
@@ -146,11 +423,21 @@ impl FromWorld for SphPipelines {
         let count_cells_shader = asset_server.load("shaders/count_cells.wgsl");
         let prefix_sum_shader = asset_server.load("shaders/prefix_sum.wgsl");
         let scatter_shader = asset_server.load("shaders/scatter_sort.wgsl");
+        let reorder_shader = asset_server.load("shaders/reorder.wgsl");
         let density_shader = asset_server.load("shaders/density.wgsl");
         let forces_shader = asset_server.load("shaders/forces.wgsl");
         let bonds_shader = asset_server.load("shaders/bonds.wgsl");
         let constraints_shader = asset_server.load("shaders/constraints.wgsl");
         let physics_shader = asset_server.load("shaders/physics.wgsl");
+        let pcisph_shader = asset_server.load("shaders/pcisph.wgsl");
+        let timestep_shader = asset_server.load("shaders/timestep.wgsl");
+        let kinematics_shader = asset_server.load("shaders/kinematics.wgsl");
+        let rigid_body_shader = asset_server.load("shaders/rigid_body.wgsl");
+        let bending_shader = asset_server.load("shaders/bending.wgsl");
+        let weld_shader = asset_server.load("shaders/weld.wgsl");
+        let bond_gather_shader = asset_server.load("shaders/bond_gather.wgsl");
+        let bond_adjacency_shader = asset_server.load("shaders/bond_adjacency.wgsl");
+        let stats_shader = asset_server.load("shaders/stats.wgsl");
 
         // Create pipelines
         let cell_id = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
@@ -183,22 +470,62 @@ impl FromWorld for SphPipelines {
             zero_initialize_workgroup_memory: true,
         });
 
-        let prefix_sum = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
-            label: Some("Prefix Sum Pipeline".into()),
-            layout: vec![prefix_layout.clone()],
-            shader: prefix_sum_shader,
+        let prefix_pipeline = |entry_point: &'static str, label: &'static str| {
+            pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+                label: Some(label.into()),
+                layout: vec![prefix_layout.clone()],
+                shader: prefix_sum_shader.clone(),
+                shader_defs: vec![],
+                entry_point: entry_point.into(),
+                push_constant_ranges: vec![],
+                zero_initialize_workgroup_memory: true,
+            })
+        };
+        let prefix_scan_blocks = prefix_pipeline("scan_blocks", "Prefix Sum Blocks Pipeline");
+        let prefix_scan_block_sums = prefix_pipeline("scan_block_sums", "Prefix Sum Block Sums Pipeline");
+        let prefix_add_block_offsets = prefix_pipeline("add_block_offsets", "Prefix Sum Add Offsets Pipeline");
+
+        let scatter = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+            label: Some("Scatter Pipeline".into()),
+            layout: vec![scatter_layout.clone()],
+            shader: scatter_shader.clone(),
             shader_defs: vec![],
-            entry_point: "prefix_sum".into(),
+            entry_point: "scatter".into(),
             push_constant_ranges: vec![],
             zero_initialize_workgroup_memory: true,
         });
-
-        let scatter = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
-            label: Some("Scatter Pipeline".into()),
+        let cell_sort = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+            label: Some("Cell Sort Pipeline".into()),
             layout: vec![scatter_layout.clone()],
             shader: scatter_shader,
             shader_defs: vec![],
-            entry_point: "scatter".into(),
+            entry_point: "sort_cells".into(),
+            push_constant_ranges: vec![],
+            zero_initialize_workgroup_memory: true,
+        });
+
+        let reorder_pipeline = |entry_point: &'static str, label: &'static str, layout: &BindGroupLayout| {
+            pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+                label: Some(label.into()),
+                layout: vec![layout.clone()],
+                shader: reorder_shader.clone(),
+                shader_defs: vec![],
+                entry_point: entry_point.into(),
+                push_constant_ranges: vec![],
+                zero_initialize_workgroup_memory: true,
+            })
+        };
+        let reorder_flag = reorder_pipeline("flag", "Reorder Flag Pipeline", &reorder_layout);
+        let reorder_remap = reorder_pipeline("remap", "Reorder Remap Pipeline", &reorder_layout);
+        let reorder_remap_bonds = reorder_pipeline("remap_bonds", "Reorder Remap Bonds Pipeline", &reorder_layout);
+        let reorder_permute = reorder_pipeline("permute", "Reorder Permute Pipeline", &reorder_permute_layout);
+
+        let boundary_volume = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+            label: Some("Boundary Volume Pipeline".into()),
+            layout: vec![density_layout.clone()],
+            shader: density_shader.clone(),
+            shader_defs: vec![],
+            entry_point: "boundary_volume".into(),
             push_constant_ranges: vec![],
             zero_initialize_workgroup_memory: true,
         });
@@ -253,25 +580,204 @@ impl FromWorld for SphPipelines {
             zero_initialize_workgroup_memory: true,
         });
 
+        // PCISPH: one pipeline per entry point, all sharing the same group 0 layout.
+        // begin and check also write the indirect args (group 1); the passes dispatched
+        // from those args must not bind them.
+        let pcisph_pipeline = |entry_point: &'static str, label: &'static str, writes_dispatch: bool| {
+            let mut layout = vec![pcisph_layout.clone()];
+            if writes_dispatch {
+                layout.push(pcisph_dispatch_layout.clone());
+            }
+            pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+                label: Some(label.into()),
+                layout,
+                shader: pcisph_shader.clone(),
+                shader_defs: vec![],
+                entry_point: entry_point.into(),
+                push_constant_ranges: vec![],
+                zero_initialize_workgroup_memory: true,
+            })
+        };
+        let pcisph_begin = pcisph_pipeline("begin", "PCISPH Begin Pipeline", true);
+        let pcisph_predict = pcisph_pipeline("predict", "PCISPH Predict Pipeline", false);
+        let pcisph_correct_density = pcisph_pipeline("correct_density", "PCISPH Correct Density Pipeline", false);
+        let pcisph_pressure_force = pcisph_pipeline("pressure_force", "PCISPH Pressure Force Pipeline", false);
+        let pcisph_check = pcisph_pipeline("check", "PCISPH Check Pipeline", true);
+        let pcisph_apply = pcisph_pipeline("apply", "PCISPH Apply Pipeline", false);
+
+        // Adaptive timestep: reductions + controller, all sharing the same layout
+        let timestep_pipeline = |entry_point: &'static str, label: &'static str| {
+            pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+                label: Some(label.into()),
+                layout: vec![timestep_layout.clone()],
+                shader: timestep_shader.clone(),
+                shader_defs: vec![],
+                entry_point: entry_point.into(),
+                push_constant_ranges: vec![],
+                zero_initialize_workgroup_memory: true,
+            })
+        };
+        let timestep_reduce_particles = timestep_pipeline("reduce_particles", "Timestep Reduce Particles Pipeline");
+        let timestep_reduce_bonds = timestep_pipeline("reduce_bonds", "Timestep Reduce Bonds Pipeline");
+        let timestep_control = timestep_pipeline("control", "Timestep Control Pipeline");
+
+        let kinematics = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+            label: Some("Kinematics Pipeline".into()),
+            layout: vec![kinematics_layout.clone()],
+            shader: kinematics_shader,
+            shader_defs: vec![],
+            entry_point: "main".into(),
+            push_constant_ranges: vec![],
+            zero_initialize_workgroup_memory: true,
+        });
+
+        let rigid_body = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+            label: Some("Rigid Body Pipeline".into()),
+            layout: vec![rigid_body_layout.clone()],
+            shader: rigid_body_shader,
+            shader_defs: vec![],
+            entry_point: "shape_match".into(),
+            push_constant_ranges: vec![],
+            zero_initialize_workgroup_memory: true,
+        });
+
+        let stats = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+            label: Some("Stats Pipeline".into()),
+            layout: vec![stats_layout.clone()],
+            shader: stats_shader,
+            shader_defs: vec![],
+            entry_point: "reduce".into(),
+            push_constant_ranges: vec![],
+            zero_initialize_workgroup_memory: true,
+        });
+
+        let bending = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+            label: Some("Bending Pipeline".into()),
+            layout: vec![bending_layout.clone()],
+            shader: bending_shader,
+            shader_defs: vec![],
+            entry_point: "main".into(),
+            push_constant_ranges: vec![],
+            zero_initialize_workgroup_memory: true,
+        });
+
+        // Runtime bonds: one pipeline per weld.wgsl entry point, all sharing the same layout
+        let weld_pipeline = |entry_point: &'static str, label: &'static str| {
+            pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+                label: Some(label.into()),
+                layout: vec![weld_layout.clone()],
+                shader: weld_shader.clone(),
+                shader_defs: vec![],
+                entry_point: entry_point.into(),
+                push_constant_ranges: vec![],
+                zero_initialize_workgroup_memory: true,
+            })
+        };
+        let bond_repairs = weld_pipeline("apply_repairs", "Bond Repairs Pipeline");
+        let bond_reset_free_list = weld_pipeline("reset_free_list", "Bond Reset Free List Pipeline");
+        let bond_collect_free = weld_pipeline("collect_free", "Bond Collect Free Pipeline");
+        let bond_edits = weld_pipeline("apply_edits", "Bond Edits Pipeline");
+        let weld = weld_pipeline("weld", "Weld Pipeline");
+
+        let bond_gather = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+            label: Some("Bond Gather Pipeline".into()),
+            layout: vec![bond_gather_layout.clone()],
+            shader: bond_gather_shader,
+            shader_defs: vec![],
+            entry_point: "main".into(),
+            push_constant_ranges: vec![],
+            zero_initialize_workgroup_memory: true,
+        });
+
+        let adjacency_pipeline = |entry_point: &'static str, label: &'static str| {
+            pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+                label: Some(label.into()),
+                layout: vec![bond_adjacency_layout.clone()],
+                shader: bond_adjacency_shader.clone(),
+                shader_defs: vec![],
+                entry_point: entry_point.into(),
+                push_constant_ranges: vec![],
+                zero_initialize_workgroup_memory: true,
+            })
+        };
+        let adjacency_clear = adjacency_pipeline("clear", "Bond Adjacency Clear Pipeline");
+        let adjacency_count = adjacency_pipeline("count", "Bond Adjacency Count Pipeline");
+        let adjacency_scatter = adjacency_pipeline("scatter", "Bond Adjacency Scatter Pipeline");
+        let adjacency_sort = adjacency_pipeline("sort", "Bond Adjacency Sort Pipeline");
+        let adjacency_plan = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+            label: Some("Bond Adjacency Plan Pipeline".into()),
+            layout: vec![bond_adjacency_layout.clone(), bond_adjacency_plan_layout.clone()],
+            shader: bond_adjacency_shader.clone(),
+            shader_defs: vec![],
+            entry_point: "plan".into(),
+            push_constant_ranges: vec![],
+            zero_initialize_workgroup_memory: true,
+        });
+
         Self {
             cell_id,
             clear_counts,
             count_cells,
-            prefix_sum,
+            prefix_scan_blocks,
+            prefix_scan_block_sums,
+            prefix_add_block_offsets,
             scatter,
+            cell_sort,
+            reorder_flag,
+            reorder_remap,
+            reorder_remap_bonds,
+            reorder_permute,
             density,
+            boundary_volume,
             forces,
             bonds,
             constraints,
             physics,
+            pcisph_begin,
+            pcisph_predict,
+            pcisph_correct_density,
+            pcisph_pressure_force,
+            pcisph_check,
+            pcisph_apply,
+            timestep_reduce_particles,
+            timestep_reduce_bonds,
+            timestep_control,
+            kinematics,
+            rigid_body,
+            bending,
+            bond_edits,
+            bond_repairs,
+            bond_reset_free_list,
+            bond_collect_free,
+            weld,
+            bond_gather,
+            adjacency_clear,
+            adjacency_count,
+            adjacency_plan,
+            adjacency_scatter,
+            adjacency_sort,
+            stats,
             cell_id_layout,
             count_layout,
             prefix_layout,
             scatter_layout,
+            reorder_layout,
+            reorder_permute_layout,
             density_layout,
             forces_layout,
             bonds_layout,
             physics_layout,
+            pcisph_layout,
+            pcisph_dispatch_layout,
+            timestep_layout,
+            kinematics_layout,
+            rigid_body_layout,
+            bending_layout,
+            weld_layout,
+            bond_gather_layout,
+            bond_adjacency_layout,
+            bond_adjacency_plan_layout,
+            stats_layout,
         }
     }
 }
@@ -311,19 +817,80 @@ pub struct SphBindGroups {
     pub count: BindGroup,
     pub prefix: BindGroup,
     pub scatter: BindGroup,
-    pub density: BindGroup,
+    pub reorder: BindGroup,
+    /// prefix_sum.wgsl over the movable flags in cell order
+    pub reorder_prefix: BindGroup,
+    pub reorder_permute: BindGroup,
+    /// Indexed by the `ParticleSlot` the pass reads; ping-pong passes write the other slot
+    pub density: [BindGroup; 2],
     pub forces: BindGroup,  // Separate bind group with InteractionTable
     pub bonds: BindGroup,
-    pub constraints: BindGroup, // Re-uses density bind group structure but unique group
+    pub constraints: [BindGroup; 2], // Re-uses density bind group structure but unique group
     pub physics: BindGroup,
+    pub pcisph: BindGroup,
+    pub pcisph_dispatch: BindGroup,
+    pub timestep: BindGroup,
+    pub kinematics: BindGroup,
+    pub rigid_body: BindGroup,
+    pub bending: BindGroup,
+    pub weld: BindGroup,
+    pub bond_gather: BindGroup,
+    pub bond_adjacency: BindGroup,
+    pub bond_adjacency_plan: BindGroup,
+    /// prefix_sum.wgsl over the per-particle bond counts
+    pub bond_adjacency_prefix: BindGroup,
+    pub stats: BindGroup,
+}
+
+/// Buffers of the optional solver stages, grouped to stay within Bevy's system parameter limit
+#[derive(SystemParam)]
+pub struct SolverBuffers<'w> {
+    pressure_solver: Option<Res<'w, PressureSolverBuffer>>,
+    pressure_solver_state: Option<Res<'w, PressureSolverStateBuffer>>,
+    pcisph_dispatch: Option<Res<'w, PcisphDispatchBuffer>>,
+    timestep_params: Option<Res<'w, TimestepParamsBuffer>>,
+    timestep_state: Option<Res<'w, TimestepStateBuffer>>,
+    boundary_volumes: Option<Res<'w, BoundaryVolumeBuffer>>,
+    rigid_bodies: Option<Res<'w, RigidBodyBuffer>>,
+    rigid_members: Option<Res<'w, RigidMemberBuffer>>,
+    kinematic_targets: Option<Res<'w, KinematicTargetBuffer>>,
+    kinematic_drivers: Option<Res<'w, KinematicDriverBuffer>>,
+    angular_springs: Option<Res<'w, AngularSpringBuffer>>,
+    bond_break_log: Option<Res<'w, BondBreakLogBuffer>>,
+    stats_state: Option<Res<'w, StatsStateBuffer>>,
+}
+
+/// Runtime bond creation and bond gather buffers (split from `SolverBuffers` to stay
+/// within the `SystemParam` tuple limit)
+#[derive(SystemParam)]
+pub struct WeldBuffers<'w> {
+    allocator: Option<Res<'w, BondAllocatorBuffer>>,
+    edits: Option<Res<'w, BondEditBuffer>>,
+    free_slots: Option<Res<'w, BondFreeListBuffer>>,
+    rules: Option<Res<'w, WeldRuleBuffer>>,
+    partners: Option<Res<'w, WeldPartnerBuffer>>,
+    bond_forces: Option<Res<'w, BondForceBuffer>>,
+    bond_adjacency: Option<Res<'w, BondAdjacencyBuffer>>,
+}
+
+/// Counting sort scratch buffers and the particle reorder buffers (split from the grid
+/// buffers to stay within the `SystemParam` tuple limit)
+#[derive(SystemParam)]
+pub struct SortBuffers<'w> {
+    cell_cursor: Option<Res<'w, CellCursorBuffer>>,
+    block_sums: Option<Res<'w, ScanBlockSumsBuffer>>,
+    reorder: Option<Res<'w, ReorderBuffer>>,
+    particle_ids: Option<Res<'w, ParticleIdBuffer>>,
 }
 
 /// Prepare all bind groups
+#[allow(clippy::too_many_arguments)]
 fn prepare_bind_groups(
     mut commands: Commands,
     render_device: Res<RenderDevice>,
     pipelines: Option<Res<SphPipelines>>,
     particles: Option<Res<ParticleBuffer>>,
+    back_particles: Option<Res<ParticleBackBuffer>>,
     indices: Option<Res<IndexBuffer>>,
     cell_counts: Option<Res<CellCountsBuffer>>,
     cell_offsets: Option<Res<CellOffsetsBuffer>>,
@@ -332,15 +899,49 @@ fn prepare_bind_groups(
     bond_buffer: Option<Res<BondBuffer>>,
     force_buffer: Option<Res<ForceBuffer>>,
     interaction_table: Option<Res<InteractionTableBuffer>>,
+    solver_buffers: SolverBuffers,
+    weld_buffers: WeldBuffers,
+    sort_buffers: SortBuffers,
 ) {
     let (Some(pipelines), Some(particles), Some(indices), Some(cell_counts), 
          Some(cell_offsets), Some(grid_params), Some(sim_params), Some(bond_buffer), 
-         Some(force_buffer), Some(interaction_table)) = 
+         Some(force_buffer), Some(interaction_table), Some(solver_buffer), Some(solver_state),
+         Some(timestep_params), Some(timestep_state), Some(boundary_volumes),
+         Some(rigid_bodies), Some(rigid_members), Some(kinematic_targets),
+         Some(kinematic_drivers), Some(angular_springs), Some(bond_break_log), Some(stats_state)) = 
         (pipelines, particles, indices, cell_counts, cell_offsets, grid_params, 
-         sim_params, bond_buffer, force_buffer, interaction_table) 
+         sim_params, bond_buffer, force_buffer, interaction_table,
+         solver_buffers.pressure_solver, solver_buffers.pressure_solver_state,
+         solver_buffers.timestep_params, solver_buffers.timestep_state,
+         solver_buffers.boundary_volumes, solver_buffers.rigid_bodies,
+         solver_buffers.rigid_members, solver_buffers.kinematic_targets,
+         solver_buffers.kinematic_drivers, solver_buffers.angular_springs,
+         solver_buffers.bond_break_log, solver_buffers.stats_state) 
+    else {
+        return;
+    };
+    let (Some(bond_allocator), Some(bond_edits), Some(free_slots), Some(weld_rules), Some(weld_partners),
+         Some(bond_forces), Some(bond_adjacency)) =
+        (weld_buffers.allocator, weld_buffers.edits, weld_buffers.free_slots, weld_buffers.rules,
+         weld_buffers.partners, weld_buffers.bond_forces, weld_buffers.bond_adjacency)
+    else {
+        return;
+    };
+    let (Some(cell_cursor), Some(block_sums), Some(reorder_buffers), Some(particle_ids), Some(back_particles)) = (
+        sort_buffers.cell_cursor,
+        sort_buffers.block_sums,
+        sort_buffers.reorder,
+        sort_buffers.particle_ids,
+        back_particles,
+    )
     else {
         return;
     };
+    let Some(pcisph_dispatch_args) = solver_buffers.pcisph_dispatch else {
+        return;
+    };
+    // Particle state buffers by `ParticleSlot`
+    let particle_slots = [&particles.0, &back_particles.0];
 
     // Cell ID bind group
     let cell_id = render_device.create_bind_group(
@@ -395,6 +996,14 @@ fn prepare_bind_groups(
                 binding: 2,
                 resource: grid_params.0.as_entire_binding(),
             },
+            BindGroupEntry {
+                binding: 3,
+                resource: block_sums.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 4,
+                resource: cell_cursor.0.as_entire_binding(),
+            },
         ],
     );
 
@@ -409,43 +1018,174 @@ fn prepare_bind_groups(
             },
             BindGroupEntry {
                 binding: 1,
-                resource: cell_offsets.0.as_entire_binding(),
+                resource: cell_cursor.0.as_entire_binding(),
             },
             BindGroupEntry {
                 binding: 2,
                 resource: indices.0.as_entire_binding(),
             },
+            BindGroupEntry {
+                binding: 3,
+                resource: cell_offsets.0.as_entire_binding(),
+            },
         ],
     );
 
-    // Density bind group
-    let density = render_device.create_bind_group(
-        Some("Density BindGroup"),
-        &pipelines.density_layout,
+    // Reorder bind groups: slot remap (flag, remap, remap_bonds), rank scan, permutation
+    let reorder = render_device.create_bind_group(
+        Some("Reorder BindGroup"),
+        &pipelines.reorder_layout,
         &[
             BindGroupEntry {
                 binding: 0,
-                resource: particles.0.as_entire_binding(),
+                resource: indices.0.as_entire_binding(),
             },
             BindGroupEntry {
                 binding: 1,
-                resource: indices.0.as_entire_binding(),
+                resource: reorder_buffers.movable.as_entire_binding(),
             },
             BindGroupEntry {
                 binding: 2,
-                resource: cell_offsets.0.as_entire_binding(),
+                resource: reorder_buffers.movable_slots.as_entire_binding(),
             },
             BindGroupEntry {
                 binding: 3,
-                resource: grid_params.0.as_entire_binding(),
+                resource: reorder_buffers.flags.as_entire_binding(),
             },
             BindGroupEntry {
                 binding: 4,
-                resource: sim_params.0.as_entire_binding(),
+                resource: reorder_buffers.ranks.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 5,
+                resource: reorder_buffers.new_slot.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 6,
+                resource: bond_buffer.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 7,
+                resource: bond_allocator.0.as_entire_binding(),
+            },
+        ],
+    );
+    let reorder_prefix = render_device.create_bind_group(
+        Some("Reorder Prefix Sum BindGroup"),
+        &pipelines.prefix_layout,
+        &[
+            BindGroupEntry {
+                binding: 0,
+                resource: reorder_buffers.flags.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 1,
+                resource: reorder_buffers.ranks.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 2,
+                resource: reorder_buffers.scan_params.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 3,
+                resource: reorder_buffers.block_sums.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 4,
+                resource: reorder_buffers.cursor.as_entire_binding(),
+            },
+        ],
+    );
+    let reorder_permute = render_device.create_bind_group(
+        Some("Reorder Permute BindGroup"),
+        &pipelines.reorder_permute_layout,
+        &[
+            BindGroupEntry {
+                binding: 8,
+                resource: reorder_buffers.new_slot.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 9,
+                resource: particles.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 10,
+                resource: back_particles.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 11,
+                resource: force_buffer.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 12,
+                resource: reorder_buffers.forces_old.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 13,
+                resource: weld_partners.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 14,
+                resource: reorder_buffers.partners_old.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 15,
+                resource: particle_ids.ids.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 16,
+                resource: reorder_buffers.ids_old.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 17,
+                resource: particle_ids.slots.as_entire_binding(),
             },
         ],
     );
 
+    // Density and constraints bind groups: read the slot, write the other one
+    let ping_pong = |label: &'static str, slot: ParticleSlot| {
+        render_device.create_bind_group(
+            Some(label),
+            &pipelines.density_layout,
+            &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: particle_slots[slot.index()].as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: indices.0.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: cell_offsets.0.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: grid_params.0.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 4,
+                    resource: sim_params.0.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 5,
+                    resource: boundary_volumes.0.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 6,
+                    resource: particle_slots[slot.next().index()].as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 7,
+                    resource: interaction_table.0.as_entire_binding(),
+                },
+            ],
+        )
+    };
+    let density = [ParticleSlot::Front, ParticleSlot::Back].map(|slot| ping_pong("Density BindGroup", slot));
+
     // Forces bind group (includes InteractionTable at binding 5)
     let forces = render_device.create_bind_group(
         Some("Forces BindGroup"),
@@ -475,6 +1215,14 @@ fn prepare_bind_groups(
                 binding: 5,
                 resource: interaction_table.0.as_entire_binding(),
             },
+            BindGroupEntry {
+                binding: 6,
+                resource: force_buffer.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 7,
+                resource: boundary_volumes.0.as_entire_binding(),
+            },
         ],
     );
 
@@ -493,38 +1241,30 @@ fn prepare_bind_groups(
             },
             BindGroupEntry {
                 binding: 2,
-                resource: force_buffer.0.as_entire_binding(),
-            },
-        ],
-    );
-
-    // Constraints bind group
-    let constraints = render_device.create_bind_group(
-        Some("Constraints BindGroup"),
-        &pipelines.density_layout,
-        &[
-            BindGroupEntry {
-                binding: 0,
-                resource: particles.0.as_entire_binding(),
+                resource: bond_forces.0.as_entire_binding(),
             },
             BindGroupEntry {
-                binding: 1,
-                resource: indices.0.as_entire_binding(),
+                binding: 3,
+                resource: sim_params.0.as_entire_binding(),
             },
             BindGroupEntry {
-                binding: 2,
-                resource: cell_offsets.0.as_entire_binding(),
+                binding: 4,
+                resource: rigid_members.0.as_entire_binding(),
             },
             BindGroupEntry {
-                binding: 3,
-                resource: grid_params.0.as_entire_binding(),
+                binding: 5,
+                resource: bond_break_log.0.as_entire_binding(),
             },
             BindGroupEntry {
-                binding: 4,
-                resource: sim_params.0.as_entire_binding(),
+                binding: 6,
+                resource: particle_ids.ids.as_entire_binding(),
             },
         ],
     );
+
+    // Constraints bind groups
+    let constraints = [ParticleSlot::Front, ParticleSlot::Back].map(|slot| ping_pong("Constraints BindGroup", slot));
+
     let physics = render_device.create_bind_group(
         Some("Physics BindGroup"),
         &pipelines.physics_layout,
@@ -541,19 +1281,406 @@ fn prepare_bind_groups(
                 binding: 2,
                 resource: force_buffer.0.as_entire_binding(),
             },
-        ],
-    );
-
-    commands.insert_resource(SphBindGroups {
+            BindGroupEntry {
+                binding: 3,
+                resource: interaction_table.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 4,
+                resource: rigid_members.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 5,
+                resource: rigid_bodies.buffer.as_entire_binding(),
+            },
+        ],
+    );
+
+    // PCISPH bind group
+    let pcisph = render_device.create_bind_group(
+        Some("PCISPH BindGroup"),
+        &pipelines.pcisph_layout,
+        &[
+            BindGroupEntry {
+                binding: 0,
+                resource: particles.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 1,
+                resource: indices.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 2,
+                resource: cell_offsets.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 3,
+                resource: grid_params.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 4,
+                resource: sim_params.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 5,
+                resource: force_buffer.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 6,
+                resource: solver_buffer.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 7,
+                resource: solver_state.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 8,
+                resource: boundary_volumes.0.as_entire_binding(),
+            },
+        ],
+    );
+    let pcisph_dispatch = render_device.create_bind_group(
+        Some("PCISPH Dispatch BindGroup"),
+        &pipelines.pcisph_dispatch_layout,
+        &[BindGroupEntry {
+            binding: 0,
+            resource: pcisph_dispatch_args.0.as_entire_binding(),
+        }],
+    );
+
+    // Adaptive timestep bind group
+    let timestep = render_device.create_bind_group(
+        Some("Timestep BindGroup"),
+        &pipelines.timestep_layout,
+        &[
+            BindGroupEntry {
+                binding: 0,
+                resource: particles.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 1,
+                resource: force_buffer.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 2,
+                resource: bond_buffer.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 3,
+                resource: sim_params.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 4,
+                resource: timestep_params.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 5,
+                resource: timestep_state.0.as_entire_binding(),
+            },
+        ],
+    );
+
+    // Kinematics bind group
+    let kinematics = render_device.create_bind_group(
+        Some("Kinematics BindGroup"),
+        &pipelines.kinematics_layout,
+        &[
+            BindGroupEntry {
+                binding: 0,
+                resource: particles.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 1,
+                resource: sim_params.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 2,
+                resource: kinematic_targets.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 3,
+                resource: rigid_bodies.buffer.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 4,
+                resource: kinematic_drivers.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 5,
+                resource: rigid_members.0.as_entire_binding(),
+            },
+        ],
+    );
+
+    // Rigid body bind group
+    let rigid_body = render_device.create_bind_group(
+        Some("Rigid Body BindGroup"),
+        &pipelines.rigid_body_layout,
+        &[
+            BindGroupEntry {
+                binding: 0,
+                resource: particles.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 1,
+                resource: sim_params.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 2,
+                resource: rigid_members.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 3,
+                resource: rigid_bodies.buffer.as_entire_binding(),
+            },
+        ],
+    );
+
+    // Stats bind group
+    let stats = render_device.create_bind_group(
+        Some("Stats BindGroup"),
+        &pipelines.stats_layout,
+        &[
+            BindGroupEntry {
+                binding: 0,
+                resource: particles.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 1,
+                resource: bond_buffer.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 2,
+                resource: bond_break_log.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 3,
+                resource: stats_state.0.as_entire_binding(),
+            },
+        ],
+    );
+
+    // Bending bind group
+    let bending = render_device.create_bind_group(
+        Some("Bending BindGroup"),
+        &pipelines.bending_layout,
+        &[
+            BindGroupEntry {
+                binding: 0,
+                resource: particles.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 1,
+                resource: angular_springs.buffer.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 2,
+                resource: angular_springs.forces.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 3,
+                resource: sim_params.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 4,
+                resource: rigid_members.0.as_entire_binding(),
+            },
+        ],
+    );
+
+    // Weld bind group (bond edits and welds)
+    let weld = render_device.create_bind_group(
+        Some("Weld BindGroup"),
+        &pipelines.weld_layout,
+        &[
+            BindGroupEntry {
+                binding: 0,
+                resource: particles.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 1,
+                resource: bond_buffer.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 2,
+                resource: bond_allocator.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 3,
+                resource: bond_edits.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 4,
+                resource: weld_rules.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 5,
+                resource: weld_partners.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 6,
+                resource: indices.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 7,
+                resource: cell_offsets.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 8,
+                resource: grid_params.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 9,
+                resource: rigid_members.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 10,
+                resource: free_slots.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 11,
+                resource: particle_ids.slots.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 12,
+                resource: particle_ids.ids.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 13,
+                resource: bond_adjacency.buffer.as_entire_binding(),
+            },
+        ],
+    );
+
+    // Bond gather bind group
+    let bond_gather = render_device.create_bind_group(
+        Some("Bond Gather BindGroup"),
+        &pipelines.bond_gather_layout,
+        &[
+            BindGroupEntry {
+                binding: 0,
+                resource: bond_buffer.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 1,
+                resource: bond_forces.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 2,
+                resource: bond_adjacency.buffer.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 3,
+                resource: angular_springs.buffer.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 4,
+                resource: angular_springs.forces.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 5,
+                resource: angular_springs.adjacency.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 6,
+                resource: force_buffer.0.as_entire_binding(),
+            },
+        ],
+    );
+
+    // Bond adjacency rebuild plan bind group (group 1 of plan)
+    let bond_adjacency_plan = render_device.create_bind_group(
+        Some("Bond Adjacency Plan BindGroup"),
+        &pipelines.bond_adjacency_plan_layout,
+        &[
+            BindGroupEntry {
+                binding: 0,
+                resource: bond_allocator.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 1,
+                resource: bond_adjacency.dispatch.as_entire_binding(),
+            },
+        ],
+    );
+
+    // Bond counts -> list offsets (the packed buffer starts with them) and write cursors
+    let bond_adjacency_prefix = render_device.create_bind_group(
+        Some("Bond Adjacency Prefix Sum BindGroup"),
+        &pipelines.prefix_layout,
+        &[
+            BindGroupEntry {
+                binding: 0,
+                resource: bond_adjacency.counts.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 1,
+                resource: bond_adjacency.buffer.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 2,
+                resource: bond_adjacency.scan_params.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 3,
+                resource: bond_adjacency.block_sums.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 4,
+                resource: bond_adjacency.cursor.as_entire_binding(),
+            },
+        ],
+    );
+
+    // Bond adjacency rebuild bind group
+    let bond_adjacency = render_device.create_bind_group(
+        Some("Bond Adjacency BindGroup"),
+        &pipelines.bond_adjacency_layout,
+        &[
+            BindGroupEntry {
+                binding: 0,
+                resource: bond_buffer.0.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 1,
+                resource: bond_adjacency.buffer.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 2,
+                resource: bond_adjacency.cursor.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 3,
+                resource: bond_adjacency.counts.as_entire_binding(),
+            },
+        ],
+    );
+
+    commands.insert_resource(SphBindGroups {
         cell_id,
         count,
         prefix,
         scatter,
+        reorder,
+        reorder_prefix,
+        reorder_permute,
         density,
         forces,
         bonds,
         constraints,
         physics,
+        pcisph,
+        pcisph_dispatch,
+        timestep,
+        kinematics,
+        rigid_body,
+        bending,
+        weld,
+        bond_gather,
+        bond_adjacency,
+        bond_adjacency_plan,
+        bond_adjacency_prefix,
+        stats,
     });
 }
 
@@ -582,8 +1709,28 @@ impl render_graph::Node for SphPhysicsNode {
         let Some(bind_groups) = world.get_resource::<SphBindGroups>() else {
             return Ok(());
         };
-        // Also need Force Buffer to clear it
-        let Some(force_buffer) = world.get_resource::<ForceBuffer>() else {
+        let Some(pcisph_dispatch_args) = world.get_resource::<PcisphDispatchBuffer>() else {
+            return Ok(());
+        };
+        let Some(bond_adjacency) = world.get_resource::<BondAdjacencyBuffer>() else {
+            return Ok(());
+        };
+        // Buffers the reorder copies its previous state from
+        let (
+            Some(reorder_buffers),
+            Some(particle_ids),
+            Some(particles),
+            Some(back_particles),
+            Some(forces),
+            Some(weld_partners),
+        ) = (
+            world.get_resource::<ReorderBuffer>(),
+            world.get_resource::<ParticleIdBuffer>(),
+            world.get_resource::<ParticleBuffer>(),
+            world.get_resource::<ParticleBackBuffer>(),
+            world.get_resource::<ForceBuffer>(),
+            world.get_resource::<WeldPartnerBuffer>(),
+        ) else {
             return Ok(());
         };
 
@@ -597,15 +1744,33 @@ impl render_graph::Node for SphPhysicsNode {
         let Some(count_cells_pipeline) = pipeline_cache.get_compute_pipeline(pipelines.count_cells) else {
             return Ok(());
         };
-        let Some(prefix_sum_pipeline) = pipeline_cache.get_compute_pipeline(pipelines.prefix_sum) else {
+        let (Some(scan_blocks_pipeline), Some(scan_block_sums_pipeline), Some(add_block_offsets_pipeline)) = (
+            pipeline_cache.get_compute_pipeline(pipelines.prefix_scan_blocks),
+            pipeline_cache.get_compute_pipeline(pipelines.prefix_scan_block_sums),
+            pipeline_cache.get_compute_pipeline(pipelines.prefix_add_block_offsets),
+        ) else {
+            return Ok(());
+        };
+        let (Some(scatter_pipeline), Some(cell_sort_pipeline)) = (
+            pipeline_cache.get_compute_pipeline(pipelines.scatter),
+            pipeline_cache.get_compute_pipeline(pipelines.cell_sort),
+        ) else {
             return Ok(());
         };
-        let Some(scatter_pipeline) = pipeline_cache.get_compute_pipeline(pipelines.scatter) else {
+        let (Some(reorder_flag), Some(reorder_remap), Some(reorder_remap_bonds), Some(reorder_permute)) = (
+            pipeline_cache.get_compute_pipeline(pipelines.reorder_flag),
+            pipeline_cache.get_compute_pipeline(pipelines.reorder_remap),
+            pipeline_cache.get_compute_pipeline(pipelines.reorder_remap_bonds),
+            pipeline_cache.get_compute_pipeline(pipelines.reorder_permute),
+        ) else {
             return Ok(());
         };
         let Some(density_pipeline) = pipeline_cache.get_compute_pipeline(pipelines.density) else {
             return Ok(());
         };
+        let Some(boundary_volume_pipeline) = pipeline_cache.get_compute_pipeline(pipelines.boundary_volume) else {
+            return Ok(());
+        };
         let Some(forces_pipeline) = pipeline_cache.get_compute_pipeline(pipelines.forces) else {
             return Ok(());
         };
@@ -618,19 +1783,103 @@ impl render_graph::Node for SphPhysicsNode {
         let Some(physics_pipeline) = pipeline_cache.get_compute_pipeline(pipelines.physics) else {
             return Ok(());
         };
+        let (Some(pcisph_begin), Some(pcisph_predict), Some(pcisph_correct_density),
+             Some(pcisph_pressure_force), Some(pcisph_check), Some(pcisph_apply)) = (
+            pipeline_cache.get_compute_pipeline(pipelines.pcisph_begin),
+            pipeline_cache.get_compute_pipeline(pipelines.pcisph_predict),
+            pipeline_cache.get_compute_pipeline(pipelines.pcisph_correct_density),
+            pipeline_cache.get_compute_pipeline(pipelines.pcisph_pressure_force),
+            pipeline_cache.get_compute_pipeline(pipelines.pcisph_check),
+            pipeline_cache.get_compute_pipeline(pipelines.pcisph_apply),
+        ) else {
+            return Ok(());
+        };
+        let (Some(timestep_reduce_particles), Some(timestep_reduce_bonds), Some(timestep_control)) = (
+            pipeline_cache.get_compute_pipeline(pipelines.timestep_reduce_particles),
+            pipeline_cache.get_compute_pipeline(pipelines.timestep_reduce_bonds),
+            pipeline_cache.get_compute_pipeline(pipelines.timestep_control),
+        ) else {
+            return Ok(());
+        };
+        let (Some(kinematics_pipeline), Some(rigid_body_pipeline), Some(bending_pipeline)) = (
+            pipeline_cache.get_compute_pipeline(pipelines.kinematics),
+            pipeline_cache.get_compute_pipeline(pipelines.rigid_body),
+            pipeline_cache.get_compute_pipeline(pipelines.bending),
+        ) else {
+            return Ok(());
+        };
+        let Some(stats_pipeline) = pipeline_cache.get_compute_pipeline(pipelines.stats) else {
+            return Ok(());
+        };
+        let (Some(bond_edits_pipeline), Some(weld_pipeline), Some(bond_gather_pipeline)) = (
+            pipeline_cache.get_compute_pipeline(pipelines.bond_edits),
+            pipeline_cache.get_compute_pipeline(pipelines.weld),
+            pipeline_cache.get_compute_pipeline(pipelines.bond_gather),
+        ) else {
+            return Ok(());
+        };
+        let (Some(bond_repairs_pipeline), Some(reset_free_list_pipeline), Some(collect_free_pipeline)) = (
+            pipeline_cache.get_compute_pipeline(pipelines.bond_repairs),
+            pipeline_cache.get_compute_pipeline(pipelines.bond_reset_free_list),
+            pipeline_cache.get_compute_pipeline(pipelines.bond_collect_free),
+        ) else {
+            return Ok(());
+        };
+        let (Some(adjacency_plan), Some(adjacency_clear), Some(adjacency_count),
+             Some(adjacency_scatter), Some(adjacency_sort)) = (
+            pipeline_cache.get_compute_pipeline(pipelines.adjacency_plan),
+            pipeline_cache.get_compute_pipeline(pipelines.adjacency_clear),
+            pipeline_cache.get_compute_pipeline(pipelines.adjacency_count),
+            pipeline_cache.get_compute_pipeline(pipelines.adjacency_scatter),
+            pipeline_cache.get_compute_pipeline(pipelines.adjacency_sort),
+        ) else {
+            return Ok(());
+        };
+
+        let settings = world.get_resource::<SimulationSettings>().copied().unwrap_or_default();
+        let adaptive_timestep = settings.timestep.adaptive;
+
+        // Step budget spent (headless runs): no step, but keep serving the readbacks
+        let step_counter = world.get_resource::<StepCounter>();
+        if settings.max_steps.is_some_and(|max_steps| step_counter.map_or(0, StepCounter::get) >= max_steps) {
+            copy_end_of_step_readbacks(world, render_context);
+            return Ok(());
+        }
+
+        // Per-pass GPU timing (see profiling.rs); None leaves the passes untimed
+        let profiler = world.get_resource::<GpuProfiler>().filter(|_| settings.gpu_timing);
+        let timestamps = |stage: GpuStage, pass: usize| profiler.map(|profiler| profiler.timestamp_writes(stage, pass));
 
-        let particle_workgroup_count = (PARTICLE_COUNT as u32 + 63) / 64;
-        let bond_workgroup_count = (BOND_COUNT as u32 + 255) / 256;
+        let particle_workgroup_count = (PARTICLE_COUNT as u32).div_ceil(64);
+        let bond_workgroup_count = (BOND_COUNT as u32).div_ceil(256);
         let grid_params = GridParams::default();
         let total_cells = grid_params.grid_width * grid_params.grid_height;
-        let cell_workgroup_count = (total_cells + 255) / 256;
+        let cell_workgroup_count = total_cells.div_ceil(256);
+        let scan_workgroup_count = scan_block_count(total_cells as usize) as u32;
+
+        // Stage 0: Adaptive timestep - this step uses the dt the controller picked last step
+        // (overrides the CPU-written SimParams.delta_time; queue writes land before this copy)
+        if adaptive_timestep {
+            if let (Some(timestep_state), Some(sim_params)) = (
+                world.get_resource::<TimestepStateBuffer>(),
+                world.get_resource::<SimParamsBuffer>(),
+            ) {
+                render_context.command_encoder().copy_buffer_to_buffer(
+                    &timestep_state.0,
+                    TIMESTEP_STATE_DT_OFFSET,
+                    &sim_params.0,
+                    0,
+                    4,
+                );
+            }
+        }
 
         // Stage 1: Calculate cell IDs
         {
             let mut pass = render_context.command_encoder().begin_compute_pass(
                 &ComputePassDescriptor {
                     label: Some("CellID Pass"),
-                    timestamp_writes: None,
+                    timestamp_writes: timestamps(GpuStage::CellId, 0),
                 },
             );
             pass.set_pipeline(cell_id_pipeline);
@@ -643,7 +1892,7 @@ impl render_graph::Node for SphPhysicsNode {
             let mut pass = render_context.command_encoder().begin_compute_pass(
                 &ComputePassDescriptor {
                     label: Some("Clear Counts Pass"),
-                    timestamp_writes: None,
+                    timestamp_writes: timestamps(GpuStage::ClearCounts, 0),
                 },
             );
             pass.set_pipeline(clear_counts_pipeline);
@@ -656,7 +1905,7 @@ impl render_graph::Node for SphPhysicsNode {
             let mut pass = render_context.command_encoder().begin_compute_pass(
                 &ComputePassDescriptor {
                     label: Some("Count Cells Pass"),
-                    timestamp_writes: None,
+                    timestamp_writes: timestamps(GpuStage::CountCells, 0),
                 },
             );
             pass.set_pipeline(count_cells_pipeline);
@@ -664,59 +1913,96 @@ impl render_graph::Node for SphPhysicsNode {
             pass.dispatch_workgroups(particle_workgroup_count, 1, 1);
         }
 
-        // Stage 4: Prefix sum (single thread for simplicity)
+        // Stage 4: Prefix sum (scan blocks, scan block totals, add block offsets)
         {
             let mut pass = render_context.command_encoder().begin_compute_pass(
                 &ComputePassDescriptor {
                     label: Some("Prefix Sum Pass"),
-                    timestamp_writes: None,
+                    timestamp_writes: timestamps(GpuStage::PrefixSum, 0),
                 },
             );
-            pass.set_pipeline(prefix_sum_pipeline);
             pass.set_bind_group(0, &bind_groups.prefix, &[]);
+            pass.set_pipeline(scan_blocks_pipeline);
+            pass.dispatch_workgroups(scan_workgroup_count, 1, 1);
+            pass.set_pipeline(scan_block_sums_pipeline);
             pass.dispatch_workgroups(1, 1, 1);
+            pass.set_pipeline(add_block_offsets_pipeline);
+            pass.dispatch_workgroups(scan_workgroup_count, 1, 1);
         }
 
-        // Stage 5: Scatter particles to sorted positions
-        // NOTE: This modifies cell_offsets via atomicAdd, so we need to restore them after
+        // Stage 5: Scatter particles to sorted positions, then sort each cell by index
+        // Consumes the cursor copy, so cell_offsets is ready for the neighbour search
         {
             let mut pass = render_context.command_encoder().begin_compute_pass(
                 &ComputePassDescriptor {
                     label: Some("Scatter Pass"),
-                    timestamp_writes: None,
+                    timestamp_writes: timestamps(GpuStage::Scatter, 0),
                 },
             );
             pass.set_pipeline(scatter_pipeline);
             pass.set_bind_group(0, &bind_groups.scatter, &[]);
             pass.dispatch_workgroups(particle_workgroup_count, 1, 1);
+            pass.set_pipeline(cell_sort_pipeline);
+            pass.dispatch_workgroups(cell_workgroup_count, 1, 1);
         }
 
-        // Stage 5b: Re-run prefix sum to restore cell_offsets for neighbor lookup
-        {
-            let mut pass = render_context.command_encoder().begin_compute_pass(
-                &ComputePassDescriptor {
-                    label: Some("Prefix Sum Restore Pass"),
-                    timestamp_writes: None,
-                },
-            );
-            pass.set_pipeline(prefix_sum_pipeline);
-            pass.set_bind_group(0, &bind_groups.prefix, &[]);
+        // Stage 5.2: Every `reorder_interval` steps, permute the particles into the order
+        // the scatter produced and remap the index buffer, bonds, weld partners and id
+        // tables (see reorder.rs). The permutation reads copies of the previous state; the
+        // particles go to the back buffer, which the first ping-pong pass overwrites.
+        let step = step_counter.map_or(0, StepCounter::get);
+        let reordered = settings.reorder_interval > 0 && step.is_multiple_of(settings.reorder_interval);
+        if reordered {
+            let encoder = render_context.command_encoder();
+            encoder.copy_buffer_to_buffer(&particles.0, 0, &back_particles.0, 0, particles.0.size());
+            encoder.copy_buffer_to_buffer(&forces.0, 0, &reorder_buffers.forces_old, 0, forces.0.size());
+            encoder.copy_buffer_to_buffer(&weld_partners.0, 0, &reorder_buffers.partners_old, 0, weld_partners.0.size());
+            encoder.copy_buffer_to_buffer(&particle_ids.ids, 0, &reorder_buffers.ids_old, 0, particle_ids.ids.size());
+
+            let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor {
+                label: Some("Reorder Pass"),
+                timestamp_writes: timestamps(GpuStage::Reorder, 0),
+            });
+            pass.set_bind_group(0, &bind_groups.reorder, &[]);
+            pass.set_pipeline(reorder_flag);
+            pass.dispatch_workgroups(particle_workgroup_count, 1, 1);
+            // Rank of every movable particle in cell order (prefix_sum.wgsl over the flags)
+            let flag_block_count = scan_block_count(PARTICLE_COUNT) as u32;
+            pass.set_bind_group(0, &bind_groups.reorder_prefix, &[]);
+            pass.set_pipeline(scan_blocks_pipeline);
+            pass.dispatch_workgroups(flag_block_count, 1, 1);
+            pass.set_pipeline(scan_block_sums_pipeline);
             pass.dispatch_workgroups(1, 1, 1);
+            pass.set_pipeline(add_block_offsets_pipeline);
+            pass.dispatch_workgroups(flag_block_count, 1, 1);
+            pass.set_bind_group(0, &bind_groups.reorder, &[]);
+            pass.set_pipeline(reorder_remap);
+            pass.dispatch_workgroups(particle_workgroup_count, 1, 1);
+            pass.set_pipeline(reorder_remap_bonds);
+            pass.dispatch_workgroups(bond_workgroup_count, 1, 1);
+            pass.set_bind_group(0, &bind_groups.reorder_permute, &[]);
+            pass.set_pipeline(reorder_permute);
+            pass.dispatch_workgroups(particle_workgroup_count, 1, 1);
         }
 
+        // Ping-pong: constraints and density read the particle state from `slot` and write
+        // it to the other buffer (see ping_pong.rs); an even number of swaps ends in front
+        let mut slot = ParticleSlot::Front;
+
         // Stage 5.5: Position Constraints (PBD)
         // Corrects particle positions to prevent overlaps BEFORE density/force calc
         // Run multiple iterations for stability
-        for _ in 0..3 {
+        for iteration in 0..CONSTRAINT_ITERATIONS {
             let mut pass = render_context.command_encoder().begin_compute_pass(
                 &ComputePassDescriptor {
                     label: Some("Constraints Pass"),
-                    timestamp_writes: None,
+                    timestamp_writes: timestamps(GpuStage::Constraints, iteration),
                 },
             );
             pass.set_pipeline(constraints_pipeline);
-            pass.set_bind_group(0, &bind_groups.constraints, &[]);
+            pass.set_bind_group(0, &bind_groups.constraints[slot.index()], &[]);
             pass.dispatch_workgroups(particle_workgroup_count, 1, 1);
+            slot = slot.next();
         }
 
         // Stage 6: Density calculation
@@ -724,20 +2010,27 @@ impl render_graph::Node for SphPhysicsNode {
             let mut pass = render_context.command_encoder().begin_compute_pass(
                 &ComputePassDescriptor {
                     label: Some("Density Pass"),
-                    timestamp_writes: None,
+                    timestamp_writes: timestamps(GpuStage::Density, 0),
                 },
             );
+            pass.set_bind_group(0, &bind_groups.density[slot.index()], &[]);
+            // Akinci boundary volumes first: fluid density reads them for solid neighbours
+            if settings.boundary_handling == BoundaryHandling::Akinci {
+                pass.set_pipeline(boundary_volume_pipeline);
+                pass.dispatch_workgroups(particle_workgroup_count, 1, 1);
+            }
             pass.set_pipeline(density_pipeline);
-            pass.set_bind_group(0, &bind_groups.density, &[]);
             pass.dispatch_workgroups(particle_workgroup_count, 1, 1);
+            slot = slot.next();
         }
+        debug_assert_eq!(slot, ParticleSlot::Front, "ping-pong passes must leave the state in ParticleBuffer");
 
-        // Stage 7: Force calculation
+        // Stage 7: Force calculation (writes ForceBuffer.fluid, reads particles only)
         {
             let mut pass = render_context.command_encoder().begin_compute_pass(
                 &ComputePassDescriptor {
                     label: Some("Forces Pass"),
-                    timestamp_writes: None,
+                    timestamp_writes: timestamps(GpuStage::Forces, 0),
                 },
             );
             pass.set_pipeline(forces_pipeline);
@@ -745,15 +2038,83 @@ impl render_graph::Node for SphPhysicsNode {
             pass.dispatch_workgroups(particle_workgroup_count, 1, 1);
         }
 
-        // Stage 7.5: Bond Force calculation
-        // IMPORTANT: Clear Force Buffer first!
-        render_context.command_encoder().clear_buffer(&force_buffer.0, 0, None);
-        
+        // Stage 7.4: Runtime bonds (queued repairs, free slot list, queued adds, then welds
+        // of touching particles). A batch already applied in an earlier step is skipped.
+        // Also runs after a reorder, which moved bond ends, for the bond list rebuild.
+        let edits_applied = world.get_resource::<BondEditsApplied>();
+        let pending_edits = world.get_resource::<PendingBondEdits>();
+        let edit_count = match (pending_edits, edits_applied) {
+            (Some(pending), Some(applied)) => pending.unapplied(applied).len() as u32,
+            _ => 0,
+        };
+        let welding = world.get_resource::<WeldRules>().is_some_and(|rules| !rules.0.is_empty());
+        if edit_count > 0 || welding || reordered {
+            let mut pass = render_context.command_encoder().begin_compute_pass(
+                &ComputePassDescriptor {
+                    label: Some("Bond Edits + Weld Pass"),
+                    timestamp_writes: timestamps(GpuStage::Welding, 0),
+                },
+            );
+            pass.set_bind_group(0, &bind_groups.weld, &[]);
+            if edit_count > 0 {
+                pass.set_pipeline(bond_repairs_pipeline);
+                pass.dispatch_workgroups(edit_count.div_ceil(64), 1, 1);
+            }
+            if edit_count > 0 || welding {
+                pass.set_pipeline(reset_free_list_pipeline);
+                pass.dispatch_workgroups(1, 1, 1);
+                pass.set_pipeline(collect_free_pipeline);
+                pass.dispatch_workgroups(bond_workgroup_count, 1, 1);
+            }
+            if edit_count > 0 {
+                pass.set_pipeline(bond_edits_pipeline);
+                pass.dispatch_workgroups(edit_count.div_ceil(64), 1, 1);
+                if let (Some(pending), Some(applied)) = (pending_edits, edits_applied) {
+                    applied.set(pending.batch);
+                }
+            }
+            // Slots written (edits, welds) or particles reordered: rebuild the per-particle
+            // bond lists (counting sort + per-list sort). The passes are indirect; plan
+            // zeroes them when nothing changed.
+            let rebuild_adjacency = |pass: &mut ComputePass| {
+                let args = &bond_adjacency.dispatch;
+                pass.set_bind_group(0, &bind_groups.bond_adjacency, &[]);
+                pass.set_bind_group(1, &bind_groups.bond_adjacency_plan, &[]);
+                pass.set_pipeline(adjacency_plan);
+                pass.dispatch_workgroups(1, 1, 1);
+                pass.set_pipeline(adjacency_clear);
+                pass.dispatch_workgroups_indirect(args, adjacency_dispatch::PARTICLES);
+                pass.set_pipeline(adjacency_count);
+                pass.dispatch_workgroups_indirect(args, adjacency_dispatch::BONDS);
+                pass.set_bind_group(0, &bind_groups.bond_adjacency_prefix, &[]);
+                pass.set_pipeline(scan_blocks_pipeline);
+                pass.dispatch_workgroups_indirect(args, adjacency_dispatch::SCAN_BLOCKS);
+                pass.set_pipeline(scan_block_sums_pipeline);
+                pass.dispatch_workgroups_indirect(args, adjacency_dispatch::SCAN_BLOCK_SUMS);
+                pass.set_pipeline(add_block_offsets_pipeline);
+                pass.dispatch_workgroups_indirect(args, adjacency_dispatch::SCAN_BLOCKS);
+                pass.set_bind_group(0, &bind_groups.bond_adjacency, &[]);
+                pass.set_pipeline(adjacency_scatter);
+                pass.dispatch_workgroups_indirect(args, adjacency_dispatch::BONDS);
+                pass.set_pipeline(adjacency_sort);
+                pass.dispatch_workgroups_indirect(args, adjacency_dispatch::PARTICLES);
+            };
+            if welding {
+                // Current lists first, so weld can see the bonds already joining a pair
+                rebuild_adjacency(&mut pass);
+                pass.set_bind_group(0, &bind_groups.weld, &[]);
+                pass.set_pipeline(weld_pipeline);
+                pass.dispatch_workgroups(particle_workgroup_count, 1, 1);
+            }
+            rebuild_adjacency(&mut pass);
+        }
+
+        // Stage 7.5: Bond Force calculation (one force per bond, gathered in Stage 7.58)
         {
             let mut pass = render_context.command_encoder().begin_compute_pass(
                 &ComputePassDescriptor {
                     label: Some("Bonds Pass"),
-                    timestamp_writes: None,
+                    timestamp_writes: timestamps(GpuStage::Bonds, 0),
                 },
             );
             pass.set_pipeline(bonds_pipeline);
@@ -761,12 +2122,111 @@ impl render_graph::Node for SphPhysicsNode {
             pass.dispatch_workgroups(bond_workgroup_count, 1, 1);
         }
 
-        // Stage 8: Position integration
+        // Read back the break log (skipped while every staging slot is busy)
+        if let (Some(readback), Some(break_log)) = (
+            world.get_resource::<BondBreakReadback>(),
+            world.get_resource::<BondBreakLogBuffer>(),
+        ) {
+            readback.ring().copy(render_context.command_encoder(), &break_log.0, 0);
+        }
+
+        // Stage 7.55: Bending triplets (one force triple per triplet, gathered with the bonds)
+        if let Some(angular_springs) = world.get_resource::<AngularSpringBuffer>() {
+            if angular_springs.count > 0 {
+                let mut pass = render_context.command_encoder().begin_compute_pass(
+                    &ComputePassDescriptor {
+                        label: Some("Bending Pass"),
+                        timestamp_writes: timestamps(GpuStage::Bending, 0),
+                    },
+                );
+                pass.set_pipeline(bending_pipeline);
+                pass.set_bind_group(0, &bind_groups.bending, &[]);
+                pass.dispatch_workgroups(angular_springs.count.div_ceil(64), 1, 1);
+            }
+        }
+
+        // Stage 7.58: Gather bond and bending forces per particle (fixed order, no atomics)
+        {
+            let mut pass = render_context.command_encoder().begin_compute_pass(
+                &ComputePassDescriptor {
+                    label: Some("Bond Gather Pass"),
+                    timestamp_writes: timestamps(GpuStage::BondGather, 0),
+                },
+            );
+            pass.set_pipeline(bond_gather_pipeline);
+            pass.set_bind_group(0, &bind_groups.bond_gather, &[]);
+            pass.dispatch_workgroups(particle_workgroup_count, 1, 1);
+        }
+
+        // Stage 7.6: PCISPH pressure iterations (water only)
+        // The full budget is recorded, but the iteration passes are indirect: once check
+        // reports convergence their workgroup count is zero and only check itself runs.
+        if settings.pressure_solver == PressureSolver::Pcisph {
+            let mut pass = render_context.command_encoder().begin_compute_pass(
+                &ComputePassDescriptor {
+                    label: Some("PCISPH Pass"),
+                    timestamp_writes: timestamps(GpuStage::Pcisph, 0),
+                },
+            );
+            pass.set_bind_group(0, &bind_groups.pcisph, &[]);
+            pass.set_bind_group(1, &bind_groups.pcisph_dispatch, &[]);
+            pass.set_pipeline(pcisph_begin);
+            pass.dispatch_workgroups(particle_workgroup_count, 1, 1);
+            for _ in 0..settings.pressure_max_iterations {
+                pass.set_pipeline(pcisph_predict);
+                pass.dispatch_workgroups_indirect(&pcisph_dispatch_args.0, 0);
+                pass.set_pipeline(pcisph_correct_density);
+                pass.dispatch_workgroups_indirect(&pcisph_dispatch_args.0, 0);
+                pass.set_pipeline(pcisph_pressure_force);
+                pass.dispatch_workgroups_indirect(&pcisph_dispatch_args.0, 0);
+                pass.set_pipeline(pcisph_check);
+                pass.dispatch_workgroups(1, 1, 1);
+            }
+            pass.set_pipeline(pcisph_apply);
+            pass.dispatch_workgroups(particle_workgroup_count, 1, 1);
+            drop(pass);
+
+            // Report iterations used (skipped if every staging slot is still in flight)
+            if let (Some(readback), Some(state_buffer)) = (
+                world.get_resource::<PressureSolverReadback>(),
+                world.get_resource::<PressureSolverStateBuffer>(),
+            ) {
+                readback.ring().copy(render_context.command_encoder(), &state_buffer.0, 0);
+            }
+        }
+
+        // Stage 7.7: Adaptive timestep - max speed/acceleration/strain -> next dt
+        if adaptive_timestep {
+            {
+                let mut pass = render_context.command_encoder().begin_compute_pass(
+                    &ComputePassDescriptor {
+                        label: Some("Timestep Pass"),
+                        timestamp_writes: timestamps(GpuStage::Timestep, 0),
+                    },
+                );
+                pass.set_bind_group(0, &bind_groups.timestep, &[]);
+                pass.set_pipeline(timestep_reduce_particles);
+                pass.dispatch_workgroups(particle_workgroup_count, 1, 1);
+                pass.set_pipeline(timestep_reduce_bonds);
+                pass.dispatch_workgroups((BOND_COUNT as u32).div_ceil(64), 1, 1);
+                pass.set_pipeline(timestep_control);
+                pass.dispatch_workgroups(1, 1, 1);
+            }
+
+            if let (Some(readback), Some(state_buffer)) = (
+                world.get_resource::<TimestepReadback>(),
+                world.get_resource::<TimestepStateBuffer>(),
+            ) {
+                readback.ring().copy(render_context.command_encoder(), &state_buffer.0, 0);
+            }
+        }
+
+        // Stage 8: Integration (all forces are in the ForceBuffer by now)
         {
             let mut pass = render_context.command_encoder().begin_compute_pass(
                 &ComputePassDescriptor {
                     label: Some("Physics Pass"),
-                    timestamp_writes: None,
+                    timestamp_writes: timestamps(GpuStage::Integration, 0),
                 },
             );
             pass.set_pipeline(physics_pipeline);
@@ -774,16 +2234,121 @@ impl render_graph::Node for SphPhysicsNode {
             pass.dispatch_workgroups(particle_workgroup_count, 1, 1);
         }
 
+        // Stage 9: Kinematic joints - driven particles follow their pivot body's last fitted pose
+        {
+            let mut pass = render_context.command_encoder().begin_compute_pass(
+                &ComputePassDescriptor {
+                    label: Some("Kinematics Pass"),
+                    timestamp_writes: timestamps(GpuStage::Kinematics, 0),
+                },
+            );
+            pass.set_pipeline(kinematics_pipeline);
+            pass.set_bind_group(0, &bind_groups.kinematics, &[]);
+            pass.dispatch_workgroups(particle_workgroup_count, 1, 1);
+        }
+
+        // Stage 10: Rigid bodies - fit every body, project dynamic ones (shape matching mode)
+        if let Some(rigid_bodies) = world.get_resource::<RigidBodyBuffer>() {
+            if rigid_bodies.count > 0 {
+                {
+                    let mut pass = render_context.command_encoder().begin_compute_pass(
+                        &ComputePassDescriptor {
+                            label: Some("Rigid Body Pass"),
+                            timestamp_writes: timestamps(GpuStage::RigidBody, 0),
+                        },
+                    );
+                    pass.set_pipeline(rigid_body_pipeline);
+                    pass.set_bind_group(0, &bind_groups.rigid_body, &[]);
+                    pass.dispatch_workgroups(rigid_bodies.count, 1, 1);
+                }
+
+                if let Some(readback) = world.get_resource::<RigidBodyReadback>() {
+                    readback.ring().copy(render_context.command_encoder(), &rigid_bodies.buffer, 0);
+                }
+            }
+        }
+
+        // Stage 11: Statistics - one workgroup reduces the end-of-step state (see stats.rs)
+        {
+            let mut pass = render_context.command_encoder().begin_compute_pass(
+                &ComputePassDescriptor {
+                    label: Some("Stats Pass"),
+                    timestamp_writes: timestamps(GpuStage::Stats, 0),
+                },
+            );
+            pass.set_pipeline(stats_pipeline);
+            pass.set_bind_group(0, &bind_groups.stats, &[]);
+            pass.dispatch_workgroups(1, 1, 1);
+        }
+
+        copy_end_of_step_readbacks(world, render_context);
+
+        // Resolve this step's timestamps and queue their readback
+        if let Some(profiler) = profiler {
+            profiler.finish(render_context.command_encoder(), world.resource::<RenderQueue>());
+        }
+
+        if let Some(step_counter) = step_counter {
+            step_counter.increment();
+        }
+
         Ok(())
     }
 }
 
+/// Steps the node has run (render world). With `SimulationSettings::max_steps` the node
+/// stops stepping once the budget is spent.
+#[derive(Resource, Default)]
+pub struct StepCounter(AtomicU32);
+
+impl StepCounter {
+    pub fn get(&self) -> u32 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn increment(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Queue the readbacks of the end-of-step state: the statistics, and a snapshot for a
+/// pending request (see stats.rs and snapshot.rs)
+fn copy_end_of_step_readbacks(world: &World, render_context: &mut RenderContext) {
+    if let (Some(readback), Some(state_buffer)) = (
+        world.get_resource::<StatsReadback>(),
+        world.get_resource::<StatsStateBuffer>(),
+    ) {
+        readback.ring().copy(render_context.command_encoder(), &state_buffer.0, 0);
+    }
+
+    if let (Some(request), Some(readback), Some(particles), Some(bonds), Some(particle_ids), Some(forces)) = (
+        world.get_resource::<SnapshotRequests>().and_then(|requests| requests.pending()),
+        world.get_resource::<SnapshotReadback>(),
+        world.get_resource::<ParticleBuffer>(),
+        world.get_resource::<BondBuffer>(),
+        world.get_resource::<ParticleIdBuffer>(),
+        world.get_resource::<ForceBuffer>(),
+    ) {
+        readback.record(
+            render_context.command_encoder(),
+            world.resource::<RenderQueue>(),
+            request,
+            &particles.0,
+            &bonds.0,
+            &particle_ids.ids,
+            &forces.0,
+        );
+    }
+}
+
 // Public system wrapper
+#[allow(clippy::too_many_arguments)]
 pub fn prepare_bind_group(
     commands: Commands,
     render_device: Res<RenderDevice>,
     pipelines: Option<Res<SphPipelines>>,
     particles: Option<Res<ParticleBuffer>>,
+    back_particles: Option<Res<ParticleBackBuffer>>,
     indices: Option<Res<IndexBuffer>>,
     cell_counts: Option<Res<CellCountsBuffer>>,
     cell_offsets: Option<Res<CellOffsetsBuffer>>,
@@ -792,12 +2357,16 @@ pub fn prepare_bind_group(
     bond_buffer: Option<Res<BondBuffer>>,
     force_buffer: Option<Res<ForceBuffer>>,
     interaction_table: Option<Res<InteractionTableBuffer>>,
+    solver_buffers: SolverBuffers,
+    weld_buffers: WeldBuffers,
+    sort_buffers: SortBuffers,
 ) {
     prepare_bind_groups(
         commands,
         render_device,
         pipelines,
         particles,
+        back_particles,
         indices,
         cell_counts,
         cell_offsets,
@@ -806,9 +2375,14 @@ pub fn prepare_bind_group(
         bond_buffer,
         force_buffer,
         interaction_table,
+        solver_buffers,
+        weld_buffers,
+        sort_buffers,
     );
 }
 
 // Keep old names for compatibility with mod.rs
+#[allow(dead_code)]
 pub type PhysicsPipeline = SphPipelines;
+#[allow(dead_code)]
 pub type PhysicsNode = SphPhysicsNode;
@@ -0,0 +1,268 @@
+//! Adaptive timestep control.
+//!
+//! With the adaptive timestep enabled, `timestep.wgsl` reduces max particle speed,
+//! max acceleration and max bond strain on the GPU each step, just before integration.
+//! A single-thread controller then picks the next `delta_time`:
+//!
+//! - CFL: `dt <= cfl_number * h / max_speed` (no particle crosses more than a fraction of h)
+//! - Force: `dt <= force_number * sqrt(h / max_accel)`
+//! - Strain: `dt <= max_delta_time * strain_limit / max_strain` once a bond is stretched
+//!   past `strain_limit`. The cap depends only on the strain, not the previous dt, so a
+//!   bond that stays overstretched holds dt steady instead of shrinking it every step.
+//!
+//! limited to `max_growth` × the previous step and clamped to [min, max]. The chosen
+//! dt stays on the GPU and is copied into `SimParams.delta_time` at the start of the
+//! next step, so there is no CPU round trip. `TimestepStats` reports it asynchronously.
+
+use bevy::prelude::*;
+use bytemuck::{Pod, Zeroable};
+
+use super::readback::{ReadbackChannel, ReadbackResource, ReadbackRing};
+
+/// Adaptive timestep configuration (part of `SimulationSettings`)
+#[derive(Clone, Copy, Debug)]
+pub struct TimestepSettings {
+    /// Pick `delta_time` each step instead of using the fixed `SimParams` value
+    pub adaptive: bool,
+    /// Lower bound on dt (a hard floor, even if the criteria ask for less)
+    pub min_delta_time: f32,
+    /// Upper bound on dt (calm conditions run at this step)
+    pub max_delta_time: f32,
+    /// CFL number: fraction of the smoothing radius a particle may travel per step
+    pub cfl_number: f32,
+    /// Force criterion coefficient
+    pub force_number: f32,
+    /// Bond strain above which dt is capped below `max_delta_time` in proportion
+    pub strain_limit: f32,
+    /// Max factor by which dt may grow from one step to the next
+    pub max_growth: f32,
+}
+
+impl Default for TimestepSettings {
+    fn default() -> Self {
+        Self {
+            adaptive: false,
+            min_delta_time: 0.002,
+            max_delta_time: 0.03,
+            cfl_number: 0.4,
+            force_number: 0.25,
+            strain_limit: 0.5,
+            max_growth: 1.2,
+        }
+    }
+}
+
+impl TimestepSettings {
+    /// Controller parameters for the GPU
+    pub fn params(&self) -> TimestepParams {
+        TimestepParams {
+            min_delta_time: self.min_delta_time,
+            max_delta_time: self.max_delta_time.max(self.min_delta_time),
+            cfl_number: self.cfl_number,
+            force_number: self.force_number,
+            strain_limit: self.strain_limit,
+            max_growth: self.max_growth,
+            _padding: [0.0; 2],
+        }
+    }
+}
+
+/// Controller parameters uniform (matches `TimestepParams` in timestep.wgsl). 32 bytes.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable, Debug, Default)]
+pub struct TimestepParams {
+    pub min_delta_time: f32,
+    pub max_delta_time: f32,
+    pub cfl_number: f32,
+    pub force_number: f32,
+    pub strain_limit: f32,
+    pub max_growth: f32,
+    pub _padding: [f32; 2],
+}
+
+/// Which criterion set the last step (matches `LIMIT_*` in timestep.wgsl)
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TimestepLimit {
+    /// Upper bound (nothing else was tighter)
+    #[default]
+    Max = 0,
+    Cfl = 1,
+    Force = 2,
+    Strain = 3,
+    /// Growth from the previous step was capped
+    Growth = 4,
+    /// Criteria asked for less than the floor
+    Min = 5,
+}
+
+impl TimestepLimit {
+    fn from_u32(value: u32) -> Self {
+        match value {
+            1 => TimestepLimit::Cfl,
+            2 => TimestepLimit::Force,
+            3 => TimestepLimit::Strain,
+            4 => TimestepLimit::Growth,
+            5 => TimestepLimit::Min,
+            _ => TimestepLimit::Max,
+        }
+    }
+}
+
+/// GPU controller state (matches `TimestepState` in timestep.wgsl). 32 bytes.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable, Debug, Default)]
+pub struct TimestepState {
+    /// Running maxima of the current step (f32 bits, atomicMax)
+    pub max_speed_bits: u32,
+    pub max_accel_bits: u32,
+    pub max_strain_bits: u32,
+    /// dt for the next step. Byte offset 12, copied into `SimParams.delta_time`.
+    pub delta_time: f32,
+    /// Maxima measured by the last controller run
+    pub last_max_speed: f32,
+    pub last_max_accel: f32,
+    pub last_max_strain: f32,
+    /// `TimestepLimit` of the last controller run
+    pub limiter: u32,
+}
+
+/// Byte offset of `TimestepState.delta_time`
+pub const TIMESTEP_STATE_DT_OFFSET: u64 = 12;
+
+/// Pick the next dt (CPU mirror of `control` in timestep.wgsl)
+pub fn next_delta_time(
+    params: &TimestepParams,
+    smoothing_radius: f32,
+    previous: f32,
+    max_speed: f32,
+    max_accel: f32,
+    max_strain: f32,
+) -> (f32, TimestepLimit) {
+    let mut dt = params.max_delta_time;
+    let mut limit = TimestepLimit::Max;
+
+    let mut tighten = |candidate: f32, reason: TimestepLimit| {
+        if candidate < dt {
+            dt = candidate;
+            limit = reason;
+        }
+    };
+
+    if max_speed > 0.0 {
+        tighten(params.cfl_number * smoothing_radius / max_speed, TimestepLimit::Cfl);
+    }
+    if max_accel > 0.0 {
+        tighten(params.force_number * (smoothing_radius / max_accel).sqrt(), TimestepLimit::Force);
+    }
+    if max_strain > params.strain_limit {
+        tighten(params.max_delta_time * params.strain_limit / max_strain, TimestepLimit::Strain);
+    }
+    tighten(previous * params.max_growth, TimestepLimit::Growth);
+
+    if dt < params.min_delta_time {
+        (params.min_delta_time, TimestepLimit::Min)
+    } else {
+        (dt, limit)
+    }
+}
+
+/// Per-step timestep report, updated from an asynchronous GPU readback.
+/// Only updated while the adaptive timestep is enabled.
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct TimestepStats {
+    /// dt chosen for the next step
+    pub delta_time: f32,
+    pub max_speed: f32,
+    pub max_accel: f32,
+    pub max_strain: f32,
+    /// Criterion that set `delta_time`
+    pub limiter: TimestepLimit,
+}
+
+/// Main-world end of the timestep state readback
+#[derive(Resource, Clone, Default)]
+pub struct TimestepStatsChannel(pub ReadbackChannel);
+
+/// Render-world staging ring for the timestep state
+#[derive(Resource)]
+pub struct TimestepReadback(pub ReadbackRing);
+
+impl ReadbackResource for TimestepReadback {
+    fn ring(&self) -> &ReadbackRing {
+        &self.0
+    }
+}
+
+/// Copy the latest read back controller state into `TimestepStats`
+pub fn update_timestep_stats(channel: Res<TimestepStatsChannel>, mut stats: ResMut<TimestepStats>) {
+    let Some(bytes) = channel.0.take() else {
+        return;
+    };
+    let state: TimestepState = bytemuck::pod_read_unaligned(&bytes);
+    *stats = TimestepStats {
+        delta_time: state.delta_time,
+        max_speed: state.last_max_speed,
+        max_accel: state.last_max_accel,
+        max_strain: state.last_max_strain,
+        limiter: TimestepLimit::from_u32(state.limiter),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn controller_stays_within_bounds() {
+        let params = TimestepSettings::default().params();
+        let h = 10.0;
+
+        // Calm sea: slow particles, small forces -> grows towards the upper bound
+        let (dt, _) = next_delta_time(&params, h, 0.03, 20.0, 100.0, 0.01);
+        assert_eq!(dt, params.max_delta_time);
+
+        // Gust: fast particles -> CFL limited, below the fixed 0.02 step
+        let (dt, limit) = next_delta_time(&params, h, 0.03, 800.0, 100.0, 0.01);
+        assert_eq!(limit, TimestepLimit::Cfl);
+        assert!((dt - 0.4 * h / 800.0).abs() < 1e-6);
+
+        // Heavy impact: force limited
+        let (dt, limit) = next_delta_time(&params, h, 0.03, 20.0, 40_000.0, 0.01);
+        assert_eq!(limit, TimestepLimit::Force);
+        assert!(dt < 0.01);
+
+        // Overstretched bonds shrink the step
+        let (dt, limit) = next_delta_time(&params, h, 0.02, 20.0, 100.0, 1.0);
+        assert_eq!(limit, TimestepLimit::Strain);
+        assert!((dt - 0.015).abs() < 1e-6);
+
+        // Recovery is rate limited
+        let (dt, limit) = next_delta_time(&params, h, 0.005, 20.0, 100.0, 0.01);
+        assert_eq!(limit, TimestepLimit::Growth);
+        assert!((dt - 0.006).abs() < 1e-6);
+
+        // Never below the floor
+        let (dt, limit) = next_delta_time(&params, h, 0.03, 1e6, 1e9, 0.01);
+        assert_eq!(limit, TimestepLimit::Min);
+        assert_eq!(dt, params.min_delta_time);
+    }
+
+    #[test]
+    fn constant_overstretch_settles() {
+        let params = TimestepSettings::default().params();
+        let h = 10.0;
+
+        // A bond held at twice the strain limit must not ratchet dt down to the floor
+        let mut dt = params.max_delta_time;
+        for _ in 0..100 {
+            (dt, _) = next_delta_time(&params, h, dt, 20.0, 100.0, 2.0 * params.strain_limit);
+        }
+        let settled = params.max_delta_time * 0.5;
+        assert!((dt - settled).abs() < 1e-6, "dt {dt} did not settle at {settled}");
+
+        let (next, limit) = next_delta_time(&params, h, dt, 20.0, 100.0, 2.0 * params.strain_limit);
+        assert_eq!(limit, TimestepLimit::Strain);
+        assert_eq!(next, dt);
+    }
+}
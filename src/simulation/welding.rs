@@ -0,0 +1,475 @@
+//! Runtime bond creation.
+//!
+//! The bond buffer is padded to `BOND_COUNT` with inactive slots. `BondAllocator.next_free`
+//! starts after the scenario's bonds and hands slots out with an atomic add, so bonds can
+//! be created on the GPU in two ways:
+//!
+//! - `BondQueue` (main world): bonds between given particles, and repairs of broken bonds
+//!   (from a `BondBroken` event). Up to `MAX_BOND_EDITS` are flushed per batch and applied
+//!   by the `apply_repairs` and `apply_edits` entries of `weld.wgsl`. A batch stays in
+//!   `PendingBondEdits` until the compute node has dispatched it (`BondEditsApplied`), so
+//!   frames where the node skips the step do not lose edits.
+//! - `WeldRules` (main world): the `weld` entry bonds touching particles of a material
+//!   pair moving slower than a relative speed, so boats can raft up and debris can snag.
+//!   Each particle keeps the bond slots of up to `MAX_WELD_PARTNERS` live welds to
+//!   particles with a higher original id, so a pair is welded once while the weld holds.
+//!   A broken weld (or one whose slot was reused) is forgotten, so the pair can weld
+//!   again. Particles of the same rigid body are never welded (the scenario's bonds
+//!   already join them), and neither is a pair an active bond already joins (`bonded`,
+//!   read from the bond adjacency, which is rebuilt just before welding).
+//!
+//! Slots of broken bonds are reused: every step that creates bonds first lists them
+//! (`collect_free`), and new bonds take listed slots before growing past `next_free`.
+//! A repair therefore only applies while the slot still holds the broken bond's particle
+//! pair. Once both run out, new bonds are dropped and counted in `BondAllocator.dropped`.
+//!
+//! Edits and `BondBroken` name particles by original id, which stays valid when the
+//! particles are reordered (see `reorder`).
+
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
+
+use bevy::prelude::*;
+use bytemuck::{Pod, Zeroable};
+
+use super::adjacency::Adjacency;
+use super::bond_events::BondBroken;
+use crate::resources::Bond;
+
+/// Bond edits applied per frame (matches the edit buffer size)
+pub const MAX_BOND_EDITS: usize = 256;
+
+/// Weld rules (matches weld.wgsl)
+pub const MAX_WELD_RULES: usize = 4;
+
+/// Welds a particle remembers (matches weld.wgsl)
+pub const MAX_WELD_PARTNERS: usize = 3;
+
+/// `BondEdit.kind` values (match weld.wgsl)
+pub mod edit_kind {
+    /// Activate `bond` in a free slot
+    pub const ADD: u32 = 0;
+    /// Reactivate the broken bond at `bond_index` and clear its damage, if the slot still
+    /// joins `bond.particle_a` and `bond.particle_b`
+    pub const REPAIR: u32 = 1;
+}
+
+/// One queued change to the bond buffer (matches `BondEdit` in weld.wgsl). 64 bytes.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable, Debug)]
+pub struct BondEdit {
+    pub kind: u32,
+    pub bond_index: u32,
+    pub _padding: [u32; 2],
+    pub bond: Bond,
+}
+
+/// Slot allocator shared by queued and welded bonds (matches `BondAllocator` in weld.wgsl).
+/// 32 bytes.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable, Debug, Default)]
+pub struct BondAllocator {
+    /// First slot never handed out (grows past `BOND_COUNT` once full)
+    pub next_free: u32,
+    /// Bonds dropped because the buffer was full
+    pub dropped: u32,
+    /// Edits uploaded this frame
+    pub edit_count: u32,
+    /// Slots of broken bonds listed in the free list this step
+    pub free_count: u32,
+    /// Listed slots handed out this step (counts on past `free_count`)
+    pub free_taken: u32,
+    /// Bonds written into slots; the bond lists are rebuilt when this moves
+    pub changes: u32,
+    /// `changes` at the last bond list rebuild (bond_adjacency.wgsl)
+    pub rebuilt_changes: u32,
+    pub _padding: u32,
+}
+
+/// Byte offset of `BondAllocator.edit_count` (rewritten every frame)
+pub const BOND_ALLOCATOR_EDIT_COUNT_OFFSET: u64 = 8;
+
+/// Bonds to create or repair on the GPU
+#[derive(Resource, Clone, Debug, Default)]
+pub struct BondQueue {
+    edits: Vec<BondEdit>,
+}
+
+impl BondQueue {
+    /// Add `bond` (its `is_active` is ignored). A `rest_length` of 0 or less uses the
+    /// particles' distance when the bond is created.
+    pub fn add(&mut self, bond: Bond) {
+        self.edits.push(BondEdit {
+            kind: edit_kind::ADD,
+            bond_index: 0,
+            _padding: [0; 2],
+            bond: Bond { is_active: 1, ..bond },
+        });
+    }
+
+    /// Reactivate a broken bond with its damage cleared. Ignored if its slot was reused
+    /// by a new bond in the meantime.
+    pub fn repair(&mut self, broken: &BondBroken) {
+        self.edits.push(BondEdit {
+            kind: edit_kind::REPAIR,
+            bond_index: broken.bond_index,
+            _padding: [0; 2],
+            bond: Bond { particle_a: broken.particle_a, particle_b: broken.particle_b, ..Bond::zeroed() },
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.edits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Take the next batch (at most `MAX_BOND_EDITS`, oldest first); the rest stays queued
+    fn take_batch(&mut self) -> Vec<BondEdit> {
+        let count = self.edits.len().min(MAX_BOND_EDITS);
+        self.edits.drain(..count).collect()
+    }
+}
+
+/// The batch flushed from `BondQueue` (extracted to the render world every frame until
+/// the node applied it)
+#[derive(Resource, Clone, Debug, Default)]
+pub struct PendingBondEdits {
+    /// Increments with every non-empty batch
+    pub batch: u32,
+    pub edits: Vec<BondEdit>,
+}
+
+impl PendingBondEdits {
+    /// Edits the node still has to apply (none once `applied` has seen this batch)
+    pub fn unapplied(&self, applied: &BondEditsApplied) -> &[BondEdit] {
+        if applied.get() == self.batch {
+            &[]
+        } else {
+            &self.edits
+        }
+    }
+}
+
+/// Last `PendingBondEdits.batch` the compute node dispatched, shared by the main and
+/// render worlds
+#[derive(Resource, Clone, Default)]
+pub struct BondEditsApplied(Arc<AtomicU32>);
+
+impl BondEditsApplied {
+    pub fn get(&self) -> u32 {
+        self.0.load(Ordering::Acquire)
+    }
+
+    /// Called by the node once the batch's dispatches are recorded
+    pub fn set(&self, batch: u32) {
+        self.0.store(batch, Ordering::Release);
+    }
+}
+
+/// Replace the pending batch with the next one from `BondQueue` once the node applied it
+pub fn flush_bond_queue(
+    mut queue: ResMut<BondQueue>,
+    mut pending: ResMut<PendingBondEdits>,
+    applied: Res<BondEditsApplied>,
+) {
+    if !pending.unapplied(&applied).is_empty() {
+        return;
+    }
+    let edits = queue.take_batch();
+    if edits.is_empty() && pending.edits.is_empty() {
+        return;
+    }
+    if !edits.is_empty() {
+        pending.batch = pending.batch.wrapping_add(1);
+    }
+    pending.edits = edits;
+}
+
+/// Weld touching particles of two materials
+#[derive(Clone, Copy, Debug)]
+pub struct WeldRule {
+    /// Layer masks of the two materials (see `resources::layer`; order does not matter)
+    pub layers: (u32, u32),
+    /// Particles closer than this touch
+    pub max_distance: f32,
+    /// Only particles moving slower than this relative to each other weld
+    pub max_relative_speed: f32,
+    /// The weld bond (rest length = distance at welding)
+    pub bond_type: u32,
+    pub stiffness: f32,
+    pub breaking_strain: f32,
+}
+
+impl WeldRule {
+    /// Whether a particle pair with these layer masks, distance and relative speed welds
+    /// (CPU mirror of weld.wgsl)
+    pub fn welds(&self, mask_a: u32, mask_b: u32, distance: f32, relative_speed: f32) -> bool {
+        let (a, b) = self.layers;
+        let pair = ((mask_a & a) != 0 && (mask_b & b) != 0) || ((mask_a & b) != 0 && (mask_b & a) != 0);
+        pair && distance < self.max_distance && relative_speed < self.max_relative_speed
+    }
+
+    fn gpu(&self) -> GpuWeldRule {
+        GpuWeldRule {
+            layer_a: self.layers.0,
+            layer_b: self.layers.1,
+            max_distance: self.max_distance,
+            max_relative_speed: self.max_relative_speed,
+            bond_type: self.bond_type,
+            stiffness: self.stiffness,
+            breaking_strain: self.breaking_strain,
+            _padding: 0,
+        }
+    }
+}
+
+/// Active weld rules (empty by default: nothing welds). Only the first
+/// `MAX_WELD_RULES` are used.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct WeldRules(pub Vec<WeldRule>);
+
+impl WeldRules {
+    /// Uniform block for weld.wgsl
+    pub fn gpu_rules(&self) -> GpuWeldRules {
+        let mut gpu = GpuWeldRules::zeroed();
+        for (slot, rule) in gpu.rules.iter_mut().zip(&self.0) {
+            *slot = rule.gpu();
+        }
+        gpu.count = self.0.len().min(MAX_WELD_RULES) as u32;
+        gpu
+    }
+}
+
+/// GPU weld rule (matches `WeldRule` in weld.wgsl). 32 bytes.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable, Debug, Default)]
+pub struct GpuWeldRule {
+    pub layer_a: u32,
+    pub layer_b: u32,
+    pub max_distance: f32,
+    pub max_relative_speed: f32,
+    pub bond_type: u32,
+    pub stiffness: f32,
+    pub breaking_strain: f32,
+    pub _padding: u32,
+}
+
+/// Weld rules uniform (matches `WeldRules` in weld.wgsl)
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable, Debug)]
+pub struct GpuWeldRules {
+    pub count: u32,
+    pub _padding: [u32; 3],
+    pub rules: [GpuWeldRule; MAX_WELD_RULES],
+}
+
+/// Live welds a particle owns, by bond slot (matches `WeldPartners` in weld.wgsl). Bonds
+/// never move, so reordering the particles leaves the slots valid.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable, Debug, Default)]
+pub struct WeldPartners {
+    pub count: u32,
+    pub bonds: [u32; MAX_WELD_PARTNERS],
+}
+
+impl WeldPartners {
+    /// Forget the welds of particle `slot` whose bond broke or whose bond slot now holds
+    /// another particle's bond (CPU mirror of weld.wgsl)
+    pub fn retain_live(&mut self, bonds: &[Bond], slot: u32) {
+        let mut live = Self::default();
+        for &bond_slot in &self.bonds[..self.count as usize] {
+            let bond = &bonds[bond_slot as usize];
+            if bond.is_active != 0 && bond.particle_a == slot {
+                live.bonds[live.count as usize] = bond_slot;
+                live.count += 1;
+            }
+        }
+        *self = live;
+    }
+
+    /// Whether a live weld already joins particle `slot` to `other`
+    pub fn joins(&self, bonds: &[Bond], slot: u32, other: u32) -> bool {
+        self.bonds[..self.count as usize].iter().any(|&bond_slot| {
+            let bond = &bonds[bond_slot as usize];
+            bond.is_active != 0 && bond.particle_a == slot && bond.particle_b == other
+        })
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.count as usize >= MAX_WELD_PARTNERS
+    }
+
+    /// Remember the weld written into `bond_slot`
+    pub fn push(&mut self, bond_slot: u32) {
+        self.bonds[self.count as usize] = bond_slot;
+        self.count += 1;
+    }
+}
+
+/// Whether an active bond (scenario, rope or weld) already joins particles `slot` and
+/// `other` (CPU mirror of weld.wgsl, which reads the same lists from the packed GPU
+/// adjacency)
+pub fn bonded(adjacency: &Adjacency, bonds: &[Bond], slot: u32, other: u32) -> bool {
+    adjacency.of(slot as usize).iter().any(|&bond_slot| {
+        let bond = &bonds[bond_slot as usize];
+        bond.is_active != 0 && (bond.particle_a == other || bond.particle_b == other)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::{bond_type, layer};
+    use crate::simulation::adjacency::bond_adjacency;
+    use crate::simulation::bond_events::BreakCause;
+
+    fn broken(bond_index: u32) -> BondBroken {
+        BondBroken {
+            bond_index,
+            bond_type: bond_type::HULL,
+            particle_a: 1,
+            particle_b: 2,
+            position: Vec2::ZERO,
+            strain: 0.5,
+            cause: BreakCause::Stretched,
+        }
+    }
+
+    #[test]
+    fn queue_flushes_in_bounded_batches() {
+        let mut queue = BondQueue::default();
+        for i in 0..MAX_BOND_EDITS as u32 + 10 {
+            queue.repair(&broken(i));
+        }
+        queue.add(Bond { particle_a: 3, particle_b: 4, ..Bond::zeroed() });
+
+        let first = queue.take_batch();
+        assert_eq!(first.len(), MAX_BOND_EDITS);
+        assert_eq!((first[0].kind, first[0].bond_index), (edit_kind::REPAIR, 0));
+        assert_eq!((first[0].bond.particle_a, first[0].bond.particle_b), (1, 2));
+
+        let second = queue.take_batch();
+        assert_eq!(second.len(), 11);
+        assert_eq!(second[10].kind, edit_kind::ADD);
+        assert_eq!(second[10].bond.is_active, 1);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn rules_match_either_order_when_touching_and_slow() {
+        let raft = WeldRule {
+            layers: (layer::HULL, layer::MAST),
+            max_distance: 6.0,
+            max_relative_speed: 5.0,
+            bond_type: bond_type::FUSE,
+            stiffness: 20_000.0,
+            breaking_strain: 0.5,
+        };
+        assert!(raft.welds(layer::HULL, layer::MAST, 4.0, 1.0));
+        assert!(raft.welds(layer::MAST, layer::HULL, 4.0, 1.0));
+        assert!(!raft.welds(layer::HULL, layer::HULL, 4.0, 1.0));
+        assert!(!raft.welds(layer::HULL, layer::MAST, 8.0, 1.0));
+        assert!(!raft.welds(layer::HULL, layer::MAST, 4.0, 9.0));
+
+        let gpu = WeldRules(vec![raft; 6]).gpu_rules();
+        assert_eq!(gpu.count, MAX_WELD_RULES as u32);
+        assert_eq!(gpu.rules[3].layer_b, layer::MAST);
+        assert_eq!(std::mem::size_of::<BondEdit>(), 64);
+    }
+
+    #[test]
+    fn broken_welds_are_forgotten_so_the_pair_welds_again() {
+        let weld = |a, b| Bond { particle_a: a, particle_b: b, rest_length: 1.0, is_active: 1, ..Bond::zeroed() };
+        let mut bonds = vec![weld(0, 1), weld(0, 2), weld(0, 3)];
+        let mut partners = WeldPartners::default();
+        for slot in 0..3 {
+            partners.push(slot);
+        }
+        partners.retain_live(&bonds, 0);
+        assert!(partners.is_full());
+        assert!(partners.joins(&bonds, 0, 1));
+
+        // The 0-1 weld breaks: its entry goes, and the pair welds again into the freed slot
+        bonds[0].is_active = 0;
+        assert!(!partners.joins(&bonds, 0, 1));
+        partners.retain_live(&bonds, 0);
+        assert_eq!(partners.count, 2);
+        bonds[0] = weld(0, 1);
+        partners.push(0);
+        assert!(partners.joins(&bonds, 0, 1));
+
+        // The 0-2 weld breaks and its slot is reused by another particle's bond
+        bonds[1] = weld(5, 6);
+        partners.retain_live(&bonds, 0);
+        assert_eq!(partners.count, 2);
+        assert!(!partners.joins(&bonds, 0, 2));
+        assert_eq!(&partners.bonds[..2], &[2, 0]);
+    }
+
+    #[test]
+    fn pairs_joined_by_a_bond_do_not_weld() {
+        let bond = |a, b, bond_type| Bond {
+            particle_a: a,
+            particle_b: b,
+            rest_length: 1.0,
+            bond_type,
+            is_active: 1,
+            ..Bond::zeroed()
+        };
+        // A hull plank and a rope, either way round, and an unused padding slot
+        let mut bonds = vec![bond(0, 1, bond_type::HULL), bond(3, 2, bond_type::ROPE), Bond::zeroed()];
+        let adjacency = bond_adjacency(&bonds, 5);
+        assert!(bonded(&adjacency, &bonds, 0, 1));
+        assert!(bonded(&adjacency, &bonds, 2, 3));
+        assert!(!bonded(&adjacency, &bonds, 0, 2));
+        assert!(!bonded(&adjacency, &bonds, 4, 0));
+
+        // The same lists read from the packed GPU layout
+        let packed = adjacency.packed(8);
+        let list = |p: usize| &packed[6 + packed[p] as usize..6 + packed[p + 1] as usize];
+        assert_eq!(list(2), &[1]);
+
+        // A broken bond no longer joins the pair, so it may weld again
+        bonds[0].is_active = 0;
+        assert!(!bonded(&adjacency, &bonds, 0, 1));
+    }
+
+    #[test]
+    fn pending_batch_waits_until_the_node_applied_it() {
+        let mut app = App::new();
+        app.init_resource::<BondQueue>()
+            .init_resource::<PendingBondEdits>()
+            .init_resource::<BondEditsApplied>()
+            .add_systems(Update, flush_bond_queue);
+        app.world_mut().resource_mut::<BondQueue>().repair(&broken(7));
+        app.update();
+        let batch = app.world().resource::<PendingBondEdits>().batch;
+        assert_eq!(app.world().resource::<PendingBondEdits>().edits.len(), 1);
+
+        // Node skipped the step: the batch survives the next flush, new edits wait
+        app.world_mut().resource_mut::<BondQueue>().repair(&broken(8));
+        app.update();
+        let pending = app.world().resource::<PendingBondEdits>();
+        assert_eq!((pending.batch, pending.edits[0].bond_index), (batch, 7));
+        assert_eq!(app.world().resource::<BondQueue>().len(), 1);
+
+        // Applied: re-extracted copies are ignored, and the next batch moves in
+        let applied = app.world().resource::<BondEditsApplied>().clone();
+        applied.set(batch);
+        assert!(pending.unapplied(&applied).is_empty());
+        app.update();
+        let pending = app.world().resource::<PendingBondEdits>();
+        assert_eq!((pending.batch, pending.edits[0].bond_index), (batch + 1, 8));
+        assert_eq!(pending.unapplied(&applied).len(), 1);
+
+        // Applied with nothing queued: the pending edits clear
+        applied.set(batch + 1);
+        app.update();
+        assert!(app.world().resource::<PendingBondEdits>().edits.is_empty());
+    }
+}
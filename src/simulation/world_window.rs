@@ -0,0 +1,155 @@
+//! Open world: a simulation window that follows the fleet.
+//!
+//! The particle count is fixed and the grid covers 64x36 cells (1280x720), so instead of
+//! hashing an unbounded grid the grid moves. With `SimulationSettings::open_world`,
+//! `follow_fleet` shifts the window to keep the mass-weighted centre of the dynamic rigid
+//! bodies in the middle, in whole cells, and the render world uploads the shifted grid
+//! origin (`grid_params`) and bounds (`bounds`) every frame. Grid size, buffer sizes and
+//! dispatch counts never change.
+//!
+//! In the open world `physics.wgsl` has no side walls: water and air leaving the window
+//! on the left or right re-enter on the opposite side with their velocity (`wrap`), so
+//! the fluid budget always surrounds the fleet. Only x wraps; the floor and ceiling clamp
+//! as in the tank. By default the window only follows along x; following along y would
+//! move the floor and ceiling with the fleet.
+//!
+//! The uploaded grid has `GridParams::wrap` set, so the neighbour search continues
+//! across the left/right seam and x offsets take the short way round
+//! (`GridParams::offset`). Bonds do not wrap, so nothing welds across the seam; only
+//! fluid wraps, and the fleet sits in the middle of the window.
+//!
+//! The open world is off by default because the stock scenarios are tanks built around
+//! their walls (the dry dock's current, the wind tunnel's recycled air); turn it on with
+//! `SimulationSettings::open_world` or `sailing-headless --open-world`.
+//!
+//! The fleet centre comes from the rigid body readback, a few frames old. The window
+//! snaps to whole cells (20 units), so the lag only matters once the fleet covers a cell
+//! within the readback latency: at 60 fps and three frames of latency, 400 units/s, far
+//! above sailing speeds. Until then the window is at most one cell behind.
+
+use bevy::prelude::*;
+
+use super::rigid_body::{RigidBodies, RigidBodyKind, RigidBodyState};
+use super::SimulationSettings;
+use crate::resources::{GridParams, SimParams};
+
+/// Offset of the simulation window from its spawn position (whole cells)
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct WorldWindow {
+    pub offset: Vec2,
+    /// Axes the window follows the fleet along
+    pub follow: BVec2,
+}
+
+impl Default for WorldWindow {
+    fn default() -> Self {
+        Self { offset: Vec2::ZERO, follow: BVec2::new(true, false) }
+    }
+}
+
+impl WorldWindow {
+    /// Recentre on `centre` (world space), snapped to whole grid cells so particles keep
+    /// their position within a cell
+    pub fn follow(&mut self, centre: Vec2) {
+        let cell_size = GridParams::default().cell_size;
+        let home = Self::default().centre();
+        let snapped = ((centre - home) / cell_size).round() * cell_size;
+        self.offset = Vec2::select(self.follow, snapped, self.offset);
+    }
+
+    /// World-space centre of the window
+    pub fn centre(&self) -> Vec2 {
+        let [min_x, max_x, min_y, max_y] = SimParams::default().bounds;
+        Vec2::new(min_x + max_x, min_y + max_y) * 0.5 + self.offset
+    }
+
+    /// Simulation bounds (min_x, max_x, min_y, max_y) of the window
+    pub fn bounds(&self) -> [f32; 4] {
+        let [min_x, max_x, min_y, max_y] = SimParams::default().bounds;
+        [min_x + self.offset.x, max_x + self.offset.x, min_y + self.offset.y, max_y + self.offset.y]
+    }
+
+    /// Grid parameters with the origin moved with the window
+    pub fn grid_params(&self) -> GridParams {
+        let mut grid = GridParams::default();
+        grid.grid_origin_x += self.offset.x;
+        grid.grid_origin_y += self.offset.y;
+        grid
+    }
+
+    /// Where a fluid particle at `pos` re-enters the window: x wraps, y is left to the
+    /// floor and ceiling clamp (CPU mirror of physics.wgsl)
+    pub fn wrap(&self, pos: Vec2) -> Vec2 {
+        let [min_x, max_x, ..] = self.bounds();
+        let width = max_x - min_x;
+        let rel = pos.x - min_x;
+        Vec2::new(min_x + rel - (rel / width).floor() * width, pos.y)
+    }
+}
+
+/// Mass-weighted centre of the dynamic bodies; `None` before the first readback
+pub fn fleet_centre(bodies: &[RigidBodyState]) -> Option<Vec2> {
+    let (weighted, mass) = bodies
+        .iter()
+        .filter(|body| body.kind == RigidBodyKind::Dynamic && body.mass > 0.0)
+        .fold((Vec2::ZERO, 0.0), |(weighted, mass), body| {
+            (weighted + body.centre_of_mass * body.mass, mass + body.mass)
+        });
+    (mass > 0.0).then(|| weighted / mass)
+}
+
+/// Move the window with the fleet (open world only)
+pub fn follow_fleet(settings: Res<SimulationSettings>, bodies: Res<RigidBodies>, mut window: ResMut<WorldWindow>) {
+    if !settings.open_world {
+        return;
+    }
+    if let Some(centre) = fleet_centre(&bodies.bodies) {
+        window.follow(centre);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn body(kind: RigidBodyKind, centre_of_mass: Vec2, mass: f32) -> RigidBodyState {
+        RigidBodyState {
+            name: "body",
+            kind,
+            centre_of_mass,
+            angle: 0.0,
+            linear_velocity: Vec2::ZERO,
+            angular_velocity: 0.0,
+            mass,
+        }
+    }
+
+    #[test]
+    fn window_follows_in_whole_cells_along_x() {
+        assert_eq!(fleet_centre(&[]), None);
+        let bodies = [
+            body(RigidBodyKind::Dynamic, Vec2::new(1000.0, 50.0), 3.0),
+            body(RigidBodyKind::Dynamic, Vec2::new(1020.0, 90.0), 1.0),
+            body(RigidBodyKind::Kinematic, Vec2::new(-5000.0, 0.0), 100.0),
+        ];
+        let centre = fleet_centre(&bodies).unwrap();
+        assert!((centre - Vec2::new(1005.0, 60.0)).length() < 1e-3);
+
+        let mut window = WorldWindow::default();
+        window.follow(centre);
+        assert_eq!(window.offset, Vec2::new(1000.0, 0.0));
+        assert_eq!(window.bounds(), [360.0, 1640.0, -360.0, 360.0]);
+
+        let grid = window.grid_params();
+        assert_eq!(grid.grid_origin_x, 360.0);
+        assert_eq!((grid.grid_width, grid.grid_height), (64, 36));
+    }
+
+    #[test]
+    fn fluid_wraps_to_the_opposite_side_along_x_only() {
+        let window = WorldWindow { offset: Vec2::new(200.0, 0.0), ..default() };
+        assert_eq!(window.wrap(Vec2::new(850.0, 0.0)), Vec2::new(-430.0, 0.0));
+        assert_eq!(window.wrap(Vec2::new(-450.0, -370.0)), Vec2::new(830.0, -370.0));
+        assert_eq!(window.wrap(Vec2::new(100.0, 20.0)), Vec2::new(100.0, 20.0));
+    }
+}
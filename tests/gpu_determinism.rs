@@ -0,0 +1,74 @@
+//! GPU Determinism Check
+//!
+//! Runs `sailing-headless` twice on the same scenario and compares the per-step stats and
+//! the final particle snapshot byte for byte. The double-buffered passes must make a step
+//! a pure function of the previous state on a given adapter, so any difference is a race.
+//!
+//! Needs a wgpu adapter (a software one such as llvmpipe will do), so the tests are
+//! ignored by default. CI runs them on llvmpipe (the `gpu-determinism` job). llvmpipe
+//! runs one thread per core, and on a single core its atomics always land in the same
+//! order: set `LP_NUM_THREADS` so a race between invocations shows up.
+//!
+//! # Running tests
+//! ```bash
+//! cargo test --test gpu_determinism -- --ignored
+//! # Without a Vulkan driver, through llvmpipe's GL, with racing threads
+//! WGPU_BACKEND=gl LP_NUM_THREADS=8 cargo test --test gpu_determinism -- --ignored
+//! ```
+
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Steps per run (hurricane reorders every 10, so this covers several reorders)
+const STEPS: u32 = 50;
+
+/// Stats CSV and particle CSV of one headless run
+fn run(scenario: &str, tag: &str) -> (String, String) {
+    let dir = std::env::temp_dir().join(format!("sailing-determinism-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("create the output directory");
+    let stats: PathBuf = dir.join(format!("{scenario}-{tag}-stats.csv"));
+    let snapshot: PathBuf = dir.join(format!("{scenario}-{tag}-particles.csv"));
+
+    let status = Command::new(env!("CARGO_BIN_EXE_sailing-headless"))
+        .args(["--scenario", scenario, "--steps", &STEPS.to_string()])
+        .arg("--out")
+        .arg(&stats)
+        .arg("--snapshot")
+        .arg(&snapshot)
+        // Bevy finds assets/ through the manifest directory
+        .env("CARGO_MANIFEST_DIR", env!("CARGO_MANIFEST_DIR"))
+        .status()
+        .expect("start sailing-headless");
+    assert!(status.success(), "sailing-headless {scenario} exited with {status}");
+
+    let read = |path: &PathBuf| std::fs::read_to_string(path).expect("read the headless output");
+    (read(&stats), read(&snapshot))
+}
+
+/// Panic naming the first line that differs
+fn assert_identical(first: &str, second: &str, what: &str) {
+    for (line, (a, b)) in first.lines().zip(second.lines()).enumerate() {
+        assert_eq!(a, b, "{what} differs at line {}", line + 1);
+    }
+    assert_eq!(first.lines().count(), second.lines().count(), "{what} line counts differ");
+}
+
+fn assert_repeatable(scenario: &str) {
+    let (stats_a, particles_a) = run(scenario, "a");
+    let (stats_b, particles_b) = run(scenario, "b");
+    assert_eq!(stats_a.lines().count(), STEPS as usize + 1);
+    assert_identical(&stats_a, &stats_b, "stats");
+    assert_identical(&particles_a, &particles_b, "particle snapshot");
+}
+
+#[test]
+#[ignore = "needs a GPU adapter; run with --ignored"]
+fn hurricane_runs_are_bitwise_identical() {
+    assert_repeatable("hurricane");
+}
+
+#[test]
+#[ignore = "needs a GPU adapter; run with --ignored"]
+fn water_only_runs_are_bitwise_identical() {
+    assert_repeatable("water_only");
+}
@@ -0,0 +1,116 @@
+//! GPU / CPU Reference Parity Check
+//!
+//! Runs `sailing-headless` for a few steps and steps the same initial state with the CPU
+//! reference pipeline (`simulation::reference`), then compares every particle. The two
+//! run the same stages in the same order, so they only differ by float rounding, which
+//! stays small over a few steps; a wrong shader (or a stale mirror) moves particles by
+//! far more.
+//!
+//! Only scenarios inside the reference's scope are compared: no bending springs,
+//! kinematic joints, rigid bodies or welding (see the `reference` module docs).
+//!
+//! Needs a wgpu adapter (a software one such as llvmpipe will do), so the tests are
+//! ignored by default. CI runs them next to the determinism check.
+//!
+//! # Running tests
+//! ```bash
+//! cargo test --test gpu_reference -- --ignored
+//! # Without a Vulkan driver, through llvmpipe's GL
+//! WGPU_BACKEND=gl cargo test --test gpu_reference -- --ignored
+//! ```
+
+use std::path::PathBuf;
+use std::process::Command;
+
+use bevy::math::Vec2;
+use sailing::resources::{GridParams, Particle, SimParams};
+use sailing::simulation::reference::ReferenceSim;
+use sailing::simulation::{default_interaction_table, Scenario, SimulationSettings, PARTICLE_COUNT};
+
+/// Steps per run: long enough for every stage to act, short enough that rounding
+/// differences have not grown chaotic (llvmpipe: at most ~6e-5 units after 3 steps,
+/// 4e-3 after 20)
+const STEPS: u32 = 10;
+
+/// Largest position difference, in world units (the smoothing radius is 10)
+const POSITION_TOLERANCE: f32 = 0.05;
+/// Largest velocity difference, relative to `1 + |v|`
+const VELOCITY_TOLERANCE: f32 = 1e-3;
+/// Largest density difference, relative
+const DENSITY_TOLERANCE: f32 = 1e-3;
+
+/// Particle snapshot of a headless run (no reordering, so the rows are in spawn order)
+fn gpu_particles(scenario: &str) -> Vec<Particle> {
+    let dir = std::env::temp_dir().join(format!("sailing-reference-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("create the output directory");
+    let snapshot: PathBuf = dir.join(format!("{scenario}-particles.csv"));
+
+    let status = Command::new(env!("CARGO_BIN_EXE_sailing-headless"))
+        .args(["--scenario", scenario, "--steps", &STEPS.to_string(), "--reorder-interval", "0"])
+        .arg("--out")
+        .arg(dir.join(format!("{scenario}-stats.csv")))
+        .arg("--snapshot")
+        .arg(&snapshot)
+        // Bevy finds assets/ through the manifest directory
+        .env("CARGO_MANIFEST_DIR", env!("CARGO_MANIFEST_DIR"))
+        .status()
+        .expect("start sailing-headless");
+    assert!(status.success(), "sailing-headless {scenario} exited with {status}");
+
+    let csv = std::fs::read_to_string(&snapshot).expect("read the particle snapshot");
+    csv.lines()
+        .skip(1)
+        .map(|line| {
+            let f: Vec<f32> = line.split(',').map(|v| v.parse().expect("a number")).collect();
+            Particle {
+                pos: [f[2], f[3]],
+                vel: [f[4], f[5]],
+                mass: f[6],
+                density: f[7],
+                pressure: f[8],
+                layer_mask: f[1] as u32,
+                ..Particle::new_water([0.0; 2], [0.0; 2])
+            }
+        })
+        .collect()
+}
+
+/// The scenario stepped on the CPU with the parameters the render world uploads
+fn reference_particles(scenario: Scenario) -> Vec<Particle> {
+    let (particles, _) = scenario.spawn_particles(PARTICLE_COUNT);
+    let mut params = SimParams::default();
+    SimulationSettings::for_scenario(scenario).apply(&mut params);
+    let mut sim = ReferenceSim::new(particles, Vec::new(), params, GridParams::default(), default_interaction_table());
+    for _ in 0..STEPS {
+        sim.step();
+    }
+    sim.particles
+}
+
+fn assert_matches_reference(scenario: Scenario) {
+    let gpu = gpu_particles(scenario.name());
+    let cpu = reference_particles(scenario);
+    assert_eq!(gpu.len(), cpu.len());
+
+    for (i, (g, c)) in gpu.iter().zip(&cpu).enumerate() {
+        assert_eq!(g.layer_mask, c.layer_mask, "particle {i} changed layer");
+        let position = Vec2::from(g.pos).distance(c.pos.into());
+        assert!(position < POSITION_TOLERANCE, "particle {i} is {position} from the reference");
+        let velocity = Vec2::from(g.vel).distance(c.vel.into()) / (1.0 + Vec2::from(c.vel).length());
+        assert!(velocity < VELOCITY_TOLERANCE, "particle {i} velocity differs by {velocity} (relative)");
+        let density = (g.density - c.density).abs() / c.density.abs().max(1e-3);
+        assert!(density < DENSITY_TOLERANCE, "particle {i} density differs by {density} (relative)");
+    }
+}
+
+#[test]
+#[ignore = "needs a GPU adapter; run with --ignored"]
+fn water_only_matches_the_cpu_reference() {
+    assert_matches_reference(Scenario::WaterOnly);
+}
+
+#[test]
+#[ignore = "needs a GPU adapter; run with --ignored"]
+fn pressure_washer_matches_the_cpu_reference() {
+    assert_matches_reference(Scenario::PressureWasher);
+}
@@ -3,11 +3,17 @@
 //! These tests verify that physics parameters produce stable, non-explosive behavior
 //! across all scenarios. The "no explosion" invariant is universal.
 //!
+//! Besides static checks of the interaction table, small scenes are stepped with the CPU
+//! reference pipeline (`simulation::reference`), so no GPU is needed.
+//!
 //! # Running tests
 //! ```bash
 //! cargo test physics_regression
 //! ```
 
+use bytemuck::Zeroable;
+use sailing::resources::{bond_type, layer, Bond, GridParams, Particle, SimParams};
+use sailing::simulation::reference::ReferenceSim;
 use sailing::simulation::{default_interaction_table, InteractionTable, MaterialType};
 
 // ==================== UNIVERSAL CONSTANTS ====================
@@ -56,6 +62,36 @@ fn assert_interaction_table_stable(table: &InteractionTable) {
     }
 }
 
+/// Step `sim` and assert it never explodes, and settles below the stable threshold
+/// once `settle_steps` have passed
+fn assert_reference_run_stable(sim: &mut ReferenceSim, steps: u32, settle_steps: u32) {
+    for step in 1..=steps {
+        sim.step();
+        let stats = sim.stats;
+        assert_eq!(stats.nan_count, 0, "NaN particles at step {}", step);
+        assert!(stats.max_speed < EXPLOSION_VELOCITY_THRESHOLD,
+            "Explosion at step {}: max speed {}", step, stats.max_speed);
+        if step > settle_steps {
+            assert!(stats.max_speed < STABLE_VELOCITY_THRESHOLD,
+                "Not settled at step {}: max speed {}", step, stats.max_speed);
+        }
+    }
+}
+
+/// Square block of resting water particles
+fn water_block(origin: [f32; 2], side: usize, spacing: f32) -> Vec<Particle> {
+    (0..side * side)
+        .map(|i| {
+            let (x, y) = ((i % side) as f32, (i / side) as f32);
+            Particle::new_water([origin[0] + x * spacing, origin[1] + y * spacing], [0.0, 0.0])
+        })
+        .collect()
+}
+
+fn reference_sim(particles: Vec<Particle>, bonds: Vec<Bond>) -> ReferenceSim {
+    ReferenceSim::new(particles, bonds, SimParams::default(), GridParams::default(), default_interaction_table())
+}
+
 /// Get human-readable name for material pair index
 fn profile_name(idx: usize) -> &'static str {
     const NAMES: [&str; 25] = [
@@ -186,3 +222,51 @@ fn test_global_sph_parameters_bounded() {
     assert!(table.pressure_cap <= 10000.0,
         "Pressure cap {} too high", table.pressure_cap);
 }
+
+#[test]
+fn test_reference_water_block_spreads_without_explosion() {
+    // Packed tighter than rest density: pressure pushes the block apart
+    let mut sim = reference_sim(water_block([-50.0, -50.0], 20, 4.0), vec![]);
+    assert_reference_run_stable(&mut sim, 200, 20);
+
+    assert_eq!(sim.stats.particle_count[MaterialType::Water as usize], 400);
+    // Packed, the densest particles start above 3
+    assert!(sim.stats.max_density[MaterialType::Water as usize] < 1.0,
+        "Block did not spread: max density {}", sim.stats.max_density[MaterialType::Water as usize]);
+}
+
+#[test]
+fn test_reference_hull_ramming_water_no_explosion() {
+    let mut particles = water_block([-50.0, -50.0], 20, 5.0);
+
+    // Bonded 4×4 hull lattice moving into the block
+    let base = particles.len() as u32;
+    let side = 4u32;
+    for i in 0..side * side {
+        let pos = [100.0 + (i % side) as f32 * 8.0, (i / side) as f32 * 8.0];
+        particles.push(Particle { mass: 100.0, layer_mask: layer::HULL, ..Particle::new_water(pos, [-30.0, 0.0]) });
+    }
+    let bond = |a: u32, b: u32| Bond {
+        particle_a: base + a,
+        particle_b: base + b,
+        rest_length: 8.0,
+        stiffness: 5000.0,
+        breaking_strain: 0.5,
+        bond_type: bond_type::HULL,
+        is_active: 1,
+        ..Bond::zeroed()
+    };
+    let mut bonds = Vec::new();
+    for i in 0..side * side {
+        if i % side < side - 1 {
+            bonds.push(bond(i, i + 1));
+        }
+        if i / side < side - 1 {
+            bonds.push(bond(i, i + side));
+        }
+    }
+
+    let mut sim = reference_sim(particles, bonds);
+    assert_reference_run_stable(&mut sim, 300, 20);
+    assert!(sim.stats.active_bonds > 0, "Hull disintegrated");
+}